@@ -5,7 +5,8 @@ use syn::{Lit, Meta, MetaNameValue, NestedMeta};
 
 #[cfg(feature = "std")]
 use crate::constants::{
-    DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
+    DEFAULT_MAX_BLOB_SIZE, DEFAULT_MAX_BYTECODE_SIZE, DEFAULT_MAX_INPUT_SIZE,
+    DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MAX_TRACE_LENGTH, DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
 };
 
 pub struct Attributes {
@@ -14,6 +15,12 @@ pub struct Attributes {
     pub stack_size: u64,
     pub max_input_size: u64,
     pub max_output_size: u64,
+    pub max_blob_size: u64,
+    pub max_trace_length: u64,
+    pub max_bytecode_size: u64,
+    /// The instruction lookup decomposition `(C, M)`, if not the VM's default.
+    pub c: Option<u64>,
+    pub m: Option<u64>,
 }
 
 #[cfg(feature = "std")]
@@ -34,6 +41,11 @@ pub fn parse_attributes(attr: &Vec<NestedMeta>) -> Attributes {
                     "stack_size" => attributes.insert("stack_size", value),
                     "max_input_size" => attributes.insert("max_input_size", value),
                     "max_output_size" => attributes.insert("max_output_size", value),
+                    "max_blob_size" => attributes.insert("max_blob_size", value),
+                    "max_trace_length" => attributes.insert("max_trace_length", value),
+                    "max_bytecode_size" => attributes.insert("max_bytecode_size", value),
+                    "c" => attributes.insert("c", value),
+                    "m" => attributes.insert("m", value),
                     _ => panic!("invalid attribute"),
                 };
             }
@@ -54,6 +66,15 @@ pub fn parse_attributes(attr: &Vec<NestedMeta>) -> Attributes {
     let max_output_size = *attributes
         .get("max_output_size")
         .unwrap_or(&DEFAULT_MAX_OUTPUT_SIZE);
+    let max_blob_size = *attributes
+        .get("max_blob_size")
+        .unwrap_or(&DEFAULT_MAX_BLOB_SIZE);
+    let max_trace_length = *attributes
+        .get("max_trace_length")
+        .unwrap_or(&DEFAULT_MAX_TRACE_LENGTH);
+    let max_bytecode_size = *attributes
+        .get("max_bytecode_size")
+        .unwrap_or(&DEFAULT_MAX_BYTECODE_SIZE);
 
     Attributes {
         wasm,
@@ -61,5 +82,10 @@ pub fn parse_attributes(attr: &Vec<NestedMeta>) -> Attributes {
         stack_size,
         max_input_size,
         max_output_size,
+        max_blob_size,
+        max_trace_length,
+        max_bytecode_size,
+        c: attributes.get("c").copied(),
+        m: attributes.get("m").copied(),
     }
 }
@@ -0,0 +1,273 @@
+//! Read-only blobs: named byte strings attached to a program by the host and read by
+//! the guest from memory, for data too large to pass as inputs (e.g. a state snapshot).
+//!
+//! Blobs live in the blob region of the [`MemoryLayout`], which follows RAM. Each blob
+//! occupies a power-of-two number of words, aligned to its size, so that it is a subcube
+//! of the read-write memory witness; placing the blobs in decreasing order of size keeps
+//! them aligned without gaps. The guest finds them through the blob directory, which
+//! directly follows the termination word and holds the number of blobs followed by one
+//! entry per blob: its name (zero-padded to [`MAX_BLOB_NAME_LEN`] bytes), address and
+//! length in bytes. Neither may be written by the guest.
+//!
+//! The contents of a blob are not part of the proof. The prover commits to each blob,
+//! and the proof's `JoltDevice` only holds its name, length and the digest of that
+//! commitment, which the verifier checks against the digest it expects.
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt;
+
+use ark_serialize::{
+    CanonicalDeserialize, CanonicalSerialize, Compress, Read, SerializationError, Valid, Validate,
+    Write,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::rv_trace::MemoryLayout;
+
+/// The maximum number of blobs attached to a program.
+pub const MAX_BLOBS: usize = 16;
+/// The maximum length of the name of a blob, in bytes.
+pub const MAX_BLOB_NAME_LEN: usize = 32;
+/// The number of words of a directory entry: the name, the address and the length.
+pub const BLOB_DIRECTORY_ENTRY_WORDS: usize = MAX_BLOB_NAME_LEN / 4 + 2;
+/// The size of the blob directory in bytes: the number of blobs, then the entries.
+pub const BLOB_DIRECTORY_SIZE: u64 = 4 * (1 + MAX_BLOBS * BLOB_DIRECTORY_ENTRY_WORDS) as u64;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Blob {
+    pub name: String,
+    /// The length of the blob in bytes.
+    pub len: u64,
+    /// The address of the blob, assigned by [`place_blobs`].
+    pub address: u64,
+    /// The SHA3-256 digest of the prover's commitment to the blob, set by the prover.
+    pub digest: [u8; 32],
+    /// The contents of the blob. Only held by the prover: they are not serialized.
+    #[serde(skip)]
+    pub data: Vec<u8>,
+}
+
+impl Blob {
+    pub fn new(name: &str, data: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            len: data.len() as u64,
+            data,
+            ..Default::default()
+        }
+    }
+
+    /// The number of words the blob occupies in the blob region, a power of two.
+    pub fn num_words(&self) -> u64 {
+        self.len.div_ceil(4).next_power_of_two()
+    }
+
+    /// The blob's contents as little-endian words, padded with zeros to
+    /// [`Blob::num_words`].
+    pub fn words(&self) -> Vec<u32> {
+        let mut words = vec![0; self.num_words() as usize];
+        for (word, chunk) in words.iter_mut().zip(self.data.chunks(4)) {
+            let mut bytes = [0u8; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            *word = u32::from_le_bytes(bytes);
+        }
+        words
+    }
+
+    /// The byte at `address`, which must lie within the blob's words.
+    pub fn load(&self, address: u64) -> u8 {
+        self.data
+            .get((address - self.address) as usize)
+            .copied()
+            .unwrap_or(0)
+    }
+
+    pub fn contains(&self, address: u64) -> bool {
+        (self.address..self.address + 4 * self.num_words()).contains(&address)
+    }
+}
+
+/// A blob is serialized without its contents.
+impl CanonicalSerialize for Blob {
+    fn serialize_with_mode<W: Write>(
+        &self,
+        mut writer: W,
+        compress: Compress,
+    ) -> Result<(), SerializationError> {
+        self.name.serialize_with_mode(&mut writer, compress)?;
+        self.len.serialize_with_mode(&mut writer, compress)?;
+        self.address.serialize_with_mode(&mut writer, compress)?;
+        self.digest.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: Compress) -> usize {
+        self.name.serialized_size(compress)
+            + self.len.serialized_size(compress)
+            + self.address.serialized_size(compress)
+            + self.digest.serialized_size(compress)
+    }
+}
+
+impl Valid for Blob {
+    fn check(&self) -> Result<(), SerializationError> {
+        Ok(())
+    }
+}
+
+impl CanonicalDeserialize for Blob {
+    fn deserialize_with_mode<R: Read>(
+        mut reader: R,
+        compress: Compress,
+        validate: Validate,
+    ) -> Result<Self, SerializationError> {
+        Ok(Self {
+            name: String::deserialize_with_mode(&mut reader, compress, validate)?,
+            len: u64::deserialize_with_mode(&mut reader, compress, validate)?,
+            address: u64::deserialize_with_mode(&mut reader, compress, validate)?,
+            digest: <[u8; 32]>::deserialize_with_mode(&mut reader, compress, validate)?,
+            data: vec![],
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BlobError {
+    TooManyBlobs(usize),
+    /// The name is empty, too long, contains a zero byte or is used by another blob.
+    InvalidName(String),
+    /// The blobs need `size` bytes of blob region, but `max_blob_size` only provides
+    /// `region_size`.
+    TooLarge {
+        size: u64,
+        region_size: u64,
+    },
+}
+
+impl fmt::Display for BlobError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BlobError::TooManyBlobs(count) => {
+                write!(f, "{count} blobs attached, at most {MAX_BLOBS} are supported")
+            }
+            BlobError::InvalidName(name) => write!(
+                f,
+                "Invalid blob name {name:?}: names must be unique, non-empty, at most {MAX_BLOB_NAME_LEN} bytes and free of zero bytes"
+            ),
+            BlobError::TooLarge { size, region_size } => write!(
+                f,
+                "Blobs too large: they occupy {size} bytes but the blob region is {region_size} bytes; consider increasing `max_blob_size`"
+            ),
+        }
+    }
+}
+
+/// Validates `blobs` and assigns their addresses in the blob region of `memory_layout`.
+pub fn place_blobs(blobs: &mut [Blob], memory_layout: &MemoryLayout) -> Result<(), BlobError> {
+    if blobs.len() > MAX_BLOBS {
+        return Err(BlobError::TooManyBlobs(blobs.len()));
+    }
+    for (i, blob) in blobs.iter().enumerate() {
+        let name = blob.name.as_bytes();
+        if name.is_empty()
+            || name.len() > MAX_BLOB_NAME_LEN
+            || name.contains(&0)
+            || blobs[..i].iter().any(|other| other.name == blob.name)
+        {
+            return Err(BlobError::InvalidName(blob.name.clone()));
+        }
+    }
+
+    let mut order: Vec<usize> = (0..blobs.len()).collect();
+    order.sort_by_key(|&i| core::cmp::Reverse(blobs[i].num_words()));
+    let mut address = memory_layout.blob_start;
+    for i in order {
+        blobs[i].address = address;
+        address += 4 * blobs[i].num_words();
+    }
+
+    let size = address - memory_layout.blob_start;
+    let region_size = memory_layout.blob_end - memory_layout.blob_start;
+    if size > region_size {
+        return Err(BlobError::TooLarge { size, region_size });
+    }
+    Ok(())
+}
+
+/// The words of the blob directory of `blobs`, which must have been placed.
+pub fn blob_directory(blobs: &[Blob]) -> Vec<u32> {
+    let mut words = vec![blobs.len() as u32];
+    for blob in blobs {
+        let mut name = [0u8; MAX_BLOB_NAME_LEN];
+        name[..blob.name.len()].copy_from_slice(blob.name.as_bytes());
+        words.extend(
+            name.chunks(4)
+                .map(|chunk| u32::from_le_bytes(chunk.try_into().unwrap())),
+        );
+        words.push(blob.address as u32);
+        words.push(blob.len as u32);
+    }
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::{DEFAULT_MEMORY_SIZE, REGISTER_COUNT};
+
+    #[test]
+    fn blobs_are_aligned_to_their_size() {
+        let memory_layout = MemoryLayout::with_blobs(4096, 4096, 1 << 16, DEFAULT_MEMORY_SIZE);
+        let mut blobs = vec![
+            Blob::new("small", vec![1; 10]),
+            Blob::new("large", vec![2; 5000]),
+            Blob::new("empty", vec![]),
+        ];
+        place_blobs(&mut blobs, &memory_layout).unwrap();
+
+        for blob in &blobs {
+            let index = REGISTER_COUNT + (blob.address - memory_layout.input_start) / 4;
+            assert_eq!(index % blob.num_words(), 0, "{}", blob.name);
+            assert!(blob.address + 4 * blob.num_words() <= memory_layout.blob_end);
+        }
+        assert_eq!(blobs[1].address, memory_layout.blob_start);
+        assert_eq!(blobs[1].load(blobs[1].address + 4999), 2);
+        assert_eq!(blobs[1].load(blobs[1].address + 5000), 0);
+    }
+
+    #[test]
+    fn invalid_blobs() {
+        let memory_layout = MemoryLayout::with_blobs(4096, 4096, 1024, DEFAULT_MEMORY_SIZE);
+        let place = |blobs: &mut [Blob]| place_blobs(blobs, &memory_layout);
+
+        assert!(matches!(
+            place(&mut [Blob::new("a", vec![]), Blob::new("a", vec![])]),
+            Err(BlobError::InvalidName(_))
+        ));
+        assert!(matches!(
+            place(&mut [Blob::new("", vec![])]),
+            Err(BlobError::InvalidName(_))
+        ));
+        assert!(matches!(
+            place(&mut [Blob::new(&"x".repeat(MAX_BLOB_NAME_LEN + 1), vec![])]),
+            Err(BlobError::InvalidName(_))
+        ));
+        assert_eq!(
+            place(&mut [Blob::new("a", vec![0; 1000]), Blob::new("b", vec![0; 4])]),
+            Err(BlobError::TooLarge {
+                size: 1028,
+                region_size: 1024
+            })
+        );
+        assert_eq!(
+            place_blobs(
+                &mut [Blob::new("a", vec![])],
+                &MemoryLayout::new(4096, 4096)
+            ),
+            Err(BlobError::TooLarge {
+                size: 4,
+                region_size: 0
+            })
+        );
+    }
+}
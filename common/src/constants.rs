@@ -1,7 +1,10 @@
 pub const XLEN: usize = 32;
 const RISCV_REGISTER_COUNT: u64 = 32;
-const VIRTUAL_REGISTER_COUNT: u64 = 32; //  see Section 6.1 of Jolt paper
-pub const REGISTER_COUNT: u64 = RISCV_REGISTER_COUNT + VIRTUAL_REGISTER_COUNT;
+pub const VIRTUAL_REGISTER_COUNT: u64 = 32; //  see Section 6.1 of Jolt paper
+/// 32 floating-point registers (RV32F/D), each held in two words
+const FLOAT_REGISTER_COUNT: u64 = 2 * 32;
+pub const REGISTER_COUNT: u64 =
+    RISCV_REGISTER_COUNT + VIRTUAL_REGISTER_COUNT + FLOAT_REGISTER_COUNT;
 pub const BYTES_PER_INSTRUCTION: usize = 4;
 /// 3 registers (rd, rs1, rs2) + 1 RAM
 pub const MEMORY_OPS_PER_INSTRUCTION: usize = 4;
@@ -11,14 +14,24 @@ pub const DEFAULT_MEMORY_SIZE: u64 = 10 * 1024 * 1024;
 pub const DEFAULT_STACK_SIZE: u64 = 4096;
 pub const DEFAULT_MAX_INPUT_SIZE: u64 = 4096;
 pub const DEFAULT_MAX_OUTPUT_SIZE: u64 = 4096;
+pub const DEFAULT_MAX_BLOB_SIZE: u64 = 0;
+pub const DEFAULT_MAX_TRACE_LENGTH: u64 = 1 << 24;
+pub const DEFAULT_MAX_BYTECODE_SIZE: u64 = 1 << 20;
 
 pub const fn virtual_register_index(index: u64) -> u64 {
     index + VIRTUAL_REGISTER_COUNT
 }
 
+/// The index of the word holding the low 32 bits of floating-point register `f{index}`.
+/// The high 32 bits are held at the next index; a single-precision value is NaN-boxed,
+/// i.e. its high word is `0xffffffff`.
+pub const fn float_register_index(index: u64) -> u64 {
+    RISCV_REGISTER_COUNT + VIRTUAL_REGISTER_COUNT + 2 * index
+}
+
 // Layout of the witness (where || denotes concatenation):
-//     registers || virtual registers || inputs || outputs || panic || termination || padding || RAM
+//     registers || virtual registers || float registers || inputs || outputs || panic || termination || [blob directory] || padding || RAM || [padding || blobs]
 // Layout of VM memory:
-//     peripheral devices || inputs || outputs || panic || termination || padding || RAM
+//     peripheral devices || inputs || outputs || panic || termination || [blob directory] || padding || RAM || [padding || blobs]
 // Notably, we want to be able to map the VM memory address space to witness indices
 // using a constant shift, namely (RAM_WITNESS_OFFSET + RAM_START_ADDRESS)
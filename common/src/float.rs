@@ -0,0 +1,499 @@
+//! Reference semantics of the RV32F and RV32D instructions, shared by the tracer and
+//! by the tests of their virtual sequences.
+//!
+//! Floating-point register `f{i}` is held in two words of the register file (see
+//! [`float_register_index`](crate::constants::float_register_index)), so the value
+//! of an `f` register is 64 bits. A single-precision value is NaN-boxed: its upper
+//! 32 bits are all ones, and a value that is not NaN-boxed is read as the canonical
+//! NaN. Values of `x` registers are 32 bits.
+//!
+//! Results are those of the RISC-V specification, except that the accrued exception
+//! flags (`fflags`) are not modeled. Conversions between integers and floats support
+//! every static rounding mode; the other rounding instructions only support
+//! round-to-nearest-even. The dynamic rounding mode is assumed to be
+//! round-to-nearest-even, the reset value of `frm`.
+
+use crate::rv_trace::RV32IM;
+
+/// The canonical single-precision NaN.
+pub const CANONICAL_NAN_S: u32 = 0x7fc00000;
+/// The canonical double-precision NaN.
+pub const CANONICAL_NAN_D: u64 = 0x7ff8000000000000;
+/// The upper word of a NaN-boxed single-precision value.
+pub const NAN_BOX: u64 = 0xffffffff << 32;
+
+/// The `rm` field of a floating-point instruction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RoundingMode {
+    NearestEven,
+    TowardZero,
+    Down,
+    Up,
+    NearestMaxMagnitude,
+}
+
+impl RoundingMode {
+    pub const DYNAMIC: u64 = 7;
+
+    /// Decodes `rm`, reading the dynamic rounding mode as round-to-nearest-even.
+    pub fn from_rm(rm: u64) -> Option<Self> {
+        match rm {
+            0 | Self::DYNAMIC => Some(Self::NearestEven),
+            1 => Some(Self::TowardZero),
+            2 => Some(Self::Down),
+            3 => Some(Self::Up),
+            4 => Some(Self::NearestMaxMagnitude),
+            _ => None,
+        }
+    }
+}
+
+/// The `imm` of a floating-point `ELFInstruction`: its rounding mode and, for the
+/// fused multiply-add instructions, the register index of its third operand.
+pub fn encode_imm(rm: u64, rs3: Option<u64>) -> i64 {
+    (rm | rs3.unwrap_or(0) << 3) as i64
+}
+
+/// Inverse of [`encode_imm`].
+pub fn decode_imm(imm: i64) -> (u64, Option<u64>) {
+    let imm = imm as u64;
+    let rs3 = imm >> 3;
+    (imm & 7, (rs3 != 0).then_some(rs3))
+}
+
+/// Whether `opcode` is an RV32F or RV32D instruction.
+pub fn is_float(opcode: RV32IM) -> bool {
+    use RV32IM::*;
+    matches!(
+        opcode,
+        FLW | FSW
+            | FADD_S
+            | FSUB_S
+            | FMUL_S
+            | FDIV_S
+            | FSQRT_S
+            | FMIN_S
+            | FMAX_S
+            | FSGNJ_S
+            | FSGNJN_S
+            | FSGNJX_S
+            | FEQ_S
+            | FLT_S
+            | FLE_S
+            | FCLASS_S
+            | FCVT_W_S
+            | FCVT_WU_S
+            | FCVT_S_W
+            | FCVT_S_WU
+            | FMV_X_W
+            | FMV_W_X
+            | FMADD_S
+            | FMSUB_S
+            | FNMSUB_S
+            | FNMADD_S
+            | FLD
+            | FSD
+            | FADD_D
+            | FSUB_D
+            | FMUL_D
+            | FDIV_D
+            | FSQRT_D
+            | FMIN_D
+            | FMAX_D
+            | FSGNJ_D
+            | FSGNJN_D
+            | FSGNJX_D
+            | FEQ_D
+            | FLT_D
+            | FLE_D
+            | FCLASS_D
+            | FCVT_W_D
+            | FCVT_WU_D
+            | FCVT_D_W
+            | FCVT_D_WU
+            | FCVT_S_D
+            | FCVT_D_S
+            | FMADD_D
+            | FMSUB_D
+            | FNMSUB_D
+            | FNMADD_D
+    )
+}
+
+/// Whether `opcode` is a fused multiply-add instruction, which has a third operand.
+pub fn is_fused_multiply_add(opcode: RV32IM) -> bool {
+    use RV32IM::*;
+    matches!(
+        opcode,
+        FMADD_S | FMSUB_S | FNMSUB_S | FNMADD_S | FMADD_D | FMSUB_D | FNMSUB_D | FNMADD_D
+    )
+}
+
+/// Whether `opcode` rounds its result, i.e. whether its `rm` field is used.
+pub fn is_rounding(opcode: RV32IM) -> bool {
+    use RV32IM::*;
+    matches!(
+        opcode,
+        FADD_S
+            | FSUB_S
+            | FMUL_S
+            | FDIV_S
+            | FSQRT_S
+            | FCVT_W_S
+            | FCVT_WU_S
+            | FCVT_S_W
+            | FCVT_S_WU
+            | FMADD_S
+            | FMSUB_S
+            | FNMSUB_S
+            | FNMADD_S
+            | FADD_D
+            | FSUB_D
+            | FMUL_D
+            | FDIV_D
+            | FSQRT_D
+            | FCVT_W_D
+            | FCVT_WU_D
+            | FCVT_S_D
+            | FMADD_D
+            | FMSUB_D
+            | FNMSUB_D
+            | FNMADD_D
+    )
+}
+
+/// Whether `opcode` supports every static rounding mode, rather than only
+/// round-to-nearest-even (see the module documentation).
+pub fn supports_rounding_mode(opcode: RV32IM) -> bool {
+    use RV32IM::*;
+    matches!(
+        opcode,
+        FCVT_W_S | FCVT_WU_S | FCVT_S_W | FCVT_S_WU | FCVT_W_D | FCVT_WU_D
+    )
+}
+
+/// Reads a single-precision value from the 64-bit value of an `f` register.
+pub fn unbox(value: u64) -> f32 {
+    if value & NAN_BOX == NAN_BOX {
+        f32::from_bits(value as u32)
+    } else {
+        f32::from_bits(CANONICAL_NAN_S)
+    }
+}
+
+/// NaN-boxes a single-precision value, replacing a NaN with the canonical NaN.
+pub fn box_s(value: f32) -> u64 {
+    let bits = if value.is_nan() {
+        CANONICAL_NAN_S
+    } else {
+        value.to_bits()
+    };
+    NAN_BOX | bits as u64
+}
+
+fn canonical_d(value: f64) -> u64 {
+    if value.is_nan() {
+        CANONICAL_NAN_D
+    } else {
+        value.to_bits()
+    }
+}
+
+/// Executes the floating-point instruction `opcode`, other than a load or store, on
+/// the values of its operand registers. Returns the value written to `rd`.
+pub fn execute(opcode: RV32IM, rm: RoundingMode, rs1: u64, rs2: u64, rs3: u64) -> u64 {
+    use RV32IM::*;
+    let (a, b, c) = (unbox(rs1), unbox(rs2), unbox(rs3));
+    let (x, y, z) = (
+        f64::from_bits(rs1),
+        f64::from_bits(rs2),
+        f64::from_bits(rs3),
+    );
+    if is_rounding(opcode) && !supports_rounding_mode(opcode) {
+        assert_eq!(
+            rm,
+            RoundingMode::NearestEven,
+            "{opcode:?} only supports RNE"
+        );
+    }
+    match opcode {
+        FADD_S => box_s(a + b),
+        FSUB_S => box_s(a - b),
+        FMUL_S => box_s(a * b),
+        FDIV_S => box_s(a / b),
+        FSQRT_S => box_s(a.sqrt()),
+        FMADD_S => box_s(a.mul_add(b, c)),
+        FMSUB_S => box_s(a.mul_add(b, -c)),
+        FNMSUB_S => box_s((-a).mul_add(b, c)),
+        FNMADD_S => box_s((-a).mul_add(b, -c)),
+        FADD_D => canonical_d(x + y),
+        FSUB_D => canonical_d(x - y),
+        FMUL_D => canonical_d(x * y),
+        FDIV_D => canonical_d(x / y),
+        FSQRT_D => canonical_d(x.sqrt()),
+        FMADD_D => canonical_d(x.mul_add(y, z)),
+        FMSUB_D => canonical_d(x.mul_add(y, -z)),
+        FNMSUB_D => canonical_d((-x).mul_add(y, z)),
+        FNMADD_D => canonical_d((-x).mul_add(y, -z)),
+
+        FMIN_S | FMAX_S => box_s(min_max(a as f64, b as f64, opcode == FMAX_S) as f32),
+        FMIN_D | FMAX_D => canonical_d(min_max(x, y, opcode == FMAX_D)),
+
+        FSGNJ_S | FSGNJN_S | FSGNJX_S => {
+            let (a, b) = (a.to_bits(), b.to_bits());
+            let sign = match opcode {
+                FSGNJ_S => b,
+                FSGNJN_S => !b,
+                _ => a ^ b,
+            } & 1 << 31;
+            NAN_BOX | (a & !(1 << 31) | sign) as u64
+        }
+        FSGNJ_D | FSGNJN_D | FSGNJX_D => {
+            let sign = match opcode {
+                FSGNJ_D => rs2,
+                FSGNJN_D => !rs2,
+                _ => rs1 ^ rs2,
+            } & 1 << 63;
+            rs1 & !(1 << 63) | sign
+        }
+
+        FEQ_S => (a == b) as u64,
+        FLT_S => (a < b) as u64,
+        FLE_S => (a <= b) as u64,
+        FEQ_D => (x == y) as u64,
+        FLT_D => (x < y) as u64,
+        FLE_D => (x <= y) as u64,
+
+        FCLASS_S => classify(a as f64, a.to_bits() & 1 << 22 != 0, a.is_subnormal()),
+        FCLASS_D => classify(x, rs1 & 1 << 51 != 0, x.is_subnormal()),
+
+        FCVT_W_S | FCVT_WU_S => to_int(a as f64, rm, opcode == FCVT_W_S),
+        FCVT_W_D | FCVT_WU_D => to_int(x, rm, opcode == FCVT_W_D),
+        FCVT_S_W => box_s(to_f32(rs1 as u32 as i32 as f64, rm)),
+        FCVT_S_WU => box_s(to_f32(rs1 as u32 as f64, rm)),
+        FCVT_D_W => (rs1 as u32 as i32 as f64).to_bits(),
+        FCVT_D_WU => (rs1 as u32 as f64).to_bits(),
+        FCVT_S_D => box_s(x as f32),
+        FCVT_D_S => canonical_d(a as f64),
+
+        FMV_X_W => rs1 & 0xffffffff,
+        FMV_W_X => NAN_BOX | rs1 & 0xffffffff,
+
+        _ => panic!("{opcode:?} is not a floating-point operation"),
+    }
+}
+
+/// `FMIN`/`FMAX`: a NaN operand is ignored, and `-0.0` is less than `+0.0`.
+fn min_max(x: f64, y: f64, max: bool) -> f64 {
+    match (x.is_nan(), y.is_nan()) {
+        (true, true) => f64::NAN,
+        (true, false) => y,
+        (false, true) => x,
+        _ if x == y => {
+            // Equal up to the sign of zero
+            if max == x.is_sign_negative() {
+                y
+            } else {
+                x
+            }
+        }
+        _ if (x < y) == max => y,
+        _ => x,
+    }
+}
+
+/// The `FCLASS` mask of `x`, where `quiet` is the most significant fraction bit and
+/// `subnormal` whether `x` is subnormal in its original format.
+fn classify(x: f64, quiet: bool, subnormal: bool) -> u64 {
+    let negative = x.is_sign_negative();
+    let bit = if x.is_nan() {
+        if quiet {
+            9
+        } else {
+            8
+        }
+    } else if x.is_infinite() {
+        if negative {
+            0
+        } else {
+            7
+        }
+    } else if x == 0.0 {
+        if negative {
+            3
+        } else {
+            4
+        }
+    } else {
+        match (negative, subnormal) {
+            (true, false) => 1,
+            (true, true) => 2,
+            (false, true) => 5,
+            (false, false) => 6,
+        }
+    };
+    1 << bit
+}
+
+/// `FCVT.W[U]`: rounds `x` to an integer and saturates it, with NaN converted to the
+/// largest integer.
+fn to_int(x: f64, rm: RoundingMode, signed: bool) -> u64 {
+    let (min, max) = if signed {
+        (i32::MIN as f64, i32::MAX as f64)
+    } else {
+        (0.0, u32::MAX as f64)
+    };
+    if x.is_nan() {
+        return max as i64 as u32 as u64;
+    }
+    let rounded = match rm {
+        RoundingMode::NearestEven => x.round_ties_even(),
+        RoundingMode::TowardZero => x.trunc(),
+        RoundingMode::Down => x.floor(),
+        RoundingMode::Up => x.ceil(),
+        RoundingMode::NearestMaxMagnitude => x.round(),
+    };
+    rounded.clamp(min, max) as i64 as u32 as u64
+}
+
+/// Rounds `x`, an integer of at most 32 bits, to single precision.
+fn to_f32(x: f64, rm: RoundingMode) -> f32 {
+    let nearest = x as f32;
+    if nearest as f64 == x {
+        return nearest;
+    }
+    let (lo, hi) = if (nearest as f64) < x {
+        (nearest, nearest.next_up())
+    } else {
+        (nearest.next_down(), nearest)
+    };
+    let away = if x > 0.0 { hi } else { lo };
+    let toward_zero = if x > 0.0 { lo } else { hi };
+    match rm {
+        RoundingMode::NearestEven => nearest,
+        RoundingMode::TowardZero => toward_zero,
+        RoundingMode::Down => lo,
+        RoundingMode::Up => hi,
+        RoundingMode::NearestMaxMagnitude => {
+            if x - lo as f64 == hi as f64 - x {
+                away
+            } else {
+                nearest
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RNE: RoundingMode = RoundingMode::NearestEven;
+
+    fn s(x: f32) -> u64 {
+        box_s(x)
+    }
+
+    #[test]
+    fn nan_boxing() {
+        // Not NaN-boxed: read as the canonical NaN
+        assert_eq!(
+            execute(RV32IM::FADD_S, RNE, 1.0f32.to_bits() as u64, s(1.0), 0),
+            box_s(f32::NAN)
+        );
+        assert_eq!(execute(RV32IM::FADD_S, RNE, s(1.0), s(2.0), 0), s(3.0));
+        assert_eq!(
+            execute(RV32IM::FMV_X_W, RNE, s(-1.0), 0, 0),
+            (-1.0f32).to_bits() as u64
+        );
+        assert_eq!(execute(RV32IM::FSGNJN_S, RNE, s(2.0), s(1.0), 0), s(-2.0));
+        assert_eq!(
+            execute(RV32IM::FCVT_D_S, RNE, s(0.5), 0, 0),
+            0.5f64.to_bits()
+        );
+    }
+
+    #[test]
+    fn nan_results_are_canonical() {
+        let snan = 0x7ff0000000000001;
+        assert_eq!(
+            execute(RV32IM::FADD_D, RNE, snan, 1.0f64.to_bits(), 0),
+            CANONICAL_NAN_D
+        );
+        assert_eq!(
+            execute(RV32IM::FSQRT_S, RNE, s(-1.0), 0, 0),
+            NAN_BOX | CANONICAL_NAN_S as u64
+        );
+        // Sign injection does not canonicalize
+        assert_eq!(execute(RV32IM::FSGNJ_D, RNE, snan, 0, 0), snan);
+    }
+
+    #[test]
+    fn min_max() {
+        let (nan, zero, neg_zero) = (s(f32::NAN), s(0.0), s(-0.0));
+        assert_eq!(execute(RV32IM::FMIN_S, RNE, nan, s(1.0), 0), s(1.0));
+        assert_eq!(execute(RV32IM::FMAX_S, RNE, s(1.0), nan, 0), s(1.0));
+        assert_eq!(execute(RV32IM::FMIN_S, RNE, nan, nan, 0), nan);
+        assert_eq!(execute(RV32IM::FMIN_S, RNE, zero, neg_zero, 0), neg_zero);
+        assert_eq!(execute(RV32IM::FMAX_S, RNE, neg_zero, zero, 0), zero);
+        assert_eq!(execute(RV32IM::FMAX_S, RNE, s(-2.0), s(-3.0), 0), s(-2.0));
+    }
+
+    #[test]
+    fn classify() {
+        let class = |x: f32| {
+            execute(RV32IM::FCLASS_S, RNE, NAN_BOX | x.to_bits() as u64, 0, 0).trailing_zeros()
+        };
+        assert_eq!(class(f32::NEG_INFINITY), 0);
+        assert_eq!(class(-1.0), 1);
+        assert_eq!(class(-f32::from_bits(1)), 2);
+        assert_eq!(class(-0.0), 3);
+        assert_eq!(class(0.0), 4);
+        assert_eq!(class(f32::from_bits(0x7fffff)), 5);
+        assert_eq!(class(f32::MIN_POSITIVE), 6);
+        assert_eq!(class(f32::INFINITY), 7);
+        assert_eq!(class(f32::from_bits(0x7f800001)), 8);
+        assert_eq!(class(f32::NAN), 9);
+        let class = |x: u64| execute(RV32IM::FCLASS_D, RNE, x, 0, 0).trailing_zeros();
+        assert_eq!(class(1), 5);
+        assert_eq!(class((f32::from_bits(1) as f64).to_bits()), 6);
+        assert_eq!(class(0x7ff0000000000001), 8);
+    }
+
+    #[test]
+    fn conversions() {
+        let to_w = |x: f64, rm| execute(RV32IM::FCVT_W_D, rm, x.to_bits(), 0, 0) as u32 as i32;
+        assert_eq!(to_w(2.5, RNE), 2);
+        assert_eq!(to_w(-2.5, RoundingMode::NearestMaxMagnitude), -3);
+        assert_eq!(to_w(-2.5, RoundingMode::TowardZero), -2);
+        assert_eq!(to_w(-2.5, RoundingMode::Down), -3);
+        assert_eq!(to_w(2.1, RoundingMode::Up), 3);
+        assert_eq!(to_w(1e10, RNE), i32::MAX);
+        assert_eq!(to_w(-1e10, RNE), i32::MIN);
+        assert_eq!(to_w(f64::NAN, RNE), i32::MAX);
+        let to_wu = |x: f64| execute(RV32IM::FCVT_WU_D, RNE, x.to_bits(), 0, 0);
+        assert_eq!(to_wu(-1.0), 0);
+        assert_eq!(to_wu(f64::NAN), u32::MAX as u64);
+        assert_eq!(to_wu(4294967295.4), u32::MAX as u64);
+
+        let to_s = |x: u32, rm| unbox(execute(RV32IM::FCVT_S_WU, rm, x as u64, 0, 0));
+        // 2^24 + 1 is halfway between two singles
+        assert_eq!(to_s(0x1000001, RNE), 16777216.0);
+        assert_eq!(
+            to_s(0x1000001, RoundingMode::NearestMaxMagnitude),
+            16777218.0
+        );
+        assert_eq!(to_s(0x1000001, RoundingMode::Up), 16777218.0);
+        assert_eq!(to_s(0x1000003, RoundingMode::TowardZero), 16777218.0);
+        assert_eq!(to_s(0x1000003, RNE), 16777220.0);
+        let to_s = |x: i32, rm| unbox(execute(RV32IM::FCVT_S_W, rm, x as u32 as u64, 0, 0));
+        assert_eq!(to_s(-0x1000001, RoundingMode::Down), -16777218.0);
+        assert_eq!(to_s(-0x1000001, RoundingMode::TowardZero), -16777216.0);
+    }
+
+    #[test]
+    fn imm_encoding() {
+        assert_eq!(decode_imm(encode_imm(7, None)), (7, None));
+        assert_eq!(decode_imm(encode_imm(1, Some(70))), (1, Some(70)));
+    }
+}
@@ -4,5 +4,9 @@
 extern crate alloc;
 
 pub mod attributes;
+pub mod blob;
 pub mod constants;
+#[cfg(feature = "std")]
+pub mod float;
+pub mod precompiles;
 pub mod rv_trace;
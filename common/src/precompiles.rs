@@ -0,0 +1,756 @@
+//! Precompiles are instructions that perform a whole operation on multi-word
+//! operands in memory, e.g. a 256-bit modular multiplication, in a single
+//! instruction. A guest invokes precompile `id` with the R-type instruction
+//!
+//! ```text
+//! .insn r 0x5b, 0, id, x0, rs1, rs2
+//! ```
+//!
+//! in the custom-2 (`0b1011011`) major opcode, where `rs1` holds the (word-aligned)
+//! address of the input words and `rs2` the (word-aligned) address the output words
+//! are written to. The input and output words must not overlap. This module contains
+//! the reference implementation of each precompile, shared by the tracer and by guests
+//! that are not compiled to RISC-V.
+
+#[cfg(not(feature = "std"))]
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+
+use strum_macros::{EnumCount as EnumCountMacro, EnumIter, FromRepr};
+
+/// Major opcode of the `PRECOMPILE` instruction (custom-2).
+pub const PRECOMPILE_OPCODE: u32 = 0b1011011;
+
+/// Number of 32-bit limbs of a [`U256`].
+pub const U256_LIMBS: usize = 8;
+
+/// A 256-bit unsigned integer, as little-endian 32-bit limbs.
+pub type U256 = [u32; U256_LIMBS];
+
+/// Modulus of the BN254 scalar field.
+pub const BN254_FR_MODULUS: U256 = [
+    0xf0000001, 0x43e1f593, 0x79b97091, 0x2833e848, 0x8181585d, 0xb85045b6, 0xe131a029, 0x30644e72,
+];
+
+/// Modulus of the BN254 base field.
+pub const BN254_FQ_MODULUS: U256 = [
+    0xd87cfd47, 0x3c208c16, 0x6871ca8d, 0x97816a91, 0x8181585d, 0xb85045b6, 0xe131a029, 0x30644e72,
+];
+
+/// The prime `p = 2^256 - 2^32 - 977` of the field over which secp256k1 is defined.
+pub const SECP256K1_P: U256 = [
+    0xfffffc2f, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+/// The order `n` of the secp256k1 group.
+pub const SECP256K1_N: U256 = [
+    0xd0364141, 0xbfd25e8c, 0xaf48a03b, 0xbaaedce6, 0xfffffffe, 0xffffffff, 0xffffffff, 0xffffffff,
+];
+
+/// The prime `p = 2^255 - 19` of the field over which Curve25519 is defined.
+pub const ED25519_P: U256 = [
+    0xffffffed, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x7fffffff,
+];
+
+/// The order `l` of the prime-order subgroup of Edwards25519.
+pub const ED25519_L: U256 = [
+    0x5cf5d3ed, 0x5812631a, 0xa2f79cd6, 0x14def9de, 0x00000000, 0x00000000, 0x00000000, 0x10000000,
+];
+
+/// The curve parameter `d = -121665 / 121666 mod p` of Edwards25519,
+/// `-x^2 + y^2 = 1 + d x^2 y^2`.
+pub const ED25519_D: U256 = [
+    0x135978a3, 0x75eb4dca, 0x4141d8ab, 0x00700a4d, 0x7779e898, 0x8cc74079, 0x2b6ffe73, 0x52036cee,
+];
+
+/// The round constants of SHA-512.
+pub const SHA512_K: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+/// The supported precompiles. The discriminant is the precompile's id, i.e. the
+/// `funct7` of the `PRECOMPILE` instruction invoking it.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, EnumCountMacro, EnumIter, FromRepr,
+)]
+#[repr(u8)]
+pub enum Precompile {
+    /// `a * b mod p`, for inputs `a || b || p`, where `p > 1`.
+    ModMul,
+    /// `a + b mod p`, for inputs `a || b || p`, where `p > 1`.
+    ModAdd,
+    /// `a * b mod r`, for inputs `a || b`, where `r` is [`BN254_FR_MODULUS`].
+    Bn254FrMul,
+    /// `a + b mod r`, for inputs `a || b`, where `r` is [`BN254_FR_MODULUS`].
+    Bn254FrAdd,
+    /// `a * b mod q`, for inputs `a || b`, where `q` is [`BN254_FQ_MODULUS`].
+    Bn254FqMul,
+    /// `a + b mod q`, for inputs `a || b`, where `q` is [`BN254_FQ_MODULUS`].
+    Bn254FqAdd,
+    /// The sum of two affine secp256k1 points, for inputs `x1 || y1 || x2 || y2` with
+    /// `x1 != x2 mod p`, as `x3 || y3`. The points are not checked to be on the curve.
+    Secp256k1Add,
+    /// Twice an affine secp256k1 point, for inputs `x || y` with `y != 0 mod p`, as
+    /// `x3 || y3`. The point is not checked to be on the curve.
+    Secp256k1Double,
+    /// The sum of two Edwards25519 points, for inputs `x1 || y1 || x2 || y2`, as
+    /// `x3 || y3`. The addition law is complete, but the points are not checked to be
+    /// on the curve, and the sum is undefined if `d x1 x2 y1 y2 = ±1 mod p`.
+    Ed25519Add,
+    /// The SHA-512 compression function, for inputs `h || m` where `h` is the state
+    /// (8 words of 64 bits) and `m` the message block (16 words of 64 bits), each
+    /// 64-bit word being two 32-bit words, least significant first. Outputs the new
+    /// state followed by the message schedule words 16 to 79, in the same format.
+    Sha512Compress,
+}
+
+impl Precompile {
+    pub fn from_id(id: u32) -> Option<Self> {
+        u8::try_from(id).ok().and_then(Self::from_repr)
+    }
+
+    pub const fn id(self) -> u32 {
+        self as u32
+    }
+
+    /// Whether the precompile is a relation between integers modulo a prime (or an
+    /// input modulus), as opposed to a bitwise computation like SHA-512.
+    pub fn is_modular(self) -> bool {
+        self != Self::Sha512Compress
+    }
+
+    /// The modulus of a modular arithmetic precompile, unless it is an input or the
+    /// precompile is not modular.
+    pub fn modulus(self) -> Option<U256> {
+        match self {
+            Self::ModMul | Self::ModAdd | Self::Sha512Compress => None,
+            Self::Bn254FrMul | Self::Bn254FrAdd => Some(BN254_FR_MODULUS),
+            Self::Bn254FqMul | Self::Bn254FqAdd => Some(BN254_FQ_MODULUS),
+            Self::Secp256k1Add | Self::Secp256k1Double => Some(SECP256K1_P),
+            Self::Ed25519Add => Some(ED25519_P),
+        }
+    }
+
+    /// Number of 32-bit words read from the address in `rs1`.
+    pub fn input_words(self) -> usize {
+        match self {
+            Self::ModMul | Self::ModAdd => 3 * U256_LIMBS,
+            Self::Secp256k1Add | Self::Ed25519Add => 4 * U256_LIMBS,
+            Self::Sha512Compress => 48,
+            _ => 2 * U256_LIMBS,
+        }
+    }
+
+    /// Number of 32-bit words written to the address in `rs2`.
+    pub fn output_words(self) -> usize {
+        match self {
+            Self::Secp256k1Add | Self::Secp256k1Double | Self::Ed25519Add => 2 * U256_LIMBS,
+            Self::Sha512Compress => 16 + 128,
+            _ => U256_LIMBS,
+        }
+    }
+
+    /// Computes the output words of the precompile from its input words.
+    pub fn execute(self, input: &[u32]) -> Vec<u32> {
+        assert_eq!(input.len(), self.input_words());
+        let operand = |k: usize| to_u256(&input[k * U256_LIMBS..(k + 1) * U256_LIMBS]);
+        let p = self.modulus().unwrap_or_else(|| operand(2));
+        match self {
+            Self::ModMul | Self::Bn254FrMul | Self::Bn254FqMul => {
+                mul_mod(&operand(0), &operand(1), &p).to_vec()
+            }
+            Self::ModAdd | Self::Bn254FrAdd | Self::Bn254FqAdd => {
+                add_mod(&operand(0), &operand(1), &p).to_vec()
+            }
+            Self::Secp256k1Add => {
+                let (x, y) = secp256k1_add(&operand(0), &operand(1), &operand(2), &operand(3));
+                [x, y].concat()
+            }
+            Self::Secp256k1Double => {
+                let (x, y) = secp256k1_double(&operand(0), &operand(1));
+                [x, y].concat()
+            }
+            Self::Ed25519Add => {
+                let (x, y) = ed25519_add(&operand(0), &operand(1), &operand(2), &operand(3));
+                [x, y].concat()
+            }
+            Self::Sha512Compress => {
+                let words = |words: &[u32]| -> Vec<u64> {
+                    words
+                        .chunks_exact(2)
+                        .map(|pair| pair[0] as u64 | (pair[1] as u64) << 32)
+                        .collect()
+                };
+                let state = words(&input[..16]);
+                let block = words(&input[16..]);
+                let (state, schedule) =
+                    sha512_compress(&state.try_into().unwrap(), &block.try_into().unwrap());
+                state
+                    .iter()
+                    .chain(schedule[16..].iter())
+                    .flat_map(|&word| [word as u32, (word >> 32) as u32])
+                    .collect()
+            }
+        }
+    }
+}
+
+fn to_u256(limbs: &[u32]) -> U256 {
+    limbs.try_into().unwrap()
+}
+
+/// `a * b mod p`. Panics if `p < 2`.
+pub fn mul_mod(a: &U256, b: &U256, p: &U256) -> U256 {
+    let (_, r) = div_rem(&mul_wide(a, b), p);
+    to_u256(&r)
+}
+
+/// `a + b mod p`. Panics if `p < 2`.
+pub fn add_mod(a: &U256, b: &U256, p: &U256) -> U256 {
+    let mut sum = vec![0u32; U256_LIMBS + 1];
+    let mut carry = 0u64;
+    for i in 0..U256_LIMBS {
+        let limb = a[i] as u64 + b[i] as u64 + carry;
+        sum[i] = limb as u32;
+        carry = limb >> 32;
+    }
+    sum[U256_LIMBS] = carry as u32;
+    let (_, r) = div_rem(&sum, p);
+    to_u256(&r)
+}
+
+/// `a - b mod p`. Panics if `p < 2`.
+pub fn sub_mod(a: &U256, b: &U256, p: &U256) -> U256 {
+    sub_reduced(&reduce(a, p), &reduce(b, p), p)
+}
+
+/// `a^-1 mod p` for a prime `p > 2`, by the binary extended Euclidean algorithm.
+/// Returns zero if `a = 0 mod p`.
+pub fn inv_mod(a: &U256, p: &U256) -> U256 {
+    assert!(p[0] & 1 == 1, "modulus must be odd");
+    let mut u = reduce(a, p);
+    if u == [0; U256_LIMBS] {
+        return u;
+    }
+    // Invariants: x1 * a = u and x2 * a = v (mod p)
+    let mut v = *p;
+    let (mut x1, mut x2) = (one(), [0; U256_LIMBS]);
+    let halve = |x: &mut U256, coeff: &mut U256| {
+        while x[0] & 1 == 0 {
+            shift_right(x, false);
+            let carry = if coeff[0] & 1 == 1 {
+                add_assign(coeff, p)
+            } else {
+                false
+            };
+            shift_right(coeff, carry);
+        }
+    };
+    while u != one() && v != one() {
+        halve(&mut u, &mut x1);
+        halve(&mut v, &mut x2);
+        if compare(&u, &v) != Ordering::Less {
+            sub_assign(&mut u, &v);
+            x1 = sub_reduced(&x1, &x2, p);
+        } else {
+            sub_assign(&mut v, &u);
+            x2 = sub_reduced(&x2, &x1, p);
+        }
+    }
+    if u == one() {
+        x1
+    } else {
+        x2
+    }
+}
+
+/// `x += y`, returning the carry.
+fn add_assign(x: &mut U256, y: &U256) -> bool {
+    let mut carry = 0u64;
+    for (x_i, y_i) in x.iter_mut().zip(y.iter()) {
+        let limb = *x_i as u64 + *y_i as u64 + carry;
+        *x_i = limb as u32;
+        carry = limb >> 32;
+    }
+    carry != 0
+}
+
+/// `x -= y`, returning the borrow.
+fn sub_assign(x: &mut U256, y: &U256) -> bool {
+    let mut borrow = 0i64;
+    for (x_i, y_i) in x.iter_mut().zip(y.iter()) {
+        let diff = *x_i as i64 - *y_i as i64 - borrow;
+        *x_i = diff as u32;
+        borrow = (diff < 0) as i64;
+    }
+    borrow != 0
+}
+
+/// Shifts `x` right by one bit, shifting `carry` into the most significant bit.
+fn shift_right(x: &mut U256, carry: bool) {
+    let mut carry = carry as u32;
+    for limb in x.iter_mut().rev() {
+        let shifted_out = *limb & 1;
+        *limb = (*limb >> 1) | (carry << 31);
+        carry = shifted_out;
+    }
+}
+
+/// `a - b mod p`, for `a, b < p`.
+fn sub_reduced(a: &U256, b: &U256, p: &U256) -> U256 {
+    let mut diff = *a;
+    if sub_assign(&mut diff, b) {
+        add_assign(&mut diff, p);
+    }
+    diff
+}
+
+fn one() -> U256 {
+    let mut one = [0; U256_LIMBS];
+    one[0] = 1;
+    one
+}
+
+fn reduce(a: &U256, p: &U256) -> U256 {
+    to_u256(&div_rem(a, p).1)
+}
+
+/// The sum of two affine secp256k1 points. Panics if `x1 = x2 mod p`.
+pub fn secp256k1_add(x1: &U256, y1: &U256, x2: &U256, y2: &U256) -> (U256, U256) {
+    secp256k1_chord(&secp256k1_add_slope(x1, y1, x2, y2), x1, y1, x2)
+}
+
+/// Twice an affine secp256k1 point. Panics if `y = 0 mod p`.
+pub fn secp256k1_double(x: &U256, y: &U256) -> (U256, U256) {
+    secp256k1_chord(&secp256k1_double_slope(x, y), x, y, x)
+}
+
+/// The slope `(y2 - y1) / (x2 - x1)` of the line through two points.
+pub fn secp256k1_add_slope(x1: &U256, y1: &U256, x2: &U256, y2: &U256) -> U256 {
+    let p = &SECP256K1_P;
+    let dx = sub_mod(x2, x1, p);
+    assert!(
+        dx != [0; U256_LIMBS],
+        "points must have distinct x-coordinates"
+    );
+    mul_mod(&sub_mod(y2, y1, p), &inv_mod(&dx, p), p)
+}
+
+/// The slope `3x^2 / 2y` of the tangent at a point.
+pub fn secp256k1_double_slope(x: &U256, y: &U256) -> U256 {
+    let p = &SECP256K1_P;
+    let two_y = add_mod(y, y, p);
+    assert!(two_y != [0; U256_LIMBS], "point must not have order 2");
+    let x_squared = mul_mod(x, x, p);
+    let three_x_squared = add_mod(&add_mod(&x_squared, &x_squared, p), &x_squared, p);
+    mul_mod(&three_x_squared, &inv_mod(&two_y, p), p)
+}
+
+/// The point `(x3, y3)` with `x3 = lambda^2 - x1 - x2` and `y3 = lambda * (x1 - x3) - y1`.
+fn secp256k1_chord(lambda: &U256, x1: &U256, y1: &U256, x2: &U256) -> (U256, U256) {
+    let p = &SECP256K1_P;
+    let x3 = sub_mod(&sub_mod(&mul_mod(lambda, lambda, p), x1, p), x2, p);
+    let y3 = sub_mod(&mul_mod(lambda, &sub_mod(x1, &x3, p), p), y1, p);
+    (x3, y3)
+}
+
+/// The sum of two Edwards25519 points,
+/// `((x1 y2 + y1 x2) / (1 + t), (y1 y2 + x1 x2) / (1 - t))` where `t = d x1 x2 y1 y2`.
+/// Panics if `t = ±1 mod p`, which cannot happen for points on the curve.
+pub fn ed25519_add(x1: &U256, y1: &U256, x2: &U256, y2: &U256) -> (U256, U256) {
+    let p = &ED25519_P;
+    let [y1_y2, _, t] = ed25519_add_hints(x1, y1, x2, y2);
+    let x1_x2 = mul_mod(x1, x2, p);
+    let (x_denominator, y_denominator) = (add_mod(&one(), &t, p), sub_mod(&one(), &t, p));
+    assert!(
+        x_denominator != [0; U256_LIMBS] && y_denominator != [0; U256_LIMBS],
+        "points must be on the curve"
+    );
+    let x_numerator = add_mod(&mul_mod(x1, y2, p), &mul_mod(y1, x2, p), p);
+    let y_numerator = add_mod(&y1_y2, &x1_x2, p);
+    (
+        mul_mod(&x_numerator, &inv_mod(&x_denominator, p), p),
+        mul_mod(&y_numerator, &inv_mod(&y_denominator, p), p),
+    )
+}
+
+/// The intermediate products `[y1 y2, d x1 x2, d x1 x2 y1 y2]` (mod `p`) of the
+/// Edwards25519 addition law.
+pub fn ed25519_add_hints(x1: &U256, y1: &U256, x2: &U256, y2: &U256) -> [U256; 3] {
+    let p = &ED25519_P;
+    let y1_y2 = mul_mod(y1, y2, p);
+    let d_x1_x2 = mul_mod(&ED25519_D, &mul_mod(x1, x2, p), p);
+    [y1_y2, d_x1_x2, mul_mod(&d_x1_x2, &y1_y2, p)]
+}
+
+/// The SHA-512 compression function, returning the new state and the message
+/// schedule.
+pub fn sha512_compress(state: &[u64; 8], block: &[u64; 16]) -> ([u64; 8], [u64; 80]) {
+    let mut w = [0u64; 80];
+    w[..16].copy_from_slice(block);
+    for t in 16..80 {
+        let s0 = w[t - 15].rotate_right(1) ^ w[t - 15].rotate_right(8) ^ (w[t - 15] >> 7);
+        let s1 = w[t - 2].rotate_right(19) ^ w[t - 2].rotate_right(61) ^ (w[t - 2] >> 6);
+        w[t] = s1
+            .wrapping_add(w[t - 7])
+            .wrapping_add(s0)
+            .wrapping_add(w[t - 16]);
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..80 {
+        let s1 = e.rotate_right(14) ^ e.rotate_right(18) ^ e.rotate_right(41);
+        let ch = (e & f) ^ (!e & g);
+        let t1 = h
+            .wrapping_add(s1)
+            .wrapping_add(ch)
+            .wrapping_add(SHA512_K[t])
+            .wrapping_add(w[t]);
+        let s0 = a.rotate_right(28) ^ a.rotate_right(34) ^ a.rotate_right(39);
+        let maj = (a & b) ^ (a & c) ^ (b & c);
+        let t2 = s0.wrapping_add(maj);
+        (h, g, f, e, d, c, b, a) = (g, f, e, d.wrapping_add(t1), c, b, a, t1.wrapping_add(t2));
+    }
+
+    let mut new_state = *state;
+    for (word, var) in new_state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(var);
+    }
+    (new_state, w)
+}
+
+/// The full product of two little-endian integers.
+pub fn mul_wide(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut product = vec![0u32; a.len() + b.len()];
+    for (i, &a_i) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &b_j) in b.iter().enumerate() {
+            let limb = product[i + j] as u64 + a_i as u64 * b_j as u64 + carry;
+            product[i + j] = limb as u32;
+            carry = limb >> 32;
+        }
+        product[i + b.len()] = carry as u32;
+    }
+    product
+}
+
+/// Divides the little-endian integer `n` by `d`, returning the quotient (with as
+/// many limbs as `n`) and remainder (with as many limbs as `d`). Panics if `d < 2`.
+pub fn div_rem(n: &[u32], d: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    assert!(
+        compare(d, &[1]) == Ordering::Greater,
+        "modulus must be greater than 1"
+    );
+    let mut quotient = vec![0u32; n.len()];
+    // One extra limb, since the remainder is shifted left before being reduced
+    let mut remainder = vec![0u32; d.len() + 1];
+    for bit in (0..32 * n.len()).rev() {
+        let mut carry = (n[bit / 32] >> (bit % 32)) & 1;
+        for limb in remainder.iter_mut() {
+            let shifted_out = *limb >> 31;
+            *limb = (*limb << 1) | carry;
+            carry = shifted_out;
+        }
+        if compare(&remainder, d) != Ordering::Less {
+            let mut borrow = 0i64;
+            for (i, limb) in remainder.iter_mut().enumerate() {
+                let diff = *limb as i64 - *d.get(i).unwrap_or(&0) as i64 - borrow;
+                *limb = diff as u32;
+                borrow = (diff < 0) as i64;
+            }
+            quotient[bit / 32] |= 1 << (bit % 32);
+        }
+    }
+    remainder.truncate(d.len());
+    (quotient, remainder)
+}
+
+/// Compares two little-endian integers, which may have different numbers of limbs.
+fn compare(a: &[u32], b: &[u32]) -> Ordering {
+    for i in (0..a.len().max(b.len())).rev() {
+        let a_i = a.get(i).copied().unwrap_or(0);
+        let b_i = b.get(i).copied().unwrap_or(0);
+        match a_i.cmp(&b_i) {
+            Ordering::Equal => {}
+            ordering => return ordering,
+        }
+    }
+    Ordering::Equal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u256(value: u128) -> U256 {
+        let mut limbs = [0; U256_LIMBS];
+        for (i, limb) in limbs.iter_mut().take(4).enumerate() {
+            *limb = (value >> (32 * i)) as u32;
+        }
+        limbs
+    }
+
+    #[test]
+    fn small_values() {
+        let p = u256(1_000_000_007);
+        assert_eq!(
+            mul_mod(&u256(123_456_789), &u256(987_654_321), &p),
+            u256(259_106_859)
+        );
+        assert_eq!(add_mod(&u256(999_999_999), &u256(10), &p), u256(2));
+        assert_eq!(
+            mul_mod(&u256(u64::MAX as u128), &u256(3), &u256(1 << 64)),
+            u256(u64::MAX as u128 - 2)
+        );
+    }
+
+    #[test]
+    fn modulus_minus_one_squared() {
+        // (r - 1)^2 = 1 mod r
+        let mut r_minus_one = BN254_FR_MODULUS;
+        r_minus_one[0] -= 1;
+        assert_eq!(
+            Precompile::Bn254FrMul.execute(&[r_minus_one, r_minus_one].concat()),
+            u256(1).to_vec()
+        );
+        // (r - 1) + (r - 1) = r - 2 mod r
+        let mut r_minus_two = BN254_FR_MODULUS;
+        r_minus_two[0] -= 2;
+        assert_eq!(
+            Precompile::Bn254FrAdd.execute(&[r_minus_one, r_minus_one].concat()),
+            r_minus_two.to_vec()
+        );
+    }
+
+    #[test]
+    fn unreduced_inputs() {
+        let max = [u32::MAX; U256_LIMBS];
+        let p = u256(97);
+        // Inputs need not be reduced, so this agrees with reducing them first
+        let expected = mul_mod(&max, &max, &p);
+        let max_mod_p = u256(max_mod(97) as u128);
+        assert_eq!(expected, mul_mod(&max_mod_p, &max_mod_p, &p));
+        assert_eq!(add_mod(&max, &max, &p), add_mod(&max_mod_p, &max_mod_p, &p));
+    }
+
+    fn max_mod(p: u64) -> u64 {
+        // (2^256 - 1) mod p, computed limb by limb
+        (0..U256_LIMBS).fold(0, |acc, _| ((acc << 32) + u32::MAX as u64) % p)
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be greater than 1")]
+    fn zero_modulus() {
+        Precompile::ModMul.execute(&[0; 24]);
+    }
+
+    #[test]
+    fn inverse() {
+        let a = u256(0x1234_5678_9abc_def0);
+        let a_inv = inv_mod(&a, &SECP256K1_N);
+        assert_eq!(mul_mod(&a, &a_inv, &SECP256K1_N), u256(1));
+        assert_eq!(sub_mod(&u256(1), &u256(3), &u256(7)), u256(5));
+    }
+
+    #[test]
+    fn secp256k1_points() {
+        // The generator G and 2G, 3G
+        let g = (
+            hex("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+            hex("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8"),
+        );
+        let g2 = (
+            hex("c6047f9441ed7d6d3045406e95c07cd85c778e4b8cef3ca7abac09b95c709ee5"),
+            hex("1ae168fea63dc339a3c58419466ceaeef7f632653266d0e1236431a950cfe52a"),
+        );
+        let g3 = (
+            hex("f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9"),
+            hex("388f7b0f632de8140fe337e62a37f3566500a99934c2231b6cb9fd7584b8e672"),
+        );
+        assert_eq!(
+            Precompile::Secp256k1Double.execute(&[g.0, g.1].concat()),
+            [g2.0, g2.1].concat()
+        );
+        assert_eq!(
+            Precompile::Secp256k1Add.execute(&[g.0, g.1, g2.0, g2.1].concat()),
+            [g3.0, g3.1].concat()
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "points must have distinct x-coordinates")]
+    fn secp256k1_add_same_x() {
+        Precompile::Secp256k1Add.execute(&[u256(1), u256(2), u256(1), u256(3)].concat());
+    }
+
+    #[test]
+    fn ed25519_points() {
+        // The base point B and 2B, 3B
+        let b = (
+            hex("216936d3cd6e53fec0a4e231fdd6dc5c692cc7609525a7b2c9562d608f25d51a"),
+            hex("6666666666666666666666666666666666666666666666666666666666666658"),
+        );
+        let b2 = (
+            hex("36ab384c9f5a046c3d043b7d1833e7ac080d8e4515d7a45f83c5a14e2843ce0e"),
+            hex("2260cdf3092329c21da25ee8c9a21f5697390f51643851560e5f46ae6af8a3c9"),
+        );
+        let b3 = (
+            hex("67ae9c4a22928f491ff4ae743edac83a6343981981624886ac62485fd3f8e25c"),
+            hex("1267b1d177ee69aba126a18e60269ef79f16ec176724030402c3684878f5b4d4"),
+        );
+        assert_eq!(
+            Precompile::Ed25519Add.execute(&[b.0, b.1, b.0, b.1].concat()),
+            [b2.0, b2.1].concat()
+        );
+        assert_eq!(
+            Precompile::Ed25519Add.execute(&[b.0, b.1, b2.0, b2.1].concat()),
+            [b3.0, b3.1].concat()
+        );
+        // The identity (0, 1)
+        assert_eq!(
+            Precompile::Ed25519Add.execute(&[b.0, b.1, u256(0), u256(1)].concat()),
+            [b.0, b.1].concat()
+        );
+    }
+
+    #[test]
+    fn sha512_abc() {
+        // The initial state, and "abc" padded to a single block
+        let state: [u64; 8] = [
+            0x6a09e667f3bcc908,
+            0xbb67ae8584caa73b,
+            0x3c6ef372fe94f82b,
+            0xa54ff53a5f1d36f1,
+            0x510e527fade682d1,
+            0x9b05688c2b3e6c1f,
+            0x1f83d9abfb41bd6b,
+            0x5be0cd19137e2179,
+        ];
+        let mut block = [0u64; 16];
+        block[0] = 0x6162638000000000;
+        block[15] = 24;
+        let input: Vec<u32> = state
+            .iter()
+            .chain(block.iter())
+            .flat_map(|&word| [word as u32, (word >> 32) as u32])
+            .collect();
+        let output = Precompile::Sha512Compress.execute(&input);
+        assert_eq!(output.len(), 144);
+        let digest: Vec<u64> = output[..16]
+            .chunks_exact(2)
+            .map(|pair| pair[0] as u64 | (pair[1] as u64) << 32)
+            .collect();
+        assert_eq!(
+            digest,
+            [
+                0xddaf35a193617aba,
+                0xcc417349ae204131,
+                0x12e6fa4e89a97ea2,
+                0x0a9eeee64b55d39a,
+                0x2192992a274fc1a8,
+                0x36ba3c23a3feebbd,
+                0x454d4423643ce80e,
+                0x2a9ac94fa54ca49f,
+            ]
+        );
+    }
+
+    /// Parses a big-endian hex string.
+    fn hex(s: &str) -> U256 {
+        let mut limbs = [0; U256_LIMBS];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let end = s.len() - 8 * i;
+            *limb = u32::from_str_radix(&s[end - 8..end], 16).unwrap();
+        }
+        limbs
+    }
+
+    #[test]
+    fn ids() {
+        assert_eq!(Precompile::from_id(2), Some(Precompile::Bn254FrMul));
+        assert_eq!(
+            Precompile::from_id(Precompile::Bn254FqAdd.id()),
+            Some(Precompile::Bn254FqAdd)
+        );
+        assert_eq!(Precompile::from_id(0x7f), None);
+    }
+}
@@ -5,6 +5,7 @@ use alloc::{
 };
 use core::str::FromStr;
 
+use crate::blob::{blob_directory, Blob, BLOB_DIRECTORY_SIZE};
 use crate::constants::{MEMORY_OPS_PER_INSTRUCTION, RAM_START_ADDRESS, REGISTER_COUNT};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use serde::{Deserialize, Serialize};
@@ -17,7 +18,9 @@ pub struct RVTraceRow {
     pub register_state: RegisterState,
     pub memory_state: Option<MemoryState>,
     pub advice_value: Option<u64>,
-    pub precompile_input: Option<[u32; 16]>,
+    /// The words a `PRECOMPILE` instruction read from the address in `rs1`.
+    pub precompile_input: Option<Vec<u32>>,
+    /// The address a `PRECOMPILE` instruction wrote its output words to.
     pub precompile_output_address: Option<u64>,
 }
 
@@ -109,9 +112,14 @@ impl From<&RVTraceRow> for [MemoryOp; MEMORY_OPS_PER_INSTRUCTION] {
             | RV32IM::DIV
             | RV32IM::DIVU
             | RV32IM::REM
-            | RV32IM::REMU => [rs1_read(), rs2_read(), rd_write(), MemoryOp::noop_read()],
+            | RV32IM::REMU
+            | RV32IM::CUSTOM_0
+            | RV32IM::CUSTOM_1 => [rs1_read(), rs2_read(), rd_write(), MemoryOp::noop_read()],
 
-            RV32IM::LUI | RV32IM::AUIPC | RV32IM::VIRTUAL_ADVICE => [
+            RV32IM::LUI
+            | RV32IM::AUIPC
+            | RV32IM::VIRTUAL_ADVICE
+            | RV32IM::VIRTUAL_PRECOMPILE_ADVICE => [
                 MemoryOp::noop_read(),
                 MemoryOp::noop_read(),
                 rd_write(),
@@ -143,7 +151,7 @@ impl From<&RVTraceRow> for [MemoryOp; MEMORY_OPS_PER_INSTRUCTION] {
                 MemoryOp::noop_read(),
             ],
 
-            RV32IM::LW => [
+            RV32IM::LW | RV32IM::VIRTUAL_PRECOMPILE_LW => [
                 rs1_read(),
                 MemoryOp::noop_read(),
                 rd_write(),
@@ -156,7 +164,7 @@ impl From<&RVTraceRow> for [MemoryOp; MEMORY_OPS_PER_INSTRUCTION] {
                 MemoryOp::noop_read(),
             ],
 
-            RV32IM::SB | RV32IM::SH | RV32IM::SW => [
+            RV32IM::SB | RV32IM::SH | RV32IM::SW | RV32IM::VIRTUAL_PRECOMPILE_SW => [
                 rs1_read(),
                 rs2_read(),
                 MemoryOp::noop_write(),
@@ -246,6 +254,8 @@ pub enum CircuitFlags {
     Assert,
     /// Used in virtual sequences; the program counter should be the same for the full sequence.
     DoNotUpdatePC,
+    /// 1 if the instruction is part of the virtual sequence of a `PRECOMPILE` instruction.
+    Precompile,
 }
 pub const NUM_CIRCUIT_FLAGS: usize = CircuitFlags::COUNT;
 
@@ -275,17 +285,19 @@ impl ELFInstruction {
             | RV32IM::JALR
             | RV32IM::SW
             | RV32IM::LW
+            | RV32IM::VIRTUAL_PRECOMPILE_SW
+            | RV32IM::VIRTUAL_PRECOMPILE_LW
             | RV32IM::VIRTUAL_ASSERT_HALFWORD_ALIGNMENT,
         );
 
         flags[CircuitFlags::Load as usize] = matches!(
             self.opcode,
-            RV32IM::LW,
+            RV32IM::LW | RV32IM::VIRTUAL_PRECOMPILE_LW,
         );
 
         flags[CircuitFlags::Store as usize] = matches!(
             self.opcode,
-            RV32IM::SW,
+            RV32IM::SW | RV32IM::VIRTUAL_PRECOMPILE_SW,
         );
 
         flags[CircuitFlags::Jump as usize] = matches!(
@@ -303,6 +315,8 @@ impl ELFInstruction {
             self.opcode,
             RV32IM::SW
             | RV32IM::LW
+            | RV32IM::VIRTUAL_PRECOMPILE_SW
+            | RV32IM::VIRTUAL_PRECOMPILE_LW
             | RV32IM::BEQ
             | RV32IM::BNE
             | RV32IM::BLT
@@ -348,7 +362,9 @@ impl ELFInstruction {
             | RV32IM::VIRTUAL_ASSERT_LTE
             | RV32IM::VIRTUAL_ASSERT_VALID_SIGNED_REMAINDER
             | RV32IM::VIRTUAL_ASSERT_VALID_UNSIGNED_REMAINDER
-            | RV32IM::VIRTUAL_ASSERT_VALID_DIV0,
+            | RV32IM::VIRTUAL_ASSERT_VALID_DIV0
+            | RV32IM::CUSTOM_0
+            | RV32IM::CUSTOM_1,
         );
 
         flags[CircuitFlags::Virtual as usize] = self.virtual_sequence_remaining.is_some();
@@ -370,6 +386,13 @@ impl ELFInstruction {
             None => false
         };
 
+        flags[CircuitFlags::Precompile as usize] = matches!(
+            self.opcode,
+            RV32IM::VIRTUAL_PRECOMPILE_ADVICE
+            | RV32IM::VIRTUAL_PRECOMPILE_LW
+            | RV32IM::VIRTUAL_PRECOMPILE_SW,
+        );
+
         flags
     }
 }
@@ -378,6 +401,8 @@ impl ELFInstruction {
 pub struct RegisterState {
     pub rs1_val: Option<u64>,
     pub rs2_val: Option<u64>,
+    /// The third source operand of a fused multiply-add instruction (see `crate::float`).
+    pub rs3_val: Option<u64>,
     pub rd_post_val: Option<u64>,
 }
 
@@ -461,6 +486,65 @@ pub enum RV32IM {
     REMU,
     FENCE,
     UNIMPL,
+    // Custom instructions, see `custom_instruction_funct`
+    CUSTOM_0,
+    CUSTOM_1,
+    // See `crate::precompiles`
+    PRECOMPILE,
+    // RV32F, see `crate::float`
+    FLW,
+    FSW,
+    FADD_S,
+    FSUB_S,
+    FMUL_S,
+    FDIV_S,
+    FSQRT_S,
+    FMIN_S,
+    FMAX_S,
+    FSGNJ_S,
+    FSGNJN_S,
+    FSGNJX_S,
+    FEQ_S,
+    FLT_S,
+    FLE_S,
+    FCLASS_S,
+    FCVT_W_S,
+    FCVT_WU_S,
+    FCVT_S_W,
+    FCVT_S_WU,
+    FMV_X_W,
+    FMV_W_X,
+    FMADD_S,
+    FMSUB_S,
+    FNMSUB_S,
+    FNMADD_S,
+    // RV32D
+    FLD,
+    FSD,
+    FADD_D,
+    FSUB_D,
+    FMUL_D,
+    FDIV_D,
+    FSQRT_D,
+    FMIN_D,
+    FMAX_D,
+    FSGNJ_D,
+    FSGNJN_D,
+    FSGNJX_D,
+    FEQ_D,
+    FLT_D,
+    FLE_D,
+    FCLASS_D,
+    FCVT_W_D,
+    FCVT_WU_D,
+    FCVT_D_W,
+    FCVT_D_WU,
+    FCVT_S_D,
+    FCVT_D_S,
+    FMADD_D,
+    FMSUB_D,
+    FNMSUB_D,
+    FNMADD_D,
     // Virtual instructions
     VIRTUAL_MOVSIGN,
     VIRTUAL_MOVE,
@@ -475,6 +559,10 @@ pub enum RV32IM {
     VIRTUAL_POW2I,
     VIRTUAL_SRA_PAD,
     VIRTUAL_SRA_PADI,
+    // Virtual instructions used in the virtual sequence of a `PRECOMPILE`
+    VIRTUAL_PRECOMPILE_ADVICE,
+    VIRTUAL_PRECOMPILE_LW,
+    VIRTUAL_PRECOMPILE_SW,
 }
 
 impl FromStr for RV32IM {
@@ -532,11 +620,75 @@ impl FromStr for RV32IM {
             "REMU" => Ok(Self::REMU),
             "FENCE" => Ok(Self::FENCE),
             "UNIMPL" => Ok(Self::UNIMPL),
+            "CUSTOM_0" => Ok(Self::CUSTOM_0),
+            "CUSTOM_1" => Ok(Self::CUSTOM_1),
+            "PRECOMPILE" => Ok(Self::PRECOMPILE),
+            "FLW" => Ok(Self::FLW),
+            "FSW" => Ok(Self::FSW),
+            "FADD.S" => Ok(Self::FADD_S),
+            "FSUB.S" => Ok(Self::FSUB_S),
+            "FMUL.S" => Ok(Self::FMUL_S),
+            "FDIV.S" => Ok(Self::FDIV_S),
+            "FSQRT.S" => Ok(Self::FSQRT_S),
+            "FMIN.S" => Ok(Self::FMIN_S),
+            "FMAX.S" => Ok(Self::FMAX_S),
+            "FSGNJ.S" => Ok(Self::FSGNJ_S),
+            "FSGNJN.S" => Ok(Self::FSGNJN_S),
+            "FSGNJX.S" => Ok(Self::FSGNJX_S),
+            "FEQ.S" => Ok(Self::FEQ_S),
+            "FLT.S" => Ok(Self::FLT_S),
+            "FLE.S" => Ok(Self::FLE_S),
+            "FCLASS.S" => Ok(Self::FCLASS_S),
+            "FCVT.W.S" => Ok(Self::FCVT_W_S),
+            "FCVT.WU.S" => Ok(Self::FCVT_WU_S),
+            "FCVT.S.W" => Ok(Self::FCVT_S_W),
+            "FCVT.S.WU" => Ok(Self::FCVT_S_WU),
+            "FMV.X.W" => Ok(Self::FMV_X_W),
+            "FMV.W.X" => Ok(Self::FMV_W_X),
+            "FMADD.S" => Ok(Self::FMADD_S),
+            "FMSUB.S" => Ok(Self::FMSUB_S),
+            "FNMSUB.S" => Ok(Self::FNMSUB_S),
+            "FNMADD.S" => Ok(Self::FNMADD_S),
+            "FLD" => Ok(Self::FLD),
+            "FSD" => Ok(Self::FSD),
+            "FADD.D" => Ok(Self::FADD_D),
+            "FSUB.D" => Ok(Self::FSUB_D),
+            "FMUL.D" => Ok(Self::FMUL_D),
+            "FDIV.D" => Ok(Self::FDIV_D),
+            "FSQRT.D" => Ok(Self::FSQRT_D),
+            "FMIN.D" => Ok(Self::FMIN_D),
+            "FMAX.D" => Ok(Self::FMAX_D),
+            "FSGNJ.D" => Ok(Self::FSGNJ_D),
+            "FSGNJN.D" => Ok(Self::FSGNJN_D),
+            "FSGNJX.D" => Ok(Self::FSGNJX_D),
+            "FEQ.D" => Ok(Self::FEQ_D),
+            "FLT.D" => Ok(Self::FLT_D),
+            "FLE.D" => Ok(Self::FLE_D),
+            "FCLASS.D" => Ok(Self::FCLASS_D),
+            "FCVT.W.D" => Ok(Self::FCVT_W_D),
+            "FCVT.WU.D" => Ok(Self::FCVT_WU_D),
+            "FCVT.D.W" => Ok(Self::FCVT_D_W),
+            "FCVT.D.WU" => Ok(Self::FCVT_D_WU),
+            "FCVT.S.D" => Ok(Self::FCVT_S_D),
+            "FCVT.D.S" => Ok(Self::FCVT_D_S),
+            "FMADD.D" => Ok(Self::FMADD_D),
+            "FMSUB.D" => Ok(Self::FMSUB_D),
+            "FNMSUB.D" => Ok(Self::FNMSUB_D),
+            "FNMADD.D" => Ok(Self::FNMADD_D),
             _ => Err("Could not match instruction to RV32IM set.".to_string()),
         }
     }
 }
 
+/// `CUSTOM_0` and `CUSTOM_1` are R-type instructions in the custom-0 (`0b0001011`)
+/// and custom-1 (`0b0101011`) major opcodes, which the RISC-V spec reserves for
+/// non-standard extensions. Several custom instructions can share a major opcode,
+/// distinguished by `funct3` and `funct7`, so the `imm` of a custom `ELFInstruction`
+/// (which R-type instructions otherwise leave empty) holds this value.
+pub const fn custom_instruction_funct(funct3: u32, funct7: u32) -> i64 {
+    ((funct7 << 3) | funct3) as i64
+}
+
 #[allow(clippy::too_long_first_doc_paragraph)]
 /// Represented as a "peripheral device" in the RISC-V emulator, this captures
 /// all reads from the reserved memory address space for program inputs and all writes
@@ -548,8 +700,31 @@ impl FromStr for RV32IM {
 pub struct JoltDevice {
     pub inputs: Vec<u8>,
     pub outputs: Vec<u8>,
+    /// Whether the guest panicked, i.e. whether `panic_code` is nonzero. The verifier
+    /// rejects devices where the two disagree.
     pub panic: bool,
+    /// The value the guest wrote to the panic word (see [`PanicReason`]), or 0 if
+    /// the guest did not panic.
+    pub panic_code: u8,
     pub memory_layout: MemoryLayout,
+    /// The read-only blobs attached to the program (see `blob`). Their contents are
+    /// only held by the prover.
+    pub blobs: Vec<Blob>,
+}
+
+/// Reason codes written by the guest to the panic word of the IO region.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, FromRepr)]
+pub enum PanicReason {
+    /// The guest panicked, e.g. due to a failed assertion or `unwrap`.
+    Panic = 1,
+    /// The guest failed to deserialize its inputs.
+    InvalidInput = 2,
+    /// The guest's outputs did not fit in the output region.
+    OutputTooLarge = 3,
+    /// The function selector of a guest built with a dispatcher did not name
+    /// one of its provable functions.
+    UnknownFunction = 4,
 }
 
 impl JoltDevice {
@@ -558,13 +733,30 @@ impl JoltDevice {
             inputs: Vec::new(),
             outputs: Vec::new(),
             panic: false,
+            panic_code: 0,
             memory_layout: MemoryLayout::new(max_input_size, max_output_size),
+            blobs: Vec::new(),
+        }
+    }
+
+    pub fn with_layout(memory_layout: MemoryLayout) -> Self {
+        Self {
+            memory_layout,
+            ..Self::new(0, 0)
+        }
+    }
+
+    /// Returns why the guest panicked, if it did.
+    pub fn panic_reason(&self) -> Option<PanicReason> {
+        if !self.panic {
+            return None;
         }
+        PanicReason::from_repr(self.panic_code)
     }
 
     pub fn load(&self, address: u64) -> u8 {
         if self.is_panic(address) {
-            self.panic as u8
+            self.panic_code
         } else if self.is_termination(address) {
             0 // Termination bit should never be loaded after it is set
         } else if self.is_input(address) {
@@ -581,6 +773,11 @@ impl JoltDevice {
             } else {
                 self.outputs[internal_address]
             }
+        } else if self.is_blob_directory(address) {
+            let offset = (address - self.memory_layout.blob_directory) as usize;
+            blob_directory(&self.blobs)
+                .get(offset / 4)
+                .map_or(0, |word| word.to_le_bytes()[offset % 4])
         } else {
             0 // zero-padding
         }
@@ -590,7 +787,8 @@ impl JoltDevice {
         if address == self.memory_layout.panic {
             #[cfg(feature = "std")]
             println!("GUEST PANIC");
-            self.panic = true;
+            self.panic = value != 0;
+            self.panic_code = value;
             return;
         }
 
@@ -626,6 +824,10 @@ impl JoltDevice {
         address == self.memory_layout.termination
     }
 
+    pub fn is_blob_directory(&self, address: u64) -> bool {
+        self.memory_layout.is_blob_directory(address)
+    }
+
     fn convert_read_address(&self, address: u64) -> usize {
         (address - self.memory_layout.input_start) as usize
     }
@@ -647,17 +849,45 @@ pub struct MemoryLayout {
     pub output_end: u64,
     pub panic: u64,
     pub termination: u64,
+    /// The size of the blob region (see `blob`), or 0 if there is none, in which case
+    /// the blob addresses below are 0 too.
+    pub max_blob_size: u64,
+    /// The address of the blob directory, which follows the termination word.
+    pub blob_directory: u64,
+    /// The blob region, which is aligned to its size and follows RAM.
+    pub blob_start: u64,
+    pub blob_end: u64,
 }
 
 impl MemoryLayout {
-    pub fn new(mut max_input_size: u64, mut max_output_size: u64) -> Self {
+    pub fn new(max_input_size: u64, max_output_size: u64) -> Self {
+        Self::with_blobs(max_input_size, max_output_size, 0, 0)
+    }
+
+    /// A memory layout with a blob region of `max_blob_size` bytes (rounded up to a
+    /// power of two), placed after `memory_size` bytes of RAM.
+    pub fn with_blobs(
+        mut max_input_size: u64,
+        mut max_output_size: u64,
+        max_blob_size: u64,
+        memory_size: u64,
+    ) -> Self {
         // Must be word-aligned
         max_input_size = max_input_size.next_multiple_of(4);
         max_output_size = max_output_size.next_multiple_of(4);
+        let max_blob_size = match max_blob_size {
+            0 => 0,
+            size => size.next_power_of_two().max(4),
+        };
+        let blob_directory_size = if max_blob_size > 0 {
+            BLOB_DIRECTORY_SIZE
+        } else {
+            0
+        };
 
         // Adds 8 to account for panic bit and termination bit
         // (they each occupy one full 4-byte word)
-        let io_region_num_bytes = max_input_size + max_output_size + 8;
+        let io_region_num_bytes = max_input_size + max_output_size + 8 + blob_directory_size;
 
         // Padded so that the witness index corresponding to `RAM_START_ADDRESS`
         // is a power of 2
@@ -670,6 +900,23 @@ impl MemoryLayout {
         let panic = output_end;
         let termination = panic + 4;
 
+        // The blob region's witness indices must be aligned to its size. `input_start`
+        // is at witness index `REGISTER_COUNT`, so align its offset from there.
+        let (blob_directory, blob_start, blob_end) = if max_blob_size > 0 {
+            let input_start_offset = REGISTER_COUNT * 4;
+            let blob_start = (RAM_START_ADDRESS + memory_size - input_start + input_start_offset)
+                .next_multiple_of(max_blob_size)
+                + input_start
+                - input_start_offset;
+            assert!(
+                blob_start + max_blob_size <= 1 << 32,
+                "blob region does not fit in the address space"
+            );
+            (termination + 4, blob_start, blob_start + max_blob_size)
+        } else {
+            (0, 0, 0)
+        };
+
         Self {
             max_input_size,
             max_output_size,
@@ -679,6 +926,27 @@ impl MemoryLayout {
             output_end,
             panic,
             termination,
+            max_blob_size,
+            blob_directory,
+            blob_start,
+            blob_end,
         }
     }
+
+    /// Number of cells in the read-write memory checked by the prover (see the
+    /// witness layout in `constants`) for a program with `memory_size` bytes of
+    /// RAM. Bounds the size of the prover's memory polynomials.
+    pub fn memory_witness_size(&self, memory_size: u64) -> u64 {
+        let memory_end = (RAM_START_ADDRESS + memory_size).max(self.blob_end);
+        REGISTER_COUNT + (memory_end - self.input_start) / 4
+    }
+
+    pub fn is_blob_directory(&self, address: u64) -> bool {
+        self.max_blob_size > 0
+            && (self.blob_directory..self.blob_directory + BLOB_DIRECTORY_SIZE).contains(&address)
+    }
+
+    pub fn is_blob(&self, address: u64) -> bool {
+        (self.blob_start..self.blob_end).contains(&address)
+    }
 }
@@ -14,7 +14,7 @@ pub fn main() {
     let input = 41;
     let (output, proof) = prove_alloc(input);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_alloc(input, output, proof);
+    let is_valid = verify_alloc(input, output, proof).is_ok();
 
     println!("output: {output:?}");
     println!("valid: {is_valid}");
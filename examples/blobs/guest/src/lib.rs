@@ -0,0 +1,8 @@
+#![cfg_attr(feature = "guest", no_std)]
+
+/// Counts the occurrences of `byte` in the blob named "data".
+#[jolt::provable(max_blob_size = 65536)]
+fn count(byte: u8) -> u32 {
+    let data = jolt::blob("data").unwrap_or_default();
+    data.iter().filter(|b| **b == byte).count() as u32
+}
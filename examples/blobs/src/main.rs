@@ -0,0 +1,23 @@
+pub fn main() {
+    let data: Vec<u8> = (0..10000u32).map(|i| (i * i % 251) as u8).collect();
+
+    let target_dir = "/tmp/jolt-guest-targets";
+    let mut program = guest::compile_count(target_dir);
+    program.add_blob("data", data.clone());
+
+    let prover_preprocessing = guest::preprocess_prover_count(&program);
+    let verifier_preprocessing = guest::preprocess_verifier_count(&program);
+    let digest = verifier_preprocessing.blob_digest(&data);
+
+    let prove_count = guest::build_prover_count(program, prover_preprocessing);
+    let verify_count = guest::build_verifier_count(verifier_preprocessing);
+
+    let (output, proof) = prove_count(0);
+    let blob = proof.blobs().iter().find(|blob| blob.name == "data");
+    let digest_valid = blob.is_some_and(|blob| blob.digest == digest);
+    let is_valid = verify_count(0, output, proof).is_ok();
+
+    println!("output: {output}");
+    println!("blob digest valid: {digest_valid}");
+    println!("proof valid: {is_valid}");
+}
@@ -16,7 +16,7 @@ pub fn main() {
     let input = 19;
     let (output, proof) = prove_collatz_single(input);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_collatz_single(input, output, proof);
+    let is_valid = verify_collatz_single(input, output, proof).is_ok();
 
     println!("output: {output}");
     println!("valid: {is_valid}");
@@ -37,7 +37,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove_collatz_convergence(start, start + 100);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_collatz_convergence(start, start + 100, output, proof);
+    let is_valid = verify_collatz_convergence(start, start + 100, output, proof).is_ok();
 
     println!("output: {output}");
     println!("valid: {is_valid}");
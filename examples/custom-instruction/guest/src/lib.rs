@@ -0,0 +1,26 @@
+#![cfg_attr(feature = "guest", no_std)]
+
+/// `POPCNT rd, rs1, x0`: the custom-0 instruction with funct3 = funct7 = 0, which the
+/// host defines as `rd = popcount(rs1)`.
+#[cfg(target_arch = "riscv32")]
+fn popcnt(x: u32) -> u32 {
+    let count: u32;
+    unsafe {
+        core::arch::asm!(
+            ".insn r 0x0b, 0, 0, {rd}, {rs1}, x0",
+            rd = out(reg) count,
+            rs1 = in(reg) x,
+        );
+    }
+    count
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+fn popcnt(x: u32) -> u32 {
+    x.count_ones()
+}
+
+#[jolt::provable]
+fn popcounts(n: u32) -> u32 {
+    (0..n).map(popcnt).sum()
+}
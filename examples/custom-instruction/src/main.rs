@@ -0,0 +1,147 @@
+use std::marker::PhantomData;
+use std::time::Instant;
+
+use jolt::extension::{
+    chunk_and_concatenate_operands, CustomInstruction, ExtendedJoltVM, JoltField, JoltInstruction,
+    LassoSubtable, RV32IExtension, RngCore, StdRng, SubtableIndices, RV32IM,
+};
+use jolt::{Jolt, ProofTranscript, PCS};
+use serde::Serialize;
+
+/// table[x | y] = popcount(x)
+#[derive(Default)]
+pub struct PopcountSubtable<F: JoltField>(PhantomData<F>);
+
+impl<F: JoltField> PopcountSubtable<F> {
+    pub fn new() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F: JoltField> LassoSubtable<F> for PopcountSubtable<F> {
+    fn materialize(&self, m: usize) -> Vec<u32> {
+        let bits_per_operand = m.ilog2() / 2;
+        (0..m)
+            .map(|idx| (idx >> bits_per_operand).count_ones())
+            .collect()
+    }
+
+    fn evaluate_mle(&self, point: &[F]) -> F {
+        // \sum_i x_i
+        let b = point.len() / 2;
+        point[..b].iter().fold(F::zero(), |acc, x| acc + *x)
+    }
+}
+
+/// `POPCNT rd, rs1, rs2`: rd = popcount(rs1).
+#[derive(Copy, Clone, Default, Debug, Serialize, PartialEq)]
+pub struct POPCNTInstruction(pub u64, pub u64);
+
+impl JoltInstruction for POPCNTInstruction {
+    fn operands(&self) -> (u64, u64) {
+        (self.0, self.1)
+    }
+
+    fn combine_lookups<F: JoltField>(&self, vals: &[F], _: usize, _: usize) -> F {
+        vals.iter().fold(F::zero(), |acc, val| acc + *val)
+    }
+
+    fn g_poly_degree(&self, _: usize) -> usize {
+        1
+    }
+
+    fn subtables<F: JoltField>(
+        &self,
+        c: usize,
+        _: usize,
+    ) -> Vec<(Box<dyn LassoSubtable<F>>, SubtableIndices)> {
+        vec![(
+            Box::new(PopcountSubtable::new()),
+            SubtableIndices::from(0..c),
+        )]
+    }
+
+    fn to_indices(&self, c: usize, log_m: usize) -> Vec<usize> {
+        chunk_and_concatenate_operands(self.0, self.1, c, log_m)
+    }
+
+    fn materialize_entry(&self, index: u64) -> u64 {
+        // The bits of rs1 are the odd bits of the interleaved index
+        (index & 0xAAAA_AAAA_AAAA_AAAA).count_ones() as u64
+    }
+
+    fn lookup_entry(&self) -> u64 {
+        (self.0 as u32).count_ones() as u64
+    }
+
+    fn random(&self, rng: &mut StdRng) -> Self {
+        Self(rng.next_u32() as u64, rng.next_u32() as u64)
+    }
+
+    fn evaluate_mle<F: JoltField>(&self, r: &[F]) -> F {
+        r.iter().step_by(2).fold(F::zero(), |acc, x_i| acc + *x_i)
+    }
+}
+
+impl CustomInstruction for POPCNTInstruction {
+    const OPCODE: RV32IM = RV32IM::CUSTOM_0;
+    const FUNCT3: u32 = 0;
+    const FUNCT7: u32 = 0;
+
+    fn from_operands(rs1_val: u64, rs2_val: u64) -> Self {
+        Self(rs1_val, rs2_val)
+    }
+}
+
+jolt::rv32i_instruction_set!(PopcountInstructionSet, POPCNT: POPCNTInstruction);
+jolt::rv32i_subtable_enum!(PopcountSubtables, Popcount: PopcountSubtable<F>);
+
+pub struct Popcount;
+
+impl RV32IExtension for Popcount {
+    type InstructionSet = PopcountInstructionSet;
+    type Subtables<F: JoltField> = PopcountSubtables<F>;
+}
+
+type PopcountJoltVM = ExtendedJoltVM<Popcount>;
+
+pub fn main() {
+    let target_dir = "/tmp/jolt-guest-targets";
+    let mut program = guest::compile_popcounts(target_dir);
+    let (bytecode, memory_init) = program.decode();
+
+    let inputs = jolt::postcard::to_stdvec(&1000u32).unwrap();
+    let (io_device, trace) = program.trace_with::<PopcountInstructionSet>(&inputs);
+    let output: u32 = jolt::postcard::from_bytes(&io_device.outputs).unwrap();
+
+    let preprocessing = <PopcountJoltVM as Jolt<
+        jolt::F,
+        PCS,
+        { jolt::C },
+        { jolt::M },
+        ProofTranscript,
+    >>::prover_preprocess(
+        bytecode,
+        io_device.memory_layout.clone(),
+        memory_init,
+        1 << 20,
+        1 << 20,
+        1 << 20,
+    );
+
+    let now = Instant::now();
+    let (proof, commitments, verifier_io_device, _) =
+        PopcountJoltVM::prove(io_device, trace, preprocessing.clone());
+    println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
+    let is_valid = PopcountJoltVM::verify(
+        preprocessing.shared,
+        proof,
+        commitments,
+        verifier_io_device,
+        None,
+    )
+    .is_ok();
+
+    println!("output: {output}");
+    println!("valid: {is_valid}");
+}
@@ -0,0 +1,12 @@
+#![cfg_attr(feature = "guest", no_std)]
+
+use jolt::precompiles::ed25519;
+
+/// Verifies the Ed25519 signature `r || s` of `message` under `public_key`.
+#[jolt::provable(stack_size = 65536)]
+fn verify(public_key: [u8; 32], message: &[u8], r: [u8; 32], s: [u8; 32]) -> bool {
+    let mut signature = [0; 64];
+    signature[..32].copy_from_slice(&r);
+    signature[32..].copy_from_slice(&s);
+    ed25519::verify(&public_key, message, &signature)
+}
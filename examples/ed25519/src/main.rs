@@ -0,0 +1,35 @@
+use std::time::Instant;
+
+// Test 2 of RFC 8032, section 7.1.
+const PUBLIC_KEY: [u8; 32] = [
+    0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b, 0x7e, 0xbc,
+    0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1, 0x2a, 0xf4, 0x66, 0x0c,
+];
+const MESSAGE: [u8; 1] = [0x72];
+const R: [u8; 32] = [
+    0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64, 0x25, 0x40,
+    0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23, 0xeb, 0xdb, 0x69, 0xda,
+];
+const S: [u8; 32] = [
+    0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f, 0x36, 0x13, 0xd0, 0xf1, 0x1d, 0x8c,
+    0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee, 0xb0, 0x0d, 0x29, 0x16, 0x12, 0xbb, 0x0c, 0x00,
+];
+
+pub fn main() {
+    let target_dir = "/tmp/jolt-guest-targets";
+    let program = guest::compile_verify(target_dir);
+
+    let prover_preprocessing = guest::preprocess_prover_verify(&program);
+    let verifier_preprocessing = guest::preprocess_verifier_verify(&program);
+
+    let prove_verify = guest::build_prover_verify(program, prover_preprocessing);
+    let verify_verify = guest::build_verifier_verify(verifier_preprocessing);
+
+    let now = Instant::now();
+    let (output, proof) = prove_verify(PUBLIC_KEY, &MESSAGE, R, S);
+    println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
+    let is_valid = verify_verify(PUBLIC_KEY, &MESSAGE, R, S, output, proof).is_ok();
+
+    println!("signature valid: {output}");
+    println!("proof valid: {is_valid}");
+}
@@ -13,7 +13,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove();
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify(output, proof);
+    let is_valid = verify(output, proof).is_ok();
 
     println!(
         "outputs: {} {} {} {}",
@@ -13,7 +13,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove(12031293, 17, 92);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify(12031293, 17, 92, output, proof);
+    let is_valid = verify(12031293, 17, 92, output, proof).is_ok();
 
     println!("output: {output}");
     println!("valid: {is_valid}");
@@ -43,7 +43,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove_allocate_stack_with_increased_size();
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_allocate_stack_with_increased_size(output, proof);
+    let is_valid = verify_allocate_stack_with_increased_size(output, proof).is_ok();
 
     println!("output: {output}");
     println!("valid: {is_valid}");
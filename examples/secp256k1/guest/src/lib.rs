@@ -0,0 +1,13 @@
+#![cfg_attr(feature = "guest", no_std)]
+
+use jolt::precompiles::secp256k1::{ecdsa_recover, ecdsa_verify};
+use jolt::precompiles::U256;
+
+/// Recovers the signer of the message hash `z` from the signature `(r, s)` and checks
+/// the signature against the recovered key, returning the key as `(x, y)`.
+#[jolt::provable(stack_size = 65536)]
+fn ecrecover(z: U256, r: U256, s: U256, recovery_id: u8) -> Option<(U256, U256)> {
+    let public_key = ecdsa_recover(&z, &r, &s, recovery_id)?;
+    assert!(ecdsa_verify(&z, &r, &s, &public_key));
+    Some((public_key.x, public_key.y))
+}
@@ -0,0 +1,34 @@
+use std::time::Instant;
+
+// A signature of `Z` under the private key
+// 0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721.
+const Z: [u32; 8] = [
+    0x62add1bf, 0x62113d8a, 0x68e98915, 0x1a831d02, 0x94f41fc7, 0xe2ade1d6, 0xaa9b6ec1, 0xaf2bdbe1,
+];
+const R: [u32; 8] = [
+    0xfcd7a6c8, 0x6d970889, 0x45fb8aad, 0x783b8708, 0x83cc165c, 0x503a26ce, 0x2cb80eb6, 0x432310e3,
+];
+const S: [u32; 8] = [
+    0x02bfab69, 0x056ce89b, 0xd85863d4, 0xa6e05074, 0x93ed071c, 0x74a6305d, 0xb81c5488, 0x530128b6,
+];
+const RECOVERY_ID: u8 = 0;
+
+pub fn main() {
+    let target_dir = "/tmp/jolt-guest-targets";
+    let program = guest::compile_ecrecover(target_dir);
+
+    let prover_preprocessing = guest::preprocess_prover_ecrecover(&program);
+    let verifier_preprocessing = guest::preprocess_verifier_ecrecover(&program);
+
+    let prove_ecrecover = guest::build_prover_ecrecover(program, prover_preprocessing);
+    let verify_ecrecover = guest::build_verifier_ecrecover(verifier_preprocessing);
+
+    let now = Instant::now();
+    let (output, proof) = prove_ecrecover(Z, R, S, RECOVERY_ID);
+    println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
+    let is_valid = verify_ecrecover(Z, R, S, RECOVERY_ID, output, proof).is_ok();
+
+    let (x, y) = output.expect("invalid signature");
+    println!("public key: ({x:08x?}, {y:08x?})");
+    println!("valid: {is_valid}");
+}
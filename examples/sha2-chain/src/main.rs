@@ -16,7 +16,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove_sha2_chain(input, iters);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_sha2_chain(input, iters, output, proof);
+    let is_valid = verify_sha2_chain(input, iters, output, proof).is_ok();
 
     assert_eq!(output, native_output, "output mismatch");
     println!("output: {}", hex::encode(output));
@@ -14,7 +14,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove_sha2(input);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_sha2(input, output, proof);
+    let is_valid = verify_sha2(input, output, proof).is_ok();
 
     println!("output: {}", hex::encode(output));
     println!("valid: {is_valid}");
@@ -15,7 +15,7 @@ pub fn main() {
     let now = Instant::now();
     let (output, proof) = prove_sha3_chain(input, iters);
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
-    let is_valid = verify_sha3_chain(input, iters, output, proof);
+    let is_valid = verify_sha3_chain(input, iters, output, proof).is_ok();
 
     println!("output: {}", hex::encode(output));
     println!("valid: {is_valid}");
@@ -0,0 +1,12 @@
+#![cfg_attr(feature = "guest", no_std)]
+
+// Instruction lookups are decomposed into 8 chunks looked up in subtables of size 2^8,
+// rather than 4 chunks and subtables of size 2^16
+#[jolt::provable(c = 8, m = 256)]
+fn rotate(x: u32, n: u32) -> u32 {
+    let mut x = x;
+    for i in 0..n {
+        x = (x << (i % 32)) ^ (x >> (31 - i % 32)) ^ (x as i32 >> (i % 7)) as u32;
+    }
+    x
+}
@@ -0,0 +1,20 @@
+use std::time::Instant;
+
+pub fn main() {
+    let target_dir = "/tmp/jolt-guest-targets";
+    let program = guest::compile_rotate(target_dir);
+
+    let prover_preprocessing = guest::preprocess_prover_rotate(&program);
+    let verifier_preprocessing = guest::preprocess_verifier_rotate(&program);
+
+    let prove_rotate = guest::build_prover_rotate(program, prover_preprocessing);
+    let verify_rotate = guest::build_verifier_rotate(verifier_preprocessing);
+
+    let now = Instant::now();
+    let (output, proof) = prove_rotate(0x1234_5678, 100);
+    println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
+    let is_valid = verify_rotate(0x1234_5678, 100, output, proof).is_ok();
+
+    println!("output: {output:#x}");
+    println!("valid: {is_valid}");
+}
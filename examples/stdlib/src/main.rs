@@ -13,7 +13,7 @@ pub fn main() {
     let (output, proof) = prove(81);
     println!("int to string output: {output:?}");
 
-    let is_valid = verify(81, output, proof);
+    let is_valid = verify(81, output, proof).is_ok();
     println!("int to string valid: {is_valid}");
 
     let program = guest::compile_string_concat(target_dir);
@@ -29,6 +29,6 @@ pub fn main() {
     println!("Prover runtime: {} s", now.elapsed().as_secs_f64());
     println!("string concat output: {output:?}");
 
-    let is_valid = verify(20, output, proof);
+    let is_valid = verify(20, output, proof).is_ok();
     println!("string concat valid: {is_valid}");
 }
@@ -157,7 +157,8 @@ fn benchmark_verify<PCS, F, G, ProofTranscript>(
                     Some(&mut verifier_accumulator),
                     &mut transcript,
                     Some(&setup),
-                );
+                )
+                .unwrap();
 
                 assert_eq!(r_prover, r_verifier);
             });
@@ -0,0 +1,65 @@
+// A riscv-tests environment for Jolt. Unlike the standard `p` environment, it uses no
+// CSRs, traps or privilege modes, none of which Jolt supports: a test runs in place
+// from `_start` and reports its result by writing the end code to `tohost`, where
+// 1 means pass and `(n << 1) | 1` means test case `n` failed. Before that it sets
+// Jolt's termination bit, without which the program's execution does not verify.
+
+#ifndef _ENV_JOLT_H
+#define _ENV_JOLT_H
+
+#define RVTEST_RV64U                                                    \
+  .macro init;                                                          \
+  .endm
+
+#define RVTEST_RV32U                                                    \
+  .macro init;                                                          \
+  .endm
+
+#define TESTNUM gp
+
+#define RVTEST_CODE_BEGIN                                               \
+        .section .text.init;                                            \
+        .align  6;                                                      \
+        .globl _start;                                                  \
+_start:                                                                 \
+        init;
+
+#define RVTEST_CODE_END                                                 \
+1:      j 1b;
+
+#define JOLT_TERMINATE                                                  \
+        li t6, 1;                                                       \
+        la t5, jolt_termination;                                        \
+        sb t6, 0(t5);
+
+#define RVTEST_PASS                                                     \
+        fence;                                                          \
+        JOLT_TERMINATE                                                  \
+        li TESTNUM, 1;                                                  \
+        la t5, tohost;                                                  \
+        sw TESTNUM, 0(t5);                                              \
+1:      j 1b;
+
+#define RVTEST_FAIL                                                     \
+        fence;                                                          \
+1:      beqz TESTNUM, 1b;                                               \
+        sll TESTNUM, TESTNUM, 1;                                        \
+        or TESTNUM, TESTNUM, 1;                                         \
+        JOLT_TERMINATE                                                  \
+        la t5, tohost;                                                  \
+        sw TESTNUM, 0(t5);                                              \
+2:      j 2b;
+
+#define EXTRA_DATA
+
+#define RVTEST_DATA_BEGIN                                               \
+        EXTRA_DATA                                                      \
+        .pushsection .tohost,"aw",@progbits;                            \
+        .align 6; .global tohost; tohost: .dword 0; .size tohost, 8;    \
+        .align 6; .global fromhost; fromhost: .dword 0; .size fromhost, 8; \
+        .popsection;                                                    \
+        .align 4; .global begin_signature; begin_signature:
+
+#define RVTEST_DATA_END .align 4; .global end_signature; end_signature:
+
+#endif
@@ -0,0 +1,201 @@
+//! Cache of guest ELFs, consulted by [`Program::build`](super::Program::build) before
+//! invoking cargo.
+//!
+//! ELFs are stored under `<target_dir>/elf-cache/<elf digest>.elf`. Each build is also
+//! recorded under `<target_dir>/elf-cache/builds/<build key>`, where the build key is a
+//! digest of the build parameters (target, flags, linker script, ...). A record
+//! lists the digest of the ELF the build produced and the digest of every file it was
+//! built from: the sources cargo reports in the ELF's dep-info, plus the guest's
+//! manifest and lockfile. The cached ELF is reused as long as none of these changed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use sha3::{Digest, Sha3_256};
+
+use super::Program;
+
+/// Digest of the parameters of a build, see the module documentation.
+pub(super) fn build_key(parameters: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    for parameter in parameters {
+        hasher.update((parameter.len() as u64).to_le_bytes());
+        hasher.update(parameter);
+    }
+    hasher.finalize().into()
+}
+
+/// Returns the cached ELF of the build with the given key, if the files it was built
+/// from are unchanged.
+pub(super) fn lookup(target_dir: &str, build_key: &[u8; 32]) -> Option<PathBuf> {
+    let record = fs::read_to_string(record_path(target_dir, build_key)).ok()?;
+    let mut lines = record.lines();
+    let elf_digest = parse_digest(lines.next()?)?;
+    for line in lines {
+        let (digest, path) = line.split_once(' ')?;
+        if file_digest(Path::new(path)) != Some(parse_digest(digest)?) {
+            return None;
+        }
+    }
+
+    let elf = Program::cached_elf_path(target_dir, &elf_digest);
+    let contents = fs::read(&elf).ok()?;
+    (<[u8; 32]>::from(Sha3_256::digest(contents)) == elf_digest).then_some(elf)
+}
+
+/// Caches `elf` and records the build with the given key, reading the files it was
+/// built from from the dep-info file cargo wrote next to it.
+pub(super) fn record(target_dir: &str, build_key: &[u8; 32], elf: &Path, elf_digest: &[u8; 32]) {
+    let cached_elf = Program::cached_elf_path(target_dir, elf_digest);
+    fs::create_dir_all(cached_elf.parent().unwrap()).expect("could not create elf cache");
+    if !cached_elf.exists() {
+        fs::copy(elf, &cached_elf).expect("could not cache elf");
+    }
+
+    // Without a dep-info file, the build cannot be checked for changes later.
+    let Ok(dep_info) = fs::read_to_string(elf.with_extension("d")) else {
+        return;
+    };
+    let mut sources = parse_dep_info(&dep_info);
+    // The guest's own sources come first; its manifest and lockfile (which pins the
+    // versions of its dependencies) are found in the directories above them.
+    if let Some(guest_source) = sources.first().cloned() {
+        for file_name in ["Cargo.toml", "Cargo.lock"] {
+            if let Some(path) = guest_source
+                .ancestors()
+                .map(|dir| dir.join(file_name))
+                .find(|path| path.exists())
+            {
+                sources.push(path);
+            }
+        }
+    }
+
+    let mut record = hex(elf_digest);
+    for source in sources {
+        let Some(digest) = file_digest(&source) else {
+            return;
+        };
+        record.push_str(&format!("\n{} {}", hex(&digest), source.display()));
+    }
+    let record_path = record_path(target_dir, build_key);
+    fs::create_dir_all(record_path.parent().unwrap()).expect("could not create elf cache");
+    fs::write(record_path, record).expect("could not record build");
+}
+
+/// The dependencies listed in a Makefile-style dep-info file, as written by cargo for
+/// each artifact: `<artifact>: <dependency> <dependency> ...`, where spaces in paths
+/// are escaped with a backslash.
+fn parse_dep_info(dep_info: &str) -> Vec<PathBuf> {
+    let Some(line) = dep_info.lines().find(|line| !line.trim().is_empty()) else {
+        return vec![];
+    };
+    let mut paths = vec![];
+    let mut path = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_dependencies = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&' ') => path.push(chars.next().unwrap()),
+            ':' if !in_dependencies && chars.peek() == Some(&' ') => {
+                in_dependencies = true;
+                path.clear();
+            }
+            ' ' => {
+                if in_dependencies && !path.is_empty() {
+                    paths.push(PathBuf::from(std::mem::take(&mut path)));
+                }
+                path.clear();
+            }
+            _ => path.push(c),
+        }
+    }
+    if in_dependencies && !path.is_empty() {
+        paths.push(PathBuf::from(path));
+    }
+    paths
+}
+
+fn record_path(target_dir: &str, build_key: &[u8; 32]) -> PathBuf {
+    Path::new(target_dir)
+        .join("elf-cache")
+        .join("builds")
+        .join(hex(build_key))
+}
+
+fn file_digest(path: &Path) -> Option<[u8; 32]> {
+    fs::read(path)
+        .ok()
+        .map(|contents| Sha3_256::digest(contents).into())
+}
+
+pub(super) fn hex(bytes: &[u8; 32]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn parse_digest(hex: &str) -> Option<[u8; 32]> {
+    if hex.len() != 64 {
+        return None;
+    }
+    let mut digest = [0; 32];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(hex.get(2 * i..2 * i + 2)?, 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dep_info_paths() {
+        let dep_info = "/target/release/guest: /src/guest/src/lib.rs /src/my\\ guest/main.rs \
+                        /cargo/registry/serde-1.0/src/lib.rs\n\n/src/guest/src/lib.rs:\n";
+        assert_eq!(
+            parse_dep_info(dep_info),
+            [
+                PathBuf::from("/src/guest/src/lib.rs"),
+                PathBuf::from("/src/my guest/main.rs"),
+                PathBuf::from("/cargo/registry/serde-1.0/src/lib.rs"),
+            ]
+        );
+    }
+
+    #[test]
+    fn cached_build_invalidated_by_source_change() {
+        let dir = std::env::temp_dir().join(format!("jolt-build-cache-{}", std::process::id()));
+        let target_dir = dir.join("target");
+        let target_dir = target_dir.to_str().unwrap();
+        let source = dir.join("src/lib.rs");
+        let elf = dir.join("release/guest");
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::create_dir_all(elf.parent().unwrap()).unwrap();
+        fs::write(&source, "fn main() {}").unwrap();
+        fs::write(&elf, "elf").unwrap();
+        fs::write(
+            elf.with_extension("d"),
+            format!("{}: {}\n", elf.display(), source.display()),
+        )
+        .unwrap();
+
+        let key = build_key(&[b"guest", b"flags"]);
+        assert_eq!(lookup(target_dir, &key), None);
+        let elf_digest = Sha3_256::digest("elf").into();
+        record(target_dir, &key, &elf, &elf_digest);
+        assert_eq!(
+            lookup(target_dir, &key),
+            Some(Program::cached_elf_path(target_dir, &elf_digest))
+        );
+        assert_eq!(
+            lookup(target_dir, &build_key(&[b"guest", b"other flags"])),
+            None
+        );
+
+        fs::write(&source, "fn main() { loop {} }").unwrap();
+        assert_eq!(lookup(target_dir, &key), None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
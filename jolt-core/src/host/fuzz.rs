@@ -0,0 +1,655 @@
+//! Differential fuzzing of the tracer and of the virtual sequences. Random RV32IM
+//! programs are traced, and every instruction is checked against a simple reference
+//! interpreter. Its virtual sequence is then replayed, checking that every row is
+//! consistent with its lookup and with the registers and memory before it, and that
+//! the sequence leaves the registers and memory as the reference does.
+
+use std::collections::HashMap;
+
+use common::constants::{
+    DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MEMORY_SIZE, RAM_START_ADDRESS,
+    REGISTER_COUNT,
+};
+use common::rv_trace::MemoryLayout;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tracer::{MemoryState, RVTraceRow, RV32IM};
+
+use super::virtual_trace;
+use crate::jolt::instruction::JoltInstruction;
+use crate::jolt::vm::rv32i_vm::RV32I;
+
+/// The number of programs to fuzz, overridden by [`NUM_PROGRAMS_ENV_VAR`].
+const NUM_PROGRAMS: u64 = 64;
+/// Setting this environment variable fuzzes that many programs instead of
+/// [`NUM_PROGRAMS`], e.g. for a longer run.
+const NUM_PROGRAMS_ENV_VAR: &str = "JOLT_FUZZ_PROGRAMS";
+/// Setting this environment variable starts from that seed instead of 0, e.g. to
+/// reproduce a failing program.
+const FIRST_SEED_ENV_VAR: &str = "JOLT_FUZZ_SEED";
+const PROGRAM_LEN: usize = 256;
+/// Loads and stores are relative to `BASE`, which always holds `DATA_ADDRESS`.
+const BASE: u32 = 31;
+const DATA_ADDRESS: u32 = RAM_START_ADDRESS as u32 + 0x10000;
+/// The longest forward branch or jump, in instructions.
+const MAX_SKIP: usize = 8;
+
+const SPECIAL_VALUES: [u32; 10] = [
+    0,
+    1,
+    2,
+    0xffff_ffff,
+    0xffff_fffe,
+    0x8000_0000,
+    0x7fff_ffff,
+    0x8000_0001,
+    0xffff,
+    0x1_0000,
+];
+
+fn r_type(funct7: u32, rs2: u32, rs1: u32, funct3: u32, rd: u32) -> u32 {
+    (funct7 << 25) | (rs2 << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | 0x33
+}
+
+fn i_type(imm: i32, rs1: u32, funct3: u32, rd: u32, opcode: u32) -> u32 {
+    ((imm as u32 & 0xfff) << 20) | (rs1 << 15) | (funct3 << 12) | (rd << 7) | opcode
+}
+
+fn s_type(imm: i32, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 5) & 0x7f) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | ((imm & 0x1f) << 7)
+        | 0x23
+}
+
+fn b_type(imm: i32, rs2: u32, rs1: u32, funct3: u32) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 12) & 1) << 31)
+        | (((imm >> 5) & 0x3f) << 25)
+        | (rs2 << 20)
+        | (rs1 << 15)
+        | (funct3 << 12)
+        | (((imm >> 1) & 0xf) << 8)
+        | (((imm >> 11) & 1) << 7)
+        | 0x63
+}
+
+fn j_type(imm: i32, rd: u32) -> u32 {
+    let imm = imm as u32;
+    (((imm >> 20) & 1) << 31)
+        | (((imm >> 1) & 0x3ff) << 21)
+        | (((imm >> 11) & 1) << 20)
+        | (((imm >> 12) & 0xff) << 12)
+        | (rd << 7)
+        | 0x6f
+}
+
+/// Sets register `rd` to `value` with a LUI and an ADDI.
+fn load_immediate(rd: u32, value: u32) -> [u32; 2] {
+    let lo = ((value << 20) as i32) >> 20;
+    let hi = value.wrapping_sub(lo as u32);
+    [hi | (rd << 7) | 0x37, i_type(lo, rd, 0, rd, 0x13)]
+}
+
+/// The kinds of instruction in a random program, before branch targets are chosen.
+#[derive(Clone, Copy)]
+enum Kind {
+    Word(u32),
+    /// A branch with the given funct3 and source registers.
+    Branch(u32, u32, u32),
+    /// A JAL writing the given register.
+    Jal(u32),
+    /// An AUIPC into the first register followed by a JALR writing the second.
+    Jalr(u32, u32),
+    /// The JALR of the preceding `Jalr`.
+    Link,
+}
+
+fn random_operand(rng: &mut StdRng) -> u32 {
+    if rng.gen_bool(0.5) {
+        SPECIAL_VALUES[rng.gen_range(0, SPECIAL_VALUES.len())]
+    } else {
+        rng.gen()
+    }
+}
+
+/// Generates a random program: a prologue setting every register, `PROGRAM_LEN`
+/// random RV32IM instructions and an infinite loop. Branches and jumps only go
+/// forwards, so the program always terminates.
+fn random_program(rng: &mut StdRng) -> Vec<u32> {
+    let mut code = vec![];
+    for rd in 1..BASE {
+        code.extend(load_immediate(rd, random_operand(rng)));
+    }
+    code.extend(load_immediate(BASE, DATA_ADDRESS));
+
+    let register = |rng: &mut StdRng| rng.gen_range(0, BASE + 1);
+    let destination = |rng: &mut StdRng| rng.gen_range(0, BASE);
+    let mut kinds = vec![];
+    while kinds.len() < PROGRAM_LEN {
+        let rd = destination(rng);
+        let rs1 = register(rng);
+        let rs2 = register(rng);
+        let imm = ((rng.gen::<u32>() << 20) as i32) >> 20;
+        let kind = match rng.gen_range(0, 12) {
+            // ADD, SUB, SLL, SLT, SLTU, XOR, SRL, SRA, OR, AND
+            0 | 1 => {
+                let funct3 = rng.gen_range(0, 8);
+                let funct7 = if (funct3 == 0 || funct3 == 5) && rng.gen() {
+                    0x20
+                } else {
+                    0
+                };
+                Kind::Word(r_type(funct7, rs2, rs1, funct3, rd))
+            }
+            // MUL, MULH, MULHSU, MULHU, DIV, DIVU, REM, REMU
+            2 | 3 => Kind::Word(r_type(1, rs2, rs1, rng.gen_range(0, 8), rd)),
+            // ADDI, SLTI, SLTIU, XORI, ORI, ANDI, SLLI, SRLI, SRAI
+            4 | 5 => {
+                let funct3 = rng.gen_range(0, 8);
+                let imm = match funct3 {
+                    1 => imm & 0x1f,
+                    5 if rng.gen() => (imm & 0x1f) | 0x400,
+                    5 => imm & 0x1f,
+                    _ => imm,
+                };
+                Kind::Word(i_type(imm, rs1, funct3, rd, 0x13))
+            }
+            // LUI, AUIPC
+            6 => {
+                let opcode = if rng.gen() { 0x37 } else { 0x17 };
+                Kind::Word((rng.gen::<u32>() & 0xffff_f000) | (rd << 7) | opcode)
+            }
+            // LB, LH, LW, LBU, LHU
+            7 => {
+                let funct3 = [0, 1, 2, 4, 5][rng.gen_range(0, 5)];
+                let offset = imm & !((1 << (funct3 & 3)) - 1);
+                Kind::Word(i_type(offset, BASE, funct3, rd, 0x03))
+            }
+            // SB, SH, SW
+            8 => {
+                let funct3 = rng.gen_range(0, 3);
+                let offset = imm & !((1 << funct3) - 1);
+                Kind::Word(s_type(offset, rs2, BASE, funct3))
+            }
+            9 => Kind::Branch([0, 1, 4, 5, 6, 7][rng.gen_range(0, 6)], rs1, rs2),
+            10 if rng.gen() => Kind::Jal(rd),
+            10 => Kind::Jalr(rng.gen_range(1, BASE), rd),
+            // FENCE
+            _ => Kind::Word(0x0ff0_000f),
+        };
+        kinds.push(kind);
+        if let Kind::Jalr(..) = kind {
+            kinds.push(Kind::Link);
+        }
+    }
+    kinds.truncate(PROGRAM_LEN);
+    if let Some(Kind::Jalr(..)) = kinds.last() {
+        kinds.pop();
+    }
+
+    // The JALR of an AUIPC/JALR pair may not be a branch target
+    let target = |rng: &mut StdRng, from: usize| {
+        let target = (from + rng.gen_range(1, MAX_SKIP + 1)).min(kinds.len());
+        if target < kinds.len() && matches!(kinds[target], Kind::Link) {
+            target + 1
+        } else {
+            target
+        }
+    };
+    for (i, kind) in kinds.iter().enumerate() {
+        match *kind {
+            Kind::Word(word) => code.push(word),
+            Kind::Branch(funct3, rs1, rs2) => {
+                let offset = 4 * (target(rng, i) - i) as i32;
+                code.push(b_type(offset, rs2, rs1, funct3));
+            }
+            Kind::Jal(rd) => {
+                let offset = 4 * (target(rng, i) - i) as i32;
+                code.push(j_type(offset, rd));
+            }
+            Kind::Jalr(temp, rd) => {
+                let offset = 4 * (target(rng, i + 1) - i) as i32;
+                code.push((temp << 7) | 0x17);
+                code.push(i_type(offset, temp, 0, rd, 0x67));
+            }
+            Kind::Link => {}
+        }
+    }
+    // j .
+    code.push(j_type(0, 0));
+    code
+}
+
+/// A minimal ELF holding `code` in a `.text` section at `RAM_START_ADDRESS`.
+fn elf(code: &[u32]) -> Vec<u8> {
+    const HEADER_SIZE: u32 = 52;
+    const SECTION_HEADER_SIZE: u32 = 40;
+    let shstrtab = b"\0.text\0.shstrtab\0";
+    let text_offset = HEADER_SIZE;
+    let text_size = 4 * code.len() as u32;
+    let shstrtab_offset = text_offset + text_size;
+    let shoff = (shstrtab_offset + shstrtab.len() as u32).next_multiple_of(4);
+
+    let mut elf = vec![0x7f, b'E', b'L', b'F', 1, 1, 1];
+    elf.resize(16, 0);
+    let half = |elf: &mut Vec<u8>, value: u16| elf.extend(value.to_le_bytes());
+    let word = |elf: &mut Vec<u8>, value: u32| elf.extend(value.to_le_bytes());
+    half(&mut elf, 2); // e_type: EXEC
+    half(&mut elf, 0xf3); // e_machine: RISC-V
+    word(&mut elf, 1); // e_version
+    word(&mut elf, RAM_START_ADDRESS as u32); // e_entry
+    word(&mut elf, 0); // e_phoff
+    word(&mut elf, shoff);
+    word(&mut elf, 0); // e_flags
+    half(&mut elf, HEADER_SIZE as u16);
+    half(&mut elf, 32); // e_phentsize
+    half(&mut elf, 0); // e_phnum
+    half(&mut elf, SECTION_HEADER_SIZE as u16);
+    half(&mut elf, 3); // e_shnum
+    half(&mut elf, 2); // e_shstrndx
+
+    for instruction in code {
+        word(&mut elf, *instruction);
+    }
+    elf.extend(shstrtab);
+    elf.resize(shoff as usize, 0);
+
+    let mut section_header = |name, sh_type, flags, address, offset, size, align| {
+        for value in [name, sh_type, flags, address, offset, size, 0, 0, align, 0] {
+            word(&mut elf, value);
+        }
+    };
+    section_header(0, 0, 0, 0, 0, 0, 0);
+    // .text: PROGBITS, ALLOC | EXECINSTR
+    section_header(
+        1,
+        1,
+        0x6,
+        RAM_START_ADDRESS as u32,
+        text_offset,
+        text_size,
+        4,
+    );
+    // .shstrtab: STRTAB
+    section_header(7, 3, 0, 0, shstrtab_offset, shstrtab.len() as u32, 1);
+    elf
+}
+
+/// A reference RV32IM interpreter, independent of the tracer.
+#[derive(Clone)]
+struct Reference {
+    pc: u32,
+    registers: [u32; 32],
+    /// Memory words by (aligned) address.
+    memory: HashMap<u32, u32>,
+}
+
+impl Reference {
+    fn new() -> Self {
+        Self {
+            pc: RAM_START_ADDRESS as u32,
+            registers: [0; 32],
+            memory: HashMap::new(),
+        }
+    }
+
+    fn load(&self, address: u32) -> u32 {
+        self.memory.get(&(address & !3)).copied().unwrap_or(0)
+    }
+
+    fn step(&mut self, instruction: u32) {
+        let opcode = instruction & 0x7f;
+        let rd = (instruction >> 7) & 0x1f;
+        let funct3 = (instruction >> 12) & 0x7;
+        let x = self.registers[((instruction >> 15) & 0x1f) as usize];
+        let y = self.registers[((instruction >> 20) & 0x1f) as usize];
+        let funct7 = instruction >> 25;
+        let signed = instruction as i32;
+        let imm_i = (signed >> 20) as u32;
+        let imm_s = (((signed >> 25) << 5) as u32) | ((instruction >> 7) & 0x1f);
+        let imm_b = (((signed >> 31) << 12) as u32)
+            | (((instruction >> 7) & 1) << 11)
+            | (((instruction >> 25) & 0x3f) << 5)
+            | (((instruction >> 8) & 0xf) << 1);
+        let imm_j = (((signed >> 31) << 20) as u32)
+            | (instruction & 0xff000)
+            | (((instruction >> 20) & 1) << 11)
+            | (((instruction >> 21) & 0x3ff) << 1);
+
+        let pc = self.pc;
+        let mut next_pc = pc.wrapping_add(4);
+        let result = match opcode {
+            // LUI
+            0x37 => Some(instruction & 0xffff_f000),
+            // AUIPC
+            0x17 => Some(pc.wrapping_add(instruction & 0xffff_f000)),
+            // JAL
+            0x6f => {
+                next_pc = pc.wrapping_add(imm_j);
+                Some(pc.wrapping_add(4))
+            }
+            // JALR
+            0x67 => {
+                next_pc = x.wrapping_add(imm_i) & !1;
+                Some(pc.wrapping_add(4))
+            }
+            0x63 => {
+                let taken = match funct3 {
+                    0 => x == y,
+                    1 => x != y,
+                    4 => (x as i32) < (y as i32),
+                    5 => (x as i32) >= (y as i32),
+                    6 => x < y,
+                    7 => x >= y,
+                    _ => panic!("invalid branch {instruction:#010x}"),
+                };
+                if taken {
+                    next_pc = pc.wrapping_add(imm_b);
+                }
+                None
+            }
+            0x03 => {
+                let address = x.wrapping_add(imm_i);
+                let value = self.load(address) >> (8 * (address & 3));
+                Some(match funct3 {
+                    0 => value as u8 as i8 as u32,
+                    1 => value as u16 as i16 as u32,
+                    2 => value,
+                    4 => value as u8 as u32,
+                    5 => value as u16 as u32,
+                    _ => panic!("invalid load {instruction:#010x}"),
+                })
+            }
+            0x23 => {
+                let address = x.wrapping_add(imm_s);
+                let mask = match funct3 {
+                    0 => 0xff,
+                    1 => 0xffff,
+                    2 => 0xffff_ffff,
+                    _ => panic!("invalid store {instruction:#010x}"),
+                };
+                let shift = 8 * (address & 3);
+                let word = (self.load(address) & !(mask << shift)) | ((y & mask) << shift);
+                self.memory.insert(address & !3, word);
+                None
+            }
+            0x13 => Some(alu(funct3, funct3 == 5 && (funct7 & 0x20) != 0, x, imm_i)),
+            0x33 if funct7 == 1 => Some(mul_div(funct3, x, y)),
+            0x33 => Some(alu(funct3, funct7 == 0x20, x, y)),
+            // FENCE
+            0x0f => None,
+            _ => panic!("unsupported instruction {instruction:#010x}"),
+        };
+
+        if let (Some(result), true) = (result, rd != 0) {
+            self.registers[rd as usize] = result;
+        }
+        self.pc = next_pc;
+    }
+}
+
+/// The RV32I ALU; `alternate` selects SUB over ADD and SRA over SRL.
+fn alu(funct3: u32, alternate: bool, x: u32, y: u32) -> u32 {
+    match funct3 {
+        0 if alternate => x.wrapping_sub(y),
+        0 => x.wrapping_add(y),
+        1 => x << (y & 0x1f),
+        2 => ((x as i32) < (y as i32)) as u32,
+        3 => (x < y) as u32,
+        4 => x ^ y,
+        5 if alternate => ((x as i32) >> (y & 0x1f)) as u32,
+        5 => x >> (y & 0x1f),
+        6 => x | y,
+        7 => x & y,
+        _ => unreachable!(),
+    }
+}
+
+/// The M extension.
+fn mul_div(funct3: u32, x: u32, y: u32) -> u32 {
+    let (signed_x, signed_y) = (x as i32 as i64, y as i32 as i64);
+    match funct3 {
+        0 => x.wrapping_mul(y),
+        1 => ((signed_x * signed_y) >> 32) as u32,
+        2 => ((signed_x * y as i64) >> 32) as u32,
+        3 => ((x as u64 * y as u64) >> 32) as u32,
+        4 if y == 0 => u32::MAX,
+        4 => (x as i32).wrapping_div(y as i32) as u32,
+        5 if y == 0 => u32::MAX,
+        5 => x / y,
+        6 if y == 0 => x,
+        6 => (x as i32).wrapping_rem(y as i32) as u32,
+        7 if y == 0 => x,
+        7 => x % y,
+        _ => unreachable!(),
+    }
+}
+
+/// Replays (virtual) trace rows the way Jolt proves them: register and memory
+/// reads must match the state left by the previous rows, and results must match
+/// the instruction lookups.
+struct Replay {
+    registers: Vec<u64>,
+    memory: HashMap<u64, u64>,
+}
+
+impl Replay {
+    fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTER_COUNT as usize],
+            memory: HashMap::new(),
+        }
+    }
+
+    fn execute(&mut self, row: &RVTraceRow) {
+        let instruction = &row.instruction;
+        let state = &row.register_state;
+        if let Some(rs1) = instruction.rs1 {
+            assert_eq!(
+                state.rs1_val,
+                Some(self.registers[rs1 as usize]),
+                "rs1 of {row:?}"
+            );
+        }
+        if let Some(rs2) = instruction.rs2 {
+            assert_eq!(
+                state.rs2_val,
+                Some(self.registers[rs2 as usize]),
+                "rs2 of {row:?}"
+            );
+        }
+        let address =
+            || (state.rs1_val.unwrap() as i64 + instruction.imm.unwrap_or(0)) as u64 & 0xffff_ffff;
+
+        let result = match instruction.opcode {
+            RV32IM::LW => {
+                let Some(MemoryState::Read {
+                    address: read,
+                    value,
+                }) = row.memory_state
+                else {
+                    panic!("load without a memory read: {row:?}");
+                };
+                assert_eq!(read, address(), "load address of {row:?}");
+                assert_eq!(value, self.load(read), "loaded value of {row:?}");
+                Some(value)
+            }
+            RV32IM::SW => {
+                let Some(MemoryState::Write {
+                    address: written,
+                    pre_value,
+                    post_value,
+                }) = row.memory_state
+                else {
+                    panic!("store without a memory write: {row:?}");
+                };
+                assert_eq!(written, address(), "store address of {row:?}");
+                assert_eq!(
+                    pre_value,
+                    self.load(written),
+                    "overwritten value of {row:?}"
+                );
+                assert_eq!(Some(post_value), state.rs2_val, "stored value of {row:?}");
+                self.memory.insert(written, post_value);
+                None
+            }
+            RV32IM::JAL | RV32IM::JALR => Some(instruction.address + 4),
+            RV32IM::FENCE => None,
+            opcode => {
+                let lookup = RV32I::try_from(row).unwrap_or_else(|e| panic!("{e}: {row:?}"));
+                let output = lookup.lookup_entry();
+                if is_assertion(opcode) {
+                    assert_eq!(output, 1, "assertion failed: {row:?}");
+                }
+                instruction.rd.map(|_| output)
+            }
+        };
+
+        if let (Some(rd), Some(result)) = (instruction.rd, result) {
+            if rd != 0 {
+                assert_eq!(state.rd_post_val, Some(result), "rd of {row:?}");
+                self.registers[rd as usize] = result;
+            }
+        }
+    }
+
+    fn load(&self, address: u64) -> u64 {
+        self.memory.get(&address).copied().unwrap_or(0)
+    }
+}
+
+fn is_assertion(opcode: RV32IM) -> bool {
+    matches!(
+        opcode,
+        RV32IM::VIRTUAL_ASSERT_EQ
+            | RV32IM::VIRTUAL_ASSERT_LTE
+            | RV32IM::VIRTUAL_ASSERT_VALID_SIGNED_REMAINDER
+            | RV32IM::VIRTUAL_ASSERT_VALID_UNSIGNED_REMAINDER
+            | RV32IM::VIRTUAL_ASSERT_VALID_DIV0
+            | RV32IM::VIRTUAL_ASSERT_HALFWORD_ALIGNMENT
+    )
+}
+
+/// Checks the tracer's record of one instruction against the reference, which has
+/// not executed it yet.
+fn check_row(row: &RVTraceRow, instruction: u32, reference: &Reference) {
+    let register = |index: Option<u64>| reference.registers[index.unwrap() as usize] as u64;
+    let state = &row.register_state;
+    assert_eq!(
+        row.instruction.address, reference.pc as u64,
+        "pc of {row:?}"
+    );
+    if let Some(rs1_val) = state.rs1_val {
+        assert_eq!(rs1_val, register(row.instruction.rs1), "rs1 of {row:?}");
+    }
+    if let Some(rs2_val) = state.rs2_val {
+        assert_eq!(rs2_val, register(row.instruction.rs2), "rs2 of {row:?}");
+    }
+
+    let mut next = reference.clone();
+    next.step(instruction);
+    if let Some(rd_post_val) = state.rd_post_val {
+        assert_eq!(
+            rd_post_val,
+            next.registers[row.instruction.rd.unwrap() as usize] as u64,
+            "rd of {row:?} ({instruction:#010x})"
+        );
+    }
+    match row.memory_state {
+        Some(MemoryState::Read { address, value }) => {
+            assert_eq!(
+                value,
+                reference.load(address as u32) as u64,
+                "load of {row:?}"
+            );
+        }
+        Some(MemoryState::Write {
+            address,
+            pre_value,
+            post_value,
+        }) => {
+            assert_eq!(pre_value, reference.load(address as u32) as u64, "{row:?}");
+            assert_eq!(
+                post_value,
+                next.load(address as u32) as u64,
+                "store of {row:?}"
+            );
+        }
+        None => {}
+    }
+}
+
+fn fuzz_program(seed: u64) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let code = random_program(&mut rng);
+    let memory_layout = MemoryLayout::new(DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE);
+    // The programs use sp as a general purpose register, so there is no stack whose
+    // overflow the tracer could detect.
+    let (rows, _) = tracer::trace(
+        &elf(&code),
+        &[],
+        vec![],
+        &memory_layout,
+        DEFAULT_MEMORY_SIZE,
+        0,
+    )
+    .unwrap_or_else(|e| panic!("program {seed}: {e}"));
+
+    let mut reference = Reference::new();
+    let mut replay = Replay::new();
+    for row in rows {
+        let index = (reference.pc as u64 - RAM_START_ADDRESS) as usize / 4;
+        let instruction = code[index];
+        check_row(&row, instruction, &reference);
+        reference.step(instruction);
+
+        let accessed = match row.memory_state {
+            Some(MemoryState::Read { address, .. } | MemoryState::Write { address, .. }) => {
+                Some(address)
+            }
+            None => None,
+        };
+        for virtual_row in virtual_trace(row) {
+            replay.execute(&virtual_row);
+        }
+        for (register, value) in reference.registers.iter().enumerate() {
+            assert_eq!(
+                replay.registers[register], *value as u64,
+                "program {seed}: x{register} after {instruction:#010x} at index {index}"
+            );
+        }
+        if let Some(address) = accessed {
+            assert_eq!(
+                replay.load(address),
+                reference.load(address as u32) as u64,
+                "program {seed}: memory at {address:#x} after {instruction:#010x} at index {index}"
+            );
+        }
+    }
+    assert_eq!(
+        reference.pc as usize,
+        RAM_START_ADDRESS as usize + 4 * (code.len() - 1),
+        "program {seed} did not run to completion"
+    );
+}
+
+fn u64_from_env(name: &str, default: u64) -> u64 {
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .unwrap_or_else(|_| panic!("{name} = {value:?} is not a u64")),
+        Err(_) => default,
+    }
+}
+
+#[test]
+fn tracer_matches_reference() {
+    let first_seed = u64_from_env(FIRST_SEED_ENV_VAR, 0);
+    let num_programs = u64_from_env(NUM_PROGRAMS_ENV_VAR, NUM_PROGRAMS);
+    for seed in first_seed..first_seed + num_programs {
+        fuzz_program(seed);
+    }
+}
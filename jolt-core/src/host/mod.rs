@@ -3,54 +3,84 @@
 use core::str::FromStr;
 use std::{
     fs::{self, File},
-    io::{self, Read, Write},
-    path::PathBuf,
+    io::{self, Write},
+    path::{Path, PathBuf},
     process::Command,
 };
 
 use rayon::prelude::*;
+use sha3::{Digest, Sha3_256};
 
 use common::{
+    blob::Blob,
     constants::{
-        DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
+        DEFAULT_MAX_BLOB_SIZE, DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE,
+        DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
     },
-    rv_trace::JoltDevice,
+    rv_trace::{JoltDevice, MemoryLayout},
 };
-pub use tracer::ELFInstruction;
+pub use tracer::{ELFInstruction, ElfError, RVTraceRow, TraceError};
 
 use crate::{
     field::JoltField,
     jolt::{
         instruction::{
-            div::DIVInstruction, divu::DIVUInstruction, lb::LBInstruction, lbu::LBUInstruction,
-            lh::LHInstruction, lhu::LHUInstruction, mulh::MULHInstruction,
-            mulhsu::MULHSUInstruction, rem::REMInstruction, remu::REMUInstruction,
-            sb::SBInstruction, sh::SHInstruction, VirtualInstructionSequence,
+            div::DIVInstruction, divu::DIVUInstruction, float, lb::LBInstruction,
+            lbu::LBUInstruction, lh::LHInstruction, lhu::LHUInstruction, mulh::MULHInstruction,
+            mulhsu::MULHSUInstruction, narrow_shift, precompile, rem::REMInstruction,
+            remu::REMUInstruction, sb::SBInstruction, sh::SHInstruction, JoltInstructionSet,
+            VirtualInstructionSequence,
+        },
+        vm::{
+            bytecode::BytecodeRow,
+            rv32i_vm::{self, RV32I},
+            JoltTraceStep,
         },
-        vm::{bytecode::BytecodeRow, rv32i_vm::RV32I, JoltTraceStep},
     },
+    utils::math::Math,
 };
 
 use self::analyze::ProgramSummary;
 #[cfg(not(target_arch = "wasm32"))]
-use self::toolchain::{install_no_std_toolchain, install_toolchain};
+use self::toolchain::{install_no_std_toolchain, install_toolchain, no_std_toolchain};
 
 pub mod analyze;
+mod build_cache;
+#[cfg(test)]
+mod fuzz;
 #[cfg(not(target_arch = "wasm32"))]
 pub mod toolchain;
 
 pub const DEFAULT_TARGET_DIR: &str = "/tmp/jolt-guest-targets";
 
+/// Setting this environment variable (to anything but `0`) enables dev mode for new
+/// programs, see [`Program::set_dev_mode`].
+pub const DEV_MODE_ENV_VAR: &str = "JOLT_DEV_MODE";
+
 #[derive(Clone)]
 pub struct Program {
     guest: String,
     func: Option<String>,
+    /// Build every `#[jolt::provable]` function into a single ELF, see
+    /// [`Program::set_dispatch`].
+    dispatch: bool,
     memory_size: u64,
     stack_size: u64,
     max_input_size: u64,
     max_output_size: u64,
+    /// The size of the blob region, see [`Program::add_blob`].
+    max_blob_size: u64,
+    blobs: Vec<Blob>,
     std: bool,
+    /// Compile the guest with the F and D extensions, see [`Program::set_hard_float`].
+    hard_float: bool,
     pub elf: Option<PathBuf>,
+    /// Contents of a prebuilt ELF, see [`Program::from_elf_bytes`].
+    elf_contents: Option<Vec<u8>>,
+    /// Check executions instead of proving them, see [`Program::set_dev_mode`].
+    dev_mode: bool,
+    /// The subtable size of the VM proving traces, see [`Program::set_subtable_size`].
+    subtable_size: usize,
 }
 
 impl Program {
@@ -58,23 +88,111 @@ impl Program {
         Self {
             guest: guest.to_string(),
             func: None,
+            dispatch: false,
             memory_size: DEFAULT_MEMORY_SIZE,
             stack_size: DEFAULT_STACK_SIZE,
             max_input_size: DEFAULT_MAX_INPUT_SIZE,
             max_output_size: DEFAULT_MAX_OUTPUT_SIZE,
+            max_blob_size: DEFAULT_MAX_BLOB_SIZE,
+            blobs: vec![],
             std: false,
+            hard_float: false,
             elf: None,
+            elf_contents: None,
+            dev_mode: dev_mode_from_env(),
+            subtable_size: rv32i_vm::M,
         }
     }
 
+    /// Creates a program from a prebuilt RISC-V ELF (e.g. compiled from C or Zig),
+    /// bypassing `cargo`. The ELF must be linked to load at `RAM_START_ADDRESS`; its
+    /// sections followed by a stack of `stack_size` bytes must fit in `memory_size`
+    /// bytes of memory. Inputs and outputs are bounded by `memory_layout`.
+    pub fn from_elf_bytes(
+        elf: &[u8],
+        memory_layout: &MemoryLayout,
+        memory_size: u64,
+        stack_size: u64,
+    ) -> Result<Self, ElfError> {
+        tracer::validate_elf(elf, memory_size, stack_size)?;
+
+        Ok(Self {
+            guest: "prebuilt".to_string(),
+            func: None,
+            dispatch: false,
+            memory_size,
+            stack_size,
+            max_input_size: memory_layout.max_input_size,
+            max_output_size: memory_layout.max_output_size,
+            max_blob_size: memory_layout.max_blob_size,
+            blobs: vec![],
+            std: false,
+            hard_float: false,
+            elf: None,
+            elf_contents: Some(elf.to_vec()),
+            dev_mode: dev_mode_from_env(),
+            subtable_size: rv32i_vm::M,
+        })
+    }
+
+    /// Like [`Program::from_elf_bytes`], reading the ELF from `path`.
+    pub fn from_elf_path(
+        path: impl AsRef<Path>,
+        memory_layout: &MemoryLayout,
+        memory_size: u64,
+        stack_size: u64,
+    ) -> Result<Self, ElfError> {
+        let path = path.as_ref();
+        let elf = fs::read(path).map_err(|e| ElfError::Io(format!("{path:?}: {e}")))?;
+        let mut program = Self::from_elf_bytes(&elf, memory_layout, memory_size, stack_size)?;
+        program.elf = Some(path.to_path_buf());
+        Ok(program)
+    }
+
     pub fn set_std(&mut self, std: bool) {
         self.std = std;
     }
 
+    /// Compiles the guest with `+f,+d` so that `f32`/`f64` arithmetic uses the RV32F/D
+    /// instructions, which are proven as virtual sequences, instead of soft-float
+    /// library calls. The guest ABI is unchanged (floats are still passed in integer
+    /// registers).
+    pub fn set_hard_float(&mut self, hard_float: bool) {
+        self.hard_float = hard_float;
+    }
+
+    /// In dev mode, the SDK's `prove_*` functions trace the guest and check the witness
+    /// against Jolt's constraints without any cryptography (see `Jolt::prove_dev`),
+    /// returning a dev proof which verifiers reject. This is much faster than proving,
+    /// for iterating on a guest. Defaults to whether [`DEV_MODE_ENV_VAR`] is set.
+    pub fn set_dev_mode(&mut self, dev_mode: bool) {
+        self.dev_mode = dev_mode;
+    }
+
+    pub fn dev_mode(&self) -> bool {
+        self.dev_mode
+    }
+
+    /// Sets the subtable size `M` of the VM (e.g. `RV32IJoltVMWith<C, M>`) that will prove
+    /// this program's traces, which defaults to [`rv32i_vm::M`]. For subtables too small to
+    /// look up a shift by any amount, shifts are expanded into virtual sequences (see
+    /// `jolt::instruction::narrow_shift`), in both the trace and the bytecode.
+    pub fn set_subtable_size(&mut self, subtable_size: usize) {
+        self.subtable_size = subtable_size;
+    }
+
     pub fn set_func(&mut self, func: &str) {
         self.func = Some(func.to_string())
     }
 
+    /// Builds all of the guest's `#[jolt::provable]` functions into a single ELF
+    /// whose entry point dispatches on a function selector at the start of the
+    /// inputs (the postcard-encoded function name), so that one preprocessing can
+    /// be shared by every function. Overrides [`Program::set_func`].
+    pub fn set_dispatch(&mut self, dispatch: bool) {
+        self.dispatch = dispatch;
+    }
+
     pub fn set_memory_size(&mut self, len: u64) {
         self.memory_size = len;
     }
@@ -91,9 +209,37 @@ impl Program {
         self.max_output_size = size;
     }
 
+    /// Sets the size of the blob region, which must hold every blob attached with
+    /// [`Program::add_blob`], each rounded up to a power of two.
+    pub fn set_max_blob_size(&mut self, size: u64) {
+        self.max_blob_size = size;
+    }
+
+    /// Attaches a read-only blob, which the guest can read with `jolt::blob(name)`.
+    /// Unlike inputs, the contents of a blob are not part of the proof: the proof's
+    /// `JoltDevice` only holds the digest of the prover's commitment to it (see
+    /// `common::blob`). The blob region must have been sized with
+    /// [`Program::set_max_blob_size`].
+    pub fn add_blob(&mut self, name: &str, data: Vec<u8>) {
+        self.blobs.push(Blob::new(name, data));
+    }
+
+    /// The memory layout of the program's inputs, outputs and blobs.
+    pub fn memory_layout(&self) -> MemoryLayout {
+        MemoryLayout::with_blobs(
+            self.max_input_size,
+            self.max_output_size,
+            self.max_blob_size,
+            self.memory_size,
+        )
+    }
+
+    /// Builds the guest under `target_dir`, unless it was built from a prebuilt ELF.
+    /// Reuses the ELF cached by a previous build with the same parameters if none of
+    /// the files it was built from changed since (see `build_cache`).
     #[tracing::instrument(skip_all, name = "Program::build")]
     pub fn build(&mut self, target_dir: &str) {
-        if self.elf.is_none() {
+        if self.elf.is_none() && self.elf_contents.is_none() {
             #[cfg(not(target_arch = "wasm32"))]
             install_toolchain().unwrap();
             #[cfg(not(target_arch = "wasm32"))]
@@ -101,7 +247,12 @@ impl Program {
 
             self.save_linker();
 
-            let rust_flags = [
+            // Strip host-specific paths and fix codegen parameters so that the same
+            // sources and toolchain produce a byte-identical ELF on any machine.
+            let remap_path_prefixes = reproducible_path_prefixes(target_dir)
+                .into_iter()
+                .map(|(from, to)| format!("--remap-path-prefix={from}={to}"));
+            let mut rust_flags: Vec<String> = [
                 "-C",
                 &format!("link-arg=-T{}", self.linker_path()),
                 "-C",
@@ -112,45 +263,73 @@ impl Program {
                 "strip=symbols",
                 "-C",
                 "opt-level=z",
+                "-C",
+                "codegen-units=1",
+                "-C",
+                "link-arg=--build-id=none",
+            ]
+            .into_iter()
+            .map(String::from)
+            .chain(remap_path_prefixes)
+            .collect();
+            if self.hard_float {
+                rust_flags.extend(["-C".to_string(), "target-feature=+f,+d".to_string()]);
+            }
+
+            // Both targets are built with a pinned toolchain: std guests with Jolt's fork
+            // (see `guest-toolchain-tag`), no_std guests with `rust-toolchain.toml`'s.
+            let (toolchain, rustup_toolchain) = if self.std {
+                ("riscv32im-jolt-zkvm-elf", "riscv32im-jolt-zkvm-elf")
+            } else {
+                ("riscv32im-unknown-none-elf", no_std_toolchain())
+            };
+
+            let mut envs = vec![
+                ("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f")),
+                ("RUSTUP_TOOLCHAIN", rustup_toolchain.to_string()),
             ];
 
-            let toolchain = if self.std {
-                "riscv32im-jolt-zkvm-elf"
+            let target_suffix = if self.dispatch {
+                envs.push(("JOLT_DISPATCH", "1".to_string()));
+                "dispatch"
+            } else if let Some(func) = &self.func {
+                envs.push(("JOLT_FUNC_NAME", func.to_string()));
+                func.as_str()
             } else {
-                "riscv32im-unknown-none-elf"
+                ""
             };
 
-            let mut envs = vec![("CARGO_ENCODED_RUSTFLAGS", rust_flags.join("\x1f"))];
+            let target = format!("{}/{}-{}", target_dir, self.guest, target_suffix);
+            let args = [
+                "build",
+                "--release",
+                "--features",
+                "guest",
+                "-p",
+                &self.guest,
+                "--target-dir",
+                &target,
+                "--target",
+                toolchain,
+            ];
 
-            if self.std {
-                envs.push(("RUSTUP_TOOLCHAIN", toolchain.to_string()));
+            let linker_script = fs::read(self.linker_path()).expect("could not read linker file");
+            let toolchain_version = toolchain_version(&envs);
+            let mut build_parameters: Vec<&[u8]> =
+                vec![linker_script.as_slice(), toolchain_version.as_slice()];
+            build_parameters.extend(args.iter().map(|arg| arg.as_bytes()));
+            for (name, value) in &envs {
+                build_parameters.extend([name.as_bytes(), value.as_bytes()]);
             }
-
-            if let Some(func) = &self.func {
-                envs.push(("JOLT_FUNC_NAME", func.to_string()));
+            let build_key = build_cache::build_key(&build_parameters);
+            if let Some(elf) = build_cache::lookup(target_dir, &build_key) {
+                self.elf = Some(elf);
+                return;
             }
 
-            let target = format!(
-                "{}/{}-{}",
-                target_dir,
-                self.guest,
-                self.func.as_ref().unwrap_or(&"".to_string())
-            );
-
             let output = Command::new("cargo")
                 .envs(envs)
-                .args([
-                    "build",
-                    "--release",
-                    "--features",
-                    "guest",
-                    "-p",
-                    &self.guest,
-                    "--target-dir",
-                    &target,
-                    "--target",
-                    toolchain,
-                ])
+                .args(args)
                 .output()
                 .expect("failed to build guest");
 
@@ -159,64 +338,130 @@ impl Program {
                 panic!("failed to compile guest");
             }
 
-            let elf = format!("{}/{}/release/{}", target, toolchain, self.guest);
-            self.elf = Some(PathBuf::from_str(&elf).unwrap());
+            let elf =
+                PathBuf::from_str(&format!("{}/{}/release/{}", target, toolchain, self.guest))
+                    .unwrap();
+            self.elf = Some(elf);
+            build_cache::record(
+                target_dir,
+                &build_key,
+                self.elf.as_ref().unwrap(),
+                &self.elf_digest(),
+            );
         }
     }
 
+    /// SHA3-256 digest of the program's ELF, identifying the exact bytecode and
+    /// initial memory the program is preprocessed and proven with.
+    pub fn elf_digest(&self) -> [u8; 32] {
+        Sha3_256::digest(self.elf_contents()).into()
+    }
+
+    /// Path at which [`Program::build`] caches an ELF with the given digest under
+    /// `target_dir`. The cached ELF can be reloaded with [`Program::from_elf_path`].
+    pub fn cached_elf_path(target_dir: &str, elf_digest: &[u8; 32]) -> PathBuf {
+        Path::new(target_dir)
+            .join("elf-cache")
+            .join(format!("{}.elf", build_cache::hex(elf_digest)))
+    }
+
     pub fn decode(&self) -> (Vec<ELFInstruction>, Vec<(u64, u8)>) {
-        let elf = self.elf.as_ref().unwrap();
-        let mut elf_file =
-            File::open(elf).unwrap_or_else(|_| panic!("could not open elf file: {elf:?}"));
-        let mut elf_contents = Vec::new();
-        elf_file.read_to_end(&mut elf_contents).unwrap();
-        tracer::decode(&elf_contents)
+        tracer::decode(&self.elf_contents())
     }
 
-    // TODO(moodlezoup): Make this generic over InstructionSet
-    #[tracing::instrument(skip_all, name = "Program::trace")]
+    /// Traces the program on the given inputs, panicking if the guest overflows
+    /// its stack or runs out of memory. See [`Program::try_trace`].
     pub fn trace(&mut self, inputs: &[u8]) -> (JoltDevice, Vec<JoltTraceStep<RV32I>>) {
+        self.try_trace(inputs).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn try_trace(
+        &mut self,
+        inputs: &[u8],
+    ) -> Result<(JoltDevice, Vec<JoltTraceStep<RV32I>>), TraceError> {
+        self.try_trace_with::<RV32I>(inputs)
+    }
+
+    /// Traces the program for a VM with the given instruction set, e.g. one
+    /// extended with custom instructions (see `jolt::vm::extension`).
+    pub fn trace_with<InstructionSet>(
+        &mut self,
+        inputs: &[u8],
+    ) -> (JoltDevice, Vec<JoltTraceStep<InstructionSet>>)
+    where
+        InstructionSet: JoltInstructionSet + for<'a> TryFrom<&'a RVTraceRow>,
+    {
+        self.try_trace_with(inputs)
+            .unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[tracing::instrument(skip_all, name = "Program::trace")]
+    pub fn try_trace_with<InstructionSet>(
+        &mut self,
+        inputs: &[u8],
+    ) -> Result<(JoltDevice, Vec<JoltTraceStep<InstructionSet>>), TraceError>
+    where
+        InstructionSet: JoltInstructionSet + for<'a> TryFrom<&'a RVTraceRow>,
+    {
         self.build(DEFAULT_TARGET_DIR);
-        let elf = self.elf.clone().unwrap();
-        let (raw_trace, io_device) =
-            tracer::trace(&elf, inputs, self.max_input_size, self.max_output_size);
-
-        let trace: Vec<_> = raw_trace
-            .into_par_iter()
-            .flat_map(|row| match row.instruction.opcode {
-                tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::REM => REMInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::SH => SHInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::SB => SBInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LB => LBInstruction::<32>::virtual_trace(row),
-                tracer::RV32IM::LH => LHInstruction::<32>::virtual_trace(row),
-                _ => vec![row],
-            })
-            .map(|row| {
-                let instruction_lookup = RV32I::try_from(&row).ok();
-
-                JoltTraceStep {
-                    instruction_lookup,
-                    bytecode_row: BytecodeRow::from_instruction::<RV32I>(&row.instruction),
-                    memory_ops: (&row).into(),
-                    circuit_flags: row.instruction.to_circuit_flags(),
-                }
-            })
-            .collect();
+        InstructionSet::register_custom_instructions();
+        let (raw_trace, io_device) = tracer::trace(
+            &self.elf_contents(),
+            inputs,
+            self.blobs.clone(),
+            &self.memory_layout(),
+            self.memory_size,
+            self.stack_size,
+        )?;
+
+        Ok((io_device, expand_trace(raw_trace, self.subtable_size)))
+    }
 
-        (io_device, trace)
+    /// Traces a [riscv-tests](https://github.com/riscv-software-src/riscv-tests) program
+    /// built for Jolt's test environment. Returns the device, the trace and the end code
+    /// the program wrote to `tohost` (see [`tracer::trace_test`]).
+    pub fn trace_test(
+        &mut self,
+    ) -> Result<(JoltDevice, Vec<JoltTraceStep<RV32I>>, u32), TraceError> {
+        self.build(DEFAULT_TARGET_DIR);
+        let (raw_trace, io_device, end_code) = tracer::trace_test(
+            &self.elf_contents(),
+            &self.memory_layout(),
+            self.memory_size,
+            self.stack_size,
+        )?;
+        Ok((
+            io_device,
+            expand_trace(raw_trace, self.subtable_size),
+            end_code,
+        ))
+    }
+
+    /// Traces the program on each of the given inputs in parallel, e.g. to be
+    /// proven with `Jolt::prove_batch`. Panics like [`Program::trace`].
+    #[tracing::instrument(skip_all, name = "Program::trace_batch")]
+    pub fn trace_batch(
+        &mut self,
+        inputs: &[Vec<u8>],
+    ) -> Vec<(JoltDevice, Vec<JoltTraceStep<RV32I>>)> {
+        self.build(DEFAULT_TARGET_DIR);
+        inputs
+            .par_iter()
+            .map(|inputs| self.clone().trace(inputs))
+            .collect()
     }
 
     pub fn trace_analyze<F: JoltField>(mut self, inputs: &[u8]) -> ProgramSummary {
         self.build(DEFAULT_TARGET_DIR);
-        let elf = self.elf.as_ref().unwrap();
-        let (raw_trace, _) = tracer::trace(elf, inputs, self.max_input_size, self.max_output_size);
+        let (raw_trace, _) = tracer::trace(
+            &self.elf_contents(),
+            inputs,
+            self.blobs.clone(),
+            &self.memory_layout(),
+            self.memory_size,
+            self.stack_size,
+        )
+        .unwrap_or_else(|e| panic!("{e}"));
 
         let (bytecode, memory_init) = self.decode();
         let (io_device, processed_trace) = self.trace(inputs);
@@ -230,6 +475,14 @@ impl Program {
         }
     }
 
+    fn elf_contents(&self) -> Vec<u8> {
+        if let Some(elf_contents) = &self.elf_contents {
+            return elf_contents.clone();
+        }
+        let elf = self.elf.as_ref().unwrap();
+        fs::read(elf).unwrap_or_else(|_| panic!("could not open elf file: {elf:?}"))
+    }
+
     fn save_linker(&self) {
         let linker_path = PathBuf::from_str(&self.linker_path()).unwrap();
         if let Some(parent) = linker_path.parent() {
@@ -238,7 +491,11 @@ impl Program {
 
         let linker_script = LINKER_SCRIPT_TEMPLATE
             .replace("{MEMORY_SIZE}", &self.memory_size.to_string())
-            .replace("{STACK_SIZE}", &self.stack_size.to_string());
+            .replace("{STACK_SIZE}", &self.stack_size.to_string())
+            .replace(
+                "{BLOB_DIRECTORY}",
+                &self.memory_layout().blob_directory.to_string(),
+            );
 
         let mut file = File::create(linker_path).expect("could not create linker file");
         file.write_all(linker_script.as_bytes())
@@ -250,6 +507,99 @@ impl Program {
     }
 }
 
+/// Expands the virtual sequences of a raw trace, for a VM with subtables of size
+/// `subtable_size`, and converts it to Jolt trace steps.
+fn expand_trace<InstructionSet>(
+    raw_trace: Vec<RVTraceRow>,
+    subtable_size: usize,
+) -> Vec<JoltTraceStep<InstructionSet>>
+where
+    InstructionSet: JoltInstructionSet + for<'a> TryFrom<&'a RVTraceRow>,
+{
+    let log_M = subtable_size.log_2();
+    raw_trace
+        .into_par_iter()
+        .flat_map(|row| narrow_shift::expand_shifts(virtual_trace(row), log_M))
+        .map(|row| {
+            let instruction_lookup = InstructionSet::try_from(&row).ok();
+
+            JoltTraceStep {
+                instruction_lookup,
+                bytecode_row: BytecodeRow::from_instruction::<InstructionSet>(&row.instruction),
+                memory_ops: (&row).into(),
+                circuit_flags: row.instruction.to_circuit_flags(),
+                opcode: Some(row.instruction.opcode),
+            }
+        })
+        .collect()
+}
+
+/// Expands `row` into its virtual sequence, if its instruction has one.
+fn virtual_trace(row: RVTraceRow) -> Vec<RVTraceRow> {
+    match row.instruction.opcode {
+        tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::REM => REMInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::SH => SHInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::SB => SBInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::LB => LBInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::LH => LHInstruction::<32>::virtual_trace(row),
+        tracer::RV32IM::PRECOMPILE => precompile::virtual_trace(row),
+        opcode if common::float::is_float(opcode) => float::virtual_trace(&row),
+        _ => vec![row],
+    }
+}
+
+/// The output of `rustc -vV` and `cargo -V` for the toolchain selected by `envs`, so
+/// that upgrading the toolchain invalidates cached builds.
+fn toolchain_version(envs: &[(&str, String)]) -> Vec<u8> {
+    let mut version = vec![];
+    for (program, arg) in [("rustc", "-vV"), ("cargo", "-V")] {
+        let output = Command::new(program)
+            .envs(envs.iter().map(|(name, value)| (name, value)))
+            .arg(arg)
+            .output()
+            .unwrap_or_else(|e| panic!("failed to run {program}: {e}"));
+        if !output.status.success() {
+            io::stderr().write_all(&output.stderr).unwrap();
+            panic!("failed to get the {program} version");
+        }
+        version.extend(output.stdout);
+    }
+    version
+}
+
+fn dev_mode_from_env() -> bool {
+    std::env::var(DEV_MODE_ENV_VAR).is_ok_and(|value| value != "0")
+}
+
+/// Host-specific path prefixes that may be embedded in a guest ELF (e.g. in panic
+/// messages), paired with the machine-independent prefix they are remapped to.
+/// rustc applies the last matching remapping, so more specific prefixes come last.
+fn reproducible_path_prefixes(target_dir: &str) -> Vec<(String, &'static str)> {
+    let mut prefixes = vec![];
+    if let Ok(dir) = std::env::current_dir() {
+        prefixes.push((dir.display().to_string(), "/guest"));
+    }
+    if let Some(home) = std::env::var_os("CARGO_HOME") {
+        prefixes.push((home.to_string_lossy().to_string(), "/cargo"));
+    } else if let Some(home) = std::env::var_os("HOME") {
+        prefixes.push((format!("{}/.cargo", home.to_string_lossy()), "/cargo"));
+    }
+    if let Some(home) = std::env::var_os("RUSTUP_HOME") {
+        prefixes.push((home.to_string_lossy().to_string(), "/rustup"));
+    } else if let Some(home) = std::env::var_os("HOME") {
+        prefixes.push((format!("{}/.rustup", home.to_string_lossy()), "/rustup"));
+    }
+    prefixes.push((target_dir.to_string(), "/target"));
+    prefixes
+}
+
 const LINKER_SCRIPT_TEMPLATE: &str = r#"
 MEMORY {
   program (rwx) : ORIGIN = 0x80000000, LENGTH = {MEMORY_SIZE}
@@ -268,6 +618,12 @@ SECTIONS {
     *(.data)
   } > program
 
+  .jolt_entries : {
+    _JOLT_ENTRIES_START = .;
+    KEEP(*(.jolt_entries))
+    _JOLT_ENTRIES_END = .;
+  } > program
+
   .bss : {
     *(.bss)
   } > program
@@ -277,5 +633,6 @@ SECTIONS {
   _STACK_PTR = .;
   . = ALIGN(8);
   _HEAP_PTR = .;
+  _BLOB_DIRECTORY = {BLOB_DIRECTORY};
 }
 "#;
@@ -13,6 +13,7 @@ use reqwest::Client;
 use tokio::runtime::Runtime;
 
 const TOOLCHAIN_TAG: &str = include_str!("../../../guest-toolchain-tag");
+const RUST_TOOLCHAIN: &str = include_str!("../../../rust-toolchain.toml");
 const DOWNLOAD_RETRIES: usize = 5;
 const DELAY_BASE_MS: u64 = 500;
 
@@ -39,10 +40,30 @@ pub fn install_toolchain() -> Result<()> {
     Ok(())
 }
 
+/// The toolchain no_std guests are built with, pinned by the `channel` of the
+/// repository's `rust-toolchain.toml`.
+pub fn no_std_toolchain() -> &'static str {
+    RUST_TOOLCHAIN
+        .lines()
+        .find_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            (key.trim() == "channel").then(|| value.trim().trim_matches('"'))
+        })
+        .expect("rust-toolchain.toml does not specify a channel")
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn install_no_std_toolchain() -> Result<()> {
     std::process::Command::new("rustup")
-        .args(["target", "add", "riscv32im-unknown-none-elf"])
+        .args([
+            "toolchain",
+            "install",
+            no_std_toolchain(),
+            "--profile",
+            "minimal",
+            "--target",
+            "riscv32im-unknown-none-elf",
+        ])
         .output()?;
     Ok(())
 }
@@ -167,7 +188,13 @@ fn toolchain_url() -> String {
 #[cfg(not(target_arch = "wasm32"))]
 pub fn uninstall_no_std_toolchain() -> Result<()> {
     std::process::Command::new("rustup")
-        .args(["target", "remove", "riscv32im-unknown-none-elf"])
+        .args([
+            "target",
+            "remove",
+            "--toolchain",
+            no_std_toolchain(),
+            "riscv32im-unknown-none-elf",
+        ])
         .output()?;
 
     println!("\"riscv32im-unknown-none-elf\" toolchain uninstalled successfully");
@@ -229,3 +256,16 @@ fn jolt_dir() -> PathBuf {
 fn toolchain_tag_file() -> PathBuf {
     jolt_dir().join(".toolchaintag")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_std_toolchain_is_pinned() {
+        // A bare channel such as "nightly" would build guests with whatever is installed
+        let (channel, date) = no_std_toolchain().split_once('-').unwrap();
+        assert_eq!(channel, "nightly");
+        assert_eq!(date.len(), "yyyy-mm-dd".len());
+    }
+}
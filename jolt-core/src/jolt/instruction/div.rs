@@ -3,15 +3,17 @@ use tracer::{ELFInstruction, RVTraceRow, RegisterState, RV32IM};
 
 use super::VirtualInstructionSequence;
 use crate::jolt::instruction::{
-    add::ADDInstruction, beq::BEQInstruction, mul::MULInstruction,
-    virtual_advice::ADVICEInstruction, virtual_assert_valid_div0::AssertValidDiv0Instruction,
-    virtual_assert_valid_signed_remainder::AssertValidSignedRemainderInstruction, JoltInstruction,
+    add::ADDInstruction, and::ANDInstruction, beq::BEQInstruction, mul::MULInstruction,
+    slt::SLTInstruction, sltu::SLTUInstruction, virtual_advice::ADVICEInstruction,
+    virtual_assert_valid_div0::AssertValidDiv0Instruction,
+    virtual_assert_valid_signed_remainder::AssertValidSignedRemainderInstruction,
+    xor::XORInstruction, JoltInstruction,
 };
 /// Perform signed division and return the result
 pub struct DIVInstruction<const WORD_SIZE: usize>;
 
 impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_SIZE> {
-    const SEQUENCE_LENGTH: usize = 8;
+    const SEQUENCE_LENGTH: usize = 12;
 
     fn virtual_trace(trace_row: RVTraceRow) -> Vec<RVTraceRow> {
         assert_eq!(trace_row.instruction.opcode, RV32IM::DIV);
@@ -34,8 +36,8 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
                 if y == 0 {
                     (u32::MAX as u64, x)
                 } else {
-                    let mut quotient = x as i32 / y as i32;
-                    let mut remainder = x as i32 % y as i32;
+                    let mut quotient = (x as i32).wrapping_div(y as i32);
+                    let mut remainder = (x as i32).wrapping_rem(y as i32);
                     if (remainder < 0 && (y as i32) > 0) || (remainder > 0 && (y as i32) < 0) {
                         remainder += y as i32;
                         quotient -= 1;
@@ -47,8 +49,8 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
                 if y == 0 {
                     (u64::MAX, x)
                 } else {
-                    let mut quotient = x as i64 / y as i64;
-                    let mut remainder = x as i64 % y as i64;
+                    let mut quotient = (x as i64).wrapping_div(y as i64);
+                    let mut remainder = (x as i64).wrapping_rem(y as i64);
                     if (remainder < 0 && (y as i64) > 0) || (remainder > 0 && (y as i64) < 0) {
                         remainder += y as i64;
                         quotient -= 1;
@@ -73,6 +75,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(q),
             },
             memory_state: None,
@@ -95,6 +98,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(r),
             },
             memory_state: None,
@@ -118,6 +122,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(r),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -141,6 +146,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(y),
                 rs2_val: Some(q),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -163,6 +169,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(q),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(q_y),
             },
             memory_state: None,
@@ -185,6 +192,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(r),
+                rs3_val: None,
                 rd_post_val: Some(add_0),
             },
             memory_state: None,
@@ -207,6 +215,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(add_0),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -215,26 +224,27 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
             precompile_output_address: None,
         });
 
-        virtual_trace.push(RVTraceRow {
-            instruction: ELFInstruction {
-                address: trace_row.instruction.address,
-                opcode: RV32IM::VIRTUAL_MOVE,
-                rs1: v_q,
-                rs2: None,
-                rd: trace_row.instruction.rd,
-                imm: None,
-                virtual_sequence_remaining: Some(Self::SEQUENCE_LENGTH - virtual_trace.len() - 1),
-            },
-            register_state: RegisterState {
-                rs1_val: Some(q),
-                rs2_val: None,
-                rd_post_val: Some(q),
-            },
-            memory_state: None,
-            advice_value: None,
-            precompile_input: None,
-            precompile_output_address: None,
-        });
+        // The assertions above hold for the quotient rounded towards negative infinity,
+        // but RISC-V rounds towards zero
+        let adjustment = push_rounding_adjustment::<WORD_SIZE>(
+            &mut virtual_trace,
+            Self::SEQUENCE_LENGTH,
+            trace_row.instruction.address,
+            (r_x, x),
+            (v_r, r),
+            v_0,
+            v_qy,
+        );
+
+        let quotient = ADDInstruction::<WORD_SIZE>(q, adjustment).lookup_entry();
+        virtual_trace.push(virtual_row(
+            trace_row.instruction.address,
+            RV32IM::ADD,
+            (v_q, Some(q)),
+            (v_0, Some(adjustment)),
+            (trace_row.instruction.rd, Some(quotient)),
+            Self::SEQUENCE_LENGTH - virtual_trace.len() - 1,
+        ));
 
         virtual_trace
     }
@@ -245,18 +255,103 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVInstruction<WORD_
         if y == 0 {
             return (1 << WORD_SIZE) - 1;
         }
-        let mut quotient = x / y;
-        let remainder = x % y;
-        if (remainder < 0 && y > 0) || (remainder > 0 && y < 0) {
-            quotient -= 1;
-        }
-        quotient as u32 as u64
+        x.wrapping_div(y) as u32 as u64
     }
 }
 
+/// A virtual trace row without memory access, given its `(register, value)` pairs.
+pub(super) fn virtual_row(
+    address: u64,
+    opcode: RV32IM,
+    (rs1, rs1_val): (Option<u64>, Option<u64>),
+    (rs2, rs2_val): (Option<u64>, Option<u64>),
+    (rd, rd_post_val): (Option<u64>, Option<u64>),
+    virtual_sequence_remaining: usize,
+) -> RVTraceRow {
+    RVTraceRow {
+        instruction: ELFInstruction {
+            address,
+            opcode,
+            rs1,
+            rs2,
+            rd,
+            imm: None,
+            virtual_sequence_remaining: Some(virtual_sequence_remaining),
+        },
+        register_state: RegisterState {
+            rs1_val,
+            rs2_val,
+            rs3_val: None,
+            rd_post_val,
+        },
+        memory_state: None,
+        advice_value: None,
+        precompile_input: None,
+        precompile_output_address: None,
+    }
+}
+
+/// Appends the rows computing whether the division of `x` with remainder `r`, rounded
+/// towards negative infinity, must be adjusted to round towards zero: that is, whether
+/// `r` is nonzero and its sign differs from that of `x`. The adjustment (0 or 1) is
+/// written to `v_adjustment` and returned; `v_temp` is clobbered.
+pub(super) fn push_rounding_adjustment<const WORD_SIZE: usize>(
+    virtual_trace: &mut Vec<RVTraceRow>,
+    sequence_length: usize,
+    address: u64,
+    (r_x, x): (Option<u64>, u64),
+    (v_r, r): (Option<u64>, u64),
+    v_adjustment: Option<u64>,
+    v_temp: Option<u64>,
+) -> u64 {
+    let zero = Some(0);
+
+    let signs = XORInstruction::<WORD_SIZE>(r, x).lookup_entry();
+    virtual_trace.push(virtual_row(
+        address,
+        RV32IM::XOR,
+        (v_r, Some(r)),
+        (r_x, Some(x)),
+        (v_temp, Some(signs)),
+        sequence_length - virtual_trace.len() - 1,
+    ));
+
+    let signs_differ = SLTInstruction::<WORD_SIZE>(signs, 0).lookup_entry();
+    virtual_trace.push(virtual_row(
+        address,
+        RV32IM::SLT,
+        (v_temp, Some(signs)),
+        (zero, Some(0)),
+        (v_temp, Some(signs_differ)),
+        sequence_length - virtual_trace.len() - 1,
+    ));
+
+    let is_nonzero = SLTUInstruction::<WORD_SIZE>(0, r).lookup_entry();
+    virtual_trace.push(virtual_row(
+        address,
+        RV32IM::SLTU,
+        (zero, Some(0)),
+        (v_r, Some(r)),
+        (v_adjustment, Some(is_nonzero)),
+        sequence_length - virtual_trace.len() - 1,
+    ));
+
+    let adjustment = ANDInstruction::<WORD_SIZE>(is_nonzero, signs_differ).lookup_entry();
+    virtual_trace.push(virtual_row(
+        address,
+        RV32IM::AND,
+        (v_adjustment, Some(is_nonzero)),
+        (v_temp, Some(signs_differ)),
+        (v_adjustment, Some(adjustment)),
+        sequence_length - virtual_trace.len() - 1,
+    ));
+
+    adjustment
+}
+
 #[cfg(test)]
 mod test {
-    use crate::jolt::instruction::test::jolt_virtual_sequence_test;
+    use crate::jolt::instruction::test::{check_virtual_sequence, jolt_virtual_sequence_test};
 
     use super::*;
 
@@ -264,4 +359,31 @@ mod test {
     fn div_virtual_sequence_32() {
         jolt_virtual_sequence_test::<DIVInstruction<32>>(RV32IM::DIV);
     }
+
+    #[test]
+    fn div_rounds_towards_zero() {
+        // (x, y, x / y) as RISC-V defines signed division
+        let cases: [(i32, i32, i32); 10] = [
+            (7, 2, 3),
+            (-7, 2, -3),
+            (7, -2, -3),
+            (-7, -2, 3),
+            (-1, 2, 0),
+            (1, -2, 0),
+            (-6, 3, -2),
+            (i32::MIN, -1, i32::MIN),
+            (i32::MIN, 1, i32::MIN),
+            (5, 0, -1),
+        ];
+        for (x, y, quotient) in cases {
+            check_virtual_sequence::<DIVInstruction<32>>(
+                RV32IM::DIV,
+                0,
+                (1, x as u32 as u64),
+                (2, y as u32 as u64),
+                3,
+                quotient as u32 as u64,
+            );
+        }
+    }
 }
@@ -56,6 +56,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(q),
             },
             memory_state: None,
@@ -78,6 +79,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(r),
             },
             memory_state: None,
@@ -100,6 +102,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(q_y),
             },
             memory_state: None,
@@ -123,6 +126,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(r),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -147,6 +151,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -170,6 +175,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(y),
                 rs2_val: Some(q),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -192,6 +198,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(r),
+                rs3_val: None,
                 rd_post_val: Some(add_0),
             },
             memory_state: None,
@@ -214,6 +221,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(add_0),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -235,6 +243,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for DIVUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(q),
             },
             memory_state: None,
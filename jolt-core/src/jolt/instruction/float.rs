@@ -0,0 +1,698 @@
+//! The virtual sequences of the RV32F and RV32D instructions, which implement IEEE 754
+//! arithmetic in software with RV32I instructions (see `common::float` for the exact
+//! semantics).
+//!
+//! Floating-point register `f{i}` is held in two words of the register file, starting
+//! at `float_register_index(i)`, so loads and stores of `f` registers are checked by
+//! the same memory checking as the other registers. Values are only read from and
+//! written to these words at the start and end of a sequence; intermediate values are
+//! held in virtual registers.
+//!
+//! Nondeterministic values (leading zero counts, quotients and square roots) are given
+//! as `VIRTUAL_ADVICE` and checked by the sequence. A sequence only depends on the
+//! instruction, including its static rounding mode, not on the values of its operands.
+
+mod assembler;
+mod ops;
+mod wide;
+
+use common::constants::float_register_index;
+use common::float::{decode_imm, is_fused_multiply_add, RoundingMode, CANONICAL_NAN_S};
+use tracer::{ELFInstruction, RVTraceRow, RV32IM};
+
+use assembler::{Assembler, Reg, State, Wide, ZERO};
+use ops::Format;
+
+/// The virtual sequence of a floating-point instruction.
+pub fn virtual_sequence(instruction: &ELFInstruction) -> Vec<ELFInstruction> {
+    let mut assembler = Assembler::new(instruction.address, None);
+    assemble(&mut assembler, instruction);
+    assembler
+        .finish()
+        .into_iter()
+        .map(|row| row.instruction)
+        .collect()
+}
+
+/// The virtual trace of a floating-point trace row, obtained by executing its virtual
+/// sequence.
+pub fn virtual_trace(trace_row: &RVTraceRow) -> Vec<RVTraceRow> {
+    let instruction = &trace_row.instruction;
+    let register_state = &trace_row.register_state;
+    let mut state = State::new();
+    let mut operands = vec![
+        (instruction.rs1, register_state.rs1_val),
+        (instruction.rs2, register_state.rs2_val),
+    ];
+    if is_fused_multiply_add(instruction.opcode) {
+        operands.push((
+            decode_imm(instruction.imm.unwrap()).1,
+            register_state.rs3_val,
+        ));
+    }
+    for (index, value) in operands {
+        if let (Some(index), Some(value)) = (index, value) {
+            write_register(&mut state.registers, index, value);
+        }
+    }
+    if let RV32IM::FLW | RV32IM::FLD = instruction.opcode {
+        let address = register_state
+            .rs1_val
+            .unwrap()
+            .wrapping_add(instruction.imm.unwrap() as u64)
+            & u32::MAX as u64;
+        let value = register_state.rd_post_val.unwrap();
+        state.memory.insert(address, value & u32::MAX as u64);
+        state.memory.insert(address + 4, value >> 32);
+    }
+
+    let mut assembler = Assembler::new(instruction.address, Some(state));
+    assemble(&mut assembler, instruction);
+    if let Some(rd) = instruction.rd {
+        let mut value = assembler.value(rd).unwrap();
+        if is_float_register(rd) {
+            value |= assembler.value(rd + 1).unwrap() << 32;
+        }
+        assert_eq!(
+            Some(value),
+            register_state.rd_post_val,
+            "{:?} sequence disagrees with the tracer",
+            instruction.opcode
+        );
+    }
+    assembler.finish()
+}
+
+fn is_float_register(index: u64) -> bool {
+    index >= float_register_index(0)
+}
+
+fn write_register(registers: &mut [u64], index: u64, value: u64) {
+    if is_float_register(index) {
+        registers[index as usize] = value & u32::MAX as u64;
+        registers[index as usize + 1] = value >> 32;
+    } else if index != 0 {
+        registers[index as usize] = value;
+    }
+}
+
+/// The format of the operands of `opcode`.
+fn format(opcode: RV32IM) -> Format {
+    use RV32IM::*;
+    match opcode {
+        FLD | FSD | FADD_D | FSUB_D | FMUL_D | FDIV_D | FSQRT_D | FMIN_D | FMAX_D | FSGNJ_D
+        | FSGNJN_D | FSGNJX_D | FEQ_D | FLT_D | FLE_D | FCLASS_D | FCVT_W_D | FCVT_WU_D
+        | FCVT_S_D | FMADD_D | FMSUB_D | FNMSUB_D | FNMADD_D => Format::D,
+        _ => Format::S,
+    }
+}
+
+/// The words of the `f` register at `index` in `format`. A single-precision value that
+/// is not NaN-boxed is read as the canonical NaN.
+fn read_float(assembler: &mut Assembler, index: Reg, format: Format) -> Wide {
+    match format {
+        Format::S => {
+            let unboxed = assembler.opi(RV32IM::XORI, index + 1, u32::MAX);
+            assembler.r(RV32IM::SLTU, unboxed, ZERO, unboxed);
+            let mask = assembler.mask(unboxed);
+            let value = assembler.select_constant(mask, CANONICAL_NAN_S, index);
+            assembler.release(&[unboxed, mask]);
+            vec![value]
+        }
+        Format::D => vec![index, index + 1],
+    }
+}
+
+/// Writes `value`, in `format`, to the `f` register at `index`, NaN-boxing a
+/// single-precision value.
+fn write_float(assembler: &mut Assembler, index: Reg, value: &[Reg]) {
+    assembler.mv(index, value[0]);
+    match value.get(1) {
+        Some(&high) => assembler.mv(index + 1, high),
+        None => assembler.i(RV32IM::ADDI, index + 1, ZERO, u32::MAX),
+    }
+}
+
+/// The result of an instruction: a value written to an `f` register, or to an `x`
+/// register.
+enum Output {
+    Float(Wide),
+    Integer(Reg),
+}
+
+fn assemble(assembler: &mut Assembler, instruction: &ELFInstruction) {
+    use RV32IM::*;
+    let opcode = instruction.opcode;
+    let (rs1, rs2, rd) = (
+        instruction.rs1.unwrap_or(ZERO),
+        instruction.rs2.unwrap_or(ZERO),
+        instruction.rd.unwrap_or(ZERO),
+    );
+    let imm = instruction.imm.unwrap();
+
+    match opcode {
+        FLW => {
+            assembler.lw(rd, rs1, imm);
+            assembler.i(ADDI, rd + 1, ZERO, u32::MAX);
+            return;
+        }
+        FLD => {
+            assembler.lw(rd, rs1, imm);
+            assembler.lw(rd + 1, rs1, imm + 4);
+            return;
+        }
+        FSW => {
+            assembler.sw(rs1, rs2, imm);
+            return;
+        }
+        FSD => {
+            assembler.sw(rs1, rs2, imm);
+            assembler.sw(rs1, rs2 + 1, imm + 4);
+            return;
+        }
+        FMV_X_W => {
+            assembler.mv(rd, rs1);
+            return;
+        }
+        FMV_W_X => {
+            write_float(assembler, rd, &[rs1]);
+            return;
+        }
+        _ => {}
+    }
+
+    // An invalid rounding mode traps when executed, so its sequence is never used
+    let (rm, rs3) = decode_imm(imm);
+    let rm = RoundingMode::from_rm(rm).unwrap_or(RoundingMode::NearestEven);
+    let format = format(opcode);
+    let a = if matches!(opcode, FCVT_S_W | FCVT_S_WU | FCVT_D_W | FCVT_D_WU) {
+        vec![]
+    } else if opcode == FCVT_D_S {
+        read_float(assembler, rs1, Format::S)
+    } else {
+        read_float(assembler, rs1, format)
+    };
+    let b = match instruction.rs2 {
+        Some(rs2) => read_float(assembler, rs2, format),
+        None => vec![],
+    };
+    let c = match rs3 {
+        Some(rs3) if is_fused_multiply_add(opcode) => read_float(assembler, rs3, format),
+        _ => vec![],
+    };
+
+    let result = match opcode {
+        FADD_S | FADD_D => Output::Float(assembler.float_add(format, rm, &a, &b, false)),
+        FSUB_S | FSUB_D => Output::Float(assembler.float_add(format, rm, &a, &b, true)),
+        FMUL_S | FMUL_D => Output::Float(assembler.float_mul(format, rm, &a, &b)),
+        FDIV_S | FDIV_D => Output::Float(assembler.float_div(format, rm, &a, &b)),
+        FSQRT_S | FSQRT_D => Output::Float(assembler.float_sqrt(format, rm, &a)),
+        FMADD_S | FMADD_D | FMSUB_S | FMSUB_D | FNMSUB_S | FNMSUB_D | FNMADD_S | FNMADD_D => {
+            let negate_product = matches!(opcode, FNMSUB_S | FNMSUB_D | FNMADD_S | FNMADD_D);
+            let negate_addend = matches!(opcode, FMSUB_S | FMSUB_D | FNMADD_S | FNMADD_D);
+            let result = assembler.float_fma(format, rm, &a, &b, &c, negate_product, negate_addend);
+            Output::Float(result)
+        }
+        FMIN_S | FMIN_D | FMAX_S | FMAX_D => {
+            let max = matches!(opcode, FMAX_S | FMAX_D);
+            Output::Float(assembler.float_min_max(format, &a, &b, max))
+        }
+        FSGNJ_S | FSGNJ_D | FSGNJN_S | FSGNJN_D | FSGNJX_S | FSGNJX_D => {
+            Output::Float(assembler.float_sign_inject(&a, &b, opcode))
+        }
+        FEQ_S | FEQ_D | FLT_S | FLT_D | FLE_S | FLE_D => {
+            Output::Integer(assembler.float_compare(format, &a, &b, opcode))
+        }
+        FCLASS_S | FCLASS_D => Output::Integer(assembler.float_class(format, &a)),
+        FCVT_W_S | FCVT_W_D | FCVT_WU_S | FCVT_WU_D => {
+            let signed = matches!(opcode, FCVT_W_S | FCVT_W_D);
+            Output::Integer(assembler.float_to_int(format, rm, &a, signed))
+        }
+        FCVT_S_W | FCVT_S_WU | FCVT_D_W | FCVT_D_WU => {
+            let format = match opcode {
+                FCVT_S_W | FCVT_S_WU => Format::S,
+                _ => Format::D,
+            };
+            let signed = matches!(opcode, FCVT_S_W | FCVT_D_W);
+            Output::Float(assembler.int_to_float(format, rm, rs1, signed))
+        }
+        FCVT_S_D => Output::Float(assembler.float_convert(Format::S, rm, &a)),
+        FCVT_D_S => Output::Float(assembler.float_convert(Format::D, rm, &a)),
+        _ => panic!("{opcode:?} is not a floating-point instruction"),
+    };
+    match result {
+        Output::Float(result) => {
+            write_float(assembler, rd, &result);
+            assembler.release(&result);
+        }
+        Output::Integer(result) => {
+            assembler.mv(rd, result);
+            assembler.release(&[result]);
+        }
+    }
+    for operand in [a, b, c] {
+        assembler.release(&operand);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::jolt::instruction::JoltInstruction;
+    use crate::jolt::vm::rv32i_vm::RV32I;
+    use ark_std::test_rng;
+    use common::constants::virtual_register_index;
+    use common::float::{encode_imm, execute, NAN_BOX};
+    use rand_chacha::rand_core::RngCore;
+    use tracer::{MemoryState, RegisterState};
+
+    const F1: u64 = float_register_index(1);
+    const F2: u64 = float_register_index(2);
+    const F3: u64 = float_register_index(3);
+    const RD: u64 = float_register_index(4);
+    const X5: u64 = 5;
+    const X6: u64 = 6;
+
+    /// Interesting single-precision values.
+    fn special_s() -> Vec<u32> {
+        let mut values = vec![
+            0x00000000, 0x80000000, 0x3f800000, 0xbf800000, 0x7f800000, 0xff800000, 0x7fc00000,
+            0x7f800001, 0xffc00001, 0x00000001, 0x80000001, 0x007fffff, 0x00800000, 0x7f7fffff,
+            0xff7fffff, 0x3fffffff, 0x40000000, 0x4b800000, 0x4f000000, 0xcf000000, 0x4f800000,
+            0x3f000000, 0x3fc00000, 0x40200000, 0xbfc00000, 0x33800000, 0x34000000,
+        ];
+        values.extend(values.clone().iter().map(|x| x ^ 1));
+        values
+    }
+
+    /// Interesting double-precision values.
+    fn special_d() -> Vec<u64> {
+        let mut values = vec![
+            0x0000000000000000,
+            0x8000000000000000,
+            0x3ff0000000000000,
+            0xbff0000000000000,
+            0x7ff0000000000000,
+            0xfff0000000000000,
+            0x7ff8000000000000,
+            0x7ff0000000000001,
+            0x0000000000000001,
+            0x800fffffffffffff,
+            0x0010000000000000,
+            0x7fefffffffffffff,
+            0x41dfffffffc00000,
+            0xc1e0000000000000,
+            0x41efffffffe00000,
+            0x3fe0000000000000,
+            0x3ff8000000000000,
+            0x3810000000000000,
+            0x380fffffffffffff,
+            0x47efffffe0000000,
+            0x47efffffffffffff,
+            0x36a0000000000000,
+        ];
+        values.extend(values.clone().iter().map(|x| x ^ 1));
+        values
+    }
+
+    fn random_s(rng: &mut impl RngCore) -> u32 {
+        let x = rng.next_u32();
+        match rng.next_u32() % 4 {
+            // Nearby exponents, for cancellation
+            0 => x & 0x80ffffff | 0x3f000000,
+            1 => special_s()[x as usize % special_s().len()],
+            _ => x,
+        }
+    }
+
+    fn random_d(rng: &mut impl RngCore) -> u64 {
+        let x = rng.next_u64();
+        match rng.next_u32() % 4 {
+            0 => x & 0x801fffffffffffff | 0x3fe0000000000000,
+            1 => special_d()[x as usize % special_d().len()],
+            _ => x,
+        }
+    }
+
+    /// Traces `opcode` on the given operand values as the tracer would, and checks the
+    /// virtual trace against the reference semantics.
+    fn check(
+        opcode: RV32IM,
+        rm: RoundingMode,
+        rm_bits: u64,
+        rs1_val: u64,
+        rs2_val: u64,
+        rs3_val: u64,
+    ) {
+        let int_operand = matches!(
+            opcode,
+            RV32IM::FCVT_S_W | RV32IM::FCVT_S_WU | RV32IM::FCVT_D_W | RV32IM::FCVT_D_WU
+        );
+        let int_result = matches!(
+            opcode,
+            RV32IM::FEQ_S
+                | RV32IM::FLT_S
+                | RV32IM::FLE_S
+                | RV32IM::FEQ_D
+                | RV32IM::FLT_D
+                | RV32IM::FLE_D
+                | RV32IM::FCLASS_S
+                | RV32IM::FCLASS_D
+                | RV32IM::FCVT_W_S
+                | RV32IM::FCVT_WU_S
+                | RV32IM::FCVT_W_D
+                | RV32IM::FCVT_WU_D
+        );
+        let unary = matches!(
+            opcode,
+            RV32IM::FSQRT_S
+                | RV32IM::FSQRT_D
+                | RV32IM::FCLASS_S
+                | RV32IM::FCLASS_D
+                | RV32IM::FCVT_W_S
+                | RV32IM::FCVT_WU_S
+                | RV32IM::FCVT_W_D
+                | RV32IM::FCVT_WU_D
+                | RV32IM::FCVT_S_D
+                | RV32IM::FCVT_D_S
+        ) || int_operand;
+        let fma = is_fused_multiply_add(opcode);
+        let rs1_val = if int_operand {
+            rs1_val as u32 as u64
+        } else {
+            rs1_val
+        };
+        let rd_post_val = execute(opcode, rm, rs1_val, rs2_val, rs3_val);
+        let trace_row = RVTraceRow {
+            instruction: ELFInstruction {
+                address: 0x8000_0000,
+                opcode,
+                rs1: Some(if int_operand { X5 } else { F1 }),
+                rs2: (!unary).then_some(F2),
+                rd: Some(if int_result { X6 } else { RD }),
+                imm: Some(encode_imm(rm_bits, fma.then_some(F3))),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(rs1_val),
+                rs2_val: (!unary).then_some(rs2_val),
+                rs3_val: fma.then_some(rs3_val),
+                rd_post_val: Some(rd_post_val),
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        };
+        check_trace(&trace_row);
+    }
+
+    fn check_trace(trace_row: &RVTraceRow) {
+        let trace = virtual_trace(trace_row);
+        let sequence = virtual_sequence(&trace_row.instruction);
+        assert_eq!(
+            trace.iter().map(|row| &row.instruction).collect::<Vec<_>>(),
+            sequence.iter().collect::<Vec<_>>(),
+        );
+        for row in trace.iter() {
+            let rd = row.instruction.rd;
+            if let Some(rd) = rd {
+                let destination = trace_row.instruction.rd.unwrap();
+                assert!(
+                    rd >= virtual_register_index(0) || rd == destination || rd == destination + 1
+                );
+            }
+            match row.instruction.opcode {
+                RV32IM::LW | RV32IM::SW => {}
+                _ => {
+                    let lookup = RV32I::try_from(row).unwrap().lookup_entry();
+                    match rd {
+                        Some(_) => assert_eq!(Some(lookup), row.register_state.rd_post_val),
+                        None => assert_eq!(lookup, 1),
+                    }
+                }
+            }
+        }
+    }
+
+    const ROUNDING_MODES: [(RoundingMode, u64); 6] = [
+        (RoundingMode::NearestEven, 0),
+        (RoundingMode::TowardZero, 1),
+        (RoundingMode::Down, 2),
+        (RoundingMode::Up, 3),
+        (RoundingMode::NearestMaxMagnitude, 4),
+        (RoundingMode::NearestEven, RoundingMode::DYNAMIC),
+    ];
+
+    fn check_s(opcode: RV32IM, rm: RoundingMode, rm_bits: u64, iterations: usize) {
+        let mut rng = test_rng();
+        let box_s = |x: u32| NAN_BOX | x as u64;
+        for a in special_s() {
+            for b in [0x3f800000, 0x80000000, 0x7f800000, 0x00000001, 0xbfc00000] {
+                check(opcode, rm, rm_bits, box_s(a), box_s(b), box_s(a ^ b));
+            }
+        }
+        for _ in 0..iterations {
+            let (a, b, c) = (random_s(&mut rng), random_s(&mut rng), random_s(&mut rng));
+            check(opcode, rm, rm_bits, box_s(a), box_s(b), box_s(c));
+        }
+        // Values that are not NaN-boxed
+        check(opcode, rm, rm_bits, 0x3f800000, box_s(0x3f800000), 1);
+    }
+
+    fn check_d(opcode: RV32IM, rm: RoundingMode, rm_bits: u64, iterations: usize) {
+        let mut rng = test_rng();
+        for a in special_d() {
+            for b in [
+                0x3ff0000000000000,
+                0x8000000000000000,
+                0x7ff0000000000000,
+                1,
+            ] {
+                check(opcode, rm, rm_bits, a, b, a ^ b);
+            }
+        }
+        for _ in 0..iterations {
+            let (a, b, c) = (random_d(&mut rng), random_d(&mut rng), random_d(&mut rng));
+            check(opcode, rm, rm_bits, a, b, c);
+        }
+    }
+
+    const RNE: RoundingMode = RoundingMode::NearestEven;
+
+    #[test]
+    fn arithmetic_s() {
+        for opcode in [
+            RV32IM::FADD_S,
+            RV32IM::FSUB_S,
+            RV32IM::FMUL_S,
+            RV32IM::FDIV_S,
+            RV32IM::FSQRT_S,
+        ] {
+            check_s(opcode, RNE, 0, 500);
+        }
+    }
+
+    #[test]
+    fn arithmetic_d() {
+        for opcode in [
+            RV32IM::FADD_D,
+            RV32IM::FSUB_D,
+            RV32IM::FMUL_D,
+            RV32IM::FDIV_D,
+            RV32IM::FSQRT_D,
+        ] {
+            check_d(opcode, RNE, 0, 500);
+        }
+    }
+
+    #[test]
+    fn fused_multiply_add() {
+        for opcode in [
+            RV32IM::FMADD_S,
+            RV32IM::FMSUB_S,
+            RV32IM::FNMSUB_S,
+            RV32IM::FNMADD_S,
+        ] {
+            check_s(opcode, RNE, 0, 300);
+        }
+        for opcode in [
+            RV32IM::FMADD_D,
+            RV32IM::FMSUB_D,
+            RV32IM::FNMSUB_D,
+            RV32IM::FNMADD_D,
+        ] {
+            check_d(opcode, RNE, 0, 300);
+        }
+        // Cancellation of the exact product
+        let a = 1.0f64 + f64::EPSILON;
+        let product = a * a;
+        check(
+            RV32IM::FMSUB_D,
+            RNE,
+            0,
+            a.to_bits(),
+            a.to_bits(),
+            product.to_bits(),
+        );
+    }
+
+    #[test]
+    fn compare_and_sign() {
+        for opcode in [
+            RV32IM::FMIN_S,
+            RV32IM::FMAX_S,
+            RV32IM::FSGNJ_S,
+            RV32IM::FSGNJN_S,
+            RV32IM::FSGNJX_S,
+            RV32IM::FEQ_S,
+            RV32IM::FLT_S,
+            RV32IM::FLE_S,
+            RV32IM::FCLASS_S,
+        ] {
+            check_s(opcode, RNE, 0, 100);
+        }
+        for opcode in [
+            RV32IM::FMIN_D,
+            RV32IM::FMAX_D,
+            RV32IM::FSGNJ_D,
+            RV32IM::FSGNJN_D,
+            RV32IM::FSGNJX_D,
+            RV32IM::FEQ_D,
+            RV32IM::FLT_D,
+            RV32IM::FLE_D,
+            RV32IM::FCLASS_D,
+        ] {
+            check_d(opcode, RNE, 0, 100);
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        for (rm, rm_bits) in ROUNDING_MODES {
+            for opcode in [RV32IM::FCVT_W_S, RV32IM::FCVT_WU_S] {
+                check_s(opcode, rm, rm_bits, 100);
+            }
+            for opcode in [RV32IM::FCVT_W_D, RV32IM::FCVT_WU_D] {
+                check_d(opcode, rm, rm_bits, 100);
+            }
+            let mut rng = test_rng();
+            let integers = [
+                0,
+                1,
+                u32::MAX,
+                0x80000000,
+                0x7fffffff,
+                0x01000001,
+                0xfeffffff,
+            ];
+            for opcode in [
+                RV32IM::FCVT_S_W,
+                RV32IM::FCVT_S_WU,
+                RV32IM::FCVT_D_W,
+                RV32IM::FCVT_D_WU,
+            ] {
+                let random = (0..100).map(|_| rng.next_u32() >> (rng.next_u32() % 32));
+                for x in integers.into_iter().chain(random) {
+                    check(opcode, rm, rm_bits, x as u64, 0, 0);
+                }
+            }
+        }
+        check_d(RV32IM::FCVT_S_D, RNE, 0, 300);
+        check_s(RV32IM::FCVT_D_S, RNE, 0, 100);
+    }
+
+    #[test]
+    fn loads_stores_and_moves() {
+        let row = |opcode, rs1, rs2, rd, rs1_val, rs2_val, rd_post_val, memory_state| RVTraceRow {
+            instruction: ELFInstruction {
+                address: 0x8000_0000,
+                opcode,
+                rs1: Some(rs1),
+                rs2,
+                rd,
+                imm: Some(-8),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(rs1_val),
+                rs2_val,
+                rs3_val: None,
+                rd_post_val,
+            },
+            memory_state,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        };
+        let value = 0x0123_4567_89ab_cdef;
+        check_trace(&row(
+            RV32IM::FLD,
+            X5,
+            None,
+            Some(RD),
+            0x8000_1008,
+            None,
+            Some(value),
+            Some(MemoryState::Read {
+                address: 0x8000_1000,
+                value,
+            }),
+        ));
+        check_trace(&row(
+            RV32IM::FLW,
+            X5,
+            None,
+            Some(RD),
+            0x8000_1008,
+            None,
+            Some(NAN_BOX | 0x89ab_cdef),
+            None,
+        ));
+        let trace = virtual_trace(&row(
+            RV32IM::FSD,
+            X5,
+            Some(F2),
+            None,
+            0x8000_1008,
+            Some(value),
+            None,
+            None,
+        ));
+        let stored: Vec<_> = trace
+            .iter()
+            .map(|row| match row.memory_state {
+                Some(MemoryState::Write {
+                    address,
+                    post_value,
+                    ..
+                }) => (address, post_value),
+                _ => panic!("FSD should only store"),
+            })
+            .collect();
+        assert_eq!(
+            stored,
+            [(0x8000_1000, 0x89ab_cdef), (0x8000_1004, 0x0123_4567)]
+        );
+        check_trace(&row(
+            RV32IM::FMV_W_X,
+            X5,
+            None,
+            Some(RD),
+            0x89ab_cdef,
+            None,
+            Some(NAN_BOX | 0x89ab_cdef),
+            None,
+        ));
+        check_trace(&row(
+            RV32IM::FMV_X_W,
+            F1,
+            None,
+            Some(X6),
+            value,
+            None,
+            Some(0x89ab_cdef),
+            None,
+        ));
+    }
+}
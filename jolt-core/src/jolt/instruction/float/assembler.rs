@@ -0,0 +1,286 @@
+//! Emits the RV32I instructions of a floating-point virtual sequence, and executes them
+//! as they are emitted when the sequence is being traced.
+
+use std::collections::HashMap;
+
+use common::constants::{virtual_register_index, REGISTER_COUNT};
+use tracer::{ELFInstruction, MemoryState, RVTraceRow, RegisterState, RV32IM};
+
+use crate::jolt::instruction::JoltInstruction;
+use crate::jolt::vm::rv32i_vm::RV32I;
+
+/// A register index.
+pub type Reg = u64;
+
+/// The hardwired zero register.
+pub const ZERO: Reg = 0;
+
+/// A multi-word integer held in registers, least significant word first.
+pub type Wide = Vec<Reg>;
+
+/// The register and memory values of a sequence being traced.
+pub struct State {
+    pub registers: Vec<u64>,
+    pub memory: HashMap<u64, u64>,
+}
+
+impl State {
+    pub fn new() -> Self {
+        Self {
+            registers: vec![0; REGISTER_COUNT as usize],
+            memory: HashMap::new(),
+        }
+    }
+}
+
+pub struct Assembler {
+    address: u64,
+    rows: Vec<RVTraceRow>,
+    state: Option<State>,
+    /// The virtual registers not currently in use.
+    free: Vec<Reg>,
+}
+
+impl Assembler {
+    /// An assembler for the sequence of the instruction at `address`, which executes the
+    /// sequence if `state` is given.
+    pub fn new(address: u64, state: Option<State>) -> Self {
+        Self {
+            address,
+            rows: vec![],
+            state,
+            free: (0..32).rev().map(virtual_register_index).collect(),
+        }
+    }
+
+    /// The value of `reg`, if the sequence is being traced.
+    pub fn value(&self, reg: Reg) -> Option<u64> {
+        self.state
+            .as_ref()
+            .map(|state| state.registers[reg as usize])
+    }
+
+    /// The trace rows of the sequence, with `virtual_sequence_remaining` set.
+    pub fn finish(mut self) -> Vec<RVTraceRow> {
+        let len = self.rows.len();
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            row.instruction.virtual_sequence_remaining = Some(len - i - 1);
+        }
+        self.rows
+    }
+
+    /// Allocates a virtual register.
+    pub fn alloc(&mut self) -> Reg {
+        self.free
+            .pop()
+            .expect("Floating-point sequence ran out of virtual registers")
+    }
+
+    /// Frees the virtual registers among `regs`; other registers (operands, `ZERO`) are
+    /// left alone.
+    pub fn release(&mut self, regs: &[Reg]) {
+        for &reg in regs {
+            if (virtual_register_index(0)..virtual_register_index(32)).contains(&reg) {
+                debug_assert!(!self.free.contains(&reg), "{reg} released twice");
+                self.free.push(reg);
+            }
+        }
+    }
+
+    fn push(
+        &mut self,
+        opcode: RV32IM,
+        rd: Option<Reg>,
+        rs1: Option<Reg>,
+        rs2: Option<Reg>,
+        imm: i64,
+        advice_value: Option<u64>,
+    ) {
+        let mut row = RVTraceRow {
+            instruction: ELFInstruction {
+                address: self.address,
+                opcode,
+                rs1,
+                rs2,
+                rd,
+                imm: Some(imm),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState::default(),
+            memory_state: None,
+            advice_value,
+            precompile_input: None,
+            precompile_output_address: None,
+        };
+        if let Some(state) = self.state.as_mut() {
+            let registers = &mut state.registers;
+            row.register_state.rs1_val = rs1.map(|rs1| registers[rs1 as usize]);
+            row.register_state.rs2_val = rs2.map(|rs2| registers[rs2 as usize]);
+            let rd_value = match opcode {
+                RV32IM::LW | RV32IM::SW => {
+                    let address = row.register_state.rs1_val.unwrap().wrapping_add(imm as u64)
+                        & u32::MAX as u64;
+                    if opcode == RV32IM::LW {
+                        let value = state.memory[&address];
+                        row.memory_state = Some(MemoryState::Read { address, value });
+                        Some(value)
+                    } else {
+                        let value = row.register_state.rs2_val.unwrap();
+                        let pre_value = state.memory.insert(address, value).unwrap_or(0);
+                        row.memory_state = Some(MemoryState::Write {
+                            address,
+                            pre_value,
+                            post_value: value,
+                        });
+                        None
+                    }
+                }
+                _ => {
+                    let output = RV32I::try_from(&row).unwrap().lookup_entry();
+                    if rd.is_none() {
+                        assert_eq!(output, 1, "{opcode:?} assertion failed");
+                    }
+                    Some(output)
+                }
+            };
+            if let Some(rd) = rd {
+                let value = if rd == ZERO { 0 } else { rd_value.unwrap() };
+                row.register_state.rd_post_val = Some(value);
+                registers[rd as usize] = value;
+            }
+        }
+        self.rows.push(row);
+    }
+
+    /// `rd = rs1 op rs2`
+    pub fn r(&mut self, opcode: RV32IM, rd: Reg, rs1: Reg, rs2: Reg) {
+        self.push(opcode, Some(rd), Some(rs1), Some(rs2), 0, None);
+    }
+
+    /// `rd = rs1 op imm`
+    pub fn i(&mut self, opcode: RV32IM, rd: Reg, rs1: Reg, imm: u32) {
+        self.push(opcode, Some(rd), Some(rs1), None, imm as i64, None);
+    }
+
+    /// A new register holding `rs1 op rs2`.
+    pub fn op(&mut self, opcode: RV32IM, rs1: Reg, rs2: Reg) -> Reg {
+        let rd = self.alloc();
+        self.r(opcode, rd, rs1, rs2);
+        rd
+    }
+
+    /// A new register holding `rs1 op imm`.
+    pub fn opi(&mut self, opcode: RV32IM, rs1: Reg, imm: u32) -> Reg {
+        let rd = self.alloc();
+        self.i(opcode, rd, rs1, imm);
+        rd
+    }
+
+    /// A new register holding `value`.
+    pub fn constant(&mut self, value: u32) -> Reg {
+        self.opi(RV32IM::ADDI, ZERO, value)
+    }
+
+    /// `rd = rs`
+    pub fn mv(&mut self, rd: Reg, rs: Reg) {
+        self.i(RV32IM::ADDI, rd, rs, 0);
+    }
+
+    /// A new register holding the advice computed by `f` from the register values.
+    pub fn advice(&mut self, f: impl FnOnce(&[u64]) -> u32) -> Reg {
+        let value = self.state.as_ref().map(|state| f(&state.registers) as u64);
+        let rd = self.alloc();
+        self.push(RV32IM::VIRTUAL_ADVICE, Some(rd), None, None, 0, value);
+        rd
+    }
+
+    /// Asserts `rs1 == rs2`.
+    pub fn assert_eq(&mut self, rs1: Reg, rs2: Reg) {
+        self.push(
+            RV32IM::VIRTUAL_ASSERT_EQ,
+            None,
+            Some(rs1),
+            Some(rs2),
+            0,
+            None,
+        );
+    }
+
+    /// `rd = memory[rs1 + imm]`
+    pub fn lw(&mut self, rd: Reg, rs1: Reg, imm: i64) {
+        self.push(RV32IM::LW, Some(rd), Some(rs1), None, imm, None);
+    }
+
+    /// `memory[rs1 + imm] = rs2`
+    pub fn sw(&mut self, rs1: Reg, rs2: Reg, imm: i64) {
+        self.push(RV32IM::SW, None, Some(rs1), Some(rs2), imm, None);
+    }
+
+    /// A new register holding `rs == 0`.
+    pub fn seqz(&mut self, rs: Reg) -> Reg {
+        self.opi(RV32IM::SLTIU, rs, 1)
+    }
+
+    /// A new register holding `rs != 0`.
+    pub fn snez(&mut self, rs: Reg) -> Reg {
+        self.op(RV32IM::SLTU, ZERO, rs)
+    }
+
+    /// A new register holding `!bit`, for a bit `bit`.
+    pub fn not(&mut self, bit: Reg) -> Reg {
+        self.opi(RV32IM::XORI, bit, 1)
+    }
+
+    /// A new register holding all ones if `bit` is set, and zero otherwise.
+    pub fn mask(&mut self, bit: Reg) -> Reg {
+        self.op(RV32IM::SUB, ZERO, bit)
+    }
+
+    /// A new register holding `a` where `mask` is set, and `b` elsewhere.
+    pub fn select_masked(&mut self, mask: Reg, a: Reg, b: Reg) -> Reg {
+        let rd = self.op(RV32IM::XOR, a, b);
+        self.r(RV32IM::AND, rd, rd, mask);
+        self.r(RV32IM::XOR, rd, rd, b);
+        rd
+    }
+
+    /// A new register holding `a` if `bit` is set, and `b` otherwise.
+    pub fn select(&mut self, bit: Reg, a: Reg, b: Reg) -> Reg {
+        let mask = self.mask(bit);
+        let rd = self.select_masked(mask, a, b);
+        self.release(&[mask]);
+        rd
+    }
+
+    /// A new register holding the constant `a` where `mask` is set, and `b` elsewhere.
+    pub fn select_constant(&mut self, mask: Reg, a: u32, b: Reg) -> Reg {
+        let rd = self.opi(RV32IM::XORI, b, a);
+        self.r(RV32IM::AND, rd, rd, mask);
+        self.r(RV32IM::XOR, rd, rd, b);
+        rd
+    }
+
+    /// A new register holding the bitwise OR of `regs`.
+    pub fn or_all(&mut self, regs: &[Reg]) -> Reg {
+        let rd = self.alloc();
+        self.mv(rd, regs[0]);
+        for &reg in &regs[1..] {
+            self.r(RV32IM::OR, rd, rd, reg);
+        }
+        rd
+    }
+
+    /// The AND of bits `a` and `b`, freeing them.
+    pub fn and_bits(&mut self, a: Reg, b: Reg) -> Reg {
+        self.r(RV32IM::AND, a, a, b);
+        self.release(&[b]);
+        a
+    }
+
+    /// The OR of bits `a` and `b`, freeing them.
+    pub fn or_bits(&mut self, a: Reg, b: Reg) -> Reg {
+        self.r(RV32IM::OR, a, a, b);
+        self.release(&[b]);
+        a
+    }
+}
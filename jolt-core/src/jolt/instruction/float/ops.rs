@@ -0,0 +1,1078 @@
+//! Software implementations of the RV32F and RV32D operations, on values held in
+//! registers (see [`Format`]).
+//!
+//! Rounding follows the usual scheme: a result is computed as a significand with its
+//! leading one at the most significant bit of its words, plus a sticky bit for any
+//! nonzero bits shifted out below, and an exponent; [`Assembler::round_pack`] then
+//! rounds it to the format. Results that are NaN, infinite, or an exact special case
+//! are computed separately from the operands' classes and override the rounded result.
+
+use common::float::RoundingMode;
+use tracer::RV32IM;
+
+use super::assembler::{Assembler, Reg, Wide, ZERO};
+
+/// A floating-point format. A value is held in `words()` registers, least significant
+/// word first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    S,
+    D,
+}
+
+impl Format {
+    pub fn words(self) -> usize {
+        match self {
+            Self::S => 1,
+            Self::D => 2,
+        }
+    }
+
+    /// The number of bits in the format.
+    fn width(self) -> u32 {
+        32 * self.words() as u32
+    }
+
+    fn exponent_bits(self) -> u32 {
+        match self {
+            Self::S => 8,
+            Self::D => 11,
+        }
+    }
+
+    fn fraction_bits(self) -> u32 {
+        match self {
+            Self::S => 23,
+            Self::D => 52,
+        }
+    }
+
+    /// The number of fraction bits in the most significant word.
+    fn top_fraction_bits(self) -> u32 {
+        self.fraction_bits() - 32 * (self.words() as u32 - 1)
+    }
+
+    fn bias(self) -> u32 {
+        (1 << (self.exponent_bits() - 1)) - 1
+    }
+
+    fn max_exponent(self) -> u32 {
+        (1 << self.exponent_bits()) - 1
+    }
+
+    /// The most significant word of positive infinity.
+    fn infinity(self) -> u32 {
+        self.max_exponent() << self.top_fraction_bits()
+    }
+
+    /// The canonical NaN, least significant word first.
+    fn nan(self) -> Vec<u32> {
+        let mut words = vec![0; self.words()];
+        words[self.words() - 1] = self.infinity() | 1 << (self.top_fraction_bits() - 1);
+        words
+    }
+}
+
+/// The fields of a value: its sign bit, its biased exponent (1 for subnormals) and its
+/// significand, including the implicit bit of normal values.
+pub struct Unpacked {
+    pub sign: Reg,
+    pub exponent: Reg,
+    pub significand: Wide,
+}
+
+/// The class bits of a value.
+pub struct Class {
+    pub nan: Reg,
+    pub infinity: Reg,
+    pub zero: Reg,
+}
+
+/// The exponent given to zero operands of a fused multiply-add, below that of any
+/// nonzero value.
+const ZERO_EXPONENT: u32 = (-100_000i32) as u32;
+
+impl Assembler {
+    pub fn release_unpacked(&mut self, x: Unpacked) {
+        self.release(&[x.sign, x.exponent]);
+        self.release(&x.significand);
+    }
+
+    pub fn release_class(&mut self, x: Class) {
+        self.release(&[x.nan, x.infinity, x.zero]);
+    }
+
+    pub fn unpack(&mut self, format: Format, x: &[Reg]) -> Unpacked {
+        let top = *x.last().unwrap();
+        let sign = self.opi(RV32IM::SRLI, top, 31);
+        let exponent = self.opi(RV32IM::SRLI, top, format.top_fraction_bits());
+        self.i(RV32IM::ANDI, exponent, exponent, format.max_exponent());
+        let subnormal = self.seqz(exponent);
+        self.r(RV32IM::ADD, exponent, exponent, subnormal);
+        let implicit = self.not(subnormal);
+        self.i(RV32IM::SLLI, implicit, implicit, format.top_fraction_bits());
+        let mut significand = self.copy(&x[..x.len() - 1]);
+        let significand_top = self.opi(RV32IM::ANDI, top, (1 << format.top_fraction_bits()) - 1);
+        self.r(RV32IM::OR, significand_top, significand_top, implicit);
+        significand.push(significand_top);
+        self.release(&[subnormal, implicit]);
+        Unpacked {
+            sign,
+            exponent,
+            significand,
+        }
+    }
+
+    /// A word that orders the magnitudes of non-negative values, and of infinity and
+    /// NaN: the most significant word shifted left by one, with its least significant
+    /// bit set if any lower word is nonzero.
+    fn class_key(&mut self, x: &[Reg]) -> Reg {
+        let key = self.opi(RV32IM::SLLI, *x.last().unwrap(), 1);
+        if x.len() > 1 {
+            let low = self.or_all(&x[..x.len() - 1]);
+            let nonzero = self.snez(low);
+            self.r(RV32IM::OR, key, key, nonzero);
+            self.release(&[low, nonzero]);
+        }
+        key
+    }
+
+    pub fn is_nan(&mut self, format: Format, x: &[Reg]) -> Reg {
+        let key = self.class_key(x);
+        let nan = self.is_nan_key(format, key);
+        self.release(&[key]);
+        nan
+    }
+
+    pub fn classify(&mut self, format: Format, x: &[Reg]) -> Class {
+        let key = self.class_key(x);
+        let zero = self.seqz(key);
+        let infinity = self.opi(RV32IM::XORI, key, format.infinity() << 1);
+        self.i(RV32IM::SLTIU, infinity, infinity, 1);
+        let nan = self.is_nan_key(format, key);
+        self.release(&[key]);
+        Class {
+            nan,
+            infinity,
+            zero,
+        }
+    }
+
+    fn is_nan_key(&mut self, format: Format, key: Reg) -> Reg {
+        let nan = self.opi(RV32IM::SLTIU, key, (format.infinity() << 1) + 1);
+        self.i(RV32IM::XORI, nan, nan, 1);
+        nan
+    }
+
+    /// Replaces `x` with the constant `value` if `bit` is set.
+    fn override_constant(&mut self, x: Wide, bit: Reg, value: &[u32]) -> Wide {
+        let mask = self.mask(bit);
+        let y = x
+            .iter()
+            .zip(value)
+            .map(|(&limb, &word)| self.select_constant(mask, word, limb))
+            .collect();
+        self.release(&x);
+        self.release(&[mask]);
+        y
+    }
+
+    /// Replaces `x` with the infinity of sign `sign` if `bit` is set.
+    fn override_infinity(&mut self, format: Format, x: Wide, bit: Reg, sign: Reg) -> Wide {
+        let mut infinity = vec![0; format.words()];
+        infinity[format.words() - 1] = format.infinity();
+        let x = self.override_constant(x, bit, &infinity);
+        self.attach_sign(&x, bit, sign);
+        x
+    }
+
+    /// Sets the sign bit of `x` if `bit` and `sign` are set.
+    fn attach_sign(&mut self, x: &[Reg], bit: Reg, sign: Reg) {
+        let set = self.op(RV32IM::AND, bit, sign);
+        self.i(RV32IM::SLLI, set, set, 31);
+        let top = *x.last().unwrap();
+        self.r(RV32IM::OR, top, top, set);
+        self.release(&[set]);
+    }
+
+    /// The top `words` words of `x`, with the lower words ORed into the sticky bit.
+    fn truncate_sticky(&mut self, x: &[Reg], words: usize) -> Wide {
+        let high = self.copy(&x[x.len() - words..]);
+        let low = self.or_all(&x[..x.len() - words]);
+        let sticky = self.snez(low);
+        self.r(RV32IM::OR, high[0], high[0], sticky);
+        self.release(&[low, sticky]);
+        high
+    }
+
+    /// Rounds and packs the value `(-1)^sign * significand / 2^(w - 1) * 2^(exponent -
+    /// bias)`, where `w` is the width of the format and `significand` is either zero or
+    /// has its most significant bit set. `exponent` is a signed word.
+    pub fn round_pack(
+        &mut self,
+        format: Format,
+        rm: RoundingMode,
+        sign: Reg,
+        exponent: Reg,
+        significand: &[Reg],
+    ) -> Wide {
+        assert_eq!(significand.len(), format.words());
+        let guard_bits = format.width() - 1 - format.fraction_bits();
+        let zero = self.is_zero(significand);
+        let nonzero_mask = self.opi(RV32IM::ADDI, zero, u32::MAX);
+
+        // Shift subnormal results right, to exponent 1
+        let tiny = self.opi(RV32IM::SLTI, exponent, 1);
+        let shift = self.op(RV32IM::SUB, ZERO, exponent);
+        self.i(RV32IM::ADDI, shift, shift, 1);
+        let mask = self.mask(tiny);
+        self.r(RV32IM::AND, shift, shift, mask);
+        let x = self.shr_sticky(significand, shift);
+        let exponent = self.op(RV32IM::ADD, exponent, shift);
+        self.release(&[zero, tiny, shift, mask]);
+
+        // Round the guard bits
+        let increment = match rm {
+            RoundingMode::NearestEven => {
+                let lsb = self.opi(RV32IM::SRLI, x[0], guard_bits);
+                self.i(RV32IM::ANDI, lsb, lsb, 1);
+                self.i(RV32IM::ADDI, lsb, lsb, (1 << (guard_bits - 1)) - 1);
+                Some(lsb)
+            }
+            RoundingMode::TowardZero => None,
+            RoundingMode::Down => {
+                let mask = self.mask(sign);
+                self.i(RV32IM::ANDI, mask, mask, (1 << guard_bits) - 1);
+                Some(mask)
+            }
+            RoundingMode::Up => {
+                let mask = self.opi(RV32IM::ADDI, sign, u32::MAX);
+                self.i(RV32IM::ANDI, mask, mask, (1 << guard_bits) - 1);
+                Some(mask)
+            }
+            RoundingMode::NearestMaxMagnitude => Some(self.constant(1 << (guard_bits - 1))),
+        };
+        let carry = increment.map(|increment| {
+            let carry = self.add_word_in_place(&x, increment);
+            self.release(&[increment]);
+            carry
+        });
+        let mut result = self.shr_constant(&x, guard_bits as usize, format.words());
+        self.release(&x);
+        let top = *result.last().unwrap();
+        if let Some(carry) = carry {
+            self.i(RV32IM::SLLI, carry, carry, format.top_fraction_bits() + 1);
+            self.r(RV32IM::OR, top, top, carry);
+            self.release(&[carry]);
+        }
+
+        // Add the exponent. A carry out of the significand, when rounding up to the
+        // next power of two, increments the exponent.
+        let field = self.opi(RV32IM::ADDI, exponent, u32::MAX);
+        self.i(RV32IM::SLLI, field, field, format.top_fraction_bits());
+        self.r(RV32IM::AND, field, field, nonzero_mask);
+        self.r(RV32IM::ADD, top, top, field);
+        self.release(&[field]);
+
+        // Overflow to infinity or to the largest finite value
+        let overflow = self.opi(RV32IM::SLTIU, exponent, format.max_exponent());
+        self.i(RV32IM::XORI, overflow, overflow, 1);
+        let rounded_overflow = self.opi(RV32IM::SLTIU, top, format.infinity());
+        self.i(RV32IM::XORI, rounded_overflow, rounded_overflow, 1);
+        let overflow = self.or_bits(overflow, rounded_overflow);
+        self.r(RV32IM::AND, overflow, overflow, nonzero_mask);
+        let to_max = match rm {
+            RoundingMode::NearestEven | RoundingMode::NearestMaxMagnitude => Some(false),
+            RoundingMode::TowardZero => Some(true),
+            _ => None,
+        };
+        result = match to_max {
+            Some(to_max) => {
+                let mut value = vec![if to_max { u32::MAX } else { 0 }; format.words()];
+                value[format.words() - 1] = format.infinity() - to_max as u32;
+                self.override_constant(result, overflow, &value)
+            }
+            None => {
+                let to_max = if rm == RoundingMode::Down {
+                    self.not(sign)
+                } else {
+                    self.opi(RV32IM::ADDI, sign, 0)
+                };
+                let low = self.mask(to_max);
+                let high = self.op(RV32IM::SUB, ZERO, to_max);
+                self.i(RV32IM::ADDI, high, high, format.infinity());
+                let overflow_mask = self.mask(overflow);
+                let value: Wide = (0..format.words())
+                    .map(|k| {
+                        let word = if k + 1 == format.words() { high } else { low };
+                        self.select_masked(overflow_mask, word, result[k])
+                    })
+                    .collect();
+                self.release(&result);
+                self.release(&[to_max, low, high, overflow_mask]);
+                value
+            }
+        };
+        self.release(&[exponent, overflow, nonzero_mask]);
+
+        let top = *result.last().unwrap();
+        let sign_bit = self.opi(RV32IM::SLLI, sign, 31);
+        self.r(RV32IM::OR, top, top, sign_bit);
+        self.release(&[sign_bit]);
+        result
+    }
+
+    /// `a + b`, or `a - b` if `subtract`.
+    pub fn float_add(
+        &mut self,
+        format: Format,
+        rm: RoundingMode,
+        a: &[Reg],
+        b: &[Reg],
+        subtract: bool,
+    ) -> Wide {
+        let words = format.words();
+        let ua = self.unpack(format, a);
+        let ub = self.unpack(format, b);
+        if subtract {
+            self.i(RV32IM::XORI, ub.sign, ub.sign, 1);
+        }
+
+        // Order the operands by magnitude
+        let magnitude = |asm: &mut Self, x: &[Reg]| {
+            let mut magnitude = x.to_vec();
+            magnitude[words - 1] = asm.opi(RV32IM::ANDI, x[words - 1], 0x7fffffff);
+            magnitude
+        };
+        let (ma, mb) = (magnitude(self, a), magnitude(self, b));
+        let swap = self.lt_wide(&ma, &mb);
+        let mask = self.mask(swap);
+        self.release(&[ma[words - 1], mb[words - 1], swap]);
+        let swap_fields = |asm: &mut Self, a: Reg, b: Reg| {
+            let x = asm.select_masked(mask, b, a);
+            let y = asm.op(RV32IM::XOR, a, b);
+            asm.r(RV32IM::XOR, y, y, x);
+            (x, y)
+        };
+        let (x_sign, y_sign) = swap_fields(self, ua.sign, ub.sign);
+        let (x_exponent, y_exponent) = swap_fields(self, ua.exponent, ub.exponent);
+        let (x_significand, y_significand): (Wide, Wide) = (0..words)
+            .map(|k| swap_fields(self, ua.significand[k], ub.significand[k]))
+            .unzip();
+        self.release(&[mask]);
+        self.release_unpacked(ua);
+        self.release_unpacked(ub);
+
+        // Align the significands, with their leading ones below the most significant
+        // bit, and add them
+        let alignment = (format.width() - 2 - format.fraction_bits()) as usize;
+        let x = self.shl_constant(&x_significand, alignment, words);
+        let y = self.shl_constant(&y_significand, alignment, words);
+        self.release(&x_significand);
+        self.release(&y_significand);
+        let distance = self.op(RV32IM::SUB, x_exponent, y_exponent);
+        let y_aligned = self.shr_sticky(&y, distance);
+        self.release(&y);
+        let difference = self.op(RV32IM::XOR, x_sign, y_sign);
+        let y_signed = self.negate_if(&y_aligned, difference);
+        self.release(&y_aligned);
+        let sum = self.add_wide(&x, &y_signed);
+        self.release(&x);
+        self.release(&y_signed);
+
+        // An exact zero is positive, unless both operands are negative
+        let sum_zero = self.is_zero(&sum);
+        let zero_sign = if rm == RoundingMode::Down {
+            self.op(RV32IM::OR, x_sign, y_sign)
+        } else {
+            self.op(RV32IM::AND, x_sign, y_sign)
+        };
+        let sign = self.select(sum_zero, zero_sign, x_sign);
+        let (normalized, shift) = self.normalize(&sum);
+        self.release(&sum);
+        let exponent = self.opi(RV32IM::ADDI, x_exponent, 1);
+        self.r(RV32IM::SUB, exponent, exponent, shift);
+        let result = self.round_pack(format, rm, sign, exponent, &normalized);
+        self.release(&normalized);
+        self.release(&[sum_zero, zero_sign, sign, shift, exponent, distance]);
+        self.release(&[y_sign, x_exponent, y_exponent]);
+
+        // NaN operands and `inf - inf` give NaN; otherwise an infinite operand is the
+        // larger one
+        let ca = self.classify(format, a);
+        let cb = self.classify(format, b);
+        let infinity = self.op(RV32IM::OR, ca.infinity, cb.infinity);
+        let result = self.override_infinity(format, result, infinity, x_sign);
+        let nan = self.op(RV32IM::AND, ca.infinity, cb.infinity);
+        self.r(RV32IM::AND, nan, nan, difference);
+        self.r(RV32IM::OR, nan, nan, ca.nan);
+        self.r(RV32IM::OR, nan, nan, cb.nan);
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[infinity, nan, x_sign, difference]);
+        self.release_class(ca);
+        self.release_class(cb);
+        result
+    }
+
+    /// `a * b`
+    pub fn float_mul(&mut self, format: Format, rm: RoundingMode, a: &[Reg], b: &[Reg]) -> Wide {
+        let words = format.words();
+        let ua = self.unpack(format, a);
+        let ub = self.unpack(format, b);
+        let sign = self.op(RV32IM::XOR, ua.sign, ub.sign);
+        let product = self.mul_wide(&ua.significand, &ub.significand);
+        let (normalized, shift) = self.normalize(&product);
+        self.release(&product);
+        let significand = self.truncate_sticky(&normalized, words);
+        self.release(&normalized);
+        let exponent = self.op(RV32IM::ADD, ua.exponent, ub.exponent);
+        let offset = 2 * format.width() - 1 - 2 * format.fraction_bits();
+        self.i(
+            RV32IM::ADDI,
+            exponent,
+            exponent,
+            offset.wrapping_sub(format.bias()),
+        );
+        self.r(RV32IM::SUB, exponent, exponent, shift);
+        self.release_unpacked(ua);
+        self.release_unpacked(ub);
+        let result = self.round_pack(format, rm, sign, exponent, &significand);
+        self.release(&significand);
+        self.release(&[exponent, shift]);
+
+        // `inf * 0` is NaN
+        let ca = self.classify(format, a);
+        let cb = self.classify(format, b);
+        let infinity = self.op(RV32IM::OR, ca.infinity, cb.infinity);
+        let result = self.override_infinity(format, result, infinity, sign);
+        let nan = self.op(RV32IM::AND, ca.infinity, cb.zero);
+        let invalid = self.op(RV32IM::AND, cb.infinity, ca.zero);
+        let nan = self.or_bits(nan, invalid);
+        self.r(RV32IM::OR, nan, nan, ca.nan);
+        self.r(RV32IM::OR, nan, nan, cb.nan);
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[sign, infinity, nan]);
+        self.release_class(ca);
+        self.release_class(cb);
+        result
+    }
+
+    /// The value of `x`, if the sequence is being traced.
+    fn wide_value(registers: &[u64], x: &[Reg]) -> u128 {
+        x.iter()
+            .rev()
+            .fold(0, |acc, &limb| acc << 32 | registers[limb as usize] as u128)
+    }
+
+    /// Asserts that `x` is zero.
+    fn assert_zero(&mut self, x: Reg) {
+        self.assert_eq(x, ZERO);
+    }
+
+    /// Asserts that the bit `x` is one.
+    fn assert_one(&mut self, x: Reg) {
+        self.i(RV32IM::XORI, x, x, 1);
+        self.assert_zero(x);
+    }
+
+    /// `a / b`. The quotient of the normalized significands is given as advice, with
+    /// its remainder, and checked by multiplying it back.
+    pub fn float_div(&mut self, format: Format, rm: RoundingMode, a: &[Reg], b: &[Reg]) -> Wide {
+        let words = format.words();
+        let width = format.width() as usize;
+        let ua = self.unpack(format, a);
+        let ub = self.unpack(format, b);
+        let sign = self.op(RV32IM::XOR, ua.sign, ub.sign);
+        let (na, za) = self.normalize(&ua.significand);
+        let (nb, zb) = self.normalize(&ub.significand);
+        // A zero divisor is replaced by a nonzero one; its result is overridden below
+        self.i(RV32IM::ORI, nb[words - 1], nb[words - 1], 0x80000000);
+        let dividend = self.shl_constant(&na, width - 1, 2 * words);
+
+        let quotient_remainder = |registers: &[u64], na: &[Reg], nb: &[Reg]| {
+            let dividend = Self::wide_value(registers, na) << (width - 1);
+            let divisor = Self::wide_value(registers, nb);
+            (dividend / divisor, dividend % divisor)
+        };
+        let quotient: Wide = (0..words)
+            .map(|k| {
+                let (na, nb) = (na.clone(), nb.clone());
+                self.advice(move |registers| {
+                    (quotient_remainder(registers, &na, &nb).0 >> (32 * k)) as u32
+                })
+            })
+            .collect();
+        let remainder: Wide = (0..words)
+            .map(|k| {
+                let (na, nb) = (na.clone(), nb.clone());
+                self.advice(move |registers| {
+                    (quotient_remainder(registers, &na, &nb).1 >> (32 * k)) as u32
+                })
+            })
+            .collect();
+        let check = self.mul_wide(&quotient, &nb);
+        self.add_at_in_place(&check, 0, &remainder);
+        for (&word, &expected) in check.iter().zip(&dividend) {
+            self.assert_eq(word, expected);
+        }
+        let valid_remainder = self.lt_wide(&remainder, &nb);
+        self.assert_one(valid_remainder);
+        self.release(&check);
+        self.release(&dividend);
+        self.release(&[valid_remainder]);
+
+        // The quotient is in `(2^(w - 2), 2^w)`
+        let inexact = self.or_all(&remainder);
+        self.r(RV32IM::SLTU, inexact, ZERO, inexact);
+        self.r(RV32IM::OR, quotient[0], quotient[0], inexact);
+        let shift = self.opi(RV32IM::SRLI, quotient[words - 1], 31);
+        self.i(RV32IM::XORI, shift, shift, 1);
+        let significand = self.shl_small(&quotient, shift);
+        let exponent = self.op(RV32IM::SUB, ua.exponent, ub.exponent);
+        self.r(RV32IM::ADD, exponent, exponent, zb);
+        self.r(RV32IM::SUB, exponent, exponent, za);
+        self.r(RV32IM::SUB, exponent, exponent, shift);
+        self.i(RV32IM::ADDI, exponent, exponent, format.bias());
+        self.release(&quotient);
+        self.release(&remainder);
+        self.release(&na);
+        self.release(&nb);
+        self.release(&[za, zb, inexact, shift]);
+        self.release_unpacked(ua);
+        self.release_unpacked(ub);
+        let result = self.round_pack(format, rm, sign, exponent, &significand);
+        self.release(&significand);
+        self.release(&[exponent]);
+
+        // `0 / 0` and `inf / inf` are NaN, `x / 0` and `inf / x` infinite, and `x / inf`
+        // zero
+        let ca = self.classify(format, a);
+        let cb = self.classify(format, b);
+        let result = self.override_constant(result, cb.infinity, &vec![0; words]);
+        self.attach_sign(&result, cb.infinity, sign);
+        let infinity = self.op(RV32IM::OR, ca.infinity, cb.zero);
+        let result = self.override_infinity(format, result, infinity, sign);
+        let nan = self.op(RV32IM::AND, ca.zero, cb.zero);
+        let invalid = self.op(RV32IM::AND, ca.infinity, cb.infinity);
+        let nan = self.or_bits(nan, invalid);
+        self.r(RV32IM::OR, nan, nan, ca.nan);
+        self.r(RV32IM::OR, nan, nan, cb.nan);
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[sign, infinity, nan]);
+        self.release_class(ca);
+        self.release_class(cb);
+        result
+    }
+
+    /// `sqrt(a)`. The integer square root of the scaled significand is given as advice
+    /// and checked by squaring it.
+    pub fn float_sqrt(&mut self, format: Format, rm: RoundingMode, a: &[Reg]) -> Wide {
+        let words = format.words();
+        let width = format.width() as usize;
+        let ua = self.unpack(format, a);
+        let (normalized, shift) = self.normalize(&ua.significand);
+        // The unbiased exponent of `normalized / 2^(w - 1)`, which is halved
+        let exponent = self.opi(
+            RV32IM::ADDI,
+            ua.exponent,
+            (format.width() - 1 - format.fraction_bits()).wrapping_sub(format.bias()),
+        );
+        self.r(RV32IM::SUB, exponent, exponent, shift);
+        let odd = self.opi(RV32IM::ANDI, exponent, 1);
+        let scaled = self.shl_constant(&normalized, width - 1, 2 * words);
+        let radicand = self.shl_small(&scaled, odd);
+        self.release(&scaled);
+        self.release(&normalized);
+
+        let root: Wide = (0..words)
+            .map(|k| {
+                let radicand = radicand.clone();
+                self.advice(move |registers| {
+                    (Self::wide_value(registers, &radicand).isqrt() >> (32 * k)) as u32
+                })
+            })
+            .collect();
+        // root^2 <= radicand < (root + 1)^2
+        let square = self.mul_wide(&root, &root);
+        let too_large = self.lt_wide(&radicand, &square);
+        self.assert_zero(too_large);
+        let remainder = self.sub_wide(&radicand, &square);
+        let twice = self.shl_constant(&root, 1, 2 * words);
+        let too_small = self.lt_wide(&twice, &remainder);
+        self.assert_zero(too_small);
+        let inexact = self.or_all(&remainder);
+        self.r(RV32IM::SLTU, inexact, ZERO, inexact);
+        self.r(RV32IM::OR, root[0], root[0], inexact);
+        self.release(&radicand);
+        self.release(&square);
+        self.release(&remainder);
+        self.release(&twice);
+        self.release(&[too_large, too_small, inexact]);
+
+        self.r(RV32IM::SUB, exponent, exponent, odd);
+        self.i(RV32IM::SRAI, exponent, exponent, 1);
+        self.i(RV32IM::ADDI, exponent, exponent, format.bias());
+        let result = self.round_pack(format, rm, ua.sign, exponent, &root);
+        self.release(&root);
+        self.release(&[exponent, odd, shift]);
+
+        // The square root of a negative nonzero value is NaN, and of +inf is +inf
+        let ca = self.classify(format, a);
+        let result = self.override_infinity(format, result, ca.infinity, ZERO);
+        let nan = self.not(ca.zero);
+        self.r(RV32IM::AND, nan, nan, ua.sign);
+        self.r(RV32IM::OR, nan, nan, ca.nan);
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[nan]);
+        self.release_class(ca);
+        self.release_unpacked(ua);
+        result
+    }
+
+    /// `(-1)^negate_product * a * b + (-1)^negate_addend * c`, with a single rounding.
+    #[allow(clippy::too_many_arguments)]
+    pub fn float_fma(
+        &mut self,
+        format: Format,
+        rm: RoundingMode,
+        a: &[Reg],
+        b: &[Reg],
+        c: &[Reg],
+        negate_product: bool,
+        negate_addend: bool,
+    ) -> Wide {
+        let words = format.words();
+        let width = format.width() as usize;
+
+        // The exact product, and the addend, as significands of `2 * words` words with
+        // their leading ones at the most significant bit
+        let ua = self.unpack(format, a);
+        let ub = self.unpack(format, b);
+        let product_sign = self.op(RV32IM::XOR, ua.sign, ub.sign);
+        if negate_product {
+            self.i(RV32IM::XORI, product_sign, product_sign, 1);
+        }
+        let product = self.mul_wide(&ua.significand, &ub.significand);
+        let (normalized, shift) = self.normalize(&product);
+        self.release(&product);
+        let product_exponent = self.op(RV32IM::ADD, ua.exponent, ub.exponent);
+        let offset = 2 * format.width() - 1 - 2 * format.fraction_bits();
+        self.i(
+            RV32IM::ADDI,
+            product_exponent,
+            product_exponent,
+            offset.wrapping_sub(format.bias()),
+        );
+        self.r(RV32IM::SUB, product_exponent, product_exponent, shift);
+        self.release_unpacked(ua);
+        self.release_unpacked(ub);
+        self.release(&[shift]);
+        let product_zero = self.is_zero(&normalized);
+        let product = self.shr_constant(&normalized, 1, 2 * words);
+        self.release(&normalized);
+
+        let uc = self.unpack(format, c);
+        if negate_addend {
+            self.i(RV32IM::XORI, uc.sign, uc.sign, 1);
+        }
+        let (normalized, shift) = self.normalize(&uc.significand);
+        let addend_exponent = self.opi(
+            RV32IM::ADDI,
+            uc.exponent,
+            format.width() - 1 - format.fraction_bits(),
+        );
+        self.r(RV32IM::SUB, addend_exponent, addend_exponent, shift);
+        let addend_zero = self.is_zero(&normalized);
+        let addend = self.shl_constant(&normalized, width - 1, 2 * words);
+        self.release(&normalized);
+        self.release(&[shift]);
+        let addend_sign = uc.sign;
+        self.release(&[uc.exponent]);
+        self.release(&uc.significand);
+
+        // Zero operands have the lowest exponent, so that they are aligned to the other
+        for (exponent, zero) in [
+            (product_exponent, product_zero),
+            (addend_exponent, addend_zero),
+        ] {
+            let mask = self.mask(zero);
+            let replaced = self.select_constant(mask, ZERO_EXPONENT, exponent);
+            self.mv(exponent, replaced);
+            self.release(&[mask, replaced, zero]);
+        }
+
+        // Order the terms by exponent, and add them as in `float_add`
+        let swap = self.op(RV32IM::SLT, product_exponent, addend_exponent);
+        let mask = self.mask(swap);
+        let swap_fields = |asm: &mut Self, a: Reg, b: Reg| {
+            let x = asm.select_masked(mask, b, a);
+            let y = asm.op(RV32IM::XOR, a, b);
+            asm.r(RV32IM::XOR, y, y, x);
+            (x, y)
+        };
+        let (x_sign, y_sign) = swap_fields(self, product_sign, addend_sign);
+        let (x_exponent, y_exponent) = swap_fields(self, product_exponent, addend_exponent);
+        let (x, y): (Wide, Wide) = (0..2 * words)
+            .map(|k| swap_fields(self, product[k], addend[k]))
+            .unzip();
+        self.release(&[mask, swap, product_exponent, addend_exponent]);
+        self.release(&product);
+        self.release(&addend);
+
+        let distance = self.op(RV32IM::SUB, x_exponent, y_exponent);
+        let y_aligned = self.shr_sticky(&y, distance);
+        self.release(&y);
+        self.release(&[distance, y_exponent]);
+        let difference = self.op(RV32IM::XOR, x_sign, y_sign);
+        let y_signed = self.negate_if(&y_aligned, difference);
+        self.release(&y_aligned);
+        let sum = self.add_wide(&x, &y_signed);
+        self.release(&x);
+        self.release(&y_signed);
+        // Unlike in `float_add`, the terms may be equal in exponent but not in
+        // magnitude, so the difference may be negative
+        let negative = self.opi(RV32IM::SRLI, sum[2 * words - 1], 31);
+        self.r(RV32IM::AND, negative, negative, difference);
+        let magnitude = self.negate_if(&sum, negative);
+        self.release(&sum);
+        self.r(RV32IM::XOR, x_sign, x_sign, negative);
+        self.release(&[negative, difference]);
+
+        let sum_zero = self.is_zero(&magnitude);
+        let zero_sign = if rm == RoundingMode::Down {
+            self.op(RV32IM::OR, product_sign, addend_sign)
+        } else {
+            self.op(RV32IM::AND, product_sign, addend_sign)
+        };
+        let sign = self.select(sum_zero, zero_sign, x_sign);
+        self.release(&[sum_zero, zero_sign, x_sign, y_sign]);
+        let (normalized, shift) = self.normalize(&magnitude);
+        self.release(&magnitude);
+        self.i(RV32IM::ADDI, x_exponent, x_exponent, 1);
+        self.r(RV32IM::SUB, x_exponent, x_exponent, shift);
+        let significand = self.truncate_sticky(&normalized, words);
+        self.release(&normalized);
+        let result = self.round_pack(format, rm, sign, x_exponent, &significand);
+        self.release(&significand);
+        self.release(&[sign, x_exponent, shift]);
+
+        // NaN operands, `inf * 0`, and an infinite product plus an infinite addend of
+        // the opposite sign give NaN
+        let ca = self.classify(format, a);
+        let cb = self.classify(format, b);
+        let cc = self.classify(format, c);
+        let product_infinity = self.op(RV32IM::OR, ca.infinity, cb.infinity);
+        let infinity = self.op(RV32IM::OR, product_infinity, cc.infinity);
+        let infinity_sign = self.select(product_infinity, product_sign, addend_sign);
+        let result = self.override_infinity(format, result, infinity, infinity_sign);
+        let nan = self.op(RV32IM::AND, ca.infinity, cb.zero);
+        let invalid = self.op(RV32IM::AND, cb.infinity, ca.zero);
+        let nan = self.or_bits(nan, invalid);
+        let invalid = self.op(RV32IM::XOR, product_sign, addend_sign);
+        self.r(RV32IM::AND, invalid, invalid, product_infinity);
+        self.r(RV32IM::AND, invalid, invalid, cc.infinity);
+        let nan = self.or_bits(nan, invalid);
+        for class_nan in [ca.nan, cb.nan, cc.nan] {
+            self.r(RV32IM::OR, nan, nan, class_nan);
+        }
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[product_sign, addend_sign, product_infinity, infinity]);
+        self.release(&[infinity_sign, nan]);
+        self.release_class(ca);
+        self.release_class(cb);
+        self.release_class(cc);
+        result
+    }
+
+    /// `FCVT.W` (if `signed`) or `FCVT.WU`: `a` rounded to an integer and saturated.
+    pub fn float_to_int(
+        &mut self,
+        format: Format,
+        rm: RoundingMode,
+        a: &[Reg],
+        signed: bool,
+    ) -> Reg {
+        let words = format.words();
+        let ua = self.unpack(format, a);
+        let nan = self.is_nan(format, a);
+
+        // A fixed-point value with the integer part in the most significant word
+        let fixed = self.shl_constant(
+            &ua.significand,
+            32 * (words + 1) - 1 - format.fraction_bits() as usize,
+            words + 1,
+        );
+        let shift = self.op(RV32IM::SUB, ZERO, ua.exponent);
+        self.i(RV32IM::ADDI, shift, shift, format.bias() + 31);
+        let overflow = self.opi(RV32IM::SLTI, shift, 0);
+        let in_range = self.opi(RV32IM::ADDI, overflow, u32::MAX);
+        self.r(RV32IM::AND, shift, shift, in_range);
+        let fixed_shifted = self.shr_sticky(&fixed, shift);
+        self.release(&fixed);
+        self.release(&[shift, in_range]);
+        let integer = fixed_shifted[words];
+        let fraction = &fixed_shifted[..words];
+
+        let increment = match rm {
+            RoundingMode::NearestEven => {
+                let half = self.opi(RV32IM::SRLI, fraction[words - 1], 31);
+                let rest = self.opi(RV32IM::ANDI, fraction[words - 1], 0x7fffffff);
+                for &word in &fraction[..words - 1] {
+                    self.r(RV32IM::OR, rest, rest, word);
+                }
+                self.r(RV32IM::SLTU, rest, ZERO, rest);
+                let lsb = self.opi(RV32IM::ANDI, integer, 1);
+                let rest = self.or_bits(rest, lsb);
+                Some(self.and_bits(half, rest))
+            }
+            RoundingMode::TowardZero => None,
+            RoundingMode::NearestMaxMagnitude => {
+                Some(self.opi(RV32IM::SRLI, fraction[words - 1], 31))
+            }
+            RoundingMode::Down | RoundingMode::Up => {
+                let inexact = self.or_all(fraction);
+                self.r(RV32IM::SLTU, inexact, ZERO, inexact);
+                let direction = if rm == RoundingMode::Down {
+                    self.opi(RV32IM::ADDI, ua.sign, 0)
+                } else {
+                    self.not(ua.sign)
+                };
+                Some(self.and_bits(inexact, direction))
+            }
+        };
+        if let Some(increment) = increment {
+            self.r(RV32IM::ADD, integer, integer, increment);
+            self.r(RV32IM::SLTU, increment, integer, increment);
+            self.r(RV32IM::OR, overflow, overflow, increment);
+            self.release(&[increment]);
+        }
+
+        // NaN has the maximal exponent, so overflows
+        let negative = self.not(nan);
+        self.r(RV32IM::AND, negative, negative, ua.sign);
+        let result = if signed {
+            let limit = self.opi(RV32IM::ADDI, negative, 0x80000000);
+            let valid = self.op(RV32IM::SLTU, integer, limit);
+            let invalid = self.not(valid);
+            let invalid = self.or_bits(invalid, overflow);
+            let value = self.negate_if(&[integer], negative)[0];
+            let saturated = self.opi(RV32IM::ADDI, negative, 0x7fffffff);
+            let result = self.select(invalid, saturated, value);
+            self.release(&[limit, valid, invalid, value, saturated]);
+            result
+        } else {
+            let mask = self.mask(overflow);
+            let result = self.select_constant(mask, u32::MAX, integer);
+            let non_negative = self.opi(RV32IM::ADDI, negative, u32::MAX);
+            self.r(RV32IM::AND, result, result, non_negative);
+            self.release(&[mask, non_negative, overflow]);
+            result
+        };
+        self.release(&fixed_shifted);
+        self.release(&[nan, negative]);
+        self.release_unpacked(ua);
+        result
+    }
+
+    /// `FCVT.S.W`/`FCVT.D.W` (if `signed`) or `FCVT.S.WU`/`FCVT.D.WU`.
+    pub fn int_to_float(&mut self, format: Format, rm: RoundingMode, x: Reg, signed: bool) -> Wide {
+        let (sign, magnitude) = if signed {
+            let sign = self.opi(RV32IM::SRLI, x, 31);
+            let magnitude = self.negate_if(&[x], sign)[0];
+            (sign, magnitude)
+        } else {
+            (ZERO, x)
+        };
+        let mut significand = vec![ZERO; format.words() - 1];
+        significand.push(magnitude);
+        let (normalized, shift) = self.normalize(&significand);
+        let exponent = self.op(RV32IM::SUB, ZERO, shift);
+        self.i(RV32IM::ADDI, exponent, exponent, format.bias() + 31);
+        let result = self.round_pack(format, rm, sign, exponent, &normalized);
+        self.release(&normalized);
+        self.release(&[sign, magnitude, shift, exponent]);
+        result
+    }
+
+    /// `FCVT.S.D` (if `format` is `S`) or `FCVT.D.S`.
+    pub fn float_convert(&mut self, format: Format, rm: RoundingMode, a: &[Reg]) -> Wide {
+        let from = match format {
+            Format::S => Format::D,
+            Format::D => Format::S,
+        };
+        let ua = self.unpack(from, a);
+        let (significand, exponent) = match format {
+            Format::S => {
+                let shifted = self.shl_constant(&ua.significand, 11, 2);
+                let significand = self.truncate_sticky(&shifted, 1);
+                self.release(&shifted);
+                let exponent = self.opi(
+                    RV32IM::ADDI,
+                    ua.exponent,
+                    Format::S.bias().wrapping_sub(Format::D.bias()),
+                );
+                (significand, exponent)
+            }
+            Format::D => {
+                let (normalized, shift) = self.normalize(&ua.significand);
+                let significand = self.shl_constant(&normalized, 32, 2);
+                self.release(&normalized);
+                let exponent = self.opi(
+                    RV32IM::ADDI,
+                    ua.exponent,
+                    Format::D.bias() - Format::S.bias() + 8,
+                );
+                self.r(RV32IM::SUB, exponent, exponent, shift);
+                self.release(&[shift]);
+                (significand, exponent)
+            }
+        };
+        let result = self.round_pack(format, rm, ua.sign, exponent, &significand);
+        self.release(&significand);
+        self.release(&[exponent]);
+
+        let class = self.classify(from, a);
+        let result = self.override_infinity(format, result, class.infinity, ua.sign);
+        let result = self.override_constant(result, class.nan, &format.nan());
+        self.release_class(class);
+        self.release_unpacked(ua);
+        result
+    }
+
+    /// The words of `x` transformed so that they order values as unsigned integers,
+    /// with `-0` below `+0`.
+    fn order_key(&mut self, x: &[Reg]) -> Wide {
+        let top = *x.last().unwrap();
+        let mask = self.opi(RV32IM::SRAI, top, 31);
+        let mut key: Wide = x[..x.len() - 1]
+            .iter()
+            .map(|&word| self.op(RV32IM::XOR, word, mask))
+            .collect();
+        self.i(RV32IM::ORI, mask, mask, 0x80000000);
+        self.r(RV32IM::XOR, mask, mask, top);
+        key.push(mask);
+        key
+    }
+
+    /// Whether `a` and `b` are both zero, of either sign.
+    fn both_zero(&mut self, a: &[Reg], b: &[Reg]) -> Reg {
+        let words = a.len();
+        let any = self.op(RV32IM::OR, a[words - 1], b[words - 1]);
+        self.i(RV32IM::SLLI, any, any, 1);
+        for k in 0..words - 1 {
+            self.r(RV32IM::OR, any, any, a[k]);
+            self.r(RV32IM::OR, any, any, b[k]);
+        }
+        self.i(RV32IM::SLTIU, any, any, 1);
+        any
+    }
+
+    /// `FEQ`, `FLT` or `FLE`, according to `opcode`'s comparison.
+    pub fn float_compare(&mut self, format: Format, a: &[Reg], b: &[Reg], opcode: RV32IM) -> Reg {
+        let na = self.is_nan(format, a);
+        let nb = self.is_nan(format, b);
+        let unordered = self.or_bits(na, nb);
+        let zeros = self.both_zero(a, b);
+        let result = match opcode {
+            RV32IM::FEQ_S | RV32IM::FEQ_D => {
+                let equal = self.eq_wide(a, b);
+                self.or_bits(equal, zeros)
+            }
+            _ => {
+                let (ka, kb) = (self.order_key(a), self.order_key(b));
+                let result = if matches!(opcode, RV32IM::FLT_S | RV32IM::FLT_D) {
+                    let less = self.lt_wide(&ka, &kb);
+                    let nonzero = self.not(zeros);
+                    self.release(&[zeros]);
+                    self.and_bits(less, nonzero)
+                } else {
+                    let greater = self.lt_wide(&kb, &ka);
+                    self.i(RV32IM::XORI, greater, greater, 1);
+                    self.or_bits(greater, zeros)
+                };
+                self.release(&ka);
+                self.release(&kb);
+                result
+            }
+        };
+        self.i(RV32IM::XORI, unordered, unordered, 1);
+        self.and_bits(result, unordered)
+    }
+
+    /// `FMIN` or `FMAX`: a NaN operand is ignored, and `-0` is less than `+0`.
+    pub fn float_min_max(&mut self, format: Format, a: &[Reg], b: &[Reg], max: bool) -> Wide {
+        let na = self.is_nan(format, a);
+        let nb = self.is_nan(format, b);
+        let (ka, kb) = (self.order_key(a), self.order_key(b));
+        let choose_a = if max {
+            self.lt_wide(&kb, &ka)
+        } else {
+            self.lt_wide(&ka, &kb)
+        };
+        self.release(&ka);
+        self.release(&kb);
+        let a_ordered = self.not(na);
+        let choose_a = self.and_bits(choose_a, a_ordered);
+        self.r(RV32IM::OR, choose_a, choose_a, nb);
+        let mask = self.mask(choose_a);
+        let result: Wide = a
+            .iter()
+            .zip(b)
+            .map(|(&a, &b)| self.select_masked(mask, a, b))
+            .collect();
+        let nan = self.and_bits(na, nb);
+        let result = self.override_constant(result, nan, &format.nan());
+        self.release(&[choose_a, mask, nan]);
+        result
+    }
+
+    /// `FSGNJ`, `FSGNJN` or `FSGNJX`: `a` with the sign bit of `b`, of its complement,
+    /// or of the XOR of the operands' signs.
+    pub fn float_sign_inject(&mut self, a: &[Reg], b: &[Reg], opcode: RV32IM) -> Wide {
+        let words = a.len();
+        let (a_top, b_top) = (a[words - 1], b[words - 1]);
+        let sign = match opcode {
+            RV32IM::FSGNJ_S | RV32IM::FSGNJ_D => self.opi(RV32IM::ADDI, b_top, 0),
+            RV32IM::FSGNJN_S | RV32IM::FSGNJN_D => self.opi(RV32IM::XORI, b_top, 0x80000000),
+            _ => self.op(RV32IM::XOR, a_top, b_top),
+        };
+        self.i(RV32IM::ANDI, sign, sign, 0x80000000);
+        let mut result = self.copy(&a[..words - 1]);
+        let top = self.opi(RV32IM::ANDI, a_top, 0x7fffffff);
+        self.r(RV32IM::OR, top, top, sign);
+        self.release(&[sign]);
+        result.push(top);
+        result
+    }
+
+    /// `FCLASS`: the mask with the bit of `a`'s class set.
+    pub fn float_class(&mut self, format: Format, a: &[Reg]) -> Reg {
+        let top = *a.last().unwrap();
+        let key = self.class_key(a);
+        let zero = self.seqz(key);
+        let exponent_zero = self.opi(RV32IM::SLTIU, key, 1 << (format.top_fraction_bits() + 1));
+        let exponent_max = self.opi(RV32IM::SLTIU, key, format.infinity() << 1);
+        self.i(RV32IM::XORI, exponent_max, exponent_max, 1);
+        let nan = self.is_nan_key(format, key);
+
+        // 0 for infinity, 1 normal, 2 subnormal and 3 zero; the classes of positive
+        // values are in the reverse order, from bit 7 down
+        let class = self.opi(RV32IM::ADDI, exponent_zero, 1);
+        self.r(RV32IM::ADD, class, class, zero);
+        self.r(RV32IM::SUB, class, class, exponent_max);
+        let positive = self.opi(RV32IM::SRLI, top, 31);
+        self.i(RV32IM::ADDI, positive, positive, u32::MAX);
+        self.i(RV32IM::ANDI, positive, positive, 7);
+        self.r(RV32IM::XOR, class, class, positive);
+        // 8 for a signaling NaN and 9 for a quiet NaN
+        let nan_class = self.opi(RV32IM::SRLI, top, format.top_fraction_bits() - 1);
+        self.i(RV32IM::ANDI, nan_class, nan_class, 1);
+        self.i(RV32IM::ADDI, nan_class, nan_class, 8);
+        let bit = self.select(nan, nan_class, class);
+        let one = self.constant(1);
+        let result = self.op(RV32IM::SLL, one, bit);
+        self.release(&[key, zero, exponent_zero, exponent_max, nan, class, positive]);
+        self.release(&[nan_class, bit, one]);
+        result
+    }
+}
@@ -0,0 +1,328 @@
+//! Arithmetic on multi-word integers ([`Wide`]). Every function returns newly allocated
+//! registers; its operands may be borrowed registers or `ZERO`.
+
+use tracer::RV32IM;
+
+use super::assembler::{Assembler, Reg, Wide, ZERO};
+
+impl Assembler {
+    /// A new copy of `x`.
+    pub fn copy(&mut self, x: &[Reg]) -> Wide {
+        x.iter()
+            .map(|&limb| {
+                let rd = self.alloc();
+                self.mv(rd, limb);
+                rd
+            })
+            .collect()
+    }
+
+    /// A new register holding `x == 0`.
+    pub fn is_zero(&mut self, x: &[Reg]) -> Reg {
+        let any = self.or_all(x);
+        let rd = self.seqz(any);
+        self.release(&[any]);
+        rd
+    }
+
+    /// A new register holding `x == y`.
+    pub fn eq_wide(&mut self, x: &[Reg], y: &[Reg]) -> Reg {
+        let diff: Wide = x
+            .iter()
+            .zip(y)
+            .map(|(&a, &b)| self.op(RV32IM::XOR, a, b))
+            .collect();
+        let rd = self.is_zero(&diff);
+        self.release(&diff);
+        rd
+    }
+
+    /// A new register holding `x < y` (unsigned).
+    pub fn lt_wide(&mut self, x: &[Reg], y: &[Reg]) -> Reg {
+        let borrow = self.op(RV32IM::SLTU, x[0], y[0]);
+        for (&a, &b) in x.iter().zip(y).skip(1) {
+            let diff = self.op(RV32IM::SUB, a, b);
+            let below = self.op(RV32IM::SLTU, a, b);
+            self.r(RV32IM::SLTU, diff, diff, borrow);
+            self.r(RV32IM::OR, borrow, below, diff);
+            self.release(&[diff, below]);
+        }
+        borrow
+    }
+
+    /// `x += v` for an owned `x` and a word `v`, returning the carry out.
+    pub fn add_word_in_place(&mut self, x: &[Reg], v: Reg) -> Reg {
+        self.r(RV32IM::ADD, x[0], x[0], v);
+        let carry = self.op(RV32IM::SLTU, x[0], v);
+        for &limb in &x[1..] {
+            self.r(RV32IM::ADD, limb, limb, carry);
+            self.r(RV32IM::SLTU, carry, limb, carry);
+        }
+        carry
+    }
+
+    /// `x += y << (32 * offset)` for an owned `x`, discarding the carry out.
+    pub fn add_at_in_place(&mut self, x: &[Reg], offset: usize, y: &[Reg]) {
+        let mut carry: Option<Reg> = None;
+        for (k, &limb) in x.iter().enumerate().skip(offset) {
+            let last = k + 1 == x.len();
+            let addend = y.get(k - offset).copied();
+            let mut carries = vec![];
+            if let Some(addend) = addend {
+                self.r(RV32IM::ADD, limb, limb, addend);
+                if !last {
+                    carries.push(self.op(RV32IM::SLTU, limb, addend));
+                }
+            } else if carry.is_none() {
+                break;
+            }
+            if let Some(carry) = carry {
+                self.r(RV32IM::ADD, limb, limb, carry);
+                if !last {
+                    carries.push(self.op(RV32IM::SLTU, limb, carry));
+                }
+                self.release(&[carry]);
+            }
+            carry = match carries[..] {
+                [] => None,
+                [c] => Some(c),
+                [c, d] => Some(self.or_bits(c, d)),
+                _ => unreachable!(),
+            };
+        }
+        if let Some(carry) = carry {
+            self.release(&[carry]);
+        }
+    }
+
+    /// `x + y` modulo `2^(32 * x.len())`.
+    pub fn add_wide(&mut self, x: &[Reg], y: &[Reg]) -> Wide {
+        let sum = self.copy(x);
+        self.add_at_in_place(&sum, 0, y);
+        sum
+    }
+
+    /// `x - y` modulo `2^(32 * x.len())`.
+    pub fn sub_wide(&mut self, x: &[Reg], y: &[Reg]) -> Wide {
+        let mut borrow: Option<Reg> = None;
+        let mut diff = vec![];
+        for (k, (&a, &b)) in x.iter().zip(y).enumerate() {
+            let d = self.op(RV32IM::SUB, a, b);
+            let last = k + 1 == x.len();
+            let below = (!last).then(|| self.op(RV32IM::SLTU, a, b));
+            if let Some(borrow) = borrow {
+                if let Some(below) = below {
+                    let under = self.op(RV32IM::SLTU, d, borrow);
+                    self.r(RV32IM::OR, below, below, under);
+                    self.release(&[under]);
+                }
+                self.r(RV32IM::SUB, d, d, borrow);
+                self.release(&[borrow]);
+            }
+            borrow = below;
+            diff.push(d);
+        }
+        diff
+    }
+
+    /// `-x` if `bit` is set, and `x` otherwise, modulo `2^(32 * x.len())`.
+    pub fn negate_if(&mut self, x: &[Reg], bit: Reg) -> Wide {
+        let mask = self.mask(bit);
+        let y: Wide = x
+            .iter()
+            .map(|&limb| self.op(RV32IM::XOR, limb, mask))
+            .collect();
+        let carry = self.add_word_in_place(&y, bit);
+        self.release(&[mask, carry]);
+        y
+    }
+
+    /// `x << k` in `len` words, for a constant `k`.
+    pub fn shl_constant(&mut self, x: &[Reg], k: usize, len: usize) -> Wide {
+        let (q, r) = (k / 32, (k % 32) as u32);
+        let limb = |j: usize| {
+            j.checked_sub(q)
+                .and_then(|j| x.get(j).copied())
+                .unwrap_or(ZERO)
+        };
+        (0..len)
+            .map(|j| {
+                let rd = self.opi(RV32IM::SLLI, limb(j), r);
+                if r != 0 && j > q {
+                    let low = self.opi(RV32IM::SRLI, limb(j - 1), 32 - r);
+                    self.r(RV32IM::OR, rd, rd, low);
+                    self.release(&[low]);
+                }
+                rd
+            })
+            .collect()
+    }
+
+    /// `x >> k` in `len` words, for a constant `k`.
+    pub fn shr_constant(&mut self, x: &[Reg], k: usize, len: usize) -> Wide {
+        let (q, r) = (k / 32, (k % 32) as u32);
+        let limb = |j: usize| x.get(j + q).copied().unwrap_or(ZERO);
+        (0..len)
+            .map(|j| {
+                let rd = self.opi(RV32IM::SRLI, limb(j), r);
+                if r != 0 && j + q + 1 < x.len() {
+                    let high = self.opi(RV32IM::SLLI, limb(j + 1), 32 - r);
+                    self.r(RV32IM::OR, rd, rd, high);
+                    self.release(&[high]);
+                }
+                rd
+            })
+            .collect()
+    }
+
+    /// `x << r` modulo `2^(32 * x.len())`, for `r < 32`.
+    pub fn shl_small(&mut self, x: &[Reg], r: Reg) -> Wide {
+        let complement = self.opi(RV32IM::XORI, r, 31);
+        let shifted = (0..x.len())
+            .map(|j| {
+                let rd = self.op(RV32IM::SLL, x[j], r);
+                if j > 0 {
+                    // `x[j - 1] >> (32 - r)`, which is zero if `r` is zero
+                    let low = self.opi(RV32IM::SRLI, x[j - 1], 1);
+                    self.r(RV32IM::SRL, low, low, complement);
+                    self.r(RV32IM::OR, rd, rd, low);
+                    self.release(&[low]);
+                }
+                rd
+            })
+            .collect();
+        self.release(&[complement]);
+        shifted
+    }
+
+    /// `x >> r`, for `r < 32`.
+    pub fn shr_small(&mut self, x: &[Reg], r: Reg) -> Wide {
+        let complement = self.opi(RV32IM::XORI, r, 31);
+        let shifted = (0..x.len())
+            .map(|j| {
+                let rd = self.op(RV32IM::SRL, x[j], r);
+                if j + 1 < x.len() {
+                    // `x[j + 1] << (32 - r)`, which is zero if `r` is zero
+                    let high = self.opi(RV32IM::SLLI, x[j + 1], 1);
+                    self.r(RV32IM::SLL, high, high, complement);
+                    self.r(RV32IM::OR, rd, rd, high);
+                    self.release(&[high]);
+                }
+                rd
+            })
+            .collect();
+        self.release(&[complement]);
+        shifted
+    }
+
+    /// `x >> amount`, with the shifted-out bits ORed into the least significant bit
+    /// ("sticky"). `amount` is an unsigned word; amounts of at least `32 * x.len() - 1`
+    /// leave only the sticky bit.
+    pub fn shr_sticky(&mut self, x: &[Reg], amount: Reg) -> Wide {
+        let width = 32 * x.len() as u32;
+        let in_range = self.opi(RV32IM::SLTIU, amount, width - 1);
+        let out_of_range = self.opi(RV32IM::ADDI, in_range, u32::MAX);
+        let amount = self.select_constant(out_of_range, width - 1, amount);
+        self.release(&[in_range, out_of_range]);
+
+        // Shift by whole words
+        let mut x = self.copy(x);
+        let mut sticky = None;
+        let mut step = 1;
+        while step < x.len() {
+            let bit = self.opi(RV32IM::SRLI, amount, 5 + step.trailing_zeros());
+            self.i(RV32IM::ANDI, bit, bit, 1);
+            let mask = self.mask(bit);
+            let dropped = self.or_all(&x[..step]);
+            self.r(RV32IM::AND, dropped, dropped, mask);
+            sticky = Some(match sticky {
+                Some(sticky) => self.or_bits(sticky, dropped),
+                None => dropped,
+            });
+            let shifted: Wide = (0..x.len())
+                .map(|j| {
+                    let high = x.get(j + step).copied().unwrap_or(ZERO);
+                    self.select_masked(mask, high, x[j])
+                })
+                .collect();
+            self.release(&x);
+            self.release(&[bit, mask]);
+            x = shifted;
+            step *= 2;
+        }
+
+        // Shift by the remaining bits
+        let r = self.opi(RV32IM::ANDI, amount, 31);
+        let shifted = self.shr_small(&x, r);
+        let complement = self.opi(RV32IM::XORI, r, 31);
+        let lost = self.opi(RV32IM::SLLI, x[0], 1);
+        self.r(RV32IM::SLL, lost, lost, complement);
+        let sticky = match sticky {
+            Some(sticky) => self.or_bits(sticky, lost),
+            None => lost,
+        };
+        let sticky_bit = self.snez(sticky);
+        self.r(RV32IM::OR, shifted[0], shifted[0], sticky_bit);
+        self.release(&x);
+        self.release(&[amount, r, complement, sticky, sticky_bit]);
+        shifted
+    }
+
+    /// Shifts `x` left until its most significant bit is set, returning the shifted
+    /// value and the shift. A zero `x` gives zero and an unspecified shift.
+    pub fn normalize(&mut self, x: &[Reg]) -> (Wide, Reg) {
+        let mut x = self.copy(x);
+        let shift = self.constant(0);
+
+        // Shift by whole words
+        for _ in 1..x.len() {
+            let top = *x.last().unwrap();
+            let bit = self.seqz(top);
+            let mask = self.mask(bit);
+            let shifted: Wide = (0..x.len())
+                .map(|j| {
+                    let low = if j == 0 { ZERO } else { x[j - 1] };
+                    self.select_masked(mask, low, x[j])
+                })
+                .collect();
+            self.i(RV32IM::SLLI, bit, bit, 5);
+            self.r(RV32IM::ADD, shift, shift, bit);
+            self.release(&x);
+            self.release(&[bit, mask]);
+            x = shifted;
+        }
+
+        // Shift by the leading zeros of the top word, which are given as advice and
+        // checked by shifting the leading one back down. A zero top word is checked
+        // as if it were `0x80000000`.
+        let top = *x.last().unwrap();
+        let word = self.seqz(top);
+        self.i(RV32IM::SLLI, word, word, 31);
+        self.r(RV32IM::OR, word, word, top);
+        let zeros = self.advice(|registers| (registers[word as usize] as u32).leading_zeros());
+        self.i(RV32IM::ANDI, zeros, zeros, 31);
+        let complement = self.opi(RV32IM::XORI, zeros, 31);
+        self.r(RV32IM::SRL, word, word, complement);
+        self.i(RV32IM::XORI, word, word, 1);
+        self.assert_eq(word, ZERO);
+        let normalized = self.shl_small(&x, zeros);
+        self.r(RV32IM::ADD, shift, shift, zeros);
+        self.release(&x);
+        self.release(&[word, zeros, complement]);
+        (normalized, shift)
+    }
+
+    /// `x * y` in `x.len() + y.len()` words.
+    pub fn mul_wide(&mut self, x: &[Reg], y: &[Reg]) -> Wide {
+        let product: Wide = (0..x.len() + y.len()).map(|_| self.constant(0)).collect();
+        for (i, &a) in x.iter().enumerate() {
+            for (j, &b) in y.iter().enumerate() {
+                let lo = self.op(RV32IM::MUL, a, b);
+                let hi = self.op(RV32IM::MULHU, a, b);
+                self.add_at_in_place(&product, i + j, &[lo, hi]);
+                self.release(&[lo, hi]);
+            }
+        }
+        product
+    }
+}
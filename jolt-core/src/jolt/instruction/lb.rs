@@ -49,6 +49,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(rs1_val),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(ram_address),
             },
             memory_state: None,
@@ -73,6 +74,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_address),
             },
             memory_state: None,
@@ -110,6 +112,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word),
             },
             memory_state: Some(MemoryState::Read {
@@ -135,6 +138,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(byte_shift),
             },
             memory_state: None,
@@ -157,6 +161,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(byte_shift),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(bit_shift),
             },
             memory_state: None,
@@ -179,6 +184,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(left_aligned_byte),
             },
             memory_state: None,
@@ -202,6 +208,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(left_aligned_byte),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(sign_extended_byte),
             },
             memory_state: None,
@@ -223,6 +230,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(0),
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(0),
             },
             memory_state: Some(MemoryState::Read {
@@ -288,6 +296,7 @@ mod test {
                 register_state: RegisterState {
                     rs1_val: Some(rs1_val),
                     rs2_val: None,
+                    rs3_val: None,
                     rd_post_val: Some(byte),
                 },
                 memory_state: Some(MemoryState::Read {
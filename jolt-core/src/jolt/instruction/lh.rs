@@ -52,6 +52,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(rs1_val),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -74,6 +75,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(rs1_val),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(ram_address),
             },
             memory_state: None,
@@ -98,6 +100,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_address),
             },
             memory_state: None,
@@ -135,6 +138,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word),
             },
             memory_state: Some(MemoryState::Read {
@@ -160,6 +164,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(byte_shift),
             },
             memory_state: None,
@@ -182,6 +187,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(byte_shift),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(bit_shift),
             },
             memory_state: None,
@@ -204,6 +210,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(left_aligned_halfword),
             },
             memory_state: None,
@@ -228,6 +235,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(left_aligned_halfword),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(sign_extended_halfword),
             },
             memory_state: None,
@@ -249,6 +257,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for LHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(0),
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(0),
             },
             memory_state: Some(MemoryState::Read {
@@ -312,6 +321,7 @@ mod test {
                 register_state: RegisterState {
                     rs1_val: Some(rs1_val),
                     rs2_val: None,
+                    rs3_val: None,
                     rd_post_val: Some(halfword),
                 },
                 memory_state: Some(MemoryState::Read {
@@ -4,7 +4,6 @@ use beq::BEQInstruction;
 use bge::BGEInstruction;
 use bgeu::BGEUInstruction;
 use bne::BNEInstruction;
-use enum_dispatch::enum_dispatch;
 use fixedbitset::*;
 use mul::MULInstruction;
 use mulhu::MULHUInstruction;
@@ -43,7 +42,6 @@ use crate::utils::interleave_bits;
 use common::rv_trace::ELFInstruction;
 use std::fmt::Debug;
 
-#[enum_dispatch]
 pub trait JoltInstruction: Clone + Debug + Send + Sync + Serialize {
     /// Converts this instruction's operands into a lookup index (as used in sparse-dense Shout).
     /// By default, interleaves the two bits of the two operands together.
@@ -128,14 +126,27 @@ pub trait JoltInstruction: Clone + Debug + Send + Sync + Serialize {
     }
 }
 
+/// An enum of `JoltInstruction`s, generated by the `instruction_set!` macro.
 pub trait JoltInstructionSet:
-    JoltInstruction + IntoEnumIterator + EnumCount + for<'a> TryFrom<&'a ELFInstruction> + Send + Sync
+    JoltInstruction
+    + Copy
+    + PartialEq
+    + Default
+    + IntoEnumIterator
+    + EnumCount
+    + for<'a> TryFrom<&'a ELFInstruction>
+    + Send
+    + Sync
 {
     fn enum_index(instruction: &Self) -> usize {
         // Discriminant: https://doc.rust-lang.org/reference/items/enumerations.html#pointer-casting
         let byte = unsafe { *(instruction as *const Self as *const u8) };
         byte as usize
     }
+
+    /// Registers the semantics of this set's custom instructions (if any) with the
+    /// tracer, so that it can execute them. See `extension::CustomInstruction`.
+    fn register_custom_instructions() {}
 }
 
 #[derive(Clone)]
@@ -191,6 +202,7 @@ pub trait VirtualInstructionSequence {
             register_state: RegisterState {
                 rs1_val: Some(0),
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(0),
             },
             memory_state: None,
@@ -218,6 +230,7 @@ pub mod bgeu;
 pub mod bne;
 pub mod div;
 pub mod divu;
+pub mod float;
 pub mod lb;
 pub mod lbu;
 pub mod lh;
@@ -227,7 +240,9 @@ pub mod mulh;
 pub mod mulhsu;
 pub mod mulhu;
 pub mod mulu;
+pub mod narrow_shift;
 pub mod or;
+pub mod precompile;
 pub mod rem;
 pub mod remu;
 pub mod sb;
@@ -44,6 +44,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(x),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(s_x),
             },
             memory_state: None,
@@ -66,6 +67,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(y),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(s_y),
             },
             memory_state: None,
@@ -88,6 +90,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(x),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(xy_high_bits),
             },
             memory_state: None,
@@ -110,6 +113,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(s_x),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(sx_y_low_bits),
             },
             memory_state: None,
@@ -132,6 +136,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(s_y),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: Some(sy_x_low_bits),
             },
             memory_state: None,
@@ -154,6 +159,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(xy_high_bits),
                 rs2_val: Some(sx_y_low_bits),
+                rs3_val: None,
                 rd_post_val: Some(partial_sum),
             },
             memory_state: None,
@@ -176,6 +182,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(partial_sum),
                 rs2_val: Some(sy_x_low_bits),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
@@ -42,6 +42,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHSUInstruction<WO
             register_state: RegisterState {
                 rs1_val: Some(x),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(s_x),
             },
             memory_state: None,
@@ -64,6 +65,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHSUInstruction<WO
             register_state: RegisterState {
                 rs1_val: Some(x),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(xy_high_bits),
             },
             memory_state: None,
@@ -86,6 +88,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHSUInstruction<WO
             register_state: RegisterState {
                 rs1_val: Some(s_x),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(sx_y_low_bits),
             },
             memory_state: None,
@@ -108,6 +111,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for MULHSUInstruction<WO
             register_state: RegisterState {
                 rs1_val: Some(xy_high_bits),
                 rs2_val: Some(sx_y_low_bits),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
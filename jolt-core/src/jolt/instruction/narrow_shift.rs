@@ -0,0 +1,417 @@
+//! Shifts for instruction lookups whose operand chunks are too narrow to hold a shift
+//! amount.
+//!
+//! Each shift subtable only sees the lowest chunk of the shift amount, so with
+//! `log2(M) / 2`-bit operand chunks a single lookup can only shift by less than
+//! `2^{log2(M) / 2}`. For such decompositions, [`expand_shifts`] rewrites every shift into
+//! a sequence of shifts by amounts that fit in a chunk:
+//!
+//! - An immediate shift is split into shifts by at most `2^{log2(M) / 2} - 1`, each
+//!   writing to `rd`.
+//! - A register shift by `y` shifts by the low chunk of `y`, then repeatedly by the
+//!   remaining high bits of `y`, which are extracted into two virtual registers.
+//!
+//! The virtual registers are ones that are not read later in the enclosing virtual
+//! sequence, found by a liveness scan (virtual registers don't carry values from one
+//! instruction to the next).
+
+use common::constants::{virtual_register_index, VIRTUAL_REGISTER_COUNT};
+use tracer::{ELFInstruction, RVTraceRow, RegisterState, RV32IM};
+
+use super::{
+    and::ANDInstruction, sll::SLLInstruction, sra::SRAInstruction, srl::SRLInstruction,
+    JoltInstruction,
+};
+
+const WORD_SIZE: usize = 32;
+const LOG_WORD_SIZE: usize = 5;
+
+/// The narrowest operand chunk for which shifts can be expanded: the high bits of a shift
+/// amount must fit in a chunk once shifted up by `2 * chunk_width - LOG_WORD_SIZE`.
+pub const MIN_OPERAND_CHUNK_WIDTH: usize = 3;
+
+/// Expands the shifts in `sequence`, the virtual sequence of a single instruction (or
+/// just the instruction, if it has none), for lookups with `log_M`-bit chunks. Returns
+/// `sequence` unchanged if none of its shifts need expanding; otherwise the result is
+/// renumbered as a single virtual sequence.
+pub fn expand_shifts(sequence: Vec<RVTraceRow>, log_M: usize) -> Vec<RVTraceRow> {
+    let chunk_width = log_M / 2;
+    if !sequence
+        .iter()
+        .any(|row| needs_expansion(&row.instruction, chunk_width))
+    {
+        return sequence;
+    }
+    assert!(
+        chunk_width >= MIN_OPERAND_CHUNK_WIDTH,
+        "cannot expand shifts for {chunk_width}-bit operand chunks"
+    );
+
+    let live_after = live_registers(&sequence);
+    let mut expanded = vec![];
+    for (row, live) in sequence.into_iter().zip(live_after) {
+        if !needs_expansion(&row.instruction, chunk_width) {
+            expanded.push(row);
+        } else if row.instruction.rs2.is_some() {
+            expand_register_shift(row, chunk_width, live, &mut expanded);
+        } else {
+            expand_immediate_shift(row, chunk_width, &mut expanded);
+        }
+    }
+
+    let sequence_length = expanded.len();
+    for (i, row) in expanded.iter_mut().enumerate() {
+        row.instruction.virtual_sequence_remaining = Some(sequence_length - i - 1);
+    }
+    expanded
+}
+
+/// [`expand_shifts`] for a virtual sequence in the bytecode.
+pub fn expand_shift_instructions(
+    sequence: Vec<ELFInstruction>,
+    log_M: usize,
+) -> Vec<ELFInstruction> {
+    let dummy_trace_rows = sequence
+        .into_iter()
+        .map(|instruction| RVTraceRow {
+            instruction,
+            register_state: RegisterState {
+                rs1_val: Some(0),
+                rs2_val: Some(0),
+                rs3_val: None,
+                rd_post_val: Some(0),
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        })
+        .collect();
+    expand_shifts(dummy_trace_rows, log_M)
+        .into_iter()
+        .map(|trace_row| trace_row.instruction)
+        .collect()
+}
+
+fn needs_expansion(instruction: &ELFInstruction, chunk_width: usize) -> bool {
+    match instruction.opcode {
+        RV32IM::SLL | RV32IM::SRL | RV32IM::SRA => chunk_width < LOG_WORD_SIZE,
+        RV32IM::SLLI | RV32IM::SRLI | RV32IM::SRAI => {
+            shift_amount(instruction.imm.unwrap()) >> chunk_width != 0
+        }
+        _ => false,
+    }
+}
+
+fn shift_amount(imm: i64) -> u64 {
+    imm as u64 % WORD_SIZE as u64
+}
+
+fn shift(opcode: RV32IM, x: u64, y: u64) -> u64 {
+    match opcode {
+        RV32IM::SLL | RV32IM::SLLI => SLLInstruction::<WORD_SIZE>(x, y).lookup_entry(),
+        RV32IM::SRL | RV32IM::SRLI => SRLInstruction::<WORD_SIZE>(x, y).lookup_entry(),
+        RV32IM::SRA | RV32IM::SRAI => SRAInstruction::<WORD_SIZE>(x, y).lookup_entry(),
+        _ => unreachable!("{opcode:?} is not a shift"),
+    }
+}
+
+/// For each row of `sequence`, a bitmask of the registers that are read later in
+/// `sequence` before being overwritten.
+fn live_registers(sequence: &[RVTraceRow]) -> Vec<u128> {
+    let mut live = 0u128;
+    let mut live_after = vec![0; sequence.len()];
+    for (i, row) in sequence.iter().enumerate().rev() {
+        live_after[i] = live;
+        if let Some(rd) = row.instruction.rd {
+            live &= !(1 << rd);
+        }
+        for rs in [row.instruction.rs1, row.instruction.rs2]
+            .into_iter()
+            .flatten()
+        {
+            live |= 1 << rs;
+        }
+    }
+    live_after
+}
+
+/// Appends a row of an expanded shift to `expanded`, returning the value of `rd` after it.
+/// Its `virtual_sequence_remaining` is set once the sequence is complete.
+#[allow(clippy::too_many_arguments)]
+fn push_row(
+    expanded: &mut Vec<RVTraceRow>,
+    address: u64,
+    opcode: RV32IM,
+    (rs1, rs1_val): (u64, u64),
+    rs2: Option<(u64, u64)>,
+    rd: u64,
+    imm: Option<i64>,
+    result: u64,
+) -> u64 {
+    // Writes to x0 are discarded
+    let rd_post_val = if rd == 0 { 0 } else { result };
+    expanded.push(RVTraceRow {
+        instruction: ELFInstruction {
+            address,
+            opcode,
+            rs1: Some(rs1),
+            rs2: rs2.map(|(rs2, _)| rs2),
+            rd: Some(rd),
+            imm,
+            virtual_sequence_remaining: None,
+        },
+        register_state: RegisterState {
+            rs1_val: Some(rs1_val),
+            rs2_val: rs2.map(|(_, rs2_val)| rs2_val),
+            rs3_val: None,
+            rd_post_val: Some(rd_post_val),
+        },
+        memory_state: None,
+        advice_value: None,
+        precompile_input: None,
+        precompile_output_address: None,
+    });
+    rd_post_val
+}
+
+fn expand_immediate_shift(row: RVTraceRow, chunk_width: usize, expanded: &mut Vec<RVTraceRow>) {
+    let ELFInstruction {
+        address,
+        opcode,
+        rs1,
+        rd,
+        imm,
+        ..
+    } = row.instruction;
+    let rd = rd.unwrap();
+    let max_step = (1 << chunk_width) - 1;
+
+    let mut remaining = shift_amount(imm.unwrap());
+    let mut source = (rs1.unwrap(), row.register_state.rs1_val.unwrap());
+    while remaining != 0 {
+        let step = remaining.min(max_step);
+        remaining -= step;
+        let result = shift(opcode, source.1, step);
+        let value = push_row(
+            expanded,
+            address,
+            opcode,
+            source,
+            None,
+            rd,
+            Some(step as i64),
+            result,
+        );
+        source = (rd, value);
+    }
+}
+
+fn expand_register_shift(
+    row: RVTraceRow,
+    chunk_width: usize,
+    live: u128,
+    expanded: &mut Vec<RVTraceRow>,
+) {
+    let ELFInstruction {
+        address,
+        opcode,
+        rs1,
+        rs2,
+        rd,
+        ..
+    } = row.instruction;
+    let (rs1, rs2, rd) = (rs1.unwrap(), rs2.unwrap(), rd.unwrap());
+    let x = row.register_state.rs1_val.unwrap();
+    let y = row.register_state.rs2_val.unwrap();
+
+    let mut free_registers = (0..VIRTUAL_REGISTER_COUNT)
+        .map(virtual_register_index)
+        .filter(|&register| live & (1 << register) == 0 && ![rs1, rs2, rd].contains(&register));
+    let (Some(v_amount), Some(v_high)) = (free_registers.next(), free_registers.next()) else {
+        panic!("No free virtual registers to expand {opcode:?} at {address:#x}");
+    };
+
+    // The shift amount is `low + (high << chunk_width)`, where `low` is the low chunk of
+    // `y`. Shifting by `high << chunk_width` is done in `2^{high_shift}` steps of
+    // `high << (chunk_width - high_shift)`, which fits in a chunk.
+    let high_shift = LOG_WORD_SIZE - chunk_width;
+    let high_mask = ((1 << high_shift) - 1) << (chunk_width - high_shift);
+
+    let shifted_y = push_row(
+        expanded,
+        address,
+        RV32IM::SRLI,
+        (rs2, y),
+        None,
+        v_high,
+        Some(high_shift as i64),
+        SRLInstruction::<WORD_SIZE>(y, high_shift as u64).lookup_entry(),
+    );
+    let high = push_row(
+        expanded,
+        address,
+        RV32IM::ANDI,
+        (v_high, shifted_y),
+        None,
+        v_high,
+        Some(high_mask as i64),
+        ANDInstruction::<WORD_SIZE>(shifted_y, high_mask).lookup_entry(),
+    );
+    let low = push_row(
+        expanded,
+        address,
+        RV32IM::ANDI,
+        (rs2, y),
+        None,
+        v_amount,
+        Some((1 << chunk_width) - 1),
+        ANDInstruction::<WORD_SIZE>(y, (1 << chunk_width) - 1).lookup_entry(),
+    );
+
+    let mut value = push_row(
+        expanded,
+        address,
+        opcode,
+        (rs1, x),
+        Some((v_amount, low)),
+        v_amount,
+        None,
+        shift(opcode, x, low),
+    );
+    let steps = 1 << high_shift;
+    for step in 0..steps {
+        let dest = if step == steps - 1 { rd } else { v_amount };
+        value = push_row(
+            expanded,
+            address,
+            opcode,
+            (v_amount, value),
+            Some((v_high, high)),
+            dest,
+            None,
+            shift(opcode, value, high),
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::{rngs::StdRng, RngCore, SeedableRng};
+    use std::collections::HashMap;
+
+    fn trace_row(opcode: RV32IM, rs2: Option<u64>, imm: Option<i64>, x: u64, y: u64) -> RVTraceRow {
+        RVTraceRow {
+            instruction: ELFInstruction {
+                address: 0x80000000,
+                opcode,
+                rs1: Some(5),
+                rs2,
+                rd: Some(6),
+                imm,
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(x),
+                rs2_val: rs2.map(|_| y),
+                rs3_val: None,
+                rd_post_val: Some(shift(opcode, x, y)),
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: None,
+            precompile_output_address: None,
+        }
+    }
+
+    /// Checks that the expansion of each shift computes the same result as the shift,
+    /// and only uses shift amounts that fit in an operand chunk.
+    #[test]
+    fn expanded_shifts() {
+        let mut rng = StdRng::seed_from_u64(12345);
+        for log_M in [6, 8] {
+            let max_step = (1 << (log_M / 2)) - 1;
+            for opcode in [RV32IM::SLL, RV32IM::SRL, RV32IM::SRA] {
+                let immediate_opcode = match opcode {
+                    RV32IM::SLL => RV32IM::SLLI,
+                    RV32IM::SRL => RV32IM::SRLI,
+                    _ => RV32IM::SRAI,
+                };
+                for y in 0..WORD_SIZE as u64 {
+                    let x = rng.next_u32() as u64;
+                    let register_shift = trace_row(opcode, Some(7), None, x, y | 0xffe0);
+                    let immediate_shift = trace_row(immediate_opcode, None, Some(y as i64), x, y);
+                    for row in [register_shift, immediate_shift] {
+                        let expected = row.register_state.rd_post_val;
+                        let expanded = expand_shifts(vec![row.clone()], log_M);
+                        if expanded.len() == 1 {
+                            assert_eq!(expanded[0], row);
+                            assert!(y <= max_step);
+                            continue;
+                        }
+                        // Each row reads the values written by the rows before it
+                        let mut registers = HashMap::from([(5, x), (7, y | 0xffe0)]);
+                        for expanded_row in expanded.iter() {
+                            let instruction = &expanded_row.instruction;
+                            let rs1_val = registers[&instruction.rs1.unwrap()];
+                            let operand = match instruction.rs2 {
+                                Some(rs2) => registers[&rs2],
+                                None => instruction.imm.unwrap() as u64,
+                            };
+                            let result = match instruction.opcode {
+                                RV32IM::ANDI => rs1_val & operand,
+                                opcode => shift(opcode, rs1_val, operand),
+                            };
+                            assert_eq!(expanded_row.register_state.rs1_val, Some(rs1_val));
+                            if instruction.rs2.is_some() {
+                                assert_eq!(expanded_row.register_state.rs2_val, Some(operand));
+                            }
+                            assert_eq!(expanded_row.register_state.rd_post_val, Some(result));
+                            registers.insert(instruction.rd.unwrap(), result);
+                        }
+                        let last = expanded.last().unwrap();
+                        assert_eq!(last.instruction.rd, Some(6));
+                        assert_eq!(last.register_state.rd_post_val, expected);
+                        for (i, expanded_row) in expanded.iter().enumerate() {
+                            assert_eq!(
+                                expanded_row.instruction.virtual_sequence_remaining,
+                                Some(expanded.len() - i - 1)
+                            );
+                            let amount = if expanded_row.instruction.opcode == opcode {
+                                expanded_row.register_state.rs2_val.unwrap()
+                            } else if expanded_row.instruction.opcode == immediate_opcode {
+                                expanded_row.instruction.imm.unwrap() as u64
+                            } else {
+                                continue;
+                            };
+                            assert!(amount <= max_step, "{opcode:?} by {y}: step of {amount}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn expanded_shift_registers() {
+        // Virtual registers read later in the sequence must not be used as temporaries
+        let live = virtual_register_index(0);
+        let mut add = trace_row(RV32IM::SLL, Some(live), None, 0, 0);
+        add.instruction.opcode = RV32IM::ADD;
+        add.instruction.rs1 = Some(6);
+        let sequence = vec![trace_row(RV32IM::SLL, Some(7), None, 1, 31), add];
+        let expanded = expand_shifts(sequence, 8);
+        assert_eq!(expanded.len(), 7);
+        assert!(expanded[..6]
+            .iter()
+            .all(|row| row.instruction.rd != Some(live)));
+        assert_eq!(expanded[6].instruction.opcode, RV32IM::ADD);
+        assert_eq!(expanded[5].register_state.rd_post_val, Some(1 << 31));
+
+        // Shifts fitting in a chunk are left as they are
+        let sequence = vec![trace_row(RV32IM::SLLI, None, Some(15), 1, 15)];
+        assert_eq!(expand_shifts(sequence.clone(), 8), sequence);
+        assert_eq!(expand_shifts(sequence.clone(), 16), sequence);
+    }
+}
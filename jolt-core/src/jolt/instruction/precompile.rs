@@ -0,0 +1,948 @@
+//! Virtual sequences of `PRECOMPILE` instructions (see `common::precompiles`).
+//!
+//! A modular arithmetic precompile is a relation between 256-bit inputs and outputs,
+//! expressed as constraints that must vanish modulo `p` (see [`PrecompileSpec`]). Its
+//! virtual sequence loads the input limbs from memory, stores the (untrusted) output
+//! limbs to memory, and writes every limb of the witness to a virtual register: the
+//! quotient `q` and carries `c` such that, as polynomials in `X`,
+//!
+//! ```text
+//! E(X) - Q(X) * P(X) = (X - 2^32) * C(X)
+//! ```
+//!
+//! where `E` is a constraint whose operands are replaced by their limb polynomials,
+//! and limbs `d` and carries `c'` such that `R(X) + D(X) + 1 - P(X) = (X - 2^32) * C'(X)`
+//! for each output `r`, i.e. `r < p`. Constraints may also involve hints, e.g. the
+//! slope of the line through two elliptic curve points, which are witnessed like
+//! outputs but not stored. All limbs are written by `VIRTUAL_PRECOMPILE_ADVICE`
+//! instructions and so are range-checked to 32 bits. The identities themselves are
+//! checked by the `PrecompileProof` sub-proof, which reads the limbs from the `rd`
+//! write values of the sequence.
+//!
+//! `Sha512Compress` is not modular: its virtual sequence (see [`sha512`]) computes
+//! the compression function with ordinary instructions, which are proven by the
+//! instruction lookups like the rest of the trace.
+
+use common::constants::virtual_register_index;
+use common::precompiles::{
+    ed25519_add_hints, secp256k1_add_slope, secp256k1_double_slope, Precompile, U256, U256_LIMBS,
+};
+use num::{BigInt, Integer, One, Signed, ToPrimitive, Zero};
+use tracer::{ELFInstruction, MemoryState, RVTraceRow, RegisterState, RV32IM};
+
+mod sha512;
+
+/// The `imm` of the first instruction in the virtual sequence of precompile `id` is
+/// `PRECOMPILE_START + id`. The `imm` of every other instruction in a precompile's
+/// virtual sequence is smaller.
+pub const PRECOMPILE_START: u64 = 1 << 16;
+
+/// Bias added to the most significant limb of a quotient, which may be negative.
+const QUOTIENT_BIAS: u64 = 1 << 31;
+/// Bias added to a carry, which is split into two limbs and may be negative.
+const CARRY_BIAS: u64 = 1 << 63;
+
+/// A 256-bit operand of a precompile constraint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operand {
+    Input(usize),
+    Output(usize),
+    /// An integer less than `2^256` provided by the prover.
+    Hint(usize),
+}
+
+/// `coeff` times the product of `operands`.
+#[derive(Clone, Debug)]
+pub struct Term {
+    pub coeff: i64,
+    pub operands: Vec<Operand>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum Modulus {
+    Fixed(U256),
+    Input(usize),
+}
+
+/// The relation between the inputs and outputs of a modular arithmetic precompile:
+/// each output is reduced modulo `modulus`, and each constraint (a sum of terms) is
+/// zero modulo `modulus`.
+#[derive(Clone, Debug)]
+pub struct PrecompileSpec {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub hints: usize,
+    pub modulus: Modulus,
+    pub constraints: Vec<Vec<Term>>,
+}
+
+impl PrecompileSpec {
+    pub fn new(precompile: Precompile) -> Self {
+        let (a, b, r) = (Operand::Input(0), Operand::Input(1), Operand::Output(0));
+        let term = |coeff, operands: &[Operand]| Term {
+            coeff,
+            operands: operands.to_vec(),
+        };
+        let (constraints, hints) = match precompile {
+            Precompile::ModMul | Precompile::Bn254FrMul | Precompile::Bn254FqMul => {
+                (vec![vec![term(1, &[a, b]), term(-1, &[r])]], 0)
+            }
+            Precompile::ModAdd | Precompile::Bn254FrAdd | Precompile::Bn254FqAdd => {
+                (vec![vec![term(1, &[a]), term(1, &[b]), term(-1, &[r])]], 0)
+            }
+            Precompile::Secp256k1Add | Precompile::Secp256k1Double => {
+                let (x1, y1) = (Operand::Input(0), Operand::Input(1));
+                let (x3, y3) = (Operand::Output(0), Operand::Output(1));
+                let lambda = Operand::Hint(0);
+                let slope = if precompile == Precompile::Secp256k1Add {
+                    // lambda * (x2 - x1) = y2 - y1
+                    let (x2, y2) = (Operand::Input(2), Operand::Input(3));
+                    vec![
+                        term(1, &[lambda, x2]),
+                        term(-1, &[lambda, x1]),
+                        term(-1, &[y2]),
+                        term(1, &[y1]),
+                    ]
+                } else {
+                    // lambda * 2y1 = 3x1^2
+                    vec![term(2, &[lambda, y1]), term(-3, &[x1, x1])]
+                };
+                // x3 = lambda^2 - x1 - x2, where x2 = x1 when doubling
+                let mut x3_terms =
+                    vec![term(1, &[lambda, lambda]), term(-1, &[x1]), term(-1, &[x3])];
+                x3_terms.push(match precompile {
+                    Precompile::Secp256k1Add => term(-1, &[Operand::Input(2)]),
+                    _ => term(-1, &[x1]),
+                });
+                // y3 = lambda * (x1 - x3) - y1
+                let y3_terms = vec![
+                    term(1, &[lambda, x1]),
+                    term(-1, &[lambda, x3]),
+                    term(-1, &[y3]),
+                    term(-1, &[y1]),
+                ];
+                (vec![slope, x3_terms, y3_terms], 1)
+            }
+            Precompile::Ed25519Add => {
+                let (x1, y1) = (Operand::Input(0), Operand::Input(1));
+                let (x2, y2) = (Operand::Input(2), Operand::Input(3));
+                let (x3, y3) = (Operand::Output(0), Operand::Output(1));
+                let (y1_y2, d_x1_x2, t) = (Operand::Hint(0), Operand::Hint(1), Operand::Hint(2));
+                let constraints = vec![
+                    vec![term(1, &[y1, y2]), term(-1, &[y1_y2])],
+                    // d = -121665 / 121666
+                    vec![term(121666, &[d_x1_x2]), term(121665, &[x1, x2])],
+                    vec![term(1, &[d_x1_x2, y1_y2]), term(-1, &[t])],
+                    // x3 * (1 + t) = x1 * y2 + y1 * x2
+                    vec![
+                        term(1, &[x3]),
+                        term(1, &[x3, t]),
+                        term(-1, &[x1, y2]),
+                        term(-1, &[y1, x2]),
+                    ],
+                    // y3 * (1 - t) = y1 * y2 + x1 * x2
+                    vec![
+                        term(1, &[y3]),
+                        term(-1, &[y3, t]),
+                        term(-1, &[y1_y2]),
+                        term(-1, &[x1, x2]),
+                    ],
+                ];
+                (constraints, 3)
+            }
+            Precompile::Sha512Compress => {
+                unreachable!("{precompile:?} is not a modular arithmetic precompile")
+            }
+        };
+        let inputs = precompile.input_words() / U256_LIMBS;
+        Self {
+            inputs,
+            outputs: precompile.output_words() / U256_LIMBS,
+            hints,
+            modulus: match precompile.modulus() {
+                Some(p) => Modulus::Fixed(p),
+                None => Modulus::Input(inputs - 1),
+            },
+            constraints,
+        }
+    }
+
+    /// The maximum number of operands of a term.
+    pub fn degree(&self) -> usize {
+        self.constraints
+            .iter()
+            .flatten()
+            .map(|term| term.operands.len())
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The role of an instruction in a precompile's virtual sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Step {
+    /// Marks the start of the sequence; writes 0 to `rd`.
+    Start,
+    /// Loads the given input word into `rd`.
+    Load(usize),
+    /// Writes a limb of the witness to `rd`.
+    Advice,
+    /// Stores the limb written by the previous step as the given output word.
+    Store(usize),
+}
+
+/// A limb of an integer in a precompile's virtual sequence, `lo + 2^32 * hi - bias`,
+/// where `lo` and `hi` are the values written to `rd` at the given offsets into
+/// the sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Limb {
+    pub lo: usize,
+    pub hi: Option<usize>,
+    pub bias: u64,
+}
+
+/// The limbs of an integer, from least to most significant.
+pub type LimbGroup = Vec<Limb>;
+
+/// The virtual sequence of a precompile, and where each integer involved in its
+/// constraints is written to `rd`.
+#[derive(Clone, Debug)]
+pub struct PrecompileLayout {
+    pub precompile: Precompile,
+    pub spec: PrecompileSpec,
+    pub steps: Vec<Step>,
+    pub inputs: Vec<LimbGroup>,
+    pub outputs: Vec<LimbGroup>,
+    pub hints: Vec<LimbGroup>,
+    /// The quotient of each constraint
+    pub quotients: Vec<LimbGroup>,
+    /// The carries of each constraint
+    pub carries: Vec<LimbGroup>,
+    /// `p - 1 - r` for each output `r`
+    pub differences: Vec<LimbGroup>,
+    /// The carries of `r + (p - 1 - r) + 1 - p` for each output `r`
+    pub difference_carries: Vec<LimbGroup>,
+}
+
+impl PrecompileLayout {
+    pub fn new(precompile: Precompile) -> Self {
+        let spec = PrecompileSpec::new(precompile);
+        let num_quotient_limbs = num_quotient_limbs(&spec);
+        let num_carries = num_carries(&spec);
+
+        let mut steps = vec![Step::Start];
+        let inputs = (0..spec.inputs)
+            .map(|k| {
+                (0..U256_LIMBS)
+                    .map(|i| {
+                        steps.push(Step::Load(k * U256_LIMBS + i));
+                        narrow_limb(steps.len() - 1, 0)
+                    })
+                    .collect()
+            })
+            .collect();
+        let outputs = (0..spec.outputs)
+            .map(|k| {
+                (0..U256_LIMBS)
+                    .map(|i| {
+                        let limb = push_narrow_limb(&mut steps, 0);
+                        steps.push(Step::Store(k * U256_LIMBS + i));
+                        limb
+                    })
+                    .collect()
+            })
+            .collect();
+        let hints = (0..spec.hints)
+            .map(|_| {
+                (0..U256_LIMBS)
+                    .map(|_| push_narrow_limb(&mut steps, 0))
+                    .collect()
+            })
+            .collect();
+        let quotients = (0..spec.constraints.len())
+            .map(|_| {
+                (0..num_quotient_limbs)
+                    .map(|i| {
+                        let bias = if i == num_quotient_limbs - 1 {
+                            QUOTIENT_BIAS
+                        } else {
+                            0
+                        };
+                        push_narrow_limb(&mut steps, bias)
+                    })
+                    .collect()
+            })
+            .collect();
+        let carries = (0..spec.constraints.len())
+            .map(|_| {
+                (0..num_carries)
+                    .map(|_| push_wide_limb(&mut steps))
+                    .collect()
+            })
+            .collect();
+        let mut differences = vec![];
+        let mut difference_carries = vec![];
+        for _ in 0..spec.outputs {
+            differences.push(
+                (0..U256_LIMBS)
+                    .map(|_| push_narrow_limb(&mut steps, 0))
+                    .collect(),
+            );
+            difference_carries.push(
+                (0..U256_LIMBS - 1)
+                    .map(|_| push_wide_limb(&mut steps))
+                    .collect(),
+            );
+        }
+
+        Self {
+            precompile,
+            spec,
+            steps,
+            inputs,
+            outputs,
+            hints,
+            quotients,
+            carries,
+            differences,
+            difference_carries,
+        }
+    }
+
+    /// The number of instructions in the virtual sequence.
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    /// All limb groups, in the order inputs, outputs, hints, quotients, carries,
+    /// differences, difference carries.
+    pub fn groups(&self) -> Vec<&LimbGroup> {
+        self.inputs
+            .iter()
+            .chain(self.outputs.iter())
+            .chain(self.hints.iter())
+            .chain(self.quotients.iter())
+            .chain(self.carries.iter())
+            .chain(self.differences.iter())
+            .chain(self.difference_carries.iter())
+            .collect()
+    }
+
+    /// Index into [`PrecompileLayout::groups`] of the given operand.
+    pub fn operand_group(&self, operand: Operand) -> usize {
+        match operand {
+            Operand::Input(k) => k,
+            Operand::Output(k) => self.inputs.len() + k,
+            Operand::Hint(k) => self.inputs.len() + self.outputs.len() + k,
+        }
+    }
+
+    /// Index into [`PrecompileLayout::groups`] of the quotient of constraint `c`.
+    pub fn quotient_group(&self, c: usize) -> usize {
+        self.inputs.len() + self.outputs.len() + self.hints.len() + c
+    }
+
+    /// Index into [`PrecompileLayout::groups`] of the carries of constraint `c`.
+    pub fn carries_group(&self, c: usize) -> usize {
+        self.quotient_group(c) + self.quotients.len()
+    }
+
+    /// Index into [`PrecompileLayout::groups`] of `p - 1 - r` for output `r`.
+    pub fn difference_group(&self, k: usize) -> usize {
+        self.carries_group(0) + self.carries.len() + k
+    }
+
+    /// Index into [`PrecompileLayout::groups`] of the carries of `r + (p - 1 - r) + 1 - p`.
+    pub fn difference_carries_group(&self, k: usize) -> usize {
+        self.difference_group(0) + self.differences.len() + k
+    }
+
+    /// The virtual sequence of the given `PRECOMPILE` instruction.
+    pub fn virtual_sequence(&self, instruction: &ELFInstruction) -> Vec<ELFInstruction> {
+        let v_0 = Some(virtual_register_index(0));
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| {
+                let (opcode, rs1, rs2, rd, imm) = match *step {
+                    Step::Start => (
+                        RV32IM::VIRTUAL_PRECOMPILE_ADVICE,
+                        None,
+                        None,
+                        v_0,
+                        PRECOMPILE_START + self.precompile.id() as u64,
+                    ),
+                    Step::Load(word) => (
+                        RV32IM::VIRTUAL_PRECOMPILE_LW,
+                        instruction.rs1,
+                        None,
+                        v_0,
+                        4 * word as u64,
+                    ),
+                    Step::Advice => (RV32IM::VIRTUAL_PRECOMPILE_ADVICE, None, None, v_0, 0),
+                    Step::Store(word) => (
+                        RV32IM::VIRTUAL_PRECOMPILE_SW,
+                        instruction.rs2,
+                        v_0,
+                        None,
+                        4 * word as u64,
+                    ),
+                };
+                ELFInstruction {
+                    address: instruction.address,
+                    opcode,
+                    rs1,
+                    rs2,
+                    rd,
+                    imm: Some(imm as i64),
+                    virtual_sequence_remaining: Some(self.len() - i - 1),
+                }
+            })
+            .collect()
+    }
+
+    /// The virtual trace of the given `PRECOMPILE` trace row.
+    pub fn virtual_trace(&self, trace_row: &RVTraceRow) -> Vec<RVTraceRow> {
+        let input = trace_row.precompile_input.as_ref().unwrap();
+        let input_address = trace_row.register_state.rs1_val.unwrap();
+        let output_address = trace_row.precompile_output_address.unwrap();
+        let rd_values = self.witness(input);
+
+        self.virtual_sequence(&trace_row.instruction)
+            .into_iter()
+            .enumerate()
+            .map(|(i, instruction)| {
+                let rd_value = rd_values[i];
+                let (register_state, memory_state, advice_value) = match self.steps[i] {
+                    Step::Start | Step::Advice => (
+                        RegisterState {
+                            rs1_val: None,
+                            rs2_val: None,
+                            rs3_val: None,
+                            rd_post_val: Some(rd_value),
+                        },
+                        None,
+                        Some(rd_value),
+                    ),
+                    Step::Load(word) => (
+                        RegisterState {
+                            rs1_val: Some(input_address),
+                            rs2_val: None,
+                            rs3_val: None,
+                            rd_post_val: Some(rd_value),
+                        },
+                        Some(MemoryState::Read {
+                            address: input_address + 4 * word as u64,
+                            value: rd_value,
+                        }),
+                        None,
+                    ),
+                    Step::Store(word) => (
+                        RegisterState {
+                            rs1_val: Some(output_address),
+                            rs2_val: Some(rd_values[i - 1]),
+                            rs3_val: None,
+                            rd_post_val: None,
+                        },
+                        Some(MemoryState::Write {
+                            address: output_address + 4 * word as u64,
+                            // The previous value is not used to construct the witness
+                            pre_value: 0,
+                            post_value: rd_values[i - 1],
+                        }),
+                        None,
+                    ),
+                };
+                RVTraceRow {
+                    instruction,
+                    register_state,
+                    memory_state,
+                    advice_value,
+                    precompile_input: None,
+                    precompile_output_address: None,
+                }
+            })
+            .collect()
+    }
+
+    /// Computes the hints of the precompile from its input words.
+    fn hints(&self, input: &[u32]) -> Vec<U256> {
+        let operand = |k: usize| -> U256 {
+            input[k * U256_LIMBS..(k + 1) * U256_LIMBS]
+                .try_into()
+                .unwrap()
+        };
+        match self.precompile {
+            Precompile::Secp256k1Add => vec![secp256k1_add_slope(
+                &operand(0),
+                &operand(1),
+                &operand(2),
+                &operand(3),
+            )],
+            Precompile::Secp256k1Double => vec![secp256k1_double_slope(&operand(0), &operand(1))],
+            Precompile::Ed25519Add => {
+                ed25519_add_hints(&operand(0), &operand(1), &operand(2), &operand(3)).to_vec()
+            }
+            _ => vec![],
+        }
+    }
+
+    /// Computes the value written to `rd` by each step of the virtual sequence, given
+    /// the input words of the precompile.
+    pub fn witness(&self, input: &[u32]) -> Vec<u64> {
+        let output = self.precompile.execute(input);
+        let hints = self.hints(input);
+        let mut rd_values = vec![0u64; self.len()];
+        for (i, step) in self.steps.iter().enumerate() {
+            if let Step::Load(word) = step {
+                rd_values[i] = input[*word] as u64;
+            }
+        }
+
+        let operand_limbs = |operand: Operand| -> Vec<i128> {
+            let words = match operand {
+                Operand::Input(k) => &input[k * U256_LIMBS..(k + 1) * U256_LIMBS],
+                Operand::Output(k) => &output[k * U256_LIMBS..(k + 1) * U256_LIMBS],
+                Operand::Hint(k) => &hints[k],
+            };
+            words.iter().map(|&word| word as i128).collect()
+        };
+        let modulus: Vec<i128> = match self.spec.modulus {
+            Modulus::Fixed(p) => p.iter().map(|&limb| limb as i128).collect(),
+            Modulus::Input(k) => operand_limbs(Operand::Input(k)),
+        };
+        let p = to_bigint(&modulus);
+
+        let mut set_limbs = |group: &LimbGroup, limbs: &[i128]| {
+            for (limb, &value) in group.iter().zip(limbs.iter()) {
+                let biased = value + limb.bias as i128;
+                match limb.hi {
+                    Some(hi) => {
+                        rd_values[limb.lo] = (biased as u64) & u32::MAX as u64;
+                        rd_values[hi] = (biased as u64) >> 32;
+                    }
+                    None => {
+                        assert!((0..1 << 32).contains(&biased), "limb out of range");
+                        rd_values[limb.lo] = biased as u64;
+                    }
+                }
+            }
+        };
+
+        for (k, group) in self.outputs.iter().enumerate() {
+            set_limbs(group, &operand_limbs(Operand::Output(k)));
+        }
+        for (k, group) in self.hints.iter().enumerate() {
+            set_limbs(group, &operand_limbs(Operand::Hint(k)));
+        }
+
+        for (c, constraint) in self.spec.constraints.iter().enumerate() {
+            // E(X), the constraint with each operand replaced by its limb polynomial
+            let mut e = vec![0i128; 1];
+            for term in constraint.iter() {
+                let product = term
+                    .operands
+                    .iter()
+                    .fold(vec![term.coeff as i128], |product, &operand| {
+                        poly_mul(&product, &operand_limbs(operand))
+                    });
+                e = poly_add(&e, &product);
+            }
+
+            let (q, remainder) = to_bigint(&e).div_mod_floor(&p);
+            assert!(
+                remainder.is_zero(),
+                "precompile constraint is not satisfied"
+            );
+            let q = to_signed_limbs(&q, self.quotients[c].len());
+            set_limbs(&self.quotients[c], &q);
+
+            let q_p = poly_mul(&q, &modulus);
+            let n = poly_add(&e, &q_p.iter().map(|x| -x).collect::<Vec<_>>());
+            set_limbs(&self.carries[c], &carries(&n, self.carries[c].len()));
+        }
+
+        for k in 0..self.spec.outputs {
+            let r = operand_limbs(Operand::Output(k));
+            let d = to_unsigned_limbs(
+                &(&p - BigInt::one() - to_bigint(&r)),
+                self.differences[k].len(),
+            );
+            set_limbs(&self.differences[k], &d);
+
+            // R(X) + D(X) + 1 - P(X)
+            let mut n = poly_add(&r, &d);
+            n[0] += 1;
+            let n = poly_add(&n, &modulus.iter().map(|x| -x).collect::<Vec<_>>());
+            set_limbs(
+                &self.difference_carries[k],
+                &carries(&n, self.difference_carries[k].len()),
+            );
+        }
+
+        rd_values
+    }
+}
+
+/// The virtual sequence of a `PRECOMPILE` instruction, used to preprocess the bytecode.
+pub fn virtual_sequence(instruction: ELFInstruction) -> Vec<ELFInstruction> {
+    match precompile(&instruction) {
+        Precompile::Sha512Compress => sha512::virtual_sequence(&instruction),
+        precompile => PrecompileLayout::new(precompile).virtual_sequence(&instruction),
+    }
+}
+
+/// The virtual trace of a `PRECOMPILE` trace row.
+pub fn virtual_trace(trace_row: RVTraceRow) -> Vec<RVTraceRow> {
+    match precompile(&trace_row.instruction) {
+        Precompile::Sha512Compress => sha512::virtual_trace(&trace_row),
+        precompile => PrecompileLayout::new(precompile).virtual_trace(&trace_row),
+    }
+}
+
+fn precompile(instruction: &ELFInstruction) -> Precompile {
+    assert_eq!(instruction.opcode, RV32IM::PRECOMPILE);
+    let id = instruction.imm.unwrap() as u32;
+    Precompile::from_id(id).unwrap_or_else(|| panic!("Unknown precompile (funct7 = {id})"))
+}
+
+fn narrow_limb(offset: usize, bias: u64) -> Limb {
+    Limb {
+        lo: offset,
+        hi: None,
+        bias,
+    }
+}
+
+fn push_narrow_limb(steps: &mut Vec<Step>, bias: u64) -> Limb {
+    steps.push(Step::Advice);
+    narrow_limb(steps.len() - 1, bias)
+}
+
+fn push_wide_limb(steps: &mut Vec<Step>) -> Limb {
+    steps.push(Step::Advice);
+    steps.push(Step::Advice);
+    Limb {
+        lo: steps.len() - 2,
+        hi: Some(steps.len() - 1),
+        bias: CARRY_BIAS,
+    }
+}
+
+/// The number of limbs of each quotient, which is signed, such that it can hold
+/// `E / p` for any inputs and outputs less than `2^256`.
+fn num_quotient_limbs(spec: &PrecompileSpec) -> usize {
+    let coeff_sum = spec
+        .constraints
+        .iter()
+        .map(|terms| {
+            terms
+                .iter()
+                .map(|term| term.coeff.unsigned_abs())
+                .sum::<u64>()
+        })
+        .max()
+        .unwrap();
+    // |E| < 2^e_bits
+    let e_bits = 256 * spec.degree() + (64 - coeff_sum.leading_zeros()) as usize;
+    // p >= 2^p_bits
+    let p_bits = match spec.modulus {
+        Modulus::Fixed(p) => to_bigint(&p.map(|limb| limb as i128)).bits() as usize - 1,
+        Modulus::Input(_) => 1,
+    };
+    (e_bits - p_bits + 1).div_ceil(32)
+}
+
+/// The number of carries of each constraint, i.e. the degree of `E(X) - Q(X) * P(X)`.
+fn num_carries(spec: &PrecompileSpec) -> usize {
+    let limb_degree = U256_LIMBS - 1;
+    (limb_degree * spec.degree())
+        .max(num_quotient_limbs(spec) - 1 + limb_degree)
+        .max(limb_degree)
+}
+
+/// Divides `N(X)` by `X - 2^32`, returning the `num_carries` coefficients of the
+/// quotient. Panics if `N(2^32) != 0`.
+fn carries(n: &[i128], num_carries: usize) -> Vec<i128> {
+    assert!(n.len() <= num_carries + 1);
+    let coeff = |k: usize| n.get(k).copied().unwrap_or(0);
+    let mut carries = Vec::with_capacity(num_carries);
+    let mut carry = 0i128;
+    for k in 0..num_carries {
+        let numerator = carry - coeff(k);
+        assert_eq!(numerator % (1 << 32), 0, "carry is not exact");
+        carry = numerator >> 32;
+        assert!(
+            (-(CARRY_BIAS as i128)..CARRY_BIAS as i128).contains(&carry),
+            "carry out of range"
+        );
+        carries.push(carry);
+    }
+    assert_eq!(
+        carry,
+        coeff(num_carries),
+        "precompile constraint is not satisfied"
+    );
+    carries
+}
+
+fn poly_mul(a: &[i128], b: &[i128]) -> Vec<i128> {
+    let mut product = vec![0; a.len() + b.len() - 1];
+    for (i, a_i) in a.iter().enumerate() {
+        for (j, b_j) in b.iter().enumerate() {
+            product[i + j] += a_i * b_j;
+        }
+    }
+    product
+}
+
+fn poly_add(a: &[i128], b: &[i128]) -> Vec<i128> {
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0) + b.get(i).unwrap_or(&0))
+        .collect()
+}
+
+/// Evaluates a limb polynomial at `2^32`.
+fn to_bigint(limbs: &[i128]) -> BigInt {
+    limbs
+        .iter()
+        .rev()
+        .fold(BigInt::zero(), |acc, &limb| (acc << 32) + limb)
+}
+
+/// Splits `x` into `num_limbs` unsigned 32-bit limbs.
+fn to_unsigned_limbs(x: &BigInt, num_limbs: usize) -> Vec<i128> {
+    assert!(!x.is_negative() && x.bits() <= 32 * num_limbs as u64);
+    let mut limbs: Vec<i128> = x.iter_u32_digits().map(|limb| limb as i128).collect();
+    limbs.resize(num_limbs, 0);
+    limbs
+}
+
+/// Splits `x` into `num_limbs` limbs, all of which are unsigned 32-bit integers
+/// except the most significant, which is a signed 32-bit integer.
+fn to_signed_limbs(x: &BigInt, num_limbs: usize) -> Vec<i128> {
+    let bound = BigInt::one() << (32 * num_limbs - 1);
+    assert!(
+        -&bound <= *x && *x < bound,
+        "{x} does not fit in {num_limbs} limbs"
+    );
+    let mut x = x.clone();
+    let mut limbs = Vec::with_capacity(num_limbs);
+    for _ in 0..num_limbs - 1 {
+        let (quotient, limb) = x.div_mod_floor(&(BigInt::one() << 32));
+        limbs.push(limb.to_i128().unwrap());
+        x = quotient;
+    }
+    limbs.push(x.to_i128().unwrap());
+    limbs
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ark_std::test_rng;
+    use rand_chacha::rand_core::RngCore;
+    use strum::IntoEnumIterator;
+
+    fn random_input(precompile: Precompile, rng: &mut impl RngCore) -> Vec<u32> {
+        let mut input: Vec<u32> = (0..precompile.input_words())
+            .map(|_| rng.next_u32())
+            .collect();
+        if precompile.modulus().is_none() {
+            // Make sure the modulus is not too small
+            input[precompile.input_words() - 1] |= 1;
+        }
+        input
+    }
+
+    /// Checks the polynomial identities of a witness, as the sub-proof does.
+    fn check_witness(layout: &PrecompileLayout, input: &[u32], rd_values: &[u64]) {
+        for limb in layout.groups().into_iter().flatten() {
+            assert!(rd_values[limb.lo] <= u32::MAX as u64);
+            assert!(limb.hi.map_or(true, |hi| rd_values[hi] <= u32::MAX as u64));
+        }
+        let limbs = |group: &LimbGroup| -> Vec<i128> {
+            group
+                .iter()
+                .map(|limb| {
+                    rd_values[limb.lo] as i128
+                        + limb.hi.map_or(0, |hi| (rd_values[hi] as i128) << 32)
+                        - limb.bias as i128
+                })
+                .collect()
+        };
+        let negate = |poly: Vec<i128>| -> Vec<i128> { poly.iter().map(|x| -x).collect() };
+        // Multiplies by X - 2^32
+        let times_x_minus_base = |poly: Vec<i128>| poly_mul(&poly, &[-(1 << 32), 1]);
+        let modulus = match layout.spec.modulus {
+            Modulus::Fixed(p) => p.iter().map(|&limb| limb as i128).collect(),
+            Modulus::Input(k) => limbs(&layout.inputs[k]),
+        };
+
+        let output = layout.precompile.execute(input);
+        for (k, group) in layout.outputs.iter().enumerate() {
+            let expected: Vec<i128> = output[k * U256_LIMBS..(k + 1) * U256_LIMBS]
+                .iter()
+                .map(|&word| word as i128)
+                .collect();
+            assert_eq!(limbs(group), expected);
+
+            let mut n = poly_add(&limbs(group), &limbs(&layout.differences[k]));
+            n[0] += 1;
+            let n = poly_add(&n, &negate(modulus.clone()));
+            let c = times_x_minus_base(limbs(&layout.difference_carries[k]));
+            assert_eq!(poly_add(&n, &negate(c)), vec![0; U256_LIMBS]);
+        }
+        for (c, constraint) in layout.spec.constraints.iter().enumerate() {
+            let mut n = negate(poly_mul(&limbs(&layout.quotients[c]), &modulus));
+            for term in constraint.iter() {
+                let product =
+                    term.operands
+                        .iter()
+                        .fold(vec![term.coeff as i128], |acc, &operand| {
+                            poly_mul(&acc, &limbs(layout.groups()[layout.operand_group(operand)]))
+                        });
+                n = poly_add(&n, &product);
+            }
+            let carries = times_x_minus_base(limbs(&layout.carries[c]));
+            assert!(poly_add(&n, &negate(carries)).iter().all(|x| *x == 0));
+        }
+    }
+
+    #[test]
+    fn layout_sizes() {
+        let layout = PrecompileLayout::new(Precompile::ModMul);
+        assert_eq!(layout.quotients[0].len(), 17);
+        assert_eq!(layout.carries[0].len(), 23);
+        assert_eq!(layout.len(), 126);
+
+        let layout = PrecompileLayout::new(Precompile::Bn254FrMul);
+        assert_eq!(layout.quotients[0].len(), 9);
+        assert_eq!(layout.carries[0].len(), 15);
+
+        let layout = PrecompileLayout::new(Precompile::Bn254FqAdd);
+        assert_eq!(layout.quotients[0].len(), 1);
+        assert_eq!(layout.carries[0].len(), 7);
+
+        let layout = PrecompileLayout::new(Precompile::Secp256k1Add);
+        assert_eq!(layout.hints.len(), 1);
+        assert_eq!(layout.quotients.len(), 3);
+        assert!(layout.quotients.iter().all(|q| q.len() == 9));
+
+        let layout = PrecompileLayout::new(Precompile::Ed25519Add);
+        assert_eq!(layout.hints.len(), 3);
+        assert_eq!(layout.quotients.len(), 5);
+        assert!(layout.quotients.iter().all(|q| q.len() == 9));
+    }
+
+    #[test]
+    fn witness() {
+        let mut rng = test_rng();
+        for precompile in Precompile::iter().filter(|precompile| precompile.is_modular()) {
+            let layout = PrecompileLayout::new(precompile);
+            for _ in 0..100 {
+                let input = random_input(precompile, &mut rng);
+                check_witness(&layout, &input, &layout.witness(&input));
+            }
+            // Extreme inputs
+            let mut input = vec![u32::MAX; precompile.input_words()];
+            if precompile == Precompile::Secp256k1Add {
+                // The points must have distinct x-coordinates
+                input[2 * U256_LIMBS] = 0;
+            }
+            check_witness(&layout, &input, &layout.witness(&input));
+            if precompile.modulus().is_none() {
+                input[16..].copy_from_slice(&[2, 0, 0, 0, 0, 0, 0, 0]);
+                check_witness(&layout, &input, &layout.witness(&input));
+            }
+        }
+    }
+
+    #[test]
+    fn bn254_matches_arkworks() {
+        use ark_ff::{BigInteger, PrimeField};
+
+        fn to_field<F: PrimeField>(limbs: &[u32]) -> F {
+            let bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+            F::from_le_bytes_mod_order(&bytes)
+        }
+        fn check<F: PrimeField>(mul: Precompile, add: Precompile, rng: &mut impl RngCore) {
+            assert_eq!(
+                to_field::<F>(&mul.modulus().unwrap()),
+                F::zero(),
+                "wrong modulus"
+            );
+            for _ in 0..100 {
+                let input = random_input(mul, rng);
+                let (a, b) = (to_field::<F>(&input[..8]), to_field::<F>(&input[8..]));
+                let bytes = |x: F| x.into_bigint().to_bytes_le();
+                let words = |output: Vec<u32>| -> Vec<u8> {
+                    output.iter().flat_map(|word| word.to_le_bytes()).collect()
+                };
+                assert_eq!(words(mul.execute(&input)), bytes(a * b));
+                assert_eq!(words(add.execute(&input)), bytes(a + b));
+            }
+        }
+
+        let mut rng = test_rng();
+        check::<ark_bn254::Fr>(Precompile::Bn254FrMul, Precompile::Bn254FrAdd, &mut rng);
+        check::<ark_bn254::Fq>(Precompile::Bn254FqMul, Precompile::Bn254FqAdd, &mut rng);
+    }
+
+    #[test]
+    fn virtual_trace() {
+        let precompile = Precompile::Bn254FqMul;
+        let layout = PrecompileLayout::new(precompile);
+        let input = random_input(precompile, &mut test_rng());
+        let trace_row = RVTraceRow {
+            instruction: ELFInstruction {
+                address: 0x80000000,
+                opcode: RV32IM::PRECOMPILE,
+                rs1: Some(11),
+                rs2: Some(12),
+                rd: None,
+                imm: Some(precompile.id() as i64),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(0x80001000),
+                rs2_val: Some(0x80002000),
+                rs3_val: None,
+                rd_post_val: None,
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: Some(input.clone()),
+            precompile_output_address: Some(0x80002000),
+        };
+
+        let virtual_trace = super::virtual_trace(trace_row.clone());
+        let virtual_sequence = super::virtual_sequence(trace_row.instruction);
+        assert_eq!(virtual_trace.len(), layout.len());
+        for (row, instruction) in virtual_trace.iter().zip(virtual_sequence.iter()) {
+            assert_eq!(&row.instruction, instruction);
+        }
+        assert_eq!(
+            virtual_trace[0].instruction.imm,
+            Some((PRECOMPILE_START + precompile.id() as u64) as i64)
+        );
+        let output = precompile.execute(&input);
+        let stores: Vec<_> = virtual_trace
+            .iter()
+            .filter_map(|row| match row.memory_state {
+                Some(MemoryState::Write {
+                    address,
+                    post_value,
+                    ..
+                }) => Some((address, post_value)),
+                _ => None,
+            })
+            .collect();
+        let expected: Vec<_> = output
+            .iter()
+            .enumerate()
+            .map(|(i, &word)| (0x80002000 + 4 * i as u64, word as u64))
+            .collect();
+        assert_eq!(stores, expected);
+    }
+}
@@ -0,0 +1,408 @@
+//! The virtual sequence of `Sha512Compress`, which computes the SHA-512 compression
+//! function with RV32I instructions. Each 64-bit word is held in a pair of virtual
+//! registers, least significant word first. The message schedule is stored to the
+//! output words as it is computed, since there are too few virtual registers to hold
+//! it, and loaded back by later rounds.
+
+use std::collections::HashMap;
+
+use common::constants::{virtual_register_index, REGISTER_COUNT};
+use common::precompiles::{Precompile, SHA512_K};
+use tracer::{ELFInstruction, MemoryState, RVTraceRow, RegisterState, RV32IM};
+
+use crate::jolt::instruction::{
+    add::ADDInstruction, and::ANDInstruction, or::ORInstruction, sll::SLLInstruction,
+    sltu::SLTUInstruction, srl::SRLInstruction, xor::XORInstruction, JoltInstruction,
+};
+
+/// Offset of the message block in the input words.
+const BLOCK: usize = 16;
+/// Offset of message schedule word 16 in the output words.
+const SCHEDULE: usize = 16;
+
+/// A 64-bit word held in two virtual registers, least significant word first.
+#[derive(Clone, Copy)]
+struct Pair(u64, u64);
+
+impl Pair {
+    fn new(index: u64) -> Self {
+        Self(
+            virtual_register_index(2 * index),
+            virtual_register_index(2 * index + 1),
+        )
+    }
+
+    fn halves(self) -> [u64; 2] {
+        [self.0, self.1]
+    }
+}
+
+/// The working variables `a` to `h` occupy pairs 0 to 7.
+const W: Pair = Pair(virtual_register_index(16), virtual_register_index(17));
+const ACC: Pair = Pair(virtual_register_index(18), virtual_register_index(19));
+const X: Pair = Pair(virtual_register_index(20), virtual_register_index(21));
+const S0: u64 = virtual_register_index(22);
+const S1: u64 = virtual_register_index(23);
+const CARRY: u64 = virtual_register_index(24);
+
+/// Where a word is loaded from or stored to, relative to the `PRECOMPILE` instruction's
+/// `rs1` (input) or `rs2` (output).
+#[derive(Clone, Copy)]
+enum Word {
+    Input(usize),
+    Output(usize),
+}
+
+impl Word {
+    /// Message schedule word `t`.
+    fn schedule(t: usize) -> Self {
+        if t < 16 {
+            Self::Input(BLOCK + 2 * t)
+        } else {
+            Self::Output(SCHEDULE + 2 * (t - 16))
+        }
+    }
+
+    fn offset(self, i: usize) -> Self {
+        match self {
+            Self::Input(word) => Self::Input(word + i),
+            Self::Output(word) => Self::Output(word + i),
+        }
+    }
+}
+
+struct Assembler<'a> {
+    instruction: &'a ELFInstruction,
+    sequence: Vec<ELFInstruction>,
+}
+
+impl Assembler<'_> {
+    fn push(&mut self, opcode: RV32IM, rd: Option<u64>, rs1: u64, rs2: Option<u64>, imm: i64) {
+        self.sequence.push(ELFInstruction {
+            address: self.instruction.address,
+            opcode,
+            rs1: Some(rs1),
+            rs2,
+            rd,
+            imm: Some(imm),
+            virtual_sequence_remaining: None,
+        });
+    }
+
+    fn r(&mut self, opcode: RV32IM, rd: u64, rs1: u64, rs2: u64) {
+        self.push(opcode, Some(rd), rs1, Some(rs2), 0);
+    }
+
+    fn i(&mut self, opcode: RV32IM, rd: u64, rs1: u64, imm: u32) {
+        self.push(opcode, Some(rd), rs1, None, imm as i64);
+    }
+
+    fn base(&self, word: Word) -> (u64, i64) {
+        match word {
+            Word::Input(word) => (self.instruction.rs1.unwrap(), 4 * word as i64),
+            Word::Output(word) => (self.instruction.rs2.unwrap(), 4 * word as i64),
+        }
+    }
+
+    fn load(&mut self, dst: Pair, word: Word) {
+        for (i, rd) in dst.halves().into_iter().enumerate() {
+            let (base, imm) = self.base(word.offset(i));
+            self.push(RV32IM::LW, Some(rd), base, None, imm);
+        }
+    }
+
+    fn store(&mut self, src: Pair, word: Word) {
+        for (i, rs2) in src.halves().into_iter().enumerate() {
+            let (base, imm) = self.base(word.offset(i));
+            self.push(RV32IM::SW, None, base, Some(rs2), imm);
+        }
+    }
+
+    /// `dst += src`
+    fn add(&mut self, dst: Pair, src: Pair) {
+        self.r(RV32IM::ADD, dst.0, dst.0, src.0);
+        self.r(RV32IM::SLTU, CARRY, dst.0, src.0);
+        self.r(RV32IM::ADD, dst.1, dst.1, src.1);
+        self.r(RV32IM::ADD, dst.1, dst.1, CARRY);
+    }
+
+    /// `dst += k`
+    fn add_constant(&mut self, dst: Pair, k: u64) {
+        let (lo, hi) = (k as u32, (k >> 32) as u32);
+        self.i(RV32IM::ADDI, dst.0, dst.0, lo);
+        self.i(RV32IM::SLTIU, CARRY, dst.0, lo);
+        self.i(RV32IM::ADDI, dst.1, dst.1, hi);
+        self.r(RV32IM::ADD, dst.1, dst.1, CARRY);
+    }
+
+    /// `dst = src >>> n`, or `dst ^= src >>> n` if `xor`.
+    fn rotate_right(&mut self, dst: Pair, src: Pair, n: u32, xor: bool) {
+        let (lo, hi, n) = if n < 32 {
+            (src.0, src.1, n)
+        } else {
+            (src.1, src.0, n - 32)
+        };
+        assert!(n != 0);
+        for (dst, a, b) in [(dst.0, lo, hi), (dst.1, hi, lo)] {
+            self.i(RV32IM::SRLI, S0, a, n);
+            self.i(RV32IM::SLLI, S1, b, 32 - n);
+            if xor {
+                self.r(RV32IM::OR, S0, S0, S1);
+                self.r(RV32IM::XOR, dst, dst, S0);
+            } else {
+                self.r(RV32IM::OR, dst, S0, S1);
+            }
+        }
+    }
+
+    /// `dst ^= src >> n`, for `0 < n < 32`.
+    fn xor_shift_right(&mut self, dst: Pair, src: Pair, n: u32) {
+        self.i(RV32IM::SRLI, S0, src.0, n);
+        self.i(RV32IM::SLLI, S1, src.1, 32 - n);
+        self.r(RV32IM::OR, S0, S0, S1);
+        self.r(RV32IM::XOR, dst.0, dst.0, S0);
+        self.i(RV32IM::SRLI, S0, src.1, n);
+        self.r(RV32IM::XOR, dst.1, dst.1, S0);
+    }
+
+    /// `dst = (src >>> a) ^ (src >>> b) ^ (src >>> c)`
+    fn big_sigma(&mut self, dst: Pair, src: Pair, [a, b, c]: [u32; 3]) {
+        self.rotate_right(dst, src, a, false);
+        self.rotate_right(dst, src, b, true);
+        self.rotate_right(dst, src, c, true);
+    }
+
+    /// `dst = (src >>> a) ^ (src >>> b) ^ (src >> c)`
+    fn small_sigma(&mut self, dst: Pair, src: Pair, [a, b, c]: [u32; 3]) {
+        self.rotate_right(dst, src, a, false);
+        self.rotate_right(dst, src, b, true);
+        self.xor_shift_right(dst, src, c);
+    }
+
+    /// Computes message schedule word `t >= 16` into `W` and stores it.
+    fn schedule(&mut self, t: usize) {
+        self.load(X, Word::schedule(t - 2));
+        self.small_sigma(W, X, [19, 61, 6]);
+        self.load(X, Word::schedule(t - 15));
+        self.small_sigma(ACC, X, [1, 8, 7]);
+        self.add(W, ACC);
+        self.load(X, Word::schedule(t - 7));
+        self.add(W, X);
+        self.load(X, Word::schedule(t - 16));
+        self.add(W, X);
+        self.store(W, Word::schedule(t));
+    }
+
+    /// Round `t` of the working variables `a` to `h`. The new `a` is written to the
+    /// registers of `h`, and the new `e` to those of `d`.
+    fn round(&mut self, t: usize, [a, b, c, d, e, f, g, h]: [Pair; 8]) {
+        if t < 16 {
+            self.load(W, Word::schedule(t));
+        } else {
+            self.schedule(t);
+        }
+
+        // h + Σ1(e) + Ch(e, f, g) + K[t] + W[t]
+        self.big_sigma(ACC, e, [14, 18, 41]);
+        self.add(h, ACC);
+        for (acc, e, f, g) in [(ACC.0, e.0, f.0, g.0), (ACC.1, e.1, f.1, g.1)] {
+            // Ch(e, f, g) = g ^ (e & (f ^ g))
+            self.r(RV32IM::XOR, acc, f, g);
+            self.r(RV32IM::AND, acc, acc, e);
+            self.r(RV32IM::XOR, acc, acc, g);
+        }
+        self.add(h, ACC);
+        self.add_constant(h, SHA512_K[t]);
+        self.add(h, W);
+        self.add(d, h);
+
+        // + Σ0(a) + Maj(a, b, c)
+        self.big_sigma(ACC, a, [28, 34, 39]);
+        self.add(h, ACC);
+        for (acc, a, b, c) in [(ACC.0, a.0, b.0, c.0), (ACC.1, a.1, b.1, c.1)] {
+            // Maj(a, b, c) = ((a | b) & c) | (a & b)
+            self.r(RV32IM::OR, acc, a, b);
+            self.r(RV32IM::AND, acc, acc, c);
+            self.r(RV32IM::AND, S0, a, b);
+            self.r(RV32IM::OR, acc, acc, S0);
+        }
+        self.add(h, ACC);
+    }
+}
+
+/// The virtual sequence of a `Sha512Compress` instruction.
+pub fn virtual_sequence(instruction: &ELFInstruction) -> Vec<ELFInstruction> {
+    let mut assembler = Assembler {
+        instruction,
+        sequence: vec![],
+    };
+    let mut vars: [Pair; 8] = std::array::from_fn(|k| Pair::new(k as u64));
+    for (k, var) in vars.iter().enumerate() {
+        assembler.load(*var, Word::Input(2 * k));
+    }
+    for t in 0..80 {
+        assembler.round(t, vars);
+        vars.rotate_right(1);
+    }
+    for (k, var) in vars.iter().enumerate() {
+        assembler.load(X, Word::Input(2 * k));
+        assembler.add(*var, X);
+        assembler.store(*var, Word::Output(2 * k));
+    }
+
+    let len = assembler.sequence.len();
+    for (i, instruction) in assembler.sequence.iter_mut().enumerate() {
+        instruction.virtual_sequence_remaining = Some(len - i - 1);
+    }
+    assembler.sequence
+}
+
+/// The virtual trace of a `Sha512Compress` trace row, obtained by executing its
+/// virtual sequence.
+pub fn virtual_trace(trace_row: &RVTraceRow) -> Vec<RVTraceRow> {
+    let input = trace_row.precompile_input.as_ref().unwrap();
+    let input_address = trace_row.register_state.rs1_val.unwrap();
+    let output_address = trace_row.precompile_output_address.unwrap();
+    assert_eq!(input.len(), Precompile::Sha512Compress.input_words());
+
+    let mut registers = [0u64; REGISTER_COUNT as usize];
+    registers[trace_row.instruction.rs1.unwrap() as usize] = input_address;
+    registers[trace_row.instruction.rs2.unwrap() as usize] = output_address;
+    let mut memory: HashMap<u64, u64> = input
+        .iter()
+        .enumerate()
+        .map(|(i, &word)| (input_address + 4 * i as u64, word as u64))
+        .collect();
+
+    virtual_sequence(&trace_row.instruction)
+        .into_iter()
+        .map(|instruction| {
+            let rs1_val = registers[instruction.rs1.unwrap() as usize];
+            let rs2_val = instruction.rs2.map(|rs2| registers[rs2 as usize]);
+            let imm = instruction.imm.unwrap() as u64 & u32::MAX as u64;
+            let address = rs1_val.wrapping_add(instruction.imm.unwrap() as u64);
+            let (rd_post_val, memory_state) = match instruction.opcode {
+                RV32IM::LW => {
+                    let value = memory[&address];
+                    (Some(value), Some(MemoryState::Read { address, value }))
+                }
+                RV32IM::SW => {
+                    let value = rs2_val.unwrap();
+                    let pre_value = memory.insert(address, value).unwrap_or(0);
+                    let memory_state = MemoryState::Write {
+                        address,
+                        pre_value,
+                        post_value: value,
+                    };
+                    (None, Some(memory_state))
+                }
+                opcode => {
+                    let y = rs2_val.unwrap_or(imm);
+                    let value = match opcode {
+                        RV32IM::ADD | RV32IM::ADDI => {
+                            ADDInstruction::<32>(rs1_val, y).lookup_entry()
+                        }
+                        RV32IM::SLTU | RV32IM::SLTIU => {
+                            SLTUInstruction::<32>(rs1_val, y).lookup_entry()
+                        }
+                        RV32IM::XOR => XORInstruction::<32>(rs1_val, y).lookup_entry(),
+                        RV32IM::AND => ANDInstruction::<32>(rs1_val, y).lookup_entry(),
+                        RV32IM::OR => ORInstruction::<32>(rs1_val, y).lookup_entry(),
+                        RV32IM::SLLI => SLLInstruction::<32>(rs1_val, y).lookup_entry(),
+                        RV32IM::SRLI => SRLInstruction::<32>(rs1_val, y).lookup_entry(),
+                        _ => unreachable!("{opcode:?} is not used by the sequence"),
+                    };
+                    (Some(value), None)
+                }
+            };
+            if let Some(rd) = instruction.rd {
+                registers[rd as usize] = rd_post_val.unwrap();
+            }
+            RVTraceRow {
+                instruction,
+                register_state: RegisterState {
+                    rs1_val: Some(rs1_val),
+                    rs2_val,
+                    rs3_val: None,
+                    rd_post_val,
+                },
+                memory_state,
+                advice_value: None,
+                precompile_input: None,
+                precompile_output_address: None,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::jolt::vm::rv32i_vm::RV32I;
+    use ark_std::test_rng;
+    use rand_chacha::rand_core::RngCore;
+
+    #[test]
+    fn virtual_trace_matches_execute() {
+        let mut rng = test_rng();
+        let input: Vec<u32> = (0..Precompile::Sha512Compress.input_words())
+            .map(|_| rng.next_u32())
+            .collect();
+        let (input_address, output_address) = (0x8000_1000, 0x8000_2000);
+        let trace_row = RVTraceRow {
+            instruction: ELFInstruction {
+                address: 0x8000_0000,
+                opcode: RV32IM::PRECOMPILE,
+                rs1: Some(11),
+                rs2: Some(12),
+                rd: None,
+                imm: Some(Precompile::Sha512Compress.id() as i64),
+                virtual_sequence_remaining: None,
+            },
+            register_state: RegisterState {
+                rs1_val: Some(input_address),
+                rs2_val: Some(output_address),
+                rs3_val: None,
+                rd_post_val: None,
+            },
+            memory_state: None,
+            advice_value: None,
+            precompile_input: Some(input.clone()),
+            precompile_output_address: Some(output_address),
+        };
+        let trace = virtual_trace(&trace_row);
+
+        // The stored words are the precompile's outputs
+        let mut output = vec![None; Precompile::Sha512Compress.output_words()];
+        for row in trace.iter() {
+            if let Some(MemoryState::Write {
+                address,
+                post_value,
+                ..
+            }) = row.memory_state
+            {
+                let word = (address - output_address) as usize / 4;
+                assert!(output[word].is_none());
+                output[word] = Some(post_value as u32);
+            }
+        }
+        let output: Vec<u32> = output.into_iter().map(Option::unwrap).collect();
+        assert_eq!(output, Precompile::Sha512Compress.execute(&input));
+
+        // Every instruction is a lookup or a memory operation, and writes its lookup's
+        // output to `rd`
+        for row in trace.iter() {
+            assert!(row
+                .instruction
+                .rd
+                .map_or(true, |rd| rd >= virtual_register_index(0)));
+            match row.instruction.opcode {
+                RV32IM::LW | RV32IM::SW => {}
+                _ => {
+                    let lookup = RV32I::try_from(row).unwrap();
+                    assert_eq!(Some(lookup.lookup_entry()), row.register_state.rd_post_val);
+                }
+            }
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use common::constants::virtual_register_index;
 use tracer::{ELFInstruction, RVTraceRow, RegisterState, RV32IM};
 
+use super::div::{push_rounding_adjustment, virtual_row};
 use super::VirtualInstructionSequence;
 use crate::jolt::instruction::{
-    add::ADDInstruction, beq::BEQInstruction, mul::MULInstruction,
+    add::ADDInstruction, beq::BEQInstruction, mul::MULInstruction, sub::SUBInstruction,
     virtual_advice::ADVICEInstruction,
     virtual_assert_valid_signed_remainder::AssertValidSignedRemainderInstruction, JoltInstruction,
 };
@@ -12,7 +13,7 @@ use crate::jolt::instruction::{
 pub struct REMInstruction<const WORD_SIZE: usize>;
 
 impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_SIZE> {
-    const SEQUENCE_LENGTH: usize = 7;
+    const SEQUENCE_LENGTH: usize = 12;
 
     fn virtual_trace(trace_row: RVTraceRow) -> Vec<RVTraceRow> {
         assert_eq!(trace_row.instruction.opcode, RV32IM::REM);
@@ -35,8 +36,8 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
                 if y == 0 {
                     (u32::MAX as u64, x)
                 } else {
-                    let mut quotient = x as i32 / y as i32;
-                    let mut remainder = x as i32 % y as i32;
+                    let mut quotient = (x as i32).wrapping_div(y as i32);
+                    let mut remainder = (x as i32).wrapping_rem(y as i32);
                     if (remainder < 0 && (y as i32) > 0) || (remainder > 0 && (y as i32) < 0) {
                         remainder += y as i32;
                         quotient -= 1;
@@ -48,8 +49,8 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
                 if y == 0 {
                     (u64::MAX, x)
                 } else {
-                    let mut quotient = x as i64 / y as i64;
-                    let mut remainder = x as i64 % y as i64;
+                    let mut quotient = (x as i64).wrapping_div(y as i64);
+                    let mut remainder = (x as i64).wrapping_rem(y as i64);
                     if (remainder < 0 && (y as i64) > 0) || (remainder > 0 && (y as i64) < 0) {
                         remainder += y as i64;
                         quotient -= 1;
@@ -74,6 +75,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(q),
             },
             memory_state: None,
@@ -96,6 +98,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(r),
             },
             memory_state: None,
@@ -119,6 +122,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(r),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -141,6 +145,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(q),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(q_y),
             },
             memory_state: None,
@@ -163,6 +168,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(r),
+                rs3_val: None,
                 rd_post_val: Some(add_0),
             },
             memory_state: None,
@@ -185,6 +191,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             register_state: RegisterState {
                 rs1_val: Some(add_0),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -193,26 +200,37 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             precompile_output_address: None,
         });
 
-        virtual_trace.push(RVTraceRow {
-            instruction: ELFInstruction {
-                address: trace_row.instruction.address,
-                opcode: RV32IM::VIRTUAL_MOVE,
-                rs1: v_r,
-                rs2: None,
-                rd: trace_row.instruction.rd,
-                imm: None,
-                virtual_sequence_remaining: Some(Self::SEQUENCE_LENGTH - virtual_trace.len() - 1),
-            },
-            register_state: RegisterState {
-                rs1_val: Some(r),
-                rs2_val: None,
-                rd_post_val: Some(r),
-            },
-            memory_state: None,
-            advice_value: None,
-            precompile_input: None,
-            precompile_output_address: None,
-        });
+        // The assertions above hold for the remainder of the division rounded towards
+        // negative infinity, but RISC-V rounds towards zero
+        let adjustment = push_rounding_adjustment::<WORD_SIZE>(
+            &mut virtual_trace,
+            Self::SEQUENCE_LENGTH,
+            trace_row.instruction.address,
+            (r_x, x),
+            (v_r, r),
+            v_0,
+            v_qy,
+        );
+
+        let adjustment_y = MULInstruction::<WORD_SIZE>(adjustment, y).lookup_entry();
+        virtual_trace.push(virtual_row(
+            trace_row.instruction.address,
+            RV32IM::MUL,
+            (v_0, Some(adjustment)),
+            (r_y, Some(y)),
+            (v_qy, Some(adjustment_y)),
+            Self::SEQUENCE_LENGTH - virtual_trace.len() - 1,
+        ));
+
+        let remainder = SUBInstruction::<WORD_SIZE>(r, adjustment_y).lookup_entry();
+        virtual_trace.push(virtual_row(
+            trace_row.instruction.address,
+            RV32IM::SUB,
+            (v_r, Some(r)),
+            (v_qy, Some(adjustment_y)),
+            (trace_row.instruction.rd, Some(remainder)),
+            Self::SEQUENCE_LENGTH - virtual_trace.len() - 1,
+        ));
 
         virtual_trace
     }
@@ -222,20 +240,8 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
             return x;
         }
         match WORD_SIZE {
-            32 => {
-                let mut remainder = (x as i32) % (y as i32);
-                if (remainder < 0 && (y as i32) > 0) || (remainder > 0 && (y as i32) < 0) {
-                    remainder += y as i32;
-                }
-                remainder as u32 as u64
-            }
-            64 => {
-                let mut remainder = (x as i64) % (y as i64);
-                if (remainder < 0 && (y as i64) > 0) || (remainder > 0 && (y as i64) < 0) {
-                    remainder += y as i64;
-                }
-                remainder as u64
-            }
+            32 => (x as i32).wrapping_rem(y as i32) as u32 as u64,
+            64 => (x as i64).wrapping_rem(y as i64) as u64,
             _ => panic!("Unsupported WORD_SIZE: {WORD_SIZE}"),
         }
     }
@@ -243,7 +249,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMInstruction<WORD_
 
 #[cfg(test)]
 mod test {
-    use crate::jolt::instruction::test::jolt_virtual_sequence_test;
+    use crate::jolt::instruction::test::{check_virtual_sequence, jolt_virtual_sequence_test};
 
     use super::*;
 
@@ -251,4 +257,31 @@ mod test {
     fn rem_virtual_sequence_32() {
         jolt_virtual_sequence_test::<REMInstruction<32>>(RV32IM::REM);
     }
+
+    #[test]
+    fn rem_takes_sign_of_dividend() {
+        // (x, y, x % y) as RISC-V defines signed remainder
+        let cases: [(i32, i32, i32); 10] = [
+            (7, 2, 1),
+            (-7, 2, -1),
+            (7, -2, 1),
+            (-7, -2, -1),
+            (-1, 2, -1),
+            (1, -2, 1),
+            (-6, 3, 0),
+            (i32::MIN, -1, 0),
+            (i32::MIN, 3, -2),
+            (-5, 0, -5),
+        ];
+        for (x, y, remainder) in cases {
+            check_virtual_sequence::<REMInstruction<32>>(
+                RV32IM::REM,
+                0,
+                (1, x as u32 as u64),
+                (2, y as u32 as u64),
+                3,
+                remainder as u32 as u64,
+            );
+        }
+    }
 }
@@ -56,6 +56,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(q),
             },
             memory_state: None,
@@ -78,6 +79,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(r),
             },
             memory_state: None,
@@ -100,6 +102,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: Some(q_y),
             },
             memory_state: None,
@@ -123,6 +126,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(r),
                 rs2_val: Some(y),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -145,6 +149,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -167,6 +172,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(q_y),
                 rs2_val: Some(r),
+                rs3_val: None,
                 rd_post_val: Some(add_0),
             },
             memory_state: None,
@@ -189,6 +195,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(add_0),
                 rs2_val: Some(x),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -210,6 +217,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for REMUInstruction<WORD
             register_state: RegisterState {
                 rs1_val: Some(r),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(r),
             },
             memory_state: None,
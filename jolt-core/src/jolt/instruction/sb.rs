@@ -51,6 +51,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(dest),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(ram_address),
             },
             memory_state: None,
@@ -75,6 +76,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_address),
             },
             memory_state: None,
@@ -115,6 +117,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_loaded),
             },
             memory_state: Some(MemoryState::Read {
@@ -140,6 +143,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(bit_shift),
             },
             memory_state: None,
@@ -164,6 +168,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(byte_mask as u64),
             },
             memory_state: None,
@@ -192,6 +197,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(byte_mask as u64),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(shifted_mask),
             },
             memory_state: None,
@@ -214,6 +220,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(value),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(shifted_value),
             },
             memory_state: None,
@@ -240,6 +247,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_loaded),
                 rs2_val: Some(shifted_value),
+                rs3_val: None,
                 rd_post_val: Some(word_xor_halfword),
             },
             memory_state: None,
@@ -262,6 +270,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_xor_halfword),
                 rs2_val: Some(shifted_mask),
+                rs3_val: None,
                 rd_post_val: Some(masked),
             },
             memory_state: None,
@@ -285,6 +294,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_loaded),
                 rs2_val: Some(masked),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
@@ -306,6 +316,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: Some(result), // Lookup query
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: Some(MemoryState::Write {
@@ -331,6 +342,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SBInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(0),
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(0),
             },
             memory_state: Some(MemoryState::Write {
@@ -400,6 +412,7 @@ mod test {
                 register_state: RegisterState {
                     rs1_val: Some(rs1_val),
                     rs2_val: Some(rs2_val),
+                    rs3_val: None,
                     rd_post_val: None,
                 },
                 memory_state: Some(MemoryState::Write {
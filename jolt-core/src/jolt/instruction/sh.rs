@@ -54,6 +54,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(dest),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -77,6 +78,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(dest),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(ram_address),
             },
             memory_state: None,
@@ -101,6 +103,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_address),
             },
             memory_state: None,
@@ -141,6 +144,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(word_loaded),
             },
             memory_state: Some(MemoryState::Read {
@@ -166,6 +170,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(ram_address),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(bit_shift),
             },
             memory_state: None,
@@ -190,6 +195,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(halfword_mask as u64),
             },
             memory_state: None,
@@ -214,6 +220,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(halfword_mask as u64),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(shifted_mask),
             },
             memory_state: None,
@@ -236,6 +243,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(value),
                 rs2_val: Some(bit_shift),
+                rs3_val: None,
                 rd_post_val: Some(shifted_value),
             },
             memory_state: None,
@@ -262,6 +270,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_loaded),
                 rs2_val: Some(shifted_value),
+                rs3_val: None,
                 rd_post_val: Some(word_xor_halfword),
             },
             memory_state: None,
@@ -284,6 +293,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_xor_halfword),
                 rs2_val: Some(shifted_mask),
+                rs3_val: None,
                 rd_post_val: Some(masked),
             },
             memory_state: None,
@@ -307,6 +317,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_loaded),
                 rs2_val: Some(masked),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
@@ -328,6 +339,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(word_address),
                 rs2_val: Some(result), // Lookup query
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: Some(MemoryState::Write {
@@ -353,6 +365,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SHInstruction<WORD_S
             register_state: RegisterState {
                 rs1_val: Some(0),
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(0),
             },
             memory_state: Some(MemoryState::Write {
@@ -420,6 +433,7 @@ mod test {
                 register_state: RegisterState {
                     rs1_val: Some(rs1_val),
                     rs2_val: Some(rs2_val),
+                    rs3_val: None,
                     rd_post_val: None,
                 },
                 memory_state: Some(MemoryState::Write {
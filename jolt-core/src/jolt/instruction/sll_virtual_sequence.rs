@@ -35,6 +35,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SLLVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: trace_row.register_state.rs2_val,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(pow2),
                     },
                     memory_state: None,
@@ -66,6 +67,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SLLVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: None,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(pow2),
                     },
                     memory_state: None,
@@ -93,6 +95,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SLLVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: trace_row.register_state.rs1_val,
                 rs2_val: Some(pow2),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
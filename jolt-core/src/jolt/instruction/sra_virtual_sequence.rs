@@ -44,6 +44,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: trace_row.register_state.rs2_val,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(bitmask),
                     },
                     memory_state: None,
@@ -68,6 +69,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: trace_row.register_state.rs2_val,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(shift_pow2),
                     },
                     memory_state: None,
@@ -99,6 +101,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: None,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(bitmask),
                     },
                     memory_state: None,
@@ -123,6 +126,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: None,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(shift_pow2),
                     },
                     memory_state: None,
@@ -151,6 +155,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: None,
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
@@ -173,6 +178,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(bitmask),
                 rs2_val: Some(result),
+                rs3_val: None,
                 rd_post_val: Some(masked_advice),
             },
             memory_state: None,
@@ -195,6 +201,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: trace_row.register_state.rs1_val,
                 rs2_val: Some(0),
+                rs3_val: None,
                 rd_post_val: Some(is_negative),
             },
             memory_state: None,
@@ -216,6 +223,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(bitmask),
                 rs2_val: Some(is_negative),
+                rs3_val: None,
                 rd_post_val: Some(is_negative * bitmask),
             },
             memory_state: None,
@@ -237,6 +245,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(masked_advice),
                 rs2_val: Some(is_negative * bitmask),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -260,6 +269,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(result),
                 rs2_val: Some(shift_pow2),
+                rs3_val: None,
                 rd_post_val: Some(shifted_advice),
             },
             memory_state: None,
@@ -281,6 +291,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(x),
                 rs2_val: Some(shifted_advice),
+                rs3_val: None,
                 rd_post_val: Some(x & shifted_advice),
             },
             memory_state: None,
@@ -302,6 +313,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(shifted_advice),
                 rs2_val: Some(x & shifted_advice),
+                rs3_val: None,
                 rd_post_val: None,
             },
             memory_state: None,
@@ -323,6 +335,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRAVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: Some(result),
                 rs2_val: None,
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
@@ -34,6 +34,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRLVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: trace_row.register_state.rs2_val,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(pow2),
                     },
                     memory_state: None,
@@ -63,6 +64,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRLVirtualSequence<W
                     register_state: RegisterState {
                         rs1_val: None,
                         rs2_val: None,
+                        rs3_val: None,
                         rd_post_val: Some(pow2),
                     },
                     memory_state: None,
@@ -90,6 +92,7 @@ impl<const WORD_SIZE: usize> VirtualInstructionSequence for SRLVirtualSequence<W
             register_state: RegisterState {
                 rs1_val: trace_row.register_state.rs1_val,
                 rs2_val: Some(pow2),
+                rs3_val: None,
                 rd_post_val: Some(result),
             },
             memory_state: None,
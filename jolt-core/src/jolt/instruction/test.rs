@@ -76,83 +76,97 @@ pub fn jolt_virtual_sequence_test<I: VirtualInstructionSequence>(opcode: RV32IM)
             rng.next_u32() as u64
         };
         let result = I::sequence_output(x, y);
+        check_virtual_sequence::<I>(opcode, rng.next_u64(), (r_x, x), (r_y, y), rd, result);
+    }
+}
 
-        let mut registers = vec![0u64; REGISTER_COUNT as usize];
-        registers[r_x as usize] = x;
-        registers[r_y as usize] = y;
+/// Traces `opcode` with `rs1 = x` and `rs2 = y`, then checks that every row of its
+/// virtual sequence is consistent with the registers before it and with its lookup,
+/// that `r_x` and `r_y` are not clobbered, and that `rd` ends up holding `result`.
+pub fn check_virtual_sequence<I: VirtualInstructionSequence>(
+    opcode: RV32IM,
+    address: u64,
+    (r_x, x): (u64, u64),
+    (r_y, y): (u64, u64),
+    rd: u64,
+    result: u64,
+) {
+    let mut registers = vec![0u64; REGISTER_COUNT as usize];
+    registers[r_x as usize] = x;
+    registers[r_y as usize] = y;
 
-        let trace_row = RVTraceRow {
-            instruction: ELFInstruction {
-                address: rng.next_u64(),
-                opcode,
-                rs1: Some(r_x),
-                rs2: Some(r_y),
-                rd: Some(rd),
-                imm: None,
-                virtual_sequence_remaining: None,
-            },
-            register_state: RegisterState {
-                rs1_val: Some(x),
-                rs2_val: Some(y),
-                rd_post_val: Some(result),
-            },
-            memory_state: None,
-            advice_value: None,
-            precompile_input: None,
-            precompile_output_address: None,
-        };
+    let trace_row = RVTraceRow {
+        instruction: ELFInstruction {
+            address,
+            opcode,
+            rs1: Some(r_x),
+            rs2: Some(r_y),
+            rd: Some(rd),
+            imm: None,
+            virtual_sequence_remaining: None,
+        },
+        register_state: RegisterState {
+            rs1_val: Some(x),
+            rs2_val: Some(y),
+            rs3_val: None,
+            rd_post_val: Some(result),
+        },
+        memory_state: None,
+        advice_value: None,
+        precompile_input: None,
+        precompile_output_address: None,
+    };
 
-        let virtual_sequence = I::virtual_trace(trace_row);
-        assert_eq!(virtual_sequence.len(), I::SEQUENCE_LENGTH);
+    let virtual_sequence = I::virtual_trace(trace_row);
+    assert_eq!(virtual_sequence.len(), I::SEQUENCE_LENGTH);
 
-        for row in virtual_sequence {
-            if let Some(rs1_val) = row.register_state.rs1_val {
-                assert_eq!(
-                    registers[row.instruction.rs1.unwrap() as usize],
-                    rs1_val,
-                    "{row:?}"
-                );
-            }
-            if let Some(rs2_val) = row.register_state.rs2_val {
-                assert_eq!(
-                    registers[row.instruction.rs2.unwrap() as usize],
-                    rs2_val,
-                    "{row:?}"
-                );
-            }
+    for row in virtual_sequence {
+        if let Some(rs1_val) = row.register_state.rs1_val {
+            assert_eq!(
+                registers[row.instruction.rs1.unwrap() as usize],
+                rs1_val,
+                "{row:?}"
+            );
+        }
+        if let Some(rs2_val) = row.register_state.rs2_val {
+            assert_eq!(
+                registers[row.instruction.rs2.unwrap() as usize],
+                rs2_val,
+                "{row:?}"
+            );
+        }
 
-            let lookup = RV32I::try_from(&row).unwrap();
-            let output = lookup.lookup_entry();
-            if let Some(rd) = row.instruction.rd {
-                registers[rd as usize] = output;
-                assert_eq!(
-                    registers[rd as usize],
-                    row.register_state.rd_post_val.unwrap(),
-                    "{row:?}"
-                );
-            } else {
-                assert!(output == 1, "{row:?}")
-            }
+        let lookup = RV32I::try_from(&row).unwrap();
+        let output = lookup.lookup_entry();
+        if let Some(rd) = row.instruction.rd {
+            registers[rd as usize] = output;
+            assert_eq!(
+                registers[rd as usize],
+                row.register_state.rd_post_val.unwrap(),
+                "{row:?}"
+            );
+        } else {
+            assert!(output == 1, "{row:?}")
         }
+    }
 
-        for (index, val) in registers.iter().enumerate() {
-            if index as u64 == r_x {
-                if r_x != rd {
-                    // Check that r_x hasn't been clobbered
-                    assert_eq!(*val, x);
-                }
-            } else if index as u64 == r_y {
-                if r_y != rd {
-                    // Check that r_y hasn't been clobbered
-                    assert_eq!(*val, y);
-                }
-            } else if index as u64 == rd {
-                // Check that result was written to rd
-                assert_eq!(*val, result as u64);
-            } else if index < 32 {
-                // None of the other "real" registers were touched
-                assert_eq!(*val, 0, "Other 'real' registers should not be touched");
+    for (index, val) in registers.iter().enumerate() {
+        if index as u64 == r_x {
+            if r_x != rd {
+                // Check that r_x hasn't been clobbered
+                assert_eq!(*val, x);
+            }
+        } else if index as u64 == r_y {
+            if r_y != rd {
+                // Check that r_y hasn't been clobbered
+                assert_eq!(*val, y);
             }
+        } else if index as u64 == rd {
+            // Check that result was written to rd
+            assert_eq!(*val, result as u64);
+        } else if index < 32 {
+            // None of the other "real" registers were touched
+            assert_eq!(*val, 0, "Other 'real' registers should not be touched");
         }
     }
 }
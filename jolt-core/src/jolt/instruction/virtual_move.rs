@@ -37,9 +37,8 @@ impl<const WORD_SIZE: usize> JoltInstruction for MOVEInstruction<WORD_SIZE> {
     fn subtables<F: JoltField>(
         &self,
         C: usize,
-        M: usize,
+        _: usize,
     ) -> Vec<(Box<dyn LassoSubtable<F>>, SubtableIndices)> {
-        assert!(M == 1 << 16);
         vec![(
             // Implicitly range-checks all query chunks
             Box::new(IdentitySubtable::<F>::new()),
@@ -34,10 +34,9 @@ impl<const WORD_SIZE: usize> JoltInstruction for MOVSIGNInstruction<WORD_SIZE> {
     }
 
     fn combine_lookups<F: JoltField>(&self, vals: &[F], _: usize, M: usize) -> F {
-        // TODO(moodlezoup): make this work with different M
-        assert!(M == 1 << 16);
+        // The sign extension of the most significant chunk, repeated to fill the word
         let val = vals[0];
-        let repeat = WORD_SIZE / 16;
+        let repeat = WORD_SIZE / log2(M) as usize;
         concatenate_lookups(&vec![val; repeat], repeat, log2(M) as usize)
     }
 
@@ -50,13 +49,15 @@ impl<const WORD_SIZE: usize> JoltInstruction for MOVSIGNInstruction<WORD_SIZE> {
         C: usize,
         M: usize,
     ) -> Vec<(Box<dyn LassoSubtable<F>>, SubtableIndices)> {
-        assert!(M == 1 << 16);
-        let msb_chunk_index = C - (WORD_SIZE / 16);
+        let log_M = log2(M) as usize;
+        let sign_extend: Box<dyn LassoSubtable<F>> = match log_M {
+            8 => Box::new(SignExtendSubtable::<F, 8>::new()),
+            16 => Box::new(SignExtendSubtable::<F, 16>::new()),
+            _ => panic!("MOVSIGN is only implemented for log2(M) = 8 or 16"),
+        };
+        let msb_chunk_index = C - (WORD_SIZE / log_M);
         vec![
-            (
-                Box::new(SignExtendSubtable::<F, 16>::new()),
-                SubtableIndices::from(msb_chunk_index),
-            ),
+            (sign_extend, SubtableIndices::from(msb_chunk_index)),
             (
                 // Not used for lookup, but this implicitly range-checks
                 // the remaining query chunks
@@ -1,10 +1,8 @@
 use crate::field::JoltField;
-use enum_dispatch::enum_dispatch;
 use std::any::TypeId;
 use std::marker::Sync;
 use strum::{EnumCount, IntoEnumIterator};
 
-#[enum_dispatch]
 pub trait LassoSubtable<F: JoltField>: 'static + Sync {
     /// Returns the TypeId of this subtable.
     /// The `Jolt` trait has associated enum types `InstructionSet` and `Subtables`.
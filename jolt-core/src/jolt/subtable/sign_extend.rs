@@ -1,5 +1,4 @@
 use crate::field::JoltField;
-use ark_std::log2;
 use std::marker::PhantomData;
 
 use super::LassoSubtable;
@@ -21,8 +20,7 @@ impl<F: JoltField, const WIDTH: usize> LassoSubtable<F> for SignExtendSubtable<F
     fn materialize(&self, M: usize) -> Vec<u32> {
         // table[x] = x[b - WIDTH] * (2^{WIDTH} - 1)
         // Take the WIDTH-th bit of the input (counting from the LSB), then multiply by (2^{WIDTH} - 1)
-        // Requires `log2(M) >= WIDTH`
-        debug_assert!(WIDTH <= log2(M) as usize);
+        // If `log2(M) < WIDTH`, the input has no such bit and the table is all zeros
         let mut entries = Vec::with_capacity(M);
 
         // The sign-extension will be the same width as the value being extended
@@ -40,7 +38,9 @@ impl<F: JoltField, const WIDTH: usize> LassoSubtable<F> for SignExtendSubtable<F
 
     fn evaluate_mle(&self, point: &[F]) -> F {
         // 2 ^ {WIDTH - 1} * x_{b - WIDTH}
-        debug_assert!(point.len() >= WIDTH);
+        if point.len() < WIDTH {
+            return F::zero();
+        }
 
         let sign_bit = point[point.len() - WIDTH];
         let ones: u64 = (1 << WIDTH) - 1;
@@ -71,4 +71,11 @@ mod test {
         Fr,
         1 << 16
     );
+
+    subtable_materialize_mle_parity_test!(
+        sign_extend_16_narrow_materialize_mle_parity,
+        SignExtendSubtable<Fr, 16>,
+        Fr,
+        1 << 8
+    );
 }
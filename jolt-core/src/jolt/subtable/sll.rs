@@ -36,11 +36,12 @@ impl<F: JoltField, const CHUNK_INDEX: usize, const WORD_SIZE: usize> LassoSubtab
         for idx in 0..M {
             let (x, y) = split_bits(idx, operand_chunk_width);
 
-            // Need to handle u64::MAX in a special case because of overflow
-            let truncate_mask = if WORD_SIZE - suffix_length >= 64 {
+            // Need to handle u64::MAX in a special case because of overflow. A chunk lying
+            // entirely above the word is truncated to zero.
+            let truncate_mask = if WORD_SIZE.saturating_sub(suffix_length) >= 64 {
                 u64::MAX
             } else {
-                (1 << (WORD_SIZE - suffix_length)) - 1
+                (1 << WORD_SIZE.saturating_sub(suffix_length)) - 1
             };
 
             let row = (x as u64).checked_shl((y % WORD_SIZE) as u32).unwrap_or(0) & truncate_mask;
@@ -115,4 +116,9 @@ mod test {
     subtable_materialize_mle_parity_test!(sll_materialize_mle_parity1_32, SllSubtable<Fr, 1, 32>, Fr, 1 << 16);
     subtable_materialize_mle_parity_test!(sll_materialize_mle_parity2_32, SllSubtable<Fr, 2, 32>, Fr, 1 << 16);
     subtable_materialize_mle_parity_test!(sll_materialize_mle_parity3_32, SllSubtable<Fr, 3, 32>, Fr, 1 << 16);
+    subtable_materialize_mle_parity_test!(sll_materialize_mle_parity4_32, SllSubtable<Fr, 4, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(sll_materialize_mle_parity5_32, SllSubtable<Fr, 5, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(sll_materialize_mle_parity6_32, SllSubtable<Fr, 6, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(sll_materialize_mle_parity7_32, SllSubtable<Fr, 7, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(sll_materialize_mle_parity7_32_wide, SllSubtable<Fr, 7, 32>, Fr, 1 << 16);
 }
@@ -36,11 +36,16 @@ impl<F: JoltField, const CHUNK_INDEX: usize, const WORD_SIZE: usize> LassoSubtab
         for idx in 0..M {
             let (x, y) = split_bits(idx, operand_chunk_width);
 
-            let row = (x as u64)
-                .checked_shl(suffix_length as u32)
-                .unwrap_or(0)
-                .checked_shr((y % WORD_SIZE) as u32)
-                .unwrap_or(0);
+            // A chunk lying entirely above the word is all zeros
+            let row = if suffix_length >= WORD_SIZE {
+                0
+            } else {
+                (x as u64)
+                    .checked_shl(suffix_length as u32)
+                    .unwrap_or(0)
+                    .checked_shr((y % WORD_SIZE) as u32)
+                    .unwrap_or(0)
+            };
 
             entries.push(row as u32);
         }
@@ -86,7 +91,7 @@ impl<F: JoltField, const CHUNK_INDEX: usize, const WORD_SIZE: usize> LassoSubtab
             };
 
             // the most significant chunk might be shorter
-            let chunk_length = min(b, WORD_SIZE - b * CHUNK_INDEX);
+            let chunk_length = min(b, WORD_SIZE.saturating_sub(b * CHUNK_INDEX));
 
             let shift_x_by_k = (m..chunk_length)
                 .map(|j| F::from_u64(1_u64 << (b * CHUNK_INDEX + j - k)) * x[b - 1 - j])
@@ -112,4 +117,9 @@ mod test {
     subtable_materialize_mle_parity_test!(srl_materialize_mle_parity1, SrlSubtable<Fr, 1, 32>, Fr, 1 << 10);
     subtable_materialize_mle_parity_test!(srl_materialize_mle_parity2, SrlSubtable<Fr, 2, 32>, Fr, 1 << 10);
     subtable_materialize_mle_parity_test!(srl_materialize_mle_parity3, SrlSubtable<Fr, 3, 32>, Fr, 1 << 10);
+    subtable_materialize_mle_parity_test!(srl_materialize_mle_parity4, SrlSubtable<Fr, 4, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(srl_materialize_mle_parity5, SrlSubtable<Fr, 5, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(srl_materialize_mle_parity6, SrlSubtable<Fr, 6, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(srl_materialize_mle_parity7, SrlSubtable<Fr, 7, 32>, Fr, 1 << 8);
+    subtable_materialize_mle_parity_test!(srl_materialize_mle_parity7_wide, SrlSubtable<Fr, 7, 32>, Fr, 1 << 16);
 }
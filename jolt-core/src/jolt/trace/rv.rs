@@ -70,7 +70,7 @@ impl TryFrom<&ELFInstruction> for RV32I {
             RV32IM::MULU => Ok(MULUInstruction::default().into()),
             RV32IM::MULHU => Ok(MULHUInstruction::default().into()),
 
-            RV32IM::VIRTUAL_ADVICE => Ok(ADVICEInstruction::default().into()),
+            RV32IM::VIRTUAL_ADVICE | RV32IM::VIRTUAL_PRECOMPILE_ADVICE => Ok(ADVICEInstruction::default().into()),
             RV32IM::VIRTUAL_MOVE => Ok(MOVEInstruction::default().into()),
             RV32IM::VIRTUAL_MOVSIGN => Ok(MOVSIGNInstruction::default().into()),
             RV32IM::VIRTUAL_ASSERT_EQ => Ok(BEQInstruction::default().into()),
@@ -132,7 +132,7 @@ impl TryFrom<&RVTraceRow> for RV32I {
             RV32IM::MULU => Ok(MULUInstruction(row.register_state.rs1_val.unwrap(), row.register_state.rs2_val.unwrap()).into()),
             RV32IM::MULHU => Ok(MULHUInstruction(row.register_state.rs1_val.unwrap(), row.register_state.rs2_val.unwrap()).into()),
 
-            RV32IM::VIRTUAL_ADVICE => Ok(ADVICEInstruction(row.advice_value.unwrap()).into()),
+            RV32IM::VIRTUAL_ADVICE | RV32IM::VIRTUAL_PRECOMPILE_ADVICE => Ok(ADVICEInstruction(row.advice_value.unwrap()).into()),
             RV32IM::VIRTUAL_MOVE => Ok(MOVEInstruction(row.register_state.rs1_val.unwrap()).into()),
             RV32IM::VIRTUAL_MOVSIGN => Ok(MOVSIGNInstruction(row.register_state.rs1_val.unwrap()).into()),
             RV32IM::VIRTUAL_ASSERT_EQ => Ok(BEQInstruction(row.register_state.rs1_val.unwrap(), row.register_state.rs2_val.unwrap()).into()),
@@ -15,7 +15,9 @@ use crate::lasso::memory_checking::{
 };
 use crate::poly::commitment::commitment_scheme::CommitmentScheme;
 use crate::poly::compact_polynomial::{CompactPolynomial, SmallScalar};
+use crate::poly::dense_mlpoly::DensePolynomial;
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
+use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::subprotocols::grand_product::BatchedDenseGrandProduct;
 use common::constants::{BYTES_PER_INSTRUCTION, RAM_START_ADDRESS};
 use common::rv_trace::ELFInstruction;
@@ -23,6 +25,7 @@ use common::rv_trace::ELFInstruction;
 use rayon::prelude::*;
 
 use super::{JoltPolynomials, JoltTraceStep};
+use crate::utils::errors::{ProofVerifyError, WitnessError};
 use crate::utils::transcript::Transcript;
 
 use crate::{
@@ -46,7 +49,10 @@ pub struct BytecodeStuff<T: CanonicalSerialize + CanonicalDeserialize> {
     /// Final timestamps for offline memory-checking
     pub(crate) t_final: T,
     a_init_final: VerifierComputedOpening<T>,
-    v_init_final: VerifierComputedOpening<[T; 6]>,
+    /// Computed by the verifier, unless the bytecode is committed to in preprocessing
+    /// (see `BytecodePreprocessing::commit`), in which case the openings are provided by
+    /// the prover and the commitments are taken from the verifier preprocessing.
+    pub(crate) v_init_final: VerifierComputedOpening<[T; 6]>,
 }
 
 /// Note –– F: JoltField bound is not enforced.
@@ -136,6 +142,20 @@ impl BytecodeRow {
         }
     }
 
+    /// Memory address as read from the ELF, recovered from the compressed address the
+    /// row holds once its trace has gone through [`BytecodeProof::generate_witness`].
+    /// `None` for no-ops.
+    pub fn uncompressed_address(&self) -> Option<usize> {
+        self.address
+            .checked_sub(1)
+            .map(|index| RAM_START_ADDRESS as usize + index * BYTES_PER_INSTRUCTION)
+    }
+
+    /// The number of virtual instructions after this one in its virtual sequence, if any.
+    pub fn virtual_sequence_remaining(&self) -> Option<usize> {
+        self.virtual_sequence_remaining
+    }
+
     pub fn no_op(address: usize) -> Self {
         Self {
             address,
@@ -187,6 +207,8 @@ impl BytecodeRow {
         let imm = match instruction.opcode {
             RV32IM::LW
             | RV32IM::SW
+            | RV32IM::VIRTUAL_PRECOMPILE_LW
+            | RV32IM::VIRTUAL_PRECOMPILE_SW
             | RV32IM::BEQ
             | RV32IM::BNE
             | RV32IM::BLT
@@ -233,9 +255,18 @@ pub struct BytecodePreprocessing<F: JoltField> {
     /// is the one used to keep track of the next (potentially virtual) instruction to execute.
     /// Key: (ELF address, virtual sequence index or 0)
     virtual_address_map: BTreeMap<(usize, usize), usize>,
+    /// Whether `v_init_final` is committed to in the verifier preprocessing. If so, its
+    /// openings are proven via the opening accumulator instead of being computed by the
+    /// verifier, so the verifier does not need `v_init_final` itself (see `strip`).
+    committed: bool,
 }
 
 impl<F: JoltField> BytecodePreprocessing<F> {
+    /// Size of the bytecode, padded to a power of two.
+    pub fn code_size(&self) -> usize {
+        self.code_size
+    }
+
     #[tracing::instrument(skip_all, name = "BytecodePreprocessing::preprocess")]
     pub fn preprocess(mut bytecode: Vec<BytecodeRow>) -> Self {
         let mut virtual_address_map = BTreeMap::new();
@@ -296,8 +327,33 @@ impl<F: JoltField> BytecodePreprocessing<F> {
             v_init_final,
             code_size,
             virtual_address_map,
+            committed: false,
         }
     }
+
+    /// Commits to `v_init_final`, after which its openings are proven by the prover
+    /// rather than computed by the verifier.
+    pub fn commit<PCS, ProofTranscript>(&mut self, setup: &PCS::Setup) -> [PCS::Commitment; 6]
+    where
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        self.committed = true;
+        PCS::batch_commit(&self.v_init_final, setup)
+            .try_into()
+            .unwrap()
+    }
+
+    /// Drops the data that is only needed by the prover, leaving a constant-size
+    /// preprocessing. Only valid if the bytecode has been committed to.
+    pub fn strip(&mut self) {
+        assert!(
+            self.committed,
+            "bytecode must be committed to before stripping"
+        );
+        self.v_init_final = Default::default();
+        self.virtual_address_map.clear();
+    }
 }
 
 impl<F, PCS, ProofTranscript> BytecodeProof<F, PCS, ProofTranscript>
@@ -454,6 +510,29 @@ where
         }
     }
 
+    /// Checks that the bytecode row read at each step is the row of the preprocessed
+    /// bytecode at its (virtual) address, as shown by offline memory checking.
+    #[tracing::instrument(skip_all, name = "BytecodeProof::check_witness")]
+    pub fn check_witness(
+        preprocessing: &BytecodePreprocessing<F>,
+        polynomials: &BytecodePolynomials<F>,
+    ) -> Result<(), WitnessError> {
+        let num_steps = polynomials.a_read_write.len();
+        let violation = (0..num_steps).into_par_iter().find_first(|&step| {
+            let address = polynomials.a_read_write.get_coeff_i128(step) as usize;
+            polynomials
+                .v_read_write
+                .iter()
+                .zip(preprocessing.v_init_final.iter())
+                .any(|(v_read, v_init)| v_read.get_coeff(step) != v_init.get_coeff(address))
+        });
+
+        match violation {
+            Some(step) => Err(WitnessError::BytecodeMismatch { step }),
+            None => Ok(()),
+        }
+    }
+
     #[tracing::instrument(skip_all, name = "BytecodePolynomials::validate_bytecode")]
     pub fn validate_bytecode(bytecode: &[BytecodeRow], trace: &[BytecodeRow]) {
         let mut bytecode_map: BTreeMap<usize, &BytecodeRow> = BTreeMap::new();
@@ -612,6 +691,29 @@ where
         )
     }
 
+    fn compute_preprocessing_openings(
+        preprocessing: &BytecodePreprocessing<F>,
+        _: &BytecodePolynomials<F>,
+        openings: &mut BytecodeOpenings<F>,
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        r_init_final: &[F],
+        transcript: &mut ProofTranscript,
+    ) {
+        if !preprocessing.committed {
+            return;
+        }
+        let v_init_final: Vec<_> = preprocessing.v_init_final.iter().collect();
+        let (evals, eq) = MultilinearPolynomial::batch_evaluate(&v_init_final, r_init_final);
+        opening_accumulator.append(
+            &v_init_final,
+            DensePolynomial::new(eq),
+            r_init_final.to_vec(),
+            &evals,
+            transcript,
+        );
+        openings.v_init_final = Some(evals.try_into().unwrap());
+    }
+
     fn protocol_name() -> &'static [u8] {
         b"Bytecode memory checking"
     }
@@ -633,15 +735,44 @@ where
         openings.a_init_final =
             Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
 
-        openings.v_init_final = Some(
-            MultilinearPolynomial::batch_evaluate(
-                &preprocessing.v_init_final.iter().collect::<Vec<_>>(),
-                r_init_final,
-            )
-            .0
-            .try_into()
-            .unwrap(),
+        // If the bytecode is committed to, the openings provided by the prover were
+        // checked in `verify_preprocessing_openings`
+        if !preprocessing.committed {
+            openings.v_init_final = Some(
+                MultilinearPolynomial::batch_evaluate(
+                    &preprocessing.v_init_final.iter().collect::<Vec<_>>(),
+                    r_init_final,
+                )
+                .0
+                .try_into()
+                .unwrap(),
+            );
+        }
+    }
+
+    fn verify_preprocessing_openings(
+        preprocessing: &BytecodePreprocessing<F>,
+        commitments: &BytecodeCommitments<PCS, ProofTranscript>,
+        openings: &BytecodeOpenings<F>,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        r_init_final: &[F],
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError> {
+        if !preprocessing.committed {
+            return Ok(());
+        }
+        let (Some(v_init_final_commitments), Some(v_init_final_openings)) =
+            (&commitments.v_init_final, &openings.v_init_final)
+        else {
+            return Err(ProofVerifyError::MissingPreprocessingOpening);
+        };
+        opening_accumulator.append(
+            &v_init_final_commitments.iter().collect::<Vec<_>>(),
+            r_init_final.to_vec(),
+            &v_init_final_openings.iter().collect::<Vec<_>>(),
+            transcript,
         );
+        Ok(())
     }
 
     fn read_tuples(
@@ -736,6 +867,7 @@ mod tests {
             memory_ops: [MemoryOp::noop_read(); MEMORY_OPS_PER_INSTRUCTION],
             bytecode_row,
             circuit_flags: [false; NUM_CIRCUIT_FLAGS],
+            opcode: None,
         }
     }
 
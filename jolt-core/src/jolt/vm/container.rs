@@ -0,0 +1,179 @@
+//! A versioned, self-describing container for serialized proofs and preprocessing.
+//!
+//! Layout:
+//!
+//! | magic (4) | version (u16 LE) | header | payload | checksum (32) |
+//!
+//! where `header` is a compressed [`ContainerHeader`], `payload` is the compressed
+//! artifact, and `checksum` is the SHA3-256 digest of all preceding bytes.
+//! Loaders check the magic, version and checksum before touching the header, and
+//! check the header against the expected commitment scheme, transcript, `C`/`M`
+//! and program before touching the payload, so artifacts produced by a different
+//! Jolt version or configuration fail with a descriptive [`ContainerError`].
+
+use std::fmt;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use sha3::{Digest, Sha3_256};
+
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::utils::errors::ContainerError;
+use crate::utils::transcript::Transcript;
+
+pub const CONTAINER_MAGIC: [u8; 4] = *b"JOLT";
+/// Bumped whenever the serialized layout of any container payload changes.
+pub const CONTAINER_VERSION: u16 = 1;
+
+const PREAMBLE_LEN: usize = CONTAINER_MAGIC.len() + std::mem::size_of::<u16>();
+const CHECKSUM_LEN: usize = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum ContainerKind {
+    Proof = 0,
+    VerifierPreprocessing = 1,
+    ProverPreprocessing = 2,
+}
+
+impl fmt::Display for ContainerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContainerKind::Proof => write!(f, "proof"),
+            ContainerKind::VerifierPreprocessing => write!(f, "verifier preprocessing"),
+            ContainerKind::ProverPreprocessing => write!(f, "prover preprocessing"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ContainerHeader {
+    kind: u8,
+    /// `CommitmentScheme::protocol_name` of the PCS.
+    pub pcs: Vec<u8>,
+    /// `Transcript::protocol_name` of the Fiat-Shamir transcript.
+    pub transcript: Vec<u8>,
+    pub c: u64,
+    pub m: u64,
+    /// See `JoltVerifierPreprocessing::program_id`. Loaders recompute the program ID
+    /// of a deserialized preprocessing before comparing it to this one.
+    pub program_id: [u8; 32],
+}
+
+impl ContainerHeader {
+    pub fn new<const C: usize, PCS, ProofTranscript>(
+        kind: ContainerKind,
+        m: usize,
+        program_id: [u8; 32],
+    ) -> Self
+    where
+        PCS: CommitmentScheme<ProofTranscript>,
+        ProofTranscript: Transcript,
+    {
+        Self {
+            kind: kind as u8,
+            pcs: PCS::protocol_name().to_vec(),
+            transcript: ProofTranscript::protocol_name().to_vec(),
+            c: C as u64,
+            m: m as u64,
+            program_id,
+        }
+    }
+
+    fn kind_name(kind: u8) -> String {
+        match kind {
+            0 => ContainerKind::Proof.to_string(),
+            1 => ContainerKind::VerifierPreprocessing.to_string(),
+            2 => ContainerKind::ProverPreprocessing.to_string(),
+            _ => format!("unknown artifact ({kind})"),
+        }
+    }
+
+    /// Checks that the container holds an artifact of the same kind, produced
+    /// with the same commitment scheme, transcript and `C` as `expected`.
+    /// `M` and the program ID are only checked if `expected` sets them to
+    /// a nonzero value.
+    pub fn check(&self, expected: &Self) -> Result<(), ContainerError> {
+        if self.kind != expected.kind {
+            return Err(ContainerError::KindMismatch {
+                found: Self::kind_name(self.kind),
+                expected: Self::kind_name(expected.kind),
+            });
+        }
+        if self.pcs != expected.pcs {
+            return Err(ContainerError::CommitmentSchemeMismatch {
+                found: String::from_utf8_lossy(&self.pcs).into_owned(),
+                expected: String::from_utf8_lossy(&expected.pcs).into_owned(),
+            });
+        }
+        if self.transcript != expected.transcript {
+            return Err(ContainerError::TranscriptMismatch {
+                found: String::from_utf8_lossy(&self.transcript).into_owned(),
+                expected: String::from_utf8_lossy(&expected.transcript).into_owned(),
+            });
+        }
+        if self.c != expected.c || (expected.m != 0 && self.m != expected.m) {
+            return Err(ContainerError::ParameterMismatch(
+                self.c, self.m, expected.c, expected.m,
+            ));
+        }
+        if expected.program_id != [0; 32] && self.program_id != expected.program_id {
+            return Err(ContainerError::ProgramIdMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Serializes `payload` into a container described by `header`.
+pub fn encode_container<T: CanonicalSerialize>(
+    header: &ContainerHeader,
+    payload: &T,
+) -> Result<Vec<u8>, ContainerError> {
+    let mut bytes = Vec::with_capacity(
+        PREAMBLE_LEN + header.compressed_size() + payload.compressed_size() + CHECKSUM_LEN,
+    );
+    bytes.extend_from_slice(&CONTAINER_MAGIC);
+    bytes.extend_from_slice(&CONTAINER_VERSION.to_le_bytes());
+    header.serialize_compressed(&mut bytes)?;
+    payload.serialize_compressed(&mut bytes)?;
+    let checksum: [u8; CHECKSUM_LEN] = Sha3_256::digest(&bytes).into();
+    bytes.extend_from_slice(&checksum);
+    Ok(bytes)
+}
+
+/// Validates the magic bytes, format version and checksum of a container,
+/// returning its header and the (still serialized) payload.
+pub fn decode_container(bytes: &[u8]) -> Result<(ContainerHeader, &[u8]), ContainerError> {
+    if bytes.len() < CONTAINER_MAGIC.len() || bytes[..CONTAINER_MAGIC.len()] != CONTAINER_MAGIC {
+        return Err(ContainerError::BadMagic);
+    }
+    if bytes.len() < PREAMBLE_LEN + CHECKSUM_LEN {
+        return Err(ContainerError::Truncated(bytes.len()));
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != CONTAINER_VERSION {
+        return Err(ContainerError::UnsupportedVersion {
+            found: version,
+            expected: CONTAINER_VERSION,
+        });
+    }
+
+    let (contents, checksum) = bytes.split_at(bytes.len() - CHECKSUM_LEN);
+    if Sha3_256::digest(contents).as_slice() != checksum {
+        return Err(ContainerError::ChecksumMismatch);
+    }
+
+    let mut rest = &contents[PREAMBLE_LEN..];
+    let header = ContainerHeader::deserialize_compressed(&mut rest)?;
+    Ok((header, rest))
+}
+
+/// Decodes a container, checks its header against `expected` (see
+/// [`ContainerHeader::check`]) and deserializes the payload.
+pub fn decode_container_payload<T: CanonicalDeserialize>(
+    bytes: &[u8],
+    expected: &ContainerHeader,
+) -> Result<(ContainerHeader, T), ContainerError> {
+    let (header, payload) = decode_container(bytes)?;
+    header.check(expected)?;
+    Ok((header, T::deserialize_compressed(payload)?))
+}
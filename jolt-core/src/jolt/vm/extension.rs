@@ -0,0 +1,346 @@
+//! Extends the RV32I Jolt VM with user-defined instructions.
+//!
+//! A custom instruction is an R-type instruction in the custom-0 or custom-1 major
+//! opcode (see [`custom_instruction_funct`]) that writes the lookup entry of a
+//! [`JoltInstruction`] on the values of `rs1` and `rs2` to `rd`. To prove programs
+//! that use custom instructions:
+//!
+//! 1. Implement [`JoltInstruction`] for each instruction, [`LassoSubtable`] for any new
+//!    subtables its lookups decompose into, and [`CustomInstruction`] to assign it an
+//!    encoding.
+//! 2. Generate an instruction set and a subtable set containing RV32I and the new
+//!    instructions/subtables with [`rv32i_instruction_set!`] and [`rv32i_subtable_enum!`].
+//! 3. Implement [`RV32IExtension`] for a marker type naming the two sets, trace the guest
+//!    with `Program::trace_with` and prove it with [`ExtendedJoltVM`].
+//!
+//! The R1CS constraints check that a custom instruction's lookup query is the
+//! concatenation of the chunks of `rs1` and `rs2`, so its `to_indices` must be
+//! `chunk_and_concatenate_operands` (like e.g. `ANDInstruction`). Instruction lookups
+//! are proven with Lasso, so custom instructions do not need a
+//! `PrefixSuffixDecomposition`, which is only used by the sparse-dense Shout prototype.
+//!
+//! This module re-exports everything needed to implement these traits.
+//!
+//! [`rv32i_instruction_set!`]: crate::rv32i_instruction_set
+//! [`rv32i_subtable_enum!`]: crate::rv32i_subtable_enum
+
+use std::marker::PhantomData;
+
+use common::rv_trace::NUM_CIRCUIT_FLAGS;
+pub use common::rv_trace::{custom_instruction_funct, ELFInstruction, RVTraceRow, RV32IM};
+pub use rand::{rngs::StdRng, RngCore};
+use strum::EnumCount;
+
+use super::rv32i_vm::{assert_valid_decomposition, C, M, RV32I};
+use super::Jolt;
+pub use crate::field::JoltField;
+pub use crate::jolt::instruction::{JoltInstruction, JoltInstructionSet, SubtableIndices};
+pub use crate::jolt::subtable::{JoltSubtableSet, LassoSubtable};
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::r1cs::constraints::JoltRV32IMConstraints;
+pub use crate::utils::instruction_utils::chunk_and_concatenate_operands;
+use crate::utils::transcript::Transcript;
+
+/// A `JoltInstruction` executed by the R-type instruction with major opcode `OPCODE`
+/// (`RV32IM::CUSTOM_0` or `RV32IM::CUSTOM_1`), `funct3 = FUNCT3` and `funct7 = FUNCT7`.
+pub trait CustomInstruction: JoltInstruction + Copy + PartialEq + Default + 'static {
+    const OPCODE: RV32IM;
+    const FUNCT3: u32;
+    const FUNCT7: u32;
+
+    /// Constructs the lookup for the given values of `rs1` and `rs2`.
+    fn from_operands(rs1_val: u64, rs2_val: u64) -> Self;
+
+    /// Whether `instruction` is an occurrence of this custom instruction.
+    fn is_encoded_by(instruction: &ELFInstruction) -> bool {
+        instruction.opcode == Self::OPCODE
+            && instruction.imm == Some(custom_instruction_funct(Self::FUNCT3, Self::FUNCT7))
+    }
+}
+
+fn execute<I: CustomInstruction>(rs1_val: u32, rs2_val: u32) -> u32 {
+    I::from_operands(rs1_val as u64, rs2_val as u64).lookup_entry() as u32
+}
+
+/// Registers `I` with the tracer, which executes it by computing its lookup entry.
+pub fn register_custom_instruction<I: CustomInstruction>() {
+    tracer::register_custom_instruction(I::OPCODE, I::FUNCT3, I::FUNCT7, execute::<I>);
+}
+
+/// RV32I extended with custom instructions and subtables.
+pub trait RV32IExtension {
+    /// Generated by [`rv32i_instruction_set!`](crate::rv32i_instruction_set).
+    type InstructionSet: JoltInstructionSet
+        + From<RV32I>
+        + for<'a> TryFrom<&'a RVTraceRow>
+        + 'static;
+    /// Generated by [`rv32i_subtable_enum!`](crate::rv32i_subtable_enum).
+    type Subtables<F: JoltField>: JoltSubtableSet<F>;
+}
+
+/// The RV32I Jolt VM extended with the custom instructions of `E`, with instruction
+/// lookups decomposed as in [`RV32IJoltVMWith`](super::rv32i_vm::RV32IJoltVMWith).
+pub struct ExtendedJoltVMWith<E, const C: usize, const M: usize>(PhantomData<E>);
+
+/// The extended Jolt VM with the default decomposition parameters [`C`] and [`M`].
+pub type ExtendedJoltVM<E> = ExtendedJoltVMWith<E, C, M>;
+
+impl<E, F, PCS, ProofTranscript, const C: usize, const M: usize> Jolt<F, PCS, C, M, ProofTranscript>
+    for ExtendedJoltVMWith<E, C, M>
+where
+    E: RV32IExtension,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    type InstructionSet = E::InstructionSet;
+    type Subtables = E::Subtables<F>;
+    type Constraints = JoltRV32IMConstraints<M, E::InstructionSet>;
+
+    fn validate_decomposition() {
+        assert_valid_decomposition(C, M);
+        assert!(
+            NUM_CIRCUIT_FLAGS + E::InstructionSet::COUNT <= 64,
+            "{} circuit flags and {} instruction flags do not fit in the 64-bit bytecode bitflags",
+            NUM_CIRCUIT_FLAGS,
+            E::InstructionSet::COUNT
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::test_rng;
+    use rand_chacha::rand_core::RngCore as _;
+    use serde::Serialize;
+    use std::marker::PhantomData;
+    use strum::{EnumCount, IntoEnumIterator};
+
+    use super::{
+        chunk_and_concatenate_operands, custom_instruction_funct, CustomInstruction,
+        ELFInstruction, ExtendedJoltVM, JoltField, JoltInstruction, JoltInstructionSet,
+        JoltSubtableSet, LassoSubtable, RV32IExtension, RngCore, StdRng, SubtableIndices, RV32IM,
+    };
+    use crate::host;
+    use crate::jolt::instruction::test::{instruction_mle_random_test, materialize_entry_test};
+    use crate::jolt::vm::rv32i_vm::{RV32ISubtables, RV32I};
+    use crate::jolt::vm::Jolt;
+    use crate::poly::commitment::mock::MockCommitScheme;
+    use crate::utils::split_bits;
+    use crate::utils::transcript::KeccakTranscript;
+    use crate::utils::uninterleave_bits;
+    use crate::{jolt_instruction_test, subtable_materialize_mle_parity_test};
+
+    /// table[x | y] = popcount(x)
+    #[derive(Default)]
+    pub struct PopcountSubtable<F: JoltField>(PhantomData<F>);
+
+    impl<F: JoltField> PopcountSubtable<F> {
+        fn new() -> Self {
+            Self(PhantomData)
+        }
+    }
+
+    impl<F: JoltField> LassoSubtable<F> for PopcountSubtable<F> {
+        fn materialize(&self, M: usize) -> Vec<u32> {
+            let bits_per_operand = (ark_std::log2(M) / 2) as usize;
+            (0..M)
+                .map(|idx| split_bits(idx, bits_per_operand).0.count_ones())
+                .collect()
+        }
+
+        fn evaluate_mle(&self, point: &[F]) -> F {
+            let b = point.len() / 2;
+            point[..b].iter().fold(F::zero(), |acc, x| acc + *x)
+        }
+    }
+
+    /// rd = popcount(rs1), encoded as custom-0 with funct3 = funct7 = 0.
+    #[derive(Copy, Clone, Default, Debug, Serialize, PartialEq)]
+    pub struct POPCNTInstruction(u64, u64);
+
+    impl JoltInstruction for POPCNTInstruction {
+        fn operands(&self) -> (u64, u64) {
+            (self.0, self.1)
+        }
+
+        fn combine_lookups<F: JoltField>(&self, vals: &[F], _: usize, _: usize) -> F {
+            vals.iter().fold(F::zero(), |acc, val| acc + *val)
+        }
+
+        fn g_poly_degree(&self, _: usize) -> usize {
+            1
+        }
+
+        fn subtables<F: JoltField>(
+            &self,
+            C: usize,
+            _: usize,
+        ) -> Vec<(Box<dyn LassoSubtable<F>>, SubtableIndices)> {
+            vec![(
+                Box::new(PopcountSubtable::new()),
+                SubtableIndices::from(0..C),
+            )]
+        }
+
+        fn to_indices(&self, C: usize, log_M: usize) -> Vec<usize> {
+            chunk_and_concatenate_operands(self.0, self.1, C, log_M)
+        }
+
+        fn materialize_entry(&self, index: u64) -> u64 {
+            uninterleave_bits(index).0.count_ones() as u64
+        }
+
+        fn lookup_entry(&self) -> u64 {
+            (self.0 as u32).count_ones() as u64
+        }
+
+        fn random(&self, rng: &mut StdRng) -> Self {
+            Self(rng.next_u32() as u64, rng.next_u32() as u64)
+        }
+
+        fn evaluate_mle<F: JoltField>(&self, r: &[F]) -> F {
+            r.iter().step_by(2).fold(F::zero(), |acc, x_i| acc + *x_i)
+        }
+    }
+
+    impl CustomInstruction for POPCNTInstruction {
+        const OPCODE: RV32IM = RV32IM::CUSTOM_0;
+        const FUNCT3: u32 = 0;
+        const FUNCT7: u32 = 0;
+
+        fn from_operands(rs1_val: u64, rs2_val: u64) -> Self {
+            Self(rs1_val, rs2_val)
+        }
+    }
+
+    crate::rv32i_instruction_set!(PopcountInstructionSet, POPCNT: POPCNTInstruction);
+    crate::rv32i_subtable_enum!(PopcountSubtables, Popcount: PopcountSubtable<F>);
+
+    pub struct Popcount;
+
+    impl RV32IExtension for Popcount {
+        type InstructionSet = PopcountInstructionSet;
+        type Subtables<F: JoltField> = PopcountSubtables<F>;
+    }
+
+    subtable_materialize_mle_parity_test!(
+        popcount_materialize_mle_parity,
+        PopcountSubtable<Fr>,
+        Fr,
+        256
+    );
+
+    #[test]
+    fn popcount_materialize_entry() {
+        materialize_entry_test::<Fr, POPCNTInstruction>();
+    }
+
+    #[test]
+    fn popcount_mle_random() {
+        instruction_mle_random_test::<Fr, POPCNTInstruction>();
+    }
+
+    #[test]
+    fn popcount_instruction_e2e() {
+        const C: usize = 4;
+        const M: usize = 1 << 16;
+        let mut rng = test_rng();
+        for _ in 0..256 {
+            let instruction = POPCNTInstruction(rng.next_u32() as u64, rng.next_u32() as u64);
+            jolt_instruction_test!(instruction);
+        }
+        jolt_instruction_test!(POPCNTInstruction(u32::MAX as u64, 0));
+    }
+
+    #[test]
+    fn extension_preserves_rv32i_flags() {
+        for instruction in RV32I::iter() {
+            assert_eq!(
+                RV32I::enum_index(&instruction),
+                PopcountInstructionSet::enum_index(&instruction.into())
+            );
+        }
+        assert_eq!(
+            PopcountInstructionSet::enum_index(&POPCNTInstruction::default().into()),
+            RV32I::COUNT
+        );
+        assert_eq!(
+            PopcountSubtables::<Fr>::enum_index(Box::new(PopcountSubtable::new())),
+            RV32ISubtables::<Fr>::COUNT
+        );
+    }
+
+    #[test]
+    fn decode_custom_instruction() {
+        let mut instruction = ELFInstruction {
+            address: 0,
+            opcode: RV32IM::CUSTOM_0,
+            rs1: Some(10),
+            rs2: Some(0),
+            rd: Some(10),
+            imm: Some(custom_instruction_funct(0, 0)),
+            virtual_sequence_remaining: None,
+        };
+        assert_eq!(
+            PopcountInstructionSet::try_from(&instruction),
+            Ok(PopcountInstructionSet::POPCNT(POPCNTInstruction::default()))
+        );
+        instruction.imm = Some(custom_instruction_funct(1, 0));
+        assert!(PopcountInstructionSet::try_from(&instruction).is_err());
+        instruction.opcode = RV32IM::CUSTOM_1;
+        instruction.imm = Some(custom_instruction_funct(0, 0));
+        assert!(PopcountInstructionSet::try_from(&instruction).is_err());
+    }
+
+    #[test]
+    fn custom_instruction_e2e() {
+        use crate::jolt::vm::rv32i_vm::{C, M};
+
+        let mut program = host::Program::new("custom-instruction-guest");
+        let inputs = postcard::to_stdvec(&100u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace_with::<PopcountInstructionSet>(&inputs);
+        assert!(trace.iter().any(|step| matches!(
+            step.instruction_lookup,
+            Some(PopcountInstructionSet::POPCNT(_))
+        )));
+        let expected: u32 = (0..100u32).map(u32::count_ones).sum();
+        assert_eq!(
+            postcard::from_bytes::<u32>(&io_device.outputs).unwrap(),
+            expected
+        );
+
+        type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+        let preprocessing =
+            <ExtendedJoltVM<Popcount> as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prover_preprocess(
+                bytecode,
+                io_device.memory_layout.clone(),
+                memory_init,
+                1 << 20,
+                1 << 20,
+                1 << 20,
+            );
+        let (proof, commitments, verifier_io_device, debug_info) =
+            <ExtendedJoltVM<Popcount> as Jolt<Fr, PCS, C, M, KeccakTranscript>>::prove(
+                io_device,
+                trace,
+                preprocessing.clone(),
+            );
+        let verification_result =
+            <ExtendedJoltVM<Popcount> as Jolt<Fr, PCS, C, M, KeccakTranscript>>::verify(
+                preprocessing.shared,
+                proof,
+                commitments,
+                verifier_io_device,
+                debug_info,
+            );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+}
@@ -31,7 +31,11 @@ use crate::{
         unipoly::{CompressedUniPoly, UniPoly},
     },
     subprotocols::sumcheck::SumcheckInstanceProof,
-    utils::{errors::ProofVerifyError, math::Math, transcript::AppendToTranscript},
+    utils::{
+        errors::{ProofVerifyError, WitnessError},
+        math::Math,
+        transcript::AppendToTranscript,
+    },
 };
 
 use super::{JoltCommitments, JoltPolynomials, JoltTraceStep};
@@ -211,7 +215,16 @@ where
                 let read_cts: &CompactPolynomial<u32, F> =
                     (&polynomials.read_cts[memory_index]).try_into().unwrap();
 
-                let read_fingerprints: Vec<F> = (0..num_lookups)
+                let nonzero_len = [
+                    &polynomials.dim[dim_index],
+                    &polynomials.E_polys[memory_index],
+                    &polynomials.read_cts[memory_index],
+                ]
+                .iter()
+                .map(|poly| poly.nonzero_len())
+                .max()
+                .unwrap();
+                let mut read_fingerprints: Vec<F> = (0..nonzero_len)
                     .map(|i| {
                         let a = dim[i];
                         let v = E_poly[i];
@@ -219,6 +232,8 @@ where
                         t.field_mul(gamma_squared) + v.field_mul(gamma) + F::from_u16(a) - *tau
                     })
                     .collect();
+                // Past the last lookup (e.g. over the padding), a = v = t = 0
+                read_fingerprints.resize(num_lookups, -*tau);
                 let t_adjustment = 1u64.field_mul(gamma_squared);
                 let write_fingerprints: Vec<F> = (0..num_lookups)
                     .map(|i| read_fingerprints[i] + t_adjustment)
@@ -342,7 +357,7 @@ where
     fn check_multiset_equality(
         preprocessing: &InstructionLookupsPreprocessing<C, F>,
         multiset_hashes: &MultisetHashes<F>,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         assert_eq!(multiset_hashes.init_hashes.len(), Self::NUM_SUBTABLES);
         assert_eq!(
             multiset_hashes.read_hashes.len(),
@@ -358,18 +373,17 @@ where
         );
         (0..preprocessing.num_memories)
             .into_par_iter()
-            .for_each(|i| {
+            .try_for_each(|i| {
                 let read_hash = multiset_hashes.read_hashes[i];
                 let write_hash = multiset_hashes.write_hashes[i];
                 let init_hash =
                     multiset_hashes.init_hashes[preprocessing.memory_to_subtable_index[i]];
                 let final_hash = multiset_hashes.final_hashes[i];
-                assert_eq!(
-                    init_hash * write_hash,
-                    final_hash * read_hash,
-                    "Multiset hashes don't match"
-                );
-            });
+                if init_hash * write_hash != final_hash * read_hash {
+                    return Err(ProofVerifyError::MultisetHashMismatch);
+                }
+                Ok(())
+            })
     }
 
     fn protocol_name() -> &'static [u8] {
@@ -479,7 +493,7 @@ where
         exogenous_openings: &NoExogenousOpenings,
         gamma: &F,
         tau: &F,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         let read_tuples: Vec<_> = Self::read_tuples(preprocessing, openings, exogenous_openings);
         let write_tuples: Vec<_> = Self::write_tuples(preprocessing, openings, exogenous_openings);
         let init_tuples: Vec<_> = Self::init_tuples(preprocessing, openings, exogenous_openings);
@@ -548,10 +562,11 @@ where
         // W(r', r'') = flags(r', r'') * fingerprints(r', r'') + 1 - flags(r', r'')
         //
         // and this should equal the claim output by the read-write grand product.
-        assert_eq!(
-            combined_flags * combined_read_write_fingerprint + F::one() - combined_flags,
-            read_write_claim
-        );
+        if combined_flags * combined_read_write_fingerprint + F::one() - combined_flags
+            != read_write_claim
+        {
+            return Err(ProofVerifyError::FingerprintMismatch);
+        }
 
         // The init-final grand product isn't toggled using flags (it's just a "normal" grand product)
         // so we combine the openings the normal way.
@@ -560,7 +575,11 @@ where
             .zip(EqPolynomial::evals(r_init_final_batch_index).iter())
             .map(|(tuple, eq_eval)| Self::fingerprint(tuple, gamma, tau) * eq_eval)
             .sum();
-        assert_eq!(combined_init_final_fingerprint, init_final_claim);
+        if combined_init_final_fingerprint != init_final_claim {
+            return Err(ProofVerifyError::FingerprintMismatch);
+        }
+
+        Ok(())
     }
 }
 
@@ -671,6 +690,13 @@ impl<const C: usize, F: JoltField> InstructionLookupsPreprocessing<C, F> {
         }
     }
 
+    /// The size `M` of each materialized subtable.
+    pub fn subtable_size(&self) -> usize {
+        self.materialized_subtables
+            .first()
+            .map_or(0, |subtable| subtable.len())
+    }
+
     /// Materializes all subtables used by this Jolt instance.
     #[tracing::instrument(skip_all)]
     fn materialize_subtables<const M: usize, Subtables>() -> Vec<Vec<u32>>
@@ -807,16 +833,18 @@ where
 
         // Verify that eq(r, r_z) * [f_1(r_z) * g(E_1(r_z)) + ... + f_F(r_z) * E_F(r_z))] = claim_last
         let eq_eval = EqPolynomial::new(r_eq.to_vec()).evaluate(&r_primary_sumcheck);
-        assert_eq!(
-            eq_eval
-                * (Self::combine_lookups(
-                    preprocessing,
-                    &proof.primary_sumcheck.openings.E_poly_openings,
-                    &proof.primary_sumcheck.openings.flag_openings,
-                ) - proof.primary_sumcheck.openings.lookup_outputs_opening),
-            claim_last,
-            "Primary sumcheck check failed."
-        );
+        if eq_eval
+            * (Self::combine_lookups(
+                preprocessing,
+                &proof.primary_sumcheck.openings.E_poly_openings,
+                &proof.primary_sumcheck.openings.flag_openings,
+            ) - proof.primary_sumcheck.openings.lookup_outputs_opening)
+            != claim_last
+        {
+            return Err(ProofVerifyError::SumcheckClaimMismatch(
+                "Primary sumcheck check failed.",
+            ));
+        }
 
         let primary_sumcheck_commitments = commitments
             .instruction_lookups
@@ -955,6 +983,43 @@ where
         }
     }
 
+    /// Checks that the lookup output at each step is the entry of the instruction's lookup
+    /// table at its lookup index, and that the subtable entries read at that step combine
+    /// to it, as shown by the primary sumcheck.
+    #[tracing::instrument(skip_all, name = "InstructionLookupsProof::check_witness")]
+    pub fn check_witness(
+        preprocessing: &InstructionLookupsPreprocessing<C, F>,
+        polynomials: &InstructionLookupPolynomials<F>,
+        ops: &[JoltTraceStep<InstructionSet>],
+    ) -> Result<(), WitnessError> {
+        let violation = ops.par_iter().enumerate().find_map_first(|(step, op)| {
+            let instr = op.instruction_lookup.as_ref()?;
+            let output: F = polynomials.lookup_outputs.get_coeff(step);
+            let expected = instr.materialize_entry(instr.to_lookup_index());
+            if output != F::from_u64(expected) {
+                return Some(WitnessError::LookupOutput {
+                    step,
+                    output: polynomials.lookup_outputs.get_coeff_i128(step) as u64,
+                    expected,
+                });
+            }
+
+            let memory_indices =
+                &preprocessing.instruction_to_memory_indices[InstructionSet::enum_index(instr)];
+            let subtable_entries: Vec<F> = memory_indices
+                .iter()
+                .map(|memory_index| polynomials.E_polys[*memory_index].get_coeff(step))
+                .collect();
+            (instr.combine_lookups(&subtable_entries, C, M) != output)
+                .then_some(WitnessError::LookupDecomposition { step })
+        });
+
+        match violation {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
     /// Prove Jolt primary sumcheck including instruction collation.
     ///
     /// Computes \sum{ eq(r,x) * [ flags_0(x) * g_0(E(x)) + flags_1(x) * g_1(E(x)) + ... + flags_{NUM_INSTRUCTIONS}(E(x)) * g_{NUM_INSTRUCTIONS}(E(x)) ]}
@@ -997,6 +1062,15 @@ where
             .for_each(|flag_poly| debug_assert_eq!(flag_poly.len(), poly_len));
         debug_assert_eq!(lookup_outputs_poly.len(), poly_len);
 
+        // Steps without a lookup (in particular, the no-ops padding the trace) contribute
+        // nothing to the sum, so each round only visits the prefix of steps that have one.
+        let mut active_len = flag_polys
+            .iter()
+            .chain([&*lookup_outputs_poly])
+            .map(|poly| poly.nonzero_len())
+            .max()
+            .unwrap();
+
         let mut previous_claim = F::zero();
         let mut r: Vec<F> = Vec::with_capacity(num_rounds);
         let mut compressed_polys: Vec<CompressedUniPoly<F>> = Vec::with_capacity(num_rounds);
@@ -1008,8 +1082,10 @@ where
                 flag_polys,
                 memory_polys,
                 lookup_outputs_poly,
+                active_len.div_ceil(2),
                 previous_claim,
             );
+            active_len = active_len.div_ceil(2);
 
             let compressed_poly = univariate_poly.compress();
             compressed_poly.append_to_transcript(transcript);
@@ -1062,13 +1138,13 @@ where
         flag_polys: &[MultilinearPolynomial<F>],
         subtable_polys: &[MultilinearPolynomial<F>],
         lookup_outputs_poly: &MultilinearPolynomial<F>,
+        num_active_pairs: usize,
         previous_claim: F,
     ) -> UniPoly<F> {
         let degree = Self::sumcheck_poly_degree();
-        let mle_len = eq_poly.len();
-        let mle_half = mle_len / 2;
+        debug_assert!(num_active_pairs <= eq_poly.len() / 2);
 
-        let mut evaluations: Vec<F> = (0..mle_half)
+        let mut evaluations: Vec<F> = (0..num_active_pairs)
             .into_par_iter()
             .map(|i| {
                 let eq_evals = eq_poly.sumcheck_evals(i, degree, BindingOrder::LowToHigh);
@@ -6,17 +6,18 @@ use crate::poly::multilinear_polynomial::MultilinearPolynomial;
 use crate::poly::opening_proof::{
     ProverOpeningAccumulator, ReducedOpeningProof, VerifierOpeningAccumulator,
 };
+use crate::r1cs::builder::CombinedUniformBuilder;
 use crate::r1cs::constraints::R1CSConstraints;
+use crate::r1cs::key::UniformSpartanKey;
 use crate::r1cs::spartan::{self, UniformSpartanProof};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use common::rv_trace::{MemoryLayout, NUM_CIRCUIT_FLAGS};
+use common::rv_trace::{MemoryLayout, NUM_CIRCUIT_FLAGS, RV32IM};
 use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use std::collections::{HashMap, HashSet};
 use std::marker::PhantomData;
-use std::{
-    fs::File,
-    io::{Read, Write},
-    path::Path,
-};
+use std::time::{Duration, Instant};
+use std::{fmt, fs, path::Path};
 use strum::EnumCount;
 use timestamp_range_check::TimestampRangeCheckStuff;
 
@@ -34,29 +35,40 @@ use crate::lasso::memory_checking::{
     Initializable, MemoryCheckingProver, MemoryCheckingVerifier, StructuredPolynomialData,
 };
 use crate::msm::icicle;
-use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::commitment::commitment_scheme::{CommitmentScheme, DeferredCommitmentScheme};
 use crate::r1cs::inputs::{ConstraintInput, R1CSPolynomials, R1CSProof, R1CSStuff};
-use crate::utils::errors::ProofVerifyError;
+use crate::utils::errors::{
+    BatchVerifyError, ContainerError, ProofVerifyError, WitnessError, WitnessReport,
+};
+use crate::utils::math::Math;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use common::{
+    blob::{place_blobs, Blob},
     constants::MEMORY_OPS_PER_INSTRUCTION,
+    precompiles::Precompile,
     rv_trace::{ELFInstruction, JoltDevice, MemoryOp},
 };
+use rayon::prelude::*;
 
 use self::bytecode::{BytecodePreprocessing, BytecodeProof, BytecodeRow, BytecodeStuff};
+use self::container::{decode_container_payload, encode_container, ContainerHeader, ContainerKind};
 use self::instruction_lookups::{
     InstructionLookupStuff, InstructionLookupsPreprocessing, InstructionLookupsProof,
 };
+use self::precompiles::PrecompileProof;
 use self::read_write_memory::{
-    ReadWriteMemoryPolynomials, ReadWriteMemoryPreprocessing, ReadWriteMemoryProof,
-    ReadWriteMemoryStuff,
+    blob_digest, commit_blob, ReadWriteMemoryPolynomials, ReadWriteMemoryPreprocessing,
+    ReadWriteMemoryProof, ReadWriteMemoryStuff,
 };
 
+use super::instruction::float;
 use super::instruction::lb::LBInstruction;
 use super::instruction::lbu::LBUInstruction;
 use super::instruction::lh::LHInstruction;
 use super::instruction::lhu::LHUInstruction;
+use super::instruction::narrow_shift;
+use super::instruction::precompile;
 use super::instruction::sb::SBInstruction;
 use super::instruction::sh::SHInstruction;
 use super::instruction::JoltInstructionSet;
@@ -73,6 +85,36 @@ where
     pub bytecode: BytecodePreprocessing<F>,
     pub read_write_memory: ReadWriteMemoryPreprocessing,
     pub memory_layout: MemoryLayout,
+    /// Digest of the ELF this preprocessing was generated from (see
+    /// `host::Program::elf_digest`), if known.
+    pub elf_digest: Option<[u8; 32]>,
+    /// Digest of the bytecode, initial memory, memory layout and PCS verifier key,
+    /// computed at preprocessing time and checked whenever the preprocessing is loaded
+    /// (see [`JoltVerifierPreprocessing::compute_program_id`]).
+    /// Absorbed into the Fiat-Shamir transcript, so a proof only verifies against
+    /// the program it was generated for.
+    pub program_id: [u8; 32],
+    /// Commitments to the program-dependent preprocessing polynomials, if the program
+    /// was committed to (see [`JoltVerifierPreprocessing::commit_program`]).
+    pub program_commitments: Option<ProgramCommitments<PCS, ProofTranscript>>,
+    /// Whether the program contains modular arithmetic `PRECOMPILE` instructions, in
+    /// which case proofs must include a [`PrecompileProof`].
+    pub precompiles: bool,
+}
+
+/// Commitments to the bytecode (`BytecodePreprocessing::v_init_final`) and to the
+/// program image in the initial RAM state. A verifier holding these, rather than the
+/// polynomials themselves, has a preprocessing whose size does not depend on the program:
+/// the evaluations it needs are provided by the prover and checked by the batched
+/// opening proof.
+#[derive(Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct ProgramCommitments<PCS, ProofTranscript>
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    pub bytecode: [PCS::Commitment; 6],
+    pub program_image: PCS::Commitment,
 }
 
 impl<const C: usize, F, PCS, ProofTranscript> JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>
@@ -81,21 +123,156 @@ where
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
-    pub fn save_to_target_dir(&self, target_dir: &str) -> std::io::Result<()> {
+    /// Computes the canonical digest identifying the program this preprocessing
+    /// was generated for. Only the part of the PCS setup needed for verification is
+    /// hashed (see `CommitmentScheme::verifier_setup`), so the ID does not depend on
+    /// the size of the prover's commitment key.
+    pub fn compute_program_id(&self) -> [u8; 32] {
+        let mut hasher = Sha3_256::new();
+        match &self.program_commitments {
+            // A succinct preprocessing only holds commitments to the program
+            Some(program_commitments) => {
+                program_commitments
+                    .serialize_compressed(&mut hasher)
+                    .unwrap();
+            }
+            None => {
+                // `program_io` is populated by the verifier and is not part of the program
+                let mut read_write_memory = self.read_write_memory.clone();
+                read_write_memory.program_io = None;
+
+                self.bytecode.serialize_compressed(&mut hasher).unwrap();
+                read_write_memory.serialize_compressed(&mut hasher).unwrap();
+            }
+        }
+        self.memory_layout
+            .serialize_compressed(&mut hasher)
+            .unwrap();
+        self.precompiles.serialize_compressed(&mut hasher).unwrap();
+        PCS::verifier_setup(&self.generators)
+            .serialize_compressed(&mut hasher)
+            .unwrap();
+        hasher.finalize().into()
+    }
+
+    /// Commits to the bytecode and the program image in the initial RAM state. Proofs
+    /// generated with the resulting preprocessing open these commitments instead of
+    /// having the verifier evaluate the polynomials, so the verifier can use the
+    /// constant-size preprocessing returned by [`JoltVerifierPreprocessing::succinct`].
+    /// Updates `program_id`, which now binds the commitments.
+    pub fn commit_program(&mut self) {
+        let bytecode = self
+            .bytecode
+            .commit::<PCS, ProofTranscript>(&self.generators);
+        let program_image = self
+            .read_write_memory
+            .commit::<F, PCS, ProofTranscript>(&self.memory_layout, &self.generators);
+        self.program_commitments = Some(ProgramCommitments {
+            bytecode,
+            program_image,
+        });
+        self.program_id = self.compute_program_id();
+    }
+
+    /// Returns a copy of this preprocessing without the bytecode and program image,
+    /// and with the PCS setup trimmed to its verifier key, for use by the verifier.
+    /// Requires [`JoltVerifierPreprocessing::commit_program`] to have been called.
+    /// The program ID is unchanged, since it only covers the verifier key.
+    pub fn succinct(&self) -> Self {
+        assert!(
+            self.program_commitments.is_some(),
+            "program must be committed to before stripping the preprocessing"
+        );
+        let mut preprocessing = self.clone();
+        preprocessing.bytecode.strip();
+        preprocessing.read_write_memory.strip();
+        preprocessing.generators = PCS::verifier_setup(&self.generators);
+        preprocessing
+    }
+
+    /// The digest a proof's `JoltDevice` holds for a blob with the given contents (see
+    /// `host::Program::add_blob`). A verifier checks that the digests in the
+    /// `JoltDevice` it verifies against are the ones it expects. Commits to the blob, so
+    /// this requires the full PCS setup, i.e. not a [`JoltVerifierPreprocessing::succinct`]
+    /// preprocessing.
+    pub fn blob_digest(&self, data: &[u8]) -> [u8; 32] {
+        let commitment =
+            commit_blob::<F, PCS, ProofTranscript>(&Blob::new("", data.to_vec()), &self.generators);
+        blob_digest::<PCS, ProofTranscript>(&commitment)
+    }
+
+    /// The length of the largest polynomial a verifier commits to: the bytecode and the
+    /// program image (see [`JoltVerifierPreprocessing::commit_program`]) and the blobs
+    /// (see [`JoltVerifierPreprocessing::blob_digest`]). [`Jolt::verifier_preprocess`]
+    /// sizes the PCS setup to it.
+    pub fn verifier_setup_len(&self) -> usize {
+        verifier_setup_len(
+            &self.bytecode,
+            &self.read_write_memory,
+            &self.memory_layout,
+        )
+    }
+
+    /// The length of the largest polynomial committed to when proving an execution of
+    /// at most `max_trace_length` steps that only accesses memory cells below
+    /// `max_memory_address`: the polynomials indexed by step (including the R1CS inputs
+    /// and auxiliary variables) over the padded trace, the subtables' final counts, the
+    /// memory polynomials (see `ReadWriteMemoryPreprocessing::memory_len`) and the
+    /// polynomials a verifier commits to. [`Jolt::prover_preprocess`] sizes the PCS
+    /// setup to it.
+    pub fn prover_setup_len(&self, max_memory_address: usize, max_trace_length: usize) -> usize {
+        [
+            self.verifier_setup_len(),
+            self.instruction_lookups.subtable_size(),
+            self.read_write_memory
+                .memory_len(&self.memory_layout, max_memory_address),
+            max_trace_length.next_power_of_two(),
+        ]
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    /// Checks that `program_id` is the digest of this preprocessing, e.g. after
+    /// deserializing it.
+    pub fn check_program_id(&self) -> Result<(), ContainerError> {
+        if self.program_id != self.compute_program_id() {
+            return Err(ContainerError::ProgramIdMismatch);
+        }
+        Ok(())
+    }
+
+    /// The container header describing artifacts produced with this preprocessing.
+    pub fn container_header(&self, kind: ContainerKind) -> ContainerHeader {
+        ContainerHeader::new::<C, PCS, ProofTranscript>(
+            kind,
+            self.instruction_lookups.subtable_size(),
+            self.program_id,
+        )
+    }
+
+    pub fn save_to_target_dir(&self, target_dir: &str) -> Result<(), ContainerError> {
         let filename = Path::new(target_dir).join("jolt_verifier_preprocessing.dat");
-        let mut file = File::create(filename.as_path())?;
-        let mut data = Vec::new();
-        self.serialize_compressed(&mut data).unwrap();
-        file.write_all(&data)?;
+        let bytes = encode_container(
+            &self.container_header(ContainerKind::VerifierPreprocessing),
+            self,
+        )?;
+        fs::write(filename, bytes)?;
         Ok(())
     }
 
-    pub fn read_from_target_dir(target_dir: &str) -> std::io::Result<Self> {
+    pub fn read_from_target_dir(target_dir: &str) -> Result<Self, ContainerError> {
         let filename = Path::new(target_dir).join("jolt_verifier_preprocessing.dat");
-        let mut file = File::open(filename.as_path())?;
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
-        Ok(Self::deserialize_compressed(&*data).unwrap())
+        let bytes = fs::read(filename)?;
+        let expected = ContainerHeader::new::<C, PCS, ProofTranscript>(
+            ContainerKind::VerifierPreprocessing,
+            0,
+            [0; 32],
+        );
+        let (header, preprocessing): (_, Self) = decode_container_payload(&bytes, &expected)?;
+        preprocessing.check_program_id()?;
+        header.check(&preprocessing.container_header(ContainerKind::VerifierPreprocessing))?;
+        Ok(preprocessing)
     }
 }
 
@@ -116,21 +293,44 @@ where
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
-    pub fn save_to_target_dir(&self, target_dir: &str) -> std::io::Result<()> {
+    /// Trims the PCS setup to [`JoltVerifierPreprocessing::prover_setup_len`], e.g. to
+    /// lower the trace length bound of an existing preprocessing once the program's trace
+    /// length is known. The program ID is unchanged.
+    pub fn trim(&mut self, max_memory_address: usize, max_trace_length: usize) {
+        let max_len = self
+            .shared
+            .prover_setup_len(max_memory_address, max_trace_length);
+        self.shared.generators = PCS::trim(&self.shared.generators, max_len);
+    }
+
+    pub fn save_to_target_dir(&self, target_dir: &str) -> Result<(), ContainerError> {
         let filename = Path::new(target_dir).join("jolt_prover_preprocessing.dat");
-        let mut file = File::create(filename.as_path())?;
-        let mut data = Vec::new();
-        self.serialize_compressed(&mut data).unwrap();
-        file.write_all(&data)?;
+        let bytes = encode_container(
+            &self
+                .shared
+                .container_header(ContainerKind::ProverPreprocessing),
+            self,
+        )?;
+        fs::write(filename, bytes)?;
         Ok(())
     }
 
-    pub fn read_from_target_dir(target_dir: &str) -> std::io::Result<Self> {
+    pub fn read_from_target_dir(target_dir: &str) -> Result<Self, ContainerError> {
         let filename = Path::new(target_dir).join("jolt_prover_preprocessing.dat");
-        let mut file = File::open(filename.as_path())?;
-        let mut data = Vec::new();
-        file.read_to_end(&mut data)?;
-        Ok(Self::deserialize_compressed(&*data).unwrap())
+        let bytes = fs::read(filename)?;
+        let expected = ContainerHeader::new::<C, PCS, ProofTranscript>(
+            ContainerKind::ProverPreprocessing,
+            0,
+            [0; 32],
+        );
+        let (header, preprocessing): (_, Self) = decode_container_payload(&bytes, &expected)?;
+        preprocessing.shared.check_program_id()?;
+        header.check(
+            &preprocessing
+                .shared
+                .container_header(ContainerKind::ProverPreprocessing),
+        )?;
+        Ok(preprocessing)
     }
 }
 
@@ -140,6 +340,59 @@ pub struct JoltTraceStep<InstructionSet: JoltInstructionSet> {
     pub bytecode_row: BytecodeRow,
     pub memory_ops: [MemoryOp; MEMORY_OPS_PER_INSTRUCTION],
     pub circuit_flags: [bool; NUM_CIRCUIT_FLAGS],
+    /// Opcode of the (possibly virtual) instruction executed, or `None` for the no-ops
+    /// padding the trace. Not part of the witness; it only serves to report failing
+    /// steps (see `Jolt::debug_witness`).
+    pub opcode: Option<RV32IM>,
+}
+
+/// Throughput of [`Jolt::prove_batch`].
+#[derive(Clone, Debug, Default)]
+pub struct BatchProvingMetrics {
+    /// Total number of cycles (unpadded trace steps) proven.
+    pub total_cycles: usize,
+    /// Wall-clock time spent proving each execution, in order.
+    pub proof_times: Vec<Duration>,
+    /// Wall-clock time spent proving the whole batch.
+    pub total_time: Duration,
+}
+
+impl BatchProvingMetrics {
+    pub fn num_proofs(&self) -> usize {
+        self.proof_times.len()
+    }
+
+    pub fn proofs_per_second(&self) -> f64 {
+        self.num_proofs() as f64 / self.total_time.as_secs_f64()
+    }
+
+    pub fn cycles_per_second(&self) -> f64 {
+        self.total_cycles as f64 / self.total_time.as_secs_f64()
+    }
+}
+
+impl fmt::Display for BatchProvingMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Proved {} executions ({} cycles) in {:.2} s: {:.2} proofs/s, {:.0} cycles/s",
+            self.num_proofs(),
+            self.total_cycles,
+            self.total_time.as_secs_f64(),
+            self.proofs_per_second(),
+            self.cycles_per_second()
+        )
+    }
+}
+
+/// Returned by [`Jolt::prove_dev`] in place of a proof. It only records that the
+/// witness of an execution satisfied Jolt's constraints when it was generated: it
+/// proves nothing, and no verifier accepts it.
+#[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DevProof {
+    /// The program ID of the preprocessing the witness was checked against.
+    pub program_id: [u8; 32],
+    pub trace_length: usize,
 }
 
 pub struct ProverDebugInfo<F, ProofTranscript>
@@ -163,9 +416,14 @@ impl<InstructionSet: JoltInstructionSet> JoltTraceStep<InstructionSet> {
                 MemoryOp::noop_read(),  // RAM
             ],
             circuit_flags: [false; NUM_CIRCUIT_FLAGS],
+            opcode: None,
         }
     }
 
+    /// Pads the trace with no-ops to the next power of two. Every witness polynomial
+    /// indexed by step is a multilinear polynomial over `log2(padded_length)` variables.
+    /// No-ops perform no lookups, so most of these polynomials are zero over the padding,
+    /// and the prover skips their zero tails (see `MultilinearPolynomial::nonzero_len`).
     fn pad(trace: &mut Vec<Self>) {
         let unpadded_length = trace.len();
         let padded_length = unpadded_length.next_power_of_two();
@@ -197,6 +455,8 @@ pub struct JoltProof<
     pub instruction_lookups:
         InstructionLookupsProof<C, M, F, PCS, InstructionSet, Subtables, ProofTranscript>,
     pub r1cs: UniformSpartanProof<C, I, F, ProofTranscript>,
+    /// Present iff the program contains `PRECOMPILE` instructions
+    pub precompiles: Option<PrecompileProof<F, PCS, ProofTranscript>>,
     pub opening_proof: ReducedOpeningProof<F, PCS, ProofTranscript>,
 }
 
@@ -301,6 +561,7 @@ impl<F: JoltField> JoltPolynomials<F> {
     pub fn commit<const C: usize, PCS, ProofTranscript>(
         &self,
         preprocessing: &JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
+        generators: &PCS::Setup,
     ) -> JoltCommitments<PCS, ProofTranscript>
     where
         PCS: CommitmentScheme<ProofTranscript, Field = F>,
@@ -313,7 +574,7 @@ impl<F: JoltField> JoltPolynomials<F> {
         drop(span);
 
         let trace_polys = self.read_write_values();
-        let trace_commitments = PCS::batch_commit(&trace_polys, &preprocessing.generators);
+        let trace_commitments = PCS::batch_commit(&trace_polys, generators);
 
         commitments
             .read_write_values_mut()
@@ -324,7 +585,7 @@ impl<F: JoltField> JoltPolynomials<F> {
         let span = tracing::span!(tracing::Level::INFO, "commit::t_final");
         let _guard = span.enter();
         commitments.bytecode.t_final =
-            PCS::commit(&self.bytecode.t_final, &preprocessing.generators);
+            PCS::commit(&self.bytecode.t_final, generators);
         drop(_guard);
         drop(span);
 
@@ -334,13 +595,19 @@ impl<F: JoltField> JoltPolynomials<F> {
             commitments.read_write_memory.v_final,
             commitments.read_write_memory.t_final,
         ) = join_conditional!(
-            || PCS::commit(&self.read_write_memory.v_final, &preprocessing.generators),
-            || PCS::commit(&self.read_write_memory.t_final, &preprocessing.generators)
+            || PCS::commit(&self.read_write_memory.v_final, generators),
+            || PCS::commit(&self.read_write_memory.t_final, generators)
         );
         commitments.instruction_lookups.final_cts = PCS::batch_commit(
             &self.instruction_lookups.final_cts,
-            &preprocessing.generators,
+            generators,
         );
+        commitments.read_write_memory.blobs = self
+            .read_write_memory
+            .blobs
+            .iter()
+            .map(|blob| PCS::commit(blob, generators))
+            .collect();
         drop(_guard);
         drop(span);
 
@@ -358,16 +625,19 @@ where
     type Subtables: JoltSubtableSet<F>;
     type Constraints: R1CSConstraints<C, F>;
 
+    /// Checks that `InstructionSet` can be decomposed into `C` chunks, each looked
+    /// up in subtables of size `M`, panicking with a descriptive message otherwise.
+    fn validate_decomposition() {}
+
     #[tracing::instrument(skip_all, name = "Jolt::preprocess")]
     fn verifier_preprocess(
         bytecode: Vec<ELFInstruction>,
         memory_layout: MemoryLayout,
         memory_init: Vec<(u64, u8)>,
         max_bytecode_size: usize,
-        max_memory_address: usize,
-        max_trace_length: usize,
     ) -> JoltVerifierPreprocessing<C, F, PCS, ProofTranscript> {
         icicle::icicle_init();
+        Self::validate_decomposition();
 
         let instruction_lookups_preprocessing = InstructionLookupsPreprocessing::preprocess::<
             M,
@@ -377,45 +647,66 @@ where
 
         let read_write_memory_preprocessing = ReadWriteMemoryPreprocessing::preprocess(memory_init);
 
+        let precompiles = bytecode.iter().any(|instruction| {
+            instruction.opcode == tracer::RV32IM::PRECOMPILE
+                && Precompile::from_id(instruction.imm.unwrap() as u32)
+                    .is_some_and(|precompile| precompile.is_modular())
+        });
         let bytecode_rows: Vec<BytecodeRow> = bytecode
             .into_iter()
-            .flat_map(|instruction| match instruction.opcode {
-                tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::MULHSU => MULHSUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::REM => REMInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::SH => SHInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::SB => SBInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LB => LBInstruction::<32>::virtual_sequence(instruction),
-                tracer::RV32IM::LH => LHInstruction::<32>::virtual_sequence(instruction),
-                _ => vec![instruction],
+            .flat_map(|instruction| {
+                let virtual_sequence = match instruction.opcode {
+                    tracer::RV32IM::MULH => MULHInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::MULHSU => {
+                        MULHSUInstruction::<32>::virtual_sequence(instruction)
+                    }
+                    tracer::RV32IM::DIV => DIVInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::DIVU => DIVUInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::REM => REMInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::REMU => REMUInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::SH => SHInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::SB => SBInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::LBU => LBUInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::LHU => LHUInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::LB => LBInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::LH => LHInstruction::<32>::virtual_sequence(instruction),
+                    tracer::RV32IM::PRECOMPILE => precompile::virtual_sequence(instruction),
+                    opcode if common::float::is_float(opcode) => {
+                        float::virtual_sequence(&instruction)
+                    }
+                    _ => vec![instruction],
+                };
+                narrow_shift::expand_shift_instructions(virtual_sequence, M.log_2())
             })
             .map(|instruction| BytecodeRow::from_instruction::<Self::InstructionSet>(&instruction))
             .collect();
         let bytecode_preprocessing = BytecodePreprocessing::<F>::preprocess(bytecode_rows);
 
-        let max_poly_len: usize = [
-            (max_bytecode_size + 1).next_power_of_two(), // Account for no-op prepended to bytecode
-            max_trace_length.next_power_of_two(),
-            max_memory_address.next_power_of_two(),
-            M,
-        ]
-        .into_iter()
-        .max()
-        .unwrap();
-        let generators = PCS::setup(max_poly_len);
+        assert!(
+            bytecode_preprocessing.code_size() <= (max_bytecode_size + 1).next_power_of_two(), // Account for no-op prepended to bytecode
+            "Bytecode size {} exceeds max_bytecode_size {max_bytecode_size}",
+            bytecode_preprocessing.code_size()
+        );
 
-        JoltVerifierPreprocessing {
+        let generators = PCS::setup(verifier_setup_len(
+            &bytecode_preprocessing,
+            &read_write_memory_preprocessing,
+            &memory_layout,
+        ));
+
+        let mut preprocessing = JoltVerifierPreprocessing {
             generators,
             memory_layout,
             instruction_lookups: instruction_lookups_preprocessing,
             bytecode: bytecode_preprocessing,
             read_write_memory: read_write_memory_preprocessing,
-        }
+            elf_digest: None,
+            program_id: [0; 32],
+            program_commitments: None,
+            precompiles,
+        };
+        preprocessing.program_id = preprocessing.compute_program_id();
+        preprocessing
     }
 
     #[tracing::instrument(skip_all, name = "Jolt::preprocess")]
@@ -430,14 +721,16 @@ where
         let small_value_lookup_tables = F::compute_lookup_tables();
         F::initialize_lookup_tables(small_value_lookup_tables.clone());
 
-        let shared = Self::verifier_preprocess(
+        let mut shared = Self::verifier_preprocess(
             bytecode,
             memory_layout,
             memory_init,
             max_bytecode_size,
-            max_memory_address,
-            max_trace_length,
         );
+        // The prover also commits to the witness polynomials. The program ID only covers
+        // the verifier key, which does not depend on the setup's size.
+        shared.generators =
+            PCS::setup(shared.prover_setup_len(max_memory_address, max_trace_length));
 
         JoltProverPreprocessing {
             shared,
@@ -448,7 +741,7 @@ where
     #[tracing::instrument(skip_all, name = "Jolt::prove")]
     fn prove(
         program_io: JoltDevice,
-        mut trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
         mut preprocessing: JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
     ) -> (
         JoltProof<
@@ -466,24 +759,274 @@ where
         Option<ProverDebugInfo<F, ProofTranscript>>,
     ) {
         icicle::icicle_init();
-        let trace_length = trace.len();
-        let padded_trace_length = trace_length.next_power_of_two();
-        println!("Trace length: {trace_length}");
+        F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
+        prove_instance::<Self, F, PCS, C, M, ProofTranscript>(
+            program_io,
+            trace,
+            &preprocessing,
+            None,
+        )
+    }
+
+    /// Proves many executions of the same program, e.g. on different inputs,
+    /// against a single preprocessing. Executions are proven in parallel, sharing the
+    /// preprocessing (generators, materialized subtables, bytecode and memory
+    /// preprocessing) rather than moving a copy into each call to [`Jolt::prove`], and
+    /// the Spartan key is set up once for each distinct padded trace length. Since
+    /// several witnesses are held in memory at once, peak memory usage is higher than
+    /// when proving the executions one at a time. Returns one proof per execution, in
+    /// order, along with throughput metrics.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_batch")]
+    fn prove_batch(
+        executions: Vec<(JoltDevice, Vec<JoltTraceStep<Self::InstructionSet>>)>,
+        mut preprocessing: JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> (
+        Vec<(
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+            JoltDevice,
+        )>,
+        BatchProvingMetrics,
+    ) {
+        icicle::icicle_init();
+        F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
+
+        let batch_start = Instant::now();
+        let spartan_keys: HashMap<_, _> = executions
+            .iter()
+            .map(|(program_io, trace)| {
+                (
+                    trace.len().next_power_of_two(),
+                    program_io.memory_layout.input_start,
+                )
+            })
+            .collect::<HashSet<_>>()
+            .into_par_iter()
+            .map(|(padded_trace_length, input_start)| {
+                let r1cs_builder =
+                    Self::Constraints::construct_constraints(padded_trace_length, input_start);
+                let spartan_key = UniformSpartanProof::<
+                    C,
+                    <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                    F,
+                    ProofTranscript,
+                >::setup(&r1cs_builder, padded_trace_length);
+                ((padded_trace_length, input_start), spartan_key)
+            })
+            .collect();
 
+        let total_cycles = executions.iter().map(|(_, trace)| trace.len()).sum();
+        let (proofs, proof_times): (Vec<_>, Vec<_>) = executions
+            .into_par_iter()
+            .map(|(program_io, trace)| {
+                let start = Instant::now();
+                let spartan_key = &spartan_keys[&(
+                    trace.len().next_power_of_two(),
+                    program_io.memory_layout.input_start,
+                )];
+                let (proof, commitments, program_io, _) =
+                    prove_instance::<Self, F, PCS, C, M, ProofTranscript>(
+                        program_io,
+                        trace,
+                        &preprocessing,
+                        Some(spartan_key),
+                    );
+                ((proof, commitments, program_io), start.elapsed())
+            })
+            .unzip();
+        let metrics = BatchProvingMetrics {
+            total_cycles,
+            proof_times,
+            total_time: batch_start.elapsed(),
+        };
+
+        (proofs, metrics)
+    }
+
+    /// Dev mode counterpart of [`Jolt::prove`], for fast iteration on guests: generates
+    /// the witness of the execution and checks it against the constraints a proof would
+    /// show (see [`Jolt::check_witness`]), without committing to or proving anything.
+    /// Returns a [`DevProof`], which no verifier accepts, or the first failing step (see
+    /// [`Jolt::debug_witness`]).
+    #[tracing::instrument(skip_all, name = "Jolt::prove_dev")]
+    fn prove_dev(
+        program_io: JoltDevice,
+        trace: Vec<JoltTraceStep<Self::InstructionSet>>,
+        mut preprocessing: JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<(DevProof, JoltDevice), WitnessReport> {
+        F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
+        tracing::warn!(
+            "Dev mode: checking the witness of {} steps without proving",
+            trace.len()
+        );
+        prove_dev_instance::<Self, F, PCS, C, M, ProofTranscript>(program_io, trace, &preprocessing)
+    }
+
+    /// Dev mode counterpart of [`Jolt::prove_batch`], see [`Jolt::prove_dev`]. Checks the
+    /// executions in parallel, failing with the report of the first execution (in order)
+    /// whose witness violates a constraint.
+    #[tracing::instrument(skip_all, name = "Jolt::prove_dev_batch")]
+    fn prove_dev_batch(
+        executions: Vec<(JoltDevice, Vec<JoltTraceStep<Self::InstructionSet>>)>,
+        mut preprocessing: JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<(Vec<(DevProof, JoltDevice)>, BatchProvingMetrics), WitnessReport> {
         F::initialize_lookup_tables(std::mem::take(&mut preprocessing.field));
 
-        // TODO(moodlezoup): Truncate generators
+        let batch_start = Instant::now();
+        let total_cycles = executions.iter().map(|(_, trace)| trace.len()).sum();
+        tracing::warn!(
+            "Dev mode: checking the witnesses of {} executions ({total_cycles} steps) without proving",
+            executions.len()
+        );
+        let results: Vec<_> = executions
+            .into_par_iter()
+            .map(|(program_io, trace)| {
+                let start = Instant::now();
+                let proof = prove_dev_instance::<Self, F, PCS, C, M, ProofTranscript>(
+                    program_io,
+                    trace,
+                    &preprocessing,
+                )?;
+                Ok((proof, start.elapsed()))
+            })
+            .collect();
+        let (proofs, proof_times) = results.into_iter().collect::<Result<_, _>>()?;
+        let metrics = BatchProvingMetrics {
+            total_cycles,
+            proof_times,
+            total_time: batch_start.elapsed(),
+        };
 
-        // TODO(JP): Drop padding on number of steps
-        JoltTraceStep::pad(&mut trace);
+        Ok((proofs, metrics))
+    }
 
-        let mut transcript = ProofTranscript::new(b"Jolt transcript");
-        Self::fiat_shamir_preamble(
-            &mut transcript,
-            &program_io,
-            &program_io.memory_layout,
-            trace_length,
+    /// Checks the witness generated by [`Jolt::generate_witness`] for `trace` (padded)
+    /// against the constraints shown by a proof: that each step reads its row of the
+    /// bytecode, that register and RAM reads return the last value written and the
+    /// final memory holds `program_io`, that lookup outputs are the entries of the
+    /// instructions' lookup tables, and that the R1CS constraints hold. Returns the
+    /// error at the earliest failing step, if any.
+    #[tracing::instrument(skip_all, name = "Jolt::check_witness")]
+    fn check_witness(
+        program_io: &JoltDevice,
+        trace: &[JoltTraceStep<Self::InstructionSet>],
+        jolt_polynomials: &JoltPolynomials<F>,
+        preprocessing: &JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<(), WitnessError> {
+        let bytecode_result = BytecodeProof::<F, PCS, ProofTranscript>::check_witness(
+            &preprocessing.shared.bytecode,
+            &jolt_polynomials.bytecode,
+        );
+        let memory_result = jolt_polynomials
+            .read_write_memory
+            .check_witness(program_io, trace);
+        let instruction_lookups_result = InstructionLookupsProof::<
+            C,
+            M,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+        >::check_witness(
+            &preprocessing.shared.instruction_lookups,
+            &jolt_polynomials.instruction_lookups,
+            trace,
+        );
+
+        let r1cs_builder = Self::Constraints::construct_constraints(
+            trace.len(),
+            program_io.memory_layout.input_start,
+        );
+        let flattened_polynomials: Vec<&MultilinearPolynomial<F>> =
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs::flatten::<C>()
+                .iter()
+                .map(|var| var.get_ref(jolt_polynomials))
+                .collect();
+        let r1cs_result = r1cs_builder.check_witness(&flattened_polynomials);
+
+        // Each check returns the error at its earliest failing step; ties go to the
+        // checks above in order, and errors in the final memory state come last.
+        let earliest_error = [
+            bytecode_result,
+            memory_result,
+            instruction_lookups_result,
+            r1cs_result,
+        ]
+        .into_iter()
+        .filter_map(Result::err)
+        .min_by_key(|error| error.step().unwrap_or(usize::MAX));
+
+        match earliest_error {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Debugging counterpart of [`Jolt::check_witness`], for when proving fails or the
+    /// verifier rejects a proof: if the witness is invalid, reports the instruction
+    /// executed at the earliest failing step and, if it violates an R1CS constraint,
+    /// the constraint and the values of its variables at that step. Like
+    /// [`Jolt::check_witness`], takes `trace` as padded by [`Jolt::generate_witness`].
+    fn debug_witness(
+        program_io: &JoltDevice,
+        trace: &[JoltTraceStep<Self::InstructionSet>],
+        jolt_polynomials: &JoltPolynomials<F>,
+        preprocessing: &JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> Result<(), WitnessReport> {
+        let Err(error) = Self::check_witness(program_io, trace, jolt_polynomials, preprocessing)
+        else {
+            return Ok(());
+        };
+
+        let failing_step = error.step().map(|step| &trace[step]);
+        let r1cs_builder = Self::Constraints::construct_constraints(
+            trace.len(),
+            program_io.memory_layout.input_start,
         );
+        let flattened_polynomials: Vec<&MultilinearPolynomial<F>> =
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs::flatten::<C>()
+                .iter()
+                .map(|var| var.get_ref(jolt_polynomials))
+                .collect();
+        let constraint = r1cs_builder.pretty_fmt_violation(&error, &flattened_polynomials);
+
+        Err(WitnessReport {
+            pc: failing_step.and_then(|step| step.bytecode_row.uncompressed_address()),
+            virtual_sequence_remaining: failing_step
+                .and_then(|step| step.bytecode_row.virtual_sequence_remaining()),
+            opcode: failing_step.and_then(|step| step.opcode),
+            constraint,
+            error,
+        })
+    }
+
+    /// Generates the witness of an execution, i.e. the polynomials the prover commits
+    /// to, and the R1CS constraints they must satisfy. Pads `trace` to a power of two.
+    #[tracing::instrument(skip_all, name = "Jolt::generate_witness")]
+    fn generate_witness(
+        program_io: &JoltDevice,
+        trace: &mut Vec<JoltTraceStep<Self::InstructionSet>>,
+        preprocessing: &JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    ) -> (
+        JoltPolynomials<F>,
+        CombinedUniformBuilder<C, F, <Self::Constraints as R1CSConstraints<C, F>>::Inputs>,
+    ) {
+        // TODO(JP): Drop padding on number of steps. Polynomials that are zero over the
+        // padding are cheap, but the read timestamps (no-ops keep reading register 0 and
+        // bytecode row 0), the timestamp range checks and the opening proof still cost
+        // as much over the padding as over the trace (see the book's list of planned
+        // optimizations).
+        JoltTraceStep::pad(trace);
 
         let instruction_polynomials =
             InstructionLookupsProof::<
@@ -494,19 +1037,19 @@ where
                 Self::InstructionSet,
                 Self::Subtables,
                 ProofTranscript,
-            >::generate_witness(&preprocessing.shared.instruction_lookups, &trace);
+            >::generate_witness(&preprocessing.shared.instruction_lookups, trace);
 
         let memory_polynomials = ReadWriteMemoryPolynomials::generate_witness(
-            &program_io,
+            program_io,
             &preprocessing.shared.read_write_memory,
-            &trace,
+            trace,
         );
 
         let (bytecode_polynomials, range_check_polys) = rayon::join(
             || {
                 BytecodeProof::<F, PCS, ProofTranscript>::generate_witness(
                     &preprocessing.shared.bytecode,
-                    &mut trace,
+                    trace,
                 )
             },
             || {
@@ -517,22 +1060,15 @@ where
         );
 
         let r1cs_builder = Self::Constraints::construct_constraints(
-            padded_trace_length,
+            trace.len(),
             program_io.memory_layout.input_start,
         );
-        let spartan_key = spartan::UniformSpartanProof::<
-            C,
-            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
-            F,
-            ProofTranscript,
-        >::setup(&r1cs_builder, padded_trace_length);
-
         let r1cs_polynomials = R1CSPolynomials::new::<
             C,
             M,
             Self::InstructionSet,
             <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
-        >(&trace);
+        >(trace);
 
         let mut jolt_polynomials = JoltPolynomials {
             bytecode: bytecode_polynomials,
@@ -544,91 +1080,12 @@ where
 
         r1cs_builder.compute_aux(&mut jolt_polynomials);
 
-        let jolt_commitments =
-            jolt_polynomials.commit::<C, PCS, ProofTranscript>(&preprocessing.shared);
-
-        transcript.append_scalar(&spartan_key.vk_digest);
-
-        jolt_commitments
-            .read_write_values()
-            .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
-        jolt_commitments
-            .init_final_values()
-            .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
-
-        let mut opening_accumulator: ProverOpeningAccumulator<F, ProofTranscript> =
-            ProverOpeningAccumulator::new();
-
-        let bytecode_proof = BytecodeProof::prove_memory_checking(
-            &preprocessing.shared.generators,
-            &preprocessing.shared.bytecode,
-            &jolt_polynomials.bytecode,
-            &jolt_polynomials,
-            &mut opening_accumulator,
-            &mut transcript,
-        );
-
-        let instruction_proof = InstructionLookupsProof::prove(
-            &preprocessing.shared.generators,
-            &mut jolt_polynomials,
-            &preprocessing.shared.instruction_lookups,
-            &mut opening_accumulator,
-            &mut transcript,
-        );
-
-        let memory_proof = ReadWriteMemoryProof::prove(
-            &preprocessing.shared.generators,
-            &preprocessing.shared.read_write_memory,
-            &jolt_polynomials,
-            &program_io,
-            &mut opening_accumulator,
-            &mut transcript,
-        );
-
-        let spartan_proof = UniformSpartanProof::<
-            C,
-            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
-            F,
-            ProofTranscript,
-        >::prove::<PCS>(
-            &r1cs_builder,
-            &spartan_key,
-            &jolt_polynomials,
-            &mut opening_accumulator,
-            &mut transcript,
-        )
-        .expect("r1cs proof failed");
-
-        // Batch-prove all openings
-        let opening_proof = opening_accumulator
-            .reduce_and_prove::<PCS>(&preprocessing.shared.generators, &mut transcript);
-
-        drop_in_background_thread(jolt_polynomials);
-
-        let jolt_proof = JoltProof {
-            trace_length,
-            bytecode: bytecode_proof,
-            read_write_memory: memory_proof,
-            instruction_lookups: instruction_proof,
-            r1cs: spartan_proof,
-            opening_proof,
-        };
-
-        #[cfg(test)]
-        let debug_info = Some(ProverDebugInfo {
-            transcript,
-            opening_accumulator,
-        });
-        #[cfg(not(test))]
-        let debug_info = None;
-        (jolt_proof, jolt_commitments, program_io, debug_info)
+        (jolt_polynomials, r1cs_builder)
     }
 
     #[tracing::instrument(skip_all)]
     fn verify(
-        mut preprocessing: JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
+        preprocessing: JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
         proof: JoltProof<
             C,
             M,
@@ -653,8 +1110,141 @@ where
             opening_accumulator
                 .compare_to(debug_info.opening_accumulator, &preprocessing.generators);
         }
-        Self::fiat_shamir_preamble(
+
+        let opening_proof = Self::verify_components(
+            &preprocessing,
+            proof,
+            commitments,
+            program_io,
+            &mut opening_accumulator,
             &mut transcript,
+        )?;
+
+        // Batch-verify all openings
+        opening_accumulator
+            .reduce_and_verify(&preprocessing.generators, &opening_proof, &mut transcript)
+            .map_err(|e| ProofVerifyError::OpeningProofError(Box::new(e)))?;
+
+        Ok(())
+    }
+
+    /// Verifies many proofs against the same preprocessing, in parallel. Each proof
+    /// is checked as in [`Jolt::verify`], except that the final check of each
+    /// batched opening proof (for HyperKZG, a pairing check) is deferred, and all of
+    /// them are combined into a single random linear combination check. If that check
+    /// fails, the deferred checks are redone one by one to find the invalid proofs.
+    #[tracing::instrument(skip_all, name = "Jolt::batch_verify")]
+    fn batch_verify(
+        preprocessing: JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
+        proofs: Vec<(
+            JoltProof<
+                C,
+                M,
+                <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                PCS,
+                Self::InstructionSet,
+                Self::Subtables,
+                ProofTranscript,
+            >,
+            JoltCommitments<PCS, ProofTranscript>,
+            JoltDevice,
+        )>,
+    ) -> Result<(), BatchVerifyError>
+    where
+        PCS: DeferredCommitmentScheme<ProofTranscript>,
+    {
+        let results: Vec<Result<PCS::DeferredCheck, ProofVerifyError>> = proofs
+            .into_par_iter()
+            .map(|(proof, commitments, program_io)| {
+                let mut transcript = ProofTranscript::new(b"Jolt transcript");
+                let mut opening_accumulator: VerifierOpeningAccumulator<F, PCS, ProofTranscript> =
+                    VerifierOpeningAccumulator::new();
+                let opening_proof = Self::verify_components(
+                    &preprocessing,
+                    proof,
+                    commitments,
+                    program_io,
+                    &mut opening_accumulator,
+                    &mut transcript,
+                )?;
+                opening_accumulator
+                    .reduce_and_verify_deferred(
+                        &preprocessing.generators,
+                        &opening_proof,
+                        &mut transcript,
+                    )
+                    .map_err(|e| ProofVerifyError::OpeningProofError(Box::new(e)))
+            })
+            .collect();
+
+        let mut failures = vec![];
+        let mut deferred_checks = vec![];
+        for (index, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(check) => deferred_checks.push((index, check)),
+                Err(e) => failures.push((index, e)),
+            }
+        }
+
+        let checks: Vec<_> = deferred_checks
+            .iter()
+            .map(|(_, check)| check.clone())
+            .collect();
+        if !PCS::batch_check(&preprocessing.generators, &checks) {
+            for (index, check) in deferred_checks {
+                if !PCS::batch_check(&preprocessing.generators, &[check]) {
+                    failures.push((
+                        index,
+                        ProofVerifyError::OpeningProofError(Box::new(
+                            ProofVerifyError::InternalError,
+                        )),
+                    ));
+                }
+            }
+            failures.sort_by_key(|(index, _)| *index);
+        }
+
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(BatchVerifyError { failures })
+        }
+    }
+
+    /// Performs all checks of [`Jolt::verify`] except for the batched opening proof,
+    /// whose openings are left in `opening_accumulator`. Returns the batched opening
+    /// proof.
+    fn verify_components(
+        preprocessing: &JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
+        proof: JoltProof<
+            C,
+            M,
+            <Self::Constraints as R1CSConstraints<C, F>>::Inputs,
+            F,
+            PCS,
+            Self::InstructionSet,
+            Self::Subtables,
+            ProofTranscript,
+        >,
+        mut commitments: JoltCommitments<PCS, ProofTranscript>,
+        program_io: JoltDevice,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<ReducedOpeningProof<F, PCS, ProofTranscript>, ProofVerifyError> {
+        // Commitments to the program are part of the preprocessing, not the proof
+        commitments.bytecode.v_init_final = preprocessing
+            .program_commitments
+            .as_ref()
+            .map(|program_commitments| program_commitments.bytecode.clone());
+        commitments.read_write_memory.program_image = preprocessing
+            .program_commitments
+            .as_ref()
+            .map(|program_commitments| program_commitments.program_image.clone());
+
+        Self::fiat_shamir_preamble(
+            transcript,
+            &preprocessing.program_id,
             &program_io,
             &preprocessing.memory_layout,
             proof.trace_length,
@@ -680,53 +1270,64 @@ where
         commitments
             .read_write_values()
             .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
+            .for_each(|value| value.append_to_transcript(transcript));
         commitments
             .init_final_values()
             .iter()
-            .for_each(|value| value.append_to_transcript(&mut transcript));
+            .for_each(|value| value.append_to_transcript(transcript));
+        commitments
+            .read_write_memory
+            .blobs
+            .iter()
+            .for_each(|value| value.append_to_transcript(transcript));
 
         Self::verify_bytecode(
             &preprocessing.bytecode,
             &preprocessing.generators,
             proof.bytecode,
             &commitments,
-            &mut opening_accumulator,
-            &mut transcript,
+            opening_accumulator,
+            transcript,
         )?;
         Self::verify_instruction_lookups(
             &preprocessing.instruction_lookups,
             &preprocessing.generators,
             proof.instruction_lookups,
             &commitments,
-            &mut opening_accumulator,
-            &mut transcript,
+            opening_accumulator,
+            transcript,
         )?;
+        // The memory preprocessing is paired with this proof's program IO
+        let mut read_write_memory = preprocessing.read_write_memory.clone();
         Self::verify_memory(
-            &mut preprocessing.read_write_memory,
+            &mut read_write_memory,
             &preprocessing.generators,
             &preprocessing.memory_layout,
             proof.read_write_memory,
             &commitments,
             program_io,
-            &mut opening_accumulator,
-            &mut transcript,
-        )?;
-        Self::verify_r1cs(
-            r1cs_proof,
-            &commitments,
-            &mut opening_accumulator,
-            &mut transcript,
-        )?;
-
-        // Batch-verify all openings
-        opening_accumulator.reduce_and_verify(
-            &preprocessing.generators,
-            &proof.opening_proof,
-            &mut transcript,
+            opening_accumulator,
+            transcript,
         )?;
+        Self::verify_r1cs(r1cs_proof, &commitments, opening_accumulator, transcript)?;
+        match (&proof.precompiles, preprocessing.precompiles) {
+            (Some(precompile_proof), true) => PrecompileProof::verify(
+                precompile_proof,
+                &commitments,
+                proof.trace_length,
+                opening_accumulator,
+                transcript,
+            )
+            .map_err(|e| ProofVerifyError::PrecompileError(Box::new(e)))?,
+            (None, false) => {}
+            _ => {
+                return Err(ProofVerifyError::PrecompileError(Box::new(
+                    ProofVerifyError::InvalidPrecompileCalls,
+                )))
+            }
+        }
 
-        Ok(())
+        Ok(proof.opening_proof)
     }
 
     #[tracing::instrument(skip_all)]
@@ -754,6 +1355,7 @@ where
             opening_accumulator,
             transcript,
         )
+        .map_err(|e| ProofVerifyError::InstructionLookupsError(Box::new(e)))
     }
 
     #[tracing::instrument(skip_all)]
@@ -774,6 +1376,7 @@ where
             opening_accumulator,
             transcript,
         )
+        .map_err(|e| ProofVerifyError::BytecodeError(Box::new(e)))
     }
 
     #[allow(clippy::too_many_arguments)]
@@ -788,14 +1391,38 @@ where
         opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
-        assert!(program_io.inputs.len() <= memory_layout.max_input_size as usize);
-        assert!(program_io.outputs.len() <= memory_layout.max_output_size as usize);
+        if program_io.inputs.len() > memory_layout.max_input_size as usize {
+            return Err(ProofVerifyError::InputTooLarge(
+                program_io.inputs.len(),
+                memory_layout.max_input_size as usize,
+            ));
+        }
+        if program_io.outputs.len() > memory_layout.max_output_size as usize {
+            return Err(ProofVerifyError::OutputTooLarge(
+                program_io.outputs.len(),
+                memory_layout.max_output_size as usize,
+            ));
+        }
+        // The panic word holds `panic_code`, and the termination bit is only set if it
+        // is zero; a panic with code 0 would claim neither.
+        if program_io.panic != (program_io.panic_code != 0) {
+            return Err(ProofVerifyError::InconsistentPanic(
+                program_io.panic,
+                program_io.panic_code,
+            ));
+        }
+        // The blobs' addresses are determined by the memory layout, not the proof
+        let mut blobs = program_io.blobs;
+        place_blobs(&mut blobs, memory_layout)
+            .map_err(|e| ProofVerifyError::InvalidBlobs(e.to_string()))?;
         // pair the memory layout with the program io from the proof
         preprocessing.program_io = Some(JoltDevice {
             inputs: program_io.inputs,
             outputs: program_io.outputs,
             panic: program_io.panic,
+            panic_code: program_io.panic_code,
             memory_layout: memory_layout.clone(),
+            blobs,
         });
 
         ReadWriteMemoryProof::verify(
@@ -827,10 +1454,12 @@ where
 
     fn fiat_shamir_preamble(
         transcript: &mut ProofTranscript,
+        program_id: &[u8; 32],
         program_io: &JoltDevice,
         memory_layout: &MemoryLayout,
         trace_length: usize,
     ) {
+        transcript.append_bytes(program_id);
         transcript.append_u64(trace_length as u64);
         transcript.append_u64(C as u64);
         transcript.append_u64(M as u64);
@@ -840,12 +1469,252 @@ where
         transcript.append_u64(memory_layout.max_output_size);
         transcript.append_bytes(&program_io.inputs);
         transcript.append_bytes(&program_io.outputs);
-        transcript.append_u64(program_io.panic as u64);
+        transcript.append_u64(program_io.panic_code as u64);
+        for blob in program_io.blobs.iter() {
+            transcript.append_bytes(blob.name.as_bytes());
+            transcript.append_u64(blob.len);
+        }
+    }
+}
+
+/// Proves a single execution against `preprocessing`, reusing `spartan_key` if given
+/// (it must have been set up for the execution's padded trace length). The field's
+/// small-value lookup tables must already have been initialized from the
+/// preprocessing, which is why this is only called by [`Jolt::prove`] and
+/// [`Jolt::prove_batch`].
+fn prove_instance<J, F, PCS, const C: usize, const M: usize, ProofTranscript>(
+    mut program_io: JoltDevice,
+    mut trace: Vec<JoltTraceStep<J::InstructionSet>>,
+    preprocessing: &JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+    spartan_key: Option<
+        &UniformSpartanKey<C, <J::Constraints as R1CSConstraints<C, F>>::Inputs, F>,
+    >,
+) -> (
+    JoltProof<
+        C,
+        M,
+        <J::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        PCS,
+        J::InstructionSet,
+        J::Subtables,
+        ProofTranscript,
+    >,
+    JoltCommitments<PCS, ProofTranscript>,
+    JoltDevice,
+    Option<ProverDebugInfo<F, ProofTranscript>>,
+)
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript> + ?Sized,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let trace_length = trace.len();
+    let padded_trace_length = trace_length.next_power_of_two();
+    tracing::info!(
+        "Trace length: {trace_length} (padded to {padded_trace_length}, {:.1}% no-ops)",
+        100.0 * (padded_trace_length - trace_length) as f64 / padded_trace_length as f64
+    );
+
+    let mut transcript = ProofTranscript::new(b"Jolt transcript");
+    J::fiat_shamir_preamble(
+        &mut transcript,
+        &preprocessing.shared.program_id,
+        &program_io,
+        &program_io.memory_layout,
+        trace_length,
+    );
+
+    let (mut jolt_polynomials, r1cs_builder) =
+        J::generate_witness(&program_io, &mut trace, preprocessing);
+    let owned_spartan_key;
+    let spartan_key = match spartan_key {
+        Some(spartan_key) => spartan_key,
+        None => {
+            owned_spartan_key = spartan::UniformSpartanProof::<
+                C,
+                <J::Constraints as R1CSConstraints<C, F>>::Inputs,
+                F,
+                ProofTranscript,
+            >::setup(&r1cs_builder, padded_trace_length);
+            &owned_spartan_key
+        }
+    };
+
+    // The prover key supports the longest trace and largest memory the preprocessing
+    // allows, of which this execution's polynomials only need a prefix.
+    let generators = PCS::trim(
+        &preprocessing.shared.generators,
+        [
+            preprocessing.shared.bytecode.code_size(),
+            M,
+            jolt_polynomials.read_write_memory.v_final.len(),
+            padded_trace_length,
+        ]
+        .into_iter()
+        .max()
+        .unwrap(),
+    );
+
+    let jolt_commitments =
+        jolt_polynomials.commit::<C, PCS, ProofTranscript>(&preprocessing.shared, &generators);
+    for (blob, commitment) in program_io
+        .blobs
+        .iter_mut()
+        .zip(jolt_commitments.read_write_memory.blobs.iter())
+    {
+        blob.digest = blob_digest::<PCS, ProofTranscript>(commitment);
     }
+
+    transcript.append_scalar(&spartan_key.vk_digest);
+
+    jolt_commitments
+        .read_write_values()
+        .iter()
+        .for_each(|value| value.append_to_transcript(&mut transcript));
+    jolt_commitments
+        .init_final_values()
+        .iter()
+        .for_each(|value| value.append_to_transcript(&mut transcript));
+    jolt_commitments
+        .read_write_memory
+        .blobs
+        .iter()
+        .for_each(|value| value.append_to_transcript(&mut transcript));
+
+    let mut opening_accumulator: ProverOpeningAccumulator<F, ProofTranscript> =
+        ProverOpeningAccumulator::new();
+
+    let bytecode_proof = BytecodeProof::prove_memory_checking(
+        &generators,
+        &preprocessing.shared.bytecode,
+        &jolt_polynomials.bytecode,
+        &jolt_polynomials,
+        &mut opening_accumulator,
+        &mut transcript,
+    );
+
+    let instruction_proof = InstructionLookupsProof::prove(
+        &generators,
+        &mut jolt_polynomials,
+        &preprocessing.shared.instruction_lookups,
+        &mut opening_accumulator,
+        &mut transcript,
+    );
+
+    let memory_proof = ReadWriteMemoryProof::prove(
+        &generators,
+        &preprocessing.shared.read_write_memory,
+        &jolt_polynomials,
+        &program_io,
+        &mut opening_accumulator,
+        &mut transcript,
+    );
+
+    let spartan_proof = UniformSpartanProof::<
+        C,
+        <J::Constraints as R1CSConstraints<C, F>>::Inputs,
+        F,
+        ProofTranscript,
+    >::prove::<PCS>(
+        &r1cs_builder,
+        spartan_key,
+        &jolt_polynomials,
+        &mut opening_accumulator,
+        &mut transcript,
+    )
+    .expect("r1cs proof failed");
+
+    let precompile_proof = preprocessing.shared.precompiles.then(|| {
+        PrecompileProof::prove(
+            &jolt_polynomials,
+            trace_length,
+            &mut opening_accumulator,
+            &mut transcript,
+        )
+    });
+
+    // Batch-prove all openings
+    let opening_proof = opening_accumulator
+        .reduce_and_prove::<PCS>(&generators, &mut transcript);
+
+    drop_in_background_thread(jolt_polynomials);
+
+    let jolt_proof = JoltProof {
+        trace_length,
+        bytecode: bytecode_proof,
+        read_write_memory: memory_proof,
+        instruction_lookups: instruction_proof,
+        r1cs: spartan_proof,
+        precompiles: precompile_proof,
+        opening_proof,
+    };
+
+    #[cfg(test)]
+    let debug_info = Some(ProverDebugInfo {
+        transcript,
+        opening_accumulator,
+    });
+    #[cfg(not(test))]
+    let debug_info = None;
+    (jolt_proof, jolt_commitments, program_io, debug_info)
+}
+
+/// Checks a single execution against `preprocessing`, see [`Jolt::prove_dev`]. Like
+/// [`prove_instance`], requires the field's small-value lookup tables to have been
+/// initialized, so it is only called by [`Jolt::prove_dev`] and [`Jolt::prove_dev_batch`].
+fn prove_dev_instance<J, F, PCS, const C: usize, const M: usize, ProofTranscript>(
+    program_io: JoltDevice,
+    mut trace: Vec<JoltTraceStep<J::InstructionSet>>,
+    preprocessing: &JoltProverPreprocessing<C, F, PCS, ProofTranscript>,
+) -> Result<(DevProof, JoltDevice), WitnessReport>
+where
+    J: Jolt<F, PCS, C, M, ProofTranscript> + ?Sized,
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let trace_length = trace.len();
+    let (jolt_polynomials, _) = J::generate_witness(&program_io, &mut trace, preprocessing);
+    let result = J::debug_witness(&program_io, &trace, &jolt_polynomials, preprocessing);
+    drop_in_background_thread(jolt_polynomials);
+    result?;
+
+    let proof = DevProof {
+        program_id: preprocessing.shared.program_id,
+        trace_length,
+    };
+    Ok((proof, program_io))
+}
+
+/// See [`JoltVerifierPreprocessing::verifier_setup_len`].
+fn verifier_setup_len<F: JoltField>(
+    bytecode: &BytecodePreprocessing<F>,
+    read_write_memory: &ReadWriteMemoryPreprocessing,
+    memory_layout: &MemoryLayout,
+) -> usize {
+    [
+        bytecode.code_size(),
+        read_write_memory.program_image_len(memory_layout),
+        (memory_layout.max_blob_size as usize)
+            .div_ceil(4)
+            .next_power_of_two(),
+    ]
+    .into_iter()
+    .max()
+    .unwrap()
 }
 
 pub mod bytecode;
+pub mod container;
+pub mod extension;
 pub mod instruction_lookups;
+pub mod precompiles;
 pub mod read_write_memory;
+#[cfg(test)]
+mod riscv_tests;
 pub mod rv32i_vm;
+#[cfg(test)]
+mod tamper;
 pub mod timestamp_range_check;
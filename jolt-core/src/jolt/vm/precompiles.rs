@@ -0,0 +1,796 @@
+//! Proves that the witness of every `PRECOMPILE` instruction in the trace satisfies
+//! the precompile's constraints (see `jolt::instruction::precompile`).
+//!
+//! The prover lists the steps at which each precompile call starts. For each kind of
+//! precompile, a zero-check sumcheck over the calls of that kind shows that the
+//! polynomial identities of its constraints hold, where each limb group `g` of call
+//! `j` enters as `G_g(j) = sum_i gamma^i * limb_i`, i.e. its limb polynomial evaluated
+//! at a random `gamma`. This reduces to claims about `G_g(r)` for a random `r`, which a
+//! second ("gather") sumcheck over the trace reduces to an opening of `v_write_rd`,
+//! since each limb is written to `rd` at a known offset into the call's virtual
+//! sequence. The gather sumcheck also shows that the listed calls are genuine (the
+//! first instruction of each has the expected `imm`) and that every step flagged as
+//! part of a precompile's virtual sequence is covered by a listed call.
+
+use std::marker::PhantomData;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use common::precompiles::Precompile;
+use common::rv_trace::CircuitFlags;
+use strum::IntoEnumIterator;
+
+use crate::field::JoltField;
+use crate::jolt::instruction::precompile::{Modulus, Operand, PrecompileLayout, PRECOMPILE_START};
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::poly::dense_mlpoly::DensePolynomial;
+use crate::poly::eq_poly::EqPolynomial;
+use crate::poly::multilinear_polynomial::MultilinearPolynomial;
+use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
+use crate::subprotocols::sumcheck::SumcheckInstanceProof;
+use crate::utils::errors::ProofVerifyError;
+use crate::utils::math::Math;
+use crate::utils::transcript::Transcript;
+
+use super::{JoltCommitments, JoltPolynomials};
+
+/// Zero-check of the constraints of one kind of precompile, over all of its calls.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+struct ZeroCheckProof<F: JoltField, ProofTranscript: Transcript> {
+    sumcheck_proof: SumcheckInstanceProof<F, ProofTranscript>,
+    /// `G_g(r)` for each limb group `g`, in the order of `PrecompileLayout::groups`
+    group_openings: Vec<F>,
+}
+
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+pub struct PrecompileProof<F, PCS, ProofTranscript>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    _pcs: PhantomData<(PCS, ProofTranscript)>,
+    /// The step at which each precompile call starts, in increasing order
+    call_steps: Vec<u64>,
+    /// The id of each precompile call
+    call_ids: Vec<u8>,
+    /// One zero-check per kind of precompile called, in order of id
+    zero_checks: Vec<ZeroCheckProof<F, ProofTranscript>>,
+    /// Sumcheck reducing the zero-check openings to openings of per-step polynomials
+    gather_sumcheck_proof: SumcheckInstanceProof<F, ProofTranscript>,
+    rd_opening: F,
+    flag_opening: F,
+    imm_opening: F,
+}
+
+/// The calls of one kind of precompile.
+struct Calls {
+    layout: PrecompileLayout,
+    steps: Vec<usize>,
+}
+
+impl Calls {
+    fn num_vars(&self) -> usize {
+        self.steps.len().next_power_of_two().log_2().max(1)
+    }
+}
+
+/// Groups the calls by kind of precompile, in order of id.
+fn calls_by_kind(call_steps: &[u64], call_ids: &[u8]) -> Vec<Calls> {
+    Precompile::iter()
+        .filter_map(|precompile| {
+            let steps: Vec<usize> = call_steps
+                .iter()
+                .zip(call_ids.iter())
+                .filter(|(_, &id)| id as u32 == precompile.id())
+                .map(|(&step, _)| step as usize)
+                .collect();
+            (!steps.is_empty()).then(|| Calls {
+                layout: PrecompileLayout::new(precompile),
+                steps,
+            })
+        })
+        .collect()
+}
+
+fn append_calls<ProofTranscript: Transcript>(
+    call_steps: &[u64],
+    call_ids: &[u8],
+    transcript: &mut ProofTranscript,
+) {
+    transcript.append_u64(call_steps.len() as u64);
+    for (&step, &id) in call_steps.iter().zip(call_ids.iter()) {
+        transcript.append_u64(step);
+        transcript.append_u64(id as u64);
+    }
+}
+
+fn powers<F: JoltField>(x: F, n: usize) -> Vec<F> {
+    std::iter::successors(Some(F::one()), |power| Some(*power * x))
+        .take(n)
+        .collect()
+}
+
+/// `sum_i gamma^i * p_i` for a fixed modulus `p`, or zero if the modulus is an input.
+fn fixed_modulus<F: JoltField>(layout: &PrecompileLayout, gamma_powers: &[F]) -> F {
+    match layout.spec.modulus {
+        Modulus::Fixed(p) => p
+            .iter()
+            .zip(gamma_powers.iter())
+            .map(|(&limb, power)| *power * F::from_u32(limb))
+            .sum(),
+        Modulus::Input(_) => F::zero(),
+    }
+}
+
+/// Random linear combination (by `beta_powers`) of the identities of a call, given the
+/// limb polynomials of its groups evaluated at `gamma`. `mask` is 1 for a call and 0
+/// for padding, so that constants vanish on padding.
+fn combined_identities<F: JoltField>(
+    layout: &PrecompileLayout,
+    groups: &[F],
+    mask: F,
+    gamma: F,
+    beta_powers: &[F],
+    fixed_modulus: F,
+) -> F {
+    let p = match layout.spec.modulus {
+        Modulus::Fixed(_) => fixed_modulus * mask,
+        Modulus::Input(k) => groups[layout.operand_group(Operand::Input(k))],
+    };
+    let x_minus_base = gamma - F::from_u64(1 << 32);
+
+    let mut result = F::zero();
+    for (c, constraint) in layout.spec.constraints.iter().enumerate() {
+        // E(gamma) - Q(gamma) * P(gamma) - (gamma - 2^32) * C(gamma)
+        let e: F = constraint
+            .iter()
+            .map(|term| {
+                let product = match term.operands.split_first() {
+                    Some((&first, rest)) => rest
+                        .iter()
+                        .fold(groups[layout.operand_group(first)], |product, &operand| {
+                            product * groups[layout.operand_group(operand)]
+                        }),
+                    None => mask,
+                };
+                F::from_i64(term.coeff) * product
+            })
+            .sum();
+        result += beta_powers[c]
+            * (e - groups[layout.quotient_group(c)] * p
+                - x_minus_base * groups[layout.carries_group(c)]);
+    }
+    let num_constraints = layout.spec.constraints.len();
+    for k in 0..layout.spec.outputs {
+        // R(gamma) + D(gamma) + 1 - P(gamma) - (gamma - 2^32) * C'(gamma)
+        result += beta_powers[num_constraints + k]
+            * (groups[layout.operand_group(Operand::Output(k))]
+                + groups[layout.difference_group(k)]
+                + mask
+                - p
+                - x_minus_base * groups[layout.difference_carries_group(k)]);
+    }
+    result
+}
+
+/// The weight of the `rd` write value at each offset into a call's virtual sequence,
+/// such that the weighted sum of the call's `rd` values is `sum_g mu_g * (G_g + B_g)`,
+/// where `B_g = sum_i gamma^i * bias_i` (see [`bias`]).
+fn gather_weights<F: JoltField>(
+    layout: &PrecompileLayout,
+    gamma_powers: &[F],
+    mu_powers: &[F],
+) -> Vec<F> {
+    let base = F::from_u64(1 << 32);
+    let mut weights = vec![F::zero(); layout.len()];
+    for (group, mu) in layout.groups().into_iter().zip(mu_powers.iter()) {
+        for (limb, gamma) in group.iter().zip(gamma_powers.iter()) {
+            weights[limb.lo] += *mu * gamma;
+            if let Some(hi) = limb.hi {
+                weights[hi] += *mu * gamma * base;
+            }
+        }
+    }
+    weights
+}
+
+/// `sum_g mu_g * B_g`, where `B_g = sum_i gamma^i * bias_i`.
+fn bias<F: JoltField>(layout: &PrecompileLayout, gamma_powers: &[F], mu_powers: &[F]) -> F {
+    layout
+        .groups()
+        .into_iter()
+        .zip(mu_powers.iter())
+        .map(|(group, mu)| {
+            *mu * group
+                .iter()
+                .zip(gamma_powers.iter())
+                .map(|(limb, gamma)| *gamma * F::from_u64(limb.bias))
+                .sum::<F>()
+        })
+        .sum()
+}
+
+/// The maximum number of limbs of any group of the given calls.
+fn max_group_len(calls: &[Calls]) -> usize {
+    calls
+        .iter()
+        .flat_map(|calls| calls.layout.groups().into_iter().map(|group| group.len()))
+        .max()
+        .unwrap_or(0)
+}
+
+/// The multilinear extension of `[1; num_calls]` (padded with zeros) evaluated at `r`.
+fn mask_eval<F: JoltField>(r: &[F], num_calls: usize) -> F {
+    EqPolynomial::evals(r)[..num_calls].iter().copied().sum()
+}
+
+/// The challenges of the gather sumcheck.
+struct GatherChallenges<F: JoltField> {
+    mu_powers: Vec<F>,
+    nu: [F; 3],
+    r_calls: Vec<F>,
+}
+
+impl<F: JoltField> GatherChallenges<F> {
+    fn new<ProofTranscript: Transcript>(
+        calls: &[Calls],
+        num_calls: usize,
+        transcript: &mut ProofTranscript,
+    ) -> Self {
+        let num_groups = calls.iter().map(|calls| calls.layout.groups().len()).sum();
+        let mu_powers = transcript.challenge_scalar_powers(num_groups);
+        let nu = [
+            transcript.challenge_scalar(),
+            transcript.challenge_scalar(),
+            transcript.challenge_scalar(),
+        ];
+        let r_calls = transcript.challenge_vector(num_calls.next_power_of_two().log_2().max(1));
+        Self {
+            mu_powers,
+            nu,
+            r_calls,
+        }
+    }
+
+    /// `v_write_rd * W + nu_1 * flag * (1 - cover) + (nu_2 * imm + nu_3 * flag) * S`
+    fn combine(&self, rd: F, w: F, flag: F, cover: F, imm: F, s: F) -> F {
+        rd * w + self.nu[0] * flag * (F::one() - cover) + (self.nu[1] * imm + self.nu[2] * flag) * s
+    }
+}
+
+/// Evaluates a sparse vector over `{0, 1}^r.len()` at `r`, splitting the `eq` table
+/// in two halves so that the verifier never materializes it.
+struct SparseEvaluator<F: JoltField> {
+    eq_hi: Vec<F>,
+    eq_lo: Vec<F>,
+    lo_bits: usize,
+}
+
+impl<F: JoltField> SparseEvaluator<F> {
+    fn new(r: &[F]) -> Self {
+        let lo_bits = r.len() / 2;
+        let (r_hi, r_lo) = r.split_at(r.len() - lo_bits);
+        Self {
+            eq_hi: EqPolynomial::evals(r_hi),
+            eq_lo: EqPolynomial::evals(r_lo),
+            lo_bits,
+        }
+    }
+
+    fn eq(&self, index: usize) -> F {
+        self.eq_hi[index >> self.lo_bits] * self.eq_lo[index & ((1 << self.lo_bits) - 1)]
+    }
+}
+
+impl<F, PCS, ProofTranscript> PrecompileProof<F, PCS, ProofTranscript>
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    #[tracing::instrument(skip_all, name = "PrecompileProof::prove")]
+    pub fn prove(
+        polynomials: &JoltPolynomials<F>,
+        trace_length: usize,
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Self {
+        let rd = &polynomials.read_write_memory.v_write_rd;
+        let flag = &polynomials.r1cs.circuit_flags[CircuitFlags::Precompile as usize];
+        let imm = &polynomials.bytecode.v_read_write[5];
+
+        // Only the first instruction of a call's virtual sequence has imm >= PRECOMPILE_START
+        let mut call_steps = vec![];
+        let mut call_ids = vec![];
+        let mut step = 0;
+        while step < trace_length {
+            let id = imm.get_coeff_i64(step) - PRECOMPILE_START as i64;
+            if flag.get_coeff(step) == F::one() && id >= 0 {
+                let precompile = Precompile::from_id(id as u32).unwrap();
+                call_steps.push(step as u64);
+                call_ids.push(id as u8);
+                step += PrecompileLayout::new(precompile).len();
+            } else {
+                step += 1;
+            }
+        }
+        append_calls(&call_steps, &call_ids, transcript);
+
+        let calls = calls_by_kind(&call_steps, &call_ids);
+        let gamma: F = transcript.challenge_scalar();
+        let gamma_powers = powers(gamma, max_group_len(&calls));
+
+        let mut zero_checks = vec![];
+        let mut zero_check_points = vec![];
+        for calls in calls.iter() {
+            let layout = &calls.layout;
+            let num_vars = calls.num_vars();
+            let beta_powers: Vec<F> = transcript
+                .challenge_scalar_powers(layout.spec.constraints.len() + layout.spec.outputs);
+            let tau: Vec<F> = transcript.challenge_vector(num_vars);
+
+            let mut mask = vec![0u8; 1 << num_vars];
+            mask[..calls.steps.len()].fill(1);
+            let mut sumcheck_polys = vec![
+                MultilinearPolynomial::from(EqPolynomial::evals(&tau)),
+                MultilinearPolynomial::from(mask),
+            ];
+            for group in layout.groups() {
+                let mut values = vec![F::zero(); 1 << num_vars];
+                for (value, &start) in values.iter_mut().zip(calls.steps.iter()) {
+                    *value = group
+                        .iter()
+                        .zip(gamma_powers.iter())
+                        .map(|(limb, power)| {
+                            let hi = limb.hi.map_or(F::zero(), |hi| {
+                                rd.get_coeff(start + hi) * F::from_u64(1 << 32)
+                            });
+                            *power * (rd.get_coeff(start + limb.lo) + hi - F::from_u64(limb.bias))
+                        })
+                        .sum();
+                }
+                sumcheck_polys.push(MultilinearPolynomial::from(values));
+            }
+
+            let fixed_modulus = fixed_modulus(layout, &gamma_powers);
+            let comb_fn = |vals: &[F]| -> F {
+                vals[0]
+                    * combined_identities(
+                        layout,
+                        &vals[2..],
+                        vals[1],
+                        gamma,
+                        &beta_powers,
+                        fixed_modulus,
+                    )
+            };
+            let (sumcheck_proof, r, openings) = SumcheckInstanceProof::prove_arbitrary(
+                &F::zero(),
+                num_vars,
+                &mut sumcheck_polys,
+                comb_fn,
+                1 + layout.spec.degree().max(2),
+                transcript,
+            );
+            let group_openings = openings[2..].to_vec();
+            transcript.append_scalars(&group_openings);
+
+            zero_check_points.push(r);
+            zero_checks.push(ZeroCheckProof {
+                sumcheck_proof,
+                group_openings,
+            });
+        }
+
+        let challenges = GatherChallenges::new(&calls, call_steps.len(), transcript);
+        let num_steps = rd.len();
+        let mut w = vec![F::zero(); num_steps];
+        let mut cover = vec![0u8; num_steps];
+        let mut claim = F::zero();
+        let mut mu_powers = &challenges.mu_powers[..];
+        for ((calls, r), zero_check) in calls
+            .iter()
+            .zip(zero_check_points.iter())
+            .zip(zero_checks.iter())
+        {
+            let layout = &calls.layout;
+            let (mu, rest) = mu_powers.split_at(layout.groups().len());
+            mu_powers = rest;
+
+            let weights = gather_weights(layout, &gamma_powers, mu);
+            let eq = EqPolynomial::evals(r);
+            for (&start, eq) in calls.steps.iter().zip(eq.iter()) {
+                for (offset, weight) in weights.iter().enumerate() {
+                    w[start + offset] += *eq * weight;
+                }
+                cover[start..start + layout.len()].fill(1);
+            }
+            claim += zero_check
+                .group_openings
+                .iter()
+                .zip(mu.iter())
+                .map(|(opening, mu)| *opening * mu)
+                .sum::<F>()
+                + mask_eval(r, calls.steps.len()) * bias(layout, &gamma_powers, mu);
+        }
+        let eq_calls = EqPolynomial::evals(&challenges.r_calls);
+        let mut s = vec![F::zero(); num_steps];
+        for ((&step, &id), eq) in call_steps.iter().zip(call_ids.iter()).zip(eq_calls.iter()) {
+            s[step as usize] = *eq;
+            claim += *eq
+                * (challenges.nu[1] * F::from_u64(PRECOMPILE_START + id as u64) + challenges.nu[2]);
+        }
+
+        let mut sumcheck_polys = vec![
+            rd.clone(),
+            MultilinearPolynomial::from(w),
+            flag.clone(),
+            MultilinearPolynomial::from(cover),
+            imm.clone(),
+            MultilinearPolynomial::from(s),
+        ];
+        let (gather_sumcheck_proof, r_step, openings) = SumcheckInstanceProof::prove_arbitrary(
+            &claim,
+            num_steps.log_2(),
+            &mut sumcheck_polys,
+            |vals: &[F]| challenges.combine(vals[0], vals[1], vals[2], vals[3], vals[4], vals[5]),
+            2,
+            transcript,
+        );
+
+        opening_accumulator.append(
+            &[rd, flag, imm],
+            DensePolynomial::new(EqPolynomial::evals(&r_step)),
+            r_step.to_vec(),
+            &[openings[0], openings[2], openings[4]],
+            transcript,
+        );
+
+        Self {
+            _pcs: PhantomData,
+            call_steps,
+            call_ids,
+            zero_checks,
+            gather_sumcheck_proof,
+            rd_opening: openings[0],
+            flag_opening: openings[2],
+            imm_opening: openings[4],
+        }
+    }
+
+    pub fn verify(
+        proof: &Self,
+        commitments: &JoltCommitments<PCS, ProofTranscript>,
+        trace_length: usize,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError> {
+        if proof.call_steps.len() != proof.call_ids.len() {
+            return Err(ProofVerifyError::InvalidPrecompileCalls);
+        }
+        // Calls must not overlap, and must lie within the trace
+        let mut next_step = 0;
+        for (&step, &id) in proof.call_steps.iter().zip(proof.call_ids.iter()) {
+            let precompile = Precompile::from_id(id as u32)
+                .filter(|precompile| precompile.is_modular())
+                .ok_or(ProofVerifyError::InvalidPrecompileCalls)?;
+            let end = step.saturating_add(PrecompileLayout::new(precompile).len() as u64);
+            if step < next_step || end > trace_length as u64 {
+                return Err(ProofVerifyError::InvalidPrecompileCalls);
+            }
+            next_step = end;
+        }
+        append_calls(&proof.call_steps, &proof.call_ids, transcript);
+
+        let calls = calls_by_kind(&proof.call_steps, &proof.call_ids);
+        if proof.zero_checks.len() != calls.len() {
+            return Err(ProofVerifyError::InvalidPrecompileCalls);
+        }
+        let gamma: F = transcript.challenge_scalar();
+        let gamma_powers = powers(gamma, max_group_len(&calls));
+
+        let mut zero_check_points = vec![];
+        for (calls, zero_check) in calls.iter().zip(proof.zero_checks.iter()) {
+            let layout = &calls.layout;
+            let num_vars = calls.num_vars();
+            let beta_powers: Vec<F> = transcript
+                .challenge_scalar_powers(layout.spec.constraints.len() + layout.spec.outputs);
+            let tau: Vec<F> = transcript.challenge_vector(num_vars);
+            if zero_check.group_openings.len() != layout.groups().len() {
+                return Err(ProofVerifyError::InvalidPrecompileCalls);
+            }
+
+            let (sumcheck_claim, r) = zero_check.sumcheck_proof.verify(
+                F::zero(),
+                num_vars,
+                1 + layout.spec.degree().max(2),
+                transcript,
+            )?;
+            let expected = EqPolynomial::new(tau).evaluate(&r)
+                * combined_identities(
+                    layout,
+                    &zero_check.group_openings,
+                    mask_eval(&r, calls.steps.len()),
+                    gamma,
+                    &beta_powers,
+                    fixed_modulus(layout, &gamma_powers),
+                );
+            if sumcheck_claim != expected {
+                return Err(ProofVerifyError::SumcheckClaimMismatch(
+                    "precompile constraints",
+                ));
+            }
+            transcript.append_scalars(&zero_check.group_openings);
+            zero_check_points.push(r);
+        }
+
+        let challenges = GatherChallenges::new(&calls, proof.call_steps.len(), transcript);
+        let mut claim = F::zero();
+        let mut mu_powers = &challenges.mu_powers[..];
+        for ((calls, r), zero_check) in calls
+            .iter()
+            .zip(zero_check_points.iter())
+            .zip(proof.zero_checks.iter())
+        {
+            let (mu, rest) = mu_powers.split_at(calls.layout.groups().len());
+            mu_powers = rest;
+            claim += zero_check
+                .group_openings
+                .iter()
+                .zip(mu.iter())
+                .map(|(opening, mu)| *opening * mu)
+                .sum::<F>()
+                + mask_eval(r, calls.steps.len()) * bias(&calls.layout, &gamma_powers, mu);
+        }
+        let eq_calls = EqPolynomial::evals(&challenges.r_calls);
+        for (&id, eq) in proof.call_ids.iter().zip(eq_calls.iter()) {
+            claim += *eq
+                * (challenges.nu[1] * F::from_u64(PRECOMPILE_START + id as u64) + challenges.nu[2]);
+        }
+
+        let num_rounds = trace_length.next_power_of_two().log_2();
+        let (sumcheck_claim, r_step) = proof
+            .gather_sumcheck_proof
+            .verify(claim, num_rounds, 2, transcript)?;
+
+        // The verifier evaluates W, cover and S itself, since they are sparse
+        let eq_step = SparseEvaluator::new(&r_step);
+        let (mut w, mut cover) = (F::zero(), F::zero());
+        let mut mu_powers = &challenges.mu_powers[..];
+        for (calls, r) in calls.iter().zip(zero_check_points.iter()) {
+            let layout = &calls.layout;
+            let (mu, rest) = mu_powers.split_at(layout.groups().len());
+            mu_powers = rest;
+
+            let weights = gather_weights(layout, &gamma_powers, mu);
+            let eq = EqPolynomial::evals(r);
+            for (&start, eq) in calls.steps.iter().zip(eq.iter()) {
+                for (offset, weight) in weights.iter().enumerate() {
+                    let eq_step = eq_step.eq(start + offset);
+                    w += eq_step * eq * weight;
+                    cover += eq_step;
+                }
+            }
+        }
+        let s: F = proof
+            .call_steps
+            .iter()
+            .zip(eq_calls.iter())
+            .map(|(&step, eq)| eq_step.eq(step as usize) * eq)
+            .sum();
+
+        let expected = challenges.combine(
+            proof.rd_opening,
+            w,
+            proof.flag_opening,
+            cover,
+            proof.imm_opening,
+            s,
+        );
+        if sumcheck_claim != expected {
+            return Err(ProofVerifyError::SumcheckClaimMismatch("precompile gather"));
+        }
+
+        opening_accumulator.append(
+            &[
+                &commitments.read_write_memory.v_write_rd,
+                &commitments.r1cs.circuit_flags[CircuitFlags::Precompile as usize],
+                &commitments.bytecode.v_read_write[5],
+            ],
+            r_step,
+            &[&proof.rd_opening, &proof.flag_opening, &proof.imm_opening],
+            transcript,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::Fr;
+    use ark_std::test_rng;
+    use common::precompiles::{BN254_FR_MODULUS, U256_LIMBS};
+    use rand_chacha::rand_core::RngCore;
+    use tracer::{ELFInstruction, RV32IM};
+
+    use super::*;
+    use crate::poly::commitment::mock::MockCommitScheme;
+    use crate::utils::transcript::KeccakTranscript;
+
+    type PCS = MockCommitScheme<Fr, KeccakTranscript>;
+
+    /// Per-step `v_write_rd`, precompile flag and `imm` of a trace containing the
+    /// given calls (and no-ops elsewhere).
+    fn polynomials(
+        num_steps: usize,
+        calls: &[(usize, Precompile, Vec<u32>)],
+    ) -> JoltPolynomials<Fr> {
+        let mut rd = vec![0u32; num_steps];
+        let mut flag = vec![0u8; num_steps];
+        let mut imm = vec![0i64; num_steps];
+        for (start, precompile, input) in calls {
+            let layout = PrecompileLayout::new(*precompile);
+            let instruction = ELFInstruction {
+                address: 0,
+                opcode: RV32IM::PRECOMPILE,
+                rs1: Some(10),
+                rs2: Some(11),
+                rd: None,
+                imm: Some(precompile.id() as i64),
+                virtual_sequence_remaining: None,
+            };
+            let sequence = layout.virtual_sequence(&instruction);
+            for (i, value) in layout.witness(input).into_iter().enumerate() {
+                rd[start + i] = value as u32;
+                flag[start + i] = 1;
+                imm[start + i] = sequence[i].imm.unwrap();
+            }
+        }
+        let mut polynomials = JoltPolynomials::<Fr>::default();
+        polynomials.read_write_memory.v_write_rd = rd.into();
+        polynomials.r1cs.circuit_flags[CircuitFlags::Precompile as usize] = flag.into();
+        polynomials.bytecode.v_read_write[5] = imm.into();
+        polynomials
+    }
+
+    fn random_input(precompile: Precompile, rng: &mut impl RngCore) -> Vec<u32> {
+        let mut input: Vec<u32> = (0..precompile.input_words())
+            .map(|_| rng.next_u32())
+            .collect();
+        if precompile.modulus().is_none() {
+            // Ensure the modulus is greater than 1
+            input[precompile.input_words() - 1] |= 1;
+        }
+        input
+    }
+
+    fn prove_and_verify(
+        polynomials: &JoltPolynomials<Fr>,
+        trace_length: usize,
+        tamper: impl FnOnce(&mut PrecompileProof<Fr, PCS, KeccakTranscript>),
+    ) -> Result<(), ProofVerifyError> {
+        let mut transcript = KeccakTranscript::new(b"test_transcript");
+        let mut prover_accumulator = ProverOpeningAccumulator::new();
+        let mut proof = PrecompileProof::<Fr, PCS, KeccakTranscript>::prove(
+            polynomials,
+            trace_length,
+            &mut prover_accumulator,
+            &mut transcript,
+        );
+        let opening_proof = prover_accumulator.reduce_and_prove::<PCS>(&(), &mut transcript);
+        tamper(&mut proof);
+
+        let mut commitments = JoltCommitments::<PCS, KeccakTranscript>::default();
+        commitments.read_write_memory.v_write_rd =
+            PCS::commit(&polynomials.read_write_memory.v_write_rd, &());
+        commitments.r1cs.circuit_flags[CircuitFlags::Precompile as usize] = PCS::commit(
+            &polynomials.r1cs.circuit_flags[CircuitFlags::Precompile as usize],
+            &(),
+        );
+        commitments.bytecode.v_read_write[5] =
+            PCS::commit(&polynomials.bytecode.v_read_write[5], &());
+
+        let mut transcript = KeccakTranscript::new(b"test_transcript");
+        let mut verifier_accumulator = VerifierOpeningAccumulator::new();
+        PrecompileProof::verify(
+            &proof,
+            &commitments,
+            trace_length,
+            &mut verifier_accumulator,
+            &mut transcript,
+        )?;
+        verifier_accumulator.reduce_and_verify(&(), &opening_proof, &mut transcript)
+    }
+
+    #[test]
+    fn prove_and_verify_calls() {
+        let mut rng = test_rng();
+        let mut calls = vec![];
+        let mut start = 3;
+        for precompile in [
+            Precompile::ModMul,
+            Precompile::Bn254FrMul,
+            Precompile::ModMul,
+            Precompile::Bn254FqAdd,
+            Precompile::ModAdd,
+            Precompile::Secp256k1Double,
+            Precompile::Secp256k1Add,
+            Precompile::Ed25519Add,
+        ] {
+            calls.push((start, precompile, random_input(precompile, &mut rng)));
+            start += PrecompileLayout::new(precompile).len() + 2;
+        }
+        let polynomials = polynomials(start.next_power_of_two(), &calls);
+        assert!(prove_and_verify(&polynomials, start, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn extreme_inputs() {
+        let mut r_minus_one = BN254_FR_MODULUS;
+        r_minus_one[0] -= 1;
+        let calls = vec![
+            (
+                0,
+                Precompile::Bn254FrMul,
+                [r_minus_one, r_minus_one].concat(),
+            ),
+            (
+                200,
+                Precompile::ModMul,
+                [[u32::MAX; U256_LIMBS]; 3].concat(),
+            ),
+        ];
+        let polynomials = polynomials(512, &calls);
+        assert!(prove_and_verify(&polynomials, 400, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn no_calls() {
+        let polynomials = polynomials(16, &[]);
+        assert!(prove_and_verify(&polynomials, 10, |_| {}).is_ok());
+    }
+
+    #[test]
+    fn tampered_openings() {
+        let mut rng = test_rng();
+        let input = random_input(Precompile::Bn254FqMul, &mut rng);
+        let polynomials = polynomials(256, &[(1, Precompile::Bn254FqMul, input)]);
+        assert!(matches!(
+            prove_and_verify(&polynomials, 200, |proof| proof.zero_checks[0]
+                .group_openings[0] +=
+                Fr::from(1u64)),
+            Err(ProofVerifyError::SumcheckClaimMismatch(_))
+        ));
+        assert!(matches!(
+            prove_and_verify(&polynomials, 200, |proof| proof.rd_opening +=
+                Fr::from(1u64)),
+            Err(ProofVerifyError::SumcheckClaimMismatch(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_calls() {
+        let mut rng = test_rng();
+        let input = random_input(Precompile::Bn254FrAdd, &mut rng);
+        let polynomials = polynomials(256, &[(1, Precompile::Bn254FrAdd, input)]);
+        // A call that extends past the end of the trace
+        assert!(matches!(
+            prove_and_verify(&polynomials, 200, |proof| proof.call_steps[0] = 199),
+            Err(ProofVerifyError::InvalidPrecompileCalls)
+        ));
+        // A call to an unknown precompile
+        assert!(matches!(
+            prove_and_verify(&polynomials, 200, |proof| proof.call_ids[0] = 0x7f),
+            Err(ProofVerifyError::InvalidPrecompileCalls)
+        ));
+        // Omitting a call
+        assert!(matches!(
+            prove_and_verify(&polynomials, 200, |proof| {
+                proof.call_steps.clear();
+                proof.call_ids.clear();
+            }),
+            Err(ProofVerifyError::InvalidPrecompileCalls)
+        ));
+    }
+}
@@ -9,6 +9,7 @@ use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumu
 use crate::subprotocols::grand_product::BatchedDenseGrandProduct;
 use crate::utils::thread::unsafe_allocate_zero_vec;
 use rayon::prelude::*;
+use sha3::{Digest, Sha3_256};
 #[cfg(test)]
 use std::collections::HashSet;
 use std::marker::PhantomData;
@@ -23,9 +24,13 @@ use crate::{
         dense_mlpoly::DensePolynomial, eq_poly::EqPolynomial, identity_poly::IdentityPolynomial,
     },
     subprotocols::sumcheck::SumcheckInstanceProof,
-    utils::{errors::ProofVerifyError, math::Math},
+    utils::{
+        errors::{ProofVerifyError, WitnessError},
+        math::Math,
+    },
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use common::blob::{blob_directory, Blob};
 use common::constants::{
     BYTES_PER_INSTRUCTION, MEMORY_OPS_PER_INSTRUCTION, RAM_START_ADDRESS, REGISTER_COUNT,
 };
@@ -44,6 +49,21 @@ pub struct ReadWriteMemoryPreprocessing {
     // to compute the v_init and v_final openings, with no impact
     // on existing function signatures.
     pub program_io: Option<JoltDevice>,
+    /// Set if the program image is committed to in the verifier preprocessing (see
+    /// `ReadWriteMemoryPreprocessing::commit`), in which case the verifier does not
+    /// need `bytecode_words`.
+    committed_image: Option<CommittedProgramImage>,
+}
+
+/// The program image is `bytecode_words` placed at their witness indices in the initial
+/// RAM state (zero elsewhere), padded to a power-of-two length `1 << num_vars`. Since
+/// `v_init` is at least that long, the program image's contribution to `v_init(r)` is
+/// `prod_i (1 - r_i) * image(r')`, where `r'` comprises the last `num_vars` variables of
+/// `r` and `i` ranges over the others.
+#[derive(Clone, Copy, Debug, CanonicalSerialize, CanonicalDeserialize)]
+struct CommittedProgramImage {
+    offset: usize,
+    num_vars: usize,
 }
 
 impl ReadWriteMemoryPreprocessing {
@@ -81,8 +101,104 @@ impl ReadWriteMemoryPreprocessing {
             min_bytecode_address,
             bytecode_words,
             program_io: None,
+            committed_image: None,
         }
     }
+
+    /// The length of the program image polynomial committed to by [`Self::commit`].
+    pub fn program_image_len(&self, memory_layout: &MemoryLayout) -> usize {
+        let offset = memory_address_to_witness_index(self.min_bytecode_address, memory_layout);
+        (offset + self.bytecode_words.len()).next_power_of_two()
+    }
+
+    /// The length of the memory polynomials (`v_init`, `v_final`, `t_final`) of an
+    /// execution that only accesses memory cells below `max_memory_address` (a witness
+    /// index, see `MemoryLayout::memory_witness_size`). Besides these cells, they cover
+    /// the program image and the blob region.
+    pub fn memory_len(&self, memory_layout: &MemoryLayout, max_memory_address: usize) -> usize {
+        let blobs_end = if memory_layout.max_blob_size > 0 {
+            memory_address_to_witness_index(memory_layout.blob_end, memory_layout)
+        } else {
+            0
+        };
+        max_memory_address
+            .max(blobs_end)
+            .next_power_of_two()
+            .max(self.program_image_len(memory_layout))
+    }
+
+    fn program_image<F: JoltField>(&self, offset: usize) -> MultilinearPolynomial<F> {
+        let mut image = vec![0u32; (offset + self.bytecode_words.len()).next_power_of_two()];
+        image[offset..offset + self.bytecode_words.len()].copy_from_slice(&self.bytecode_words);
+        MultilinearPolynomial::from(image)
+    }
+
+    /// Commits to the program image, after which its openings are proven by the
+    /// prover rather than computed by the verifier.
+    pub fn commit<F, PCS, ProofTranscript>(
+        &mut self,
+        memory_layout: &MemoryLayout,
+        setup: &PCS::Setup,
+    ) -> PCS::Commitment
+    where
+        F: JoltField,
+        PCS: CommitmentScheme<ProofTranscript, Field = F>,
+        ProofTranscript: Transcript,
+    {
+        let offset = memory_address_to_witness_index(self.min_bytecode_address, memory_layout);
+        let image = self.program_image::<F>(offset);
+        self.committed_image = Some(CommittedProgramImage {
+            offset,
+            num_vars: image.get_num_vars(),
+        });
+        PCS::commit(&image, setup)
+    }
+
+    /// Drops the data that is only needed by the prover. Only valid if the program
+    /// image has been committed to.
+    pub fn strip(&mut self) {
+        assert!(
+            self.committed_image.is_some(),
+            "program image must be committed to before stripping"
+        );
+        self.bytecode_words = vec![];
+    }
+}
+
+/// Evaluates `eq(r, index)`, where `index` is interpreted as a big-endian bitstring.
+fn eq_at_index<F: JoltField>(r: &[F], index: usize) -> F {
+    r.iter()
+        .enumerate()
+        .map(|(i, r_i)| {
+            if (index >> (r.len() - 1 - i)) & 1 == 1 {
+                *r_i
+            } else {
+                F::one() - r_i
+            }
+        })
+        .product()
+}
+
+/// The contribution of the blobs to `v_init(r_init_final)`, given their openings. A blob
+/// of `2^k` words is a subcube of `v_init` at an index aligned to its size, so it
+/// contributes `eq(r_hi, index >> k) * blob(r_lo)`, where `r_lo` comprises the last `k`
+/// variables of `r_init_final` and `r_hi` the others.
+fn blobs_contribution<F: JoltField>(
+    program_io: &JoltDevice,
+    openings: &ReadWriteMemoryOpenings<F>,
+    r_init_final: &[F],
+) -> F {
+    program_io
+        .blobs
+        .iter()
+        .zip(openings.blobs.iter())
+        .map(|(blob, opening)| {
+            let num_vars = (blob.num_words() as usize).log_2();
+            let index = memory_address_to_witness_index(blob.address, &program_io.memory_layout);
+            let (r_hi, _) = r_init_final.split_at(r_init_final.len() - num_vars);
+            eq_at_index(r_hi, index >> num_vars) * opening
+        })
+        .sum()
 }
 
 fn memory_address_to_witness_index(address: u64, memory_layout: &MemoryLayout) -> usize {
@@ -101,6 +217,94 @@ fn remap_address(a: u64, memory_layout: &MemoryLayout) -> u64 {
     }
 }
 
+/// The witness index of the blob directory and its words, if the memory layout has a
+/// blob region (see `common::blob`).
+fn blob_directory_words(program_io: &JoltDevice) -> Option<(usize, Vec<u32>)> {
+    let memory_layout = &program_io.memory_layout;
+    (memory_layout.max_blob_size > 0).then(|| {
+        (
+            memory_address_to_witness_index(memory_layout.blob_directory, memory_layout),
+            blob_directory(&program_io.blobs),
+        )
+    })
+}
+
+/// The values the final memory state must hold in the I/O region (see
+/// `OutputSumcheckProof`): the inputs, outputs, blob directory, panic code and
+/// termination bit of `program_io`, and zero elsewhere.
+fn io_values(program_io: &JoltDevice, memory_size: usize) -> Vec<u32> {
+    let mut v_io: Vec<u32> = vec![0; memory_size];
+    let mut input_index = memory_address_to_witness_index(
+        program_io.memory_layout.input_start,
+        &program_io.memory_layout,
+    );
+    // Convert input bytes into words and populate `v_io`
+    for chunk in program_io.inputs.chunks(4) {
+        let mut word = [0u8; 4];
+        for (i, byte) in chunk.iter().enumerate() {
+            word[i] = *byte;
+        }
+        let word = u32::from_le_bytes(word);
+        v_io[input_index] = word;
+        input_index += 1;
+    }
+    let mut output_index = memory_address_to_witness_index(
+        program_io.memory_layout.output_start,
+        &program_io.memory_layout,
+    );
+    // Convert output bytes into words and populate `v_io`
+    for chunk in program_io.outputs.chunks(4) {
+        let mut word = [0u8; 4];
+        for (i, byte) in chunk.iter().enumerate() {
+            word[i] = *byte;
+        }
+        let word = u32::from_le_bytes(word);
+        v_io[output_index] = word;
+        output_index += 1;
+    }
+    // Copy the blob directory, which the guest may not modify
+    if let Some((directory_index, directory)) = blob_directory_words(program_io) {
+        v_io[directory_index..directory_index + directory.len()].copy_from_slice(&directory);
+    }
+
+    // Copy panic bit
+    v_io[memory_address_to_witness_index(
+        program_io.memory_layout.panic,
+        &program_io.memory_layout,
+    )] = program_io.panic_code as u32;
+    if !program_io.panic {
+        // Set termination bit
+        v_io[memory_address_to_witness_index(
+            program_io.memory_layout.termination,
+            &program_io.memory_layout,
+        )] = 1;
+    }
+
+    v_io
+}
+
+/// Commits to the words of `blob`. The digest of the commitment (see [`blob_digest`])
+/// is what a verifier checks a blob against.
+pub fn commit_blob<F, PCS, ProofTranscript>(blob: &Blob, generators: &PCS::Setup) -> PCS::Commitment
+where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    PCS::commit(&MultilinearPolynomial::from(blob.words()), generators)
+}
+
+/// The SHA3-256 digest of the compressed serialization of a commitment to a blob.
+pub fn blob_digest<PCS, ProofTranscript>(commitment: &PCS::Commitment) -> [u8; 32]
+where
+    PCS: CommitmentScheme<ProofTranscript>,
+    ProofTranscript: Transcript,
+{
+    let mut hasher = Sha3_256::new();
+    commitment.serialize_compressed(&mut hasher).unwrap();
+    hasher.finalize().into()
+}
+
 const RS1: usize = 0;
 const RS2: usize = 1;
 const RD: usize = 2;
@@ -140,6 +344,15 @@ pub struct ReadWriteMemoryStuff<T: CanonicalSerialize + CanonicalDeserialize> {
     /// Initial memory values. RAM is initialized to contain the program bytecode and inputs.
     v_init: VerifierComputedOpening<T>,
     identity: VerifierComputedOpening<T>,
+    /// Only used if the program image is committed to in preprocessing (see
+    /// `ReadWriteMemoryPreprocessing::commit`), in which case the opening is provided by
+    /// the prover and the commitment is taken from the verifier preprocessing.
+    pub(crate) program_image: Option<T>,
+    /// The words of each blob attached to the program (see `common::blob`), which are
+    /// part of the initial RAM state. The verifier does not know them: it checks each
+    /// commitment against the blob's digest in the `JoltDevice`, and the openings are
+    /// provided by the prover.
+    pub(crate) blobs: Vec<T>,
 }
 
 impl<T: CanonicalSerialize + CanonicalDeserialize> StructuredPolynomialData<T>
@@ -272,7 +485,19 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             .max()
             .unwrap();
 
-        let memory_size = max_trace_address.next_power_of_two() as usize;
+        let blobs_end = program_io
+            .blobs
+            .iter()
+            .map(|blob| {
+                memory_address_to_witness_index(blob.address, &program_io.memory_layout) as u64
+                    + blob.num_words()
+            })
+            .max()
+            .unwrap_or(0);
+
+        let memory_size = max_trace_address
+            .next_power_of_two()
+            .max(blobs_end.next_power_of_two()) as usize;
         let mut v_init: Vec<u32> = vec![0; memory_size];
         // Copy bytecode
         let mut v_init_index = memory_address_to_witness_index(
@@ -298,6 +523,21 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             v_init[v_init_index] = word;
             v_init_index += 1;
         }
+        // Copy the blob directory and blobs
+        if let Some((directory_index, directory)) = blob_directory_words(program_io) {
+            v_init[directory_index..directory_index + directory.len()].copy_from_slice(&directory);
+        }
+        let blobs: Vec<MultilinearPolynomial<F>> = program_io
+            .blobs
+            .iter()
+            .map(|blob| {
+                let words = blob.words();
+                let index =
+                    memory_address_to_witness_index(blob.address, &program_io.memory_layout);
+                v_init[index..index + words.len()].copy_from_slice(&words);
+                MultilinearPolynomial::from(words)
+            })
+            .collect();
 
         #[cfg(test)]
         let mut init_tuples: HashSet<(usize, u32, u32)> = HashSet::new();
@@ -491,6 +731,95 @@ impl<F: JoltField> ReadWriteMemoryPolynomials<F> {
             v_init: Some(v_init),
             a_init_final: None,
             identity: None,
+            program_image: None,
+            blobs,
+        }
+    }
+
+    /// Replays the memory operations of `trace` from the initial memory, checking that
+    /// each read in the witness returns the value and timestamp of the last write to its
+    /// address, that each write stores the traced value, and that the final memory state
+    /// is the result of these writes and holds the inputs and outputs of `program_io`.
+    #[tracing::instrument(skip_all, name = "ReadWriteMemoryPolynomials::check_witness")]
+    pub fn check_witness<InstructionSet: JoltInstructionSet>(
+        &self,
+        program_io: &JoltDevice,
+        trace: &[JoltTraceStep<InstructionSet>],
+    ) -> Result<(), WitnessError> {
+        let v_init = self.v_init.as_ref().unwrap();
+        let mut v_state: Vec<i128> = (0..v_init.len())
+            .map(|a| v_init.get_coeff_i128(a))
+            .collect();
+        let mut t_state: Vec<i128> = vec![0; v_init.len()];
+
+        for (i, step) in trace.iter().enumerate() {
+            let timestamp = i as i128;
+            let mismatch = |operand| Err(WitnessError::MemoryMismatch { step: i, operand });
+
+            for (operand, index, v_read, t_read) in [
+                ("rs1", RS1, &self.v_read_rs1, &self.t_read_rs1),
+                ("rs2", RS2, &self.v_read_rs2, &self.t_read_rs2),
+            ] {
+                let (MemoryOp::Read(a) | MemoryOp::Write(a, _)) = step.memory_ops[index];
+                let a = a as usize;
+                if v_read.get_coeff_i128(i) != v_state[a] || t_read.get_coeff_i128(i) != t_state[a]
+                {
+                    return mismatch(operand);
+                }
+                t_state[a] = timestamp;
+            }
+
+            let (MemoryOp::Read(a) | MemoryOp::Write(a, _)) = step.memory_ops[RD];
+            let a = a as usize;
+            if self.v_read_rd.get_coeff_i128(i) != v_state[a]
+                || self.t_read_rd.get_coeff_i128(i) != t_state[a]
+            {
+                return mismatch("rd");
+            }
+            if let MemoryOp::Write(_, v_new) = step.memory_ops[RD] {
+                if self.v_write_rd.get_coeff_i128(i) != v_new as u32 as i128 {
+                    return mismatch("rd");
+                }
+            }
+            v_state[a] = self.v_write_rd.get_coeff_i128(i);
+            t_state[a] = timestamp;
+
+            let (MemoryOp::Read(a) | MemoryOp::Write(a, _)) = step.memory_ops[RAM];
+            let a = remap_address(a, &program_io.memory_layout) as usize;
+            if self.a_ram.get_coeff_i128(i) != a as i128
+                || self.v_read_ram.get_coeff_i128(i) != v_state[a]
+                || self.t_read_ram.get_coeff_i128(i) != t_state[a]
+            {
+                return mismatch("RAM");
+            }
+            let v_write = match step.memory_ops[RAM] {
+                MemoryOp::Read(_) => v_state[a],
+                MemoryOp::Write(_, v_new) => v_new as u32 as i128,
+            };
+            if self.v_write_ram.get_coeff_i128(i) != v_write {
+                return mismatch("RAM");
+            }
+            v_state[a] = v_write;
+            t_state[a] = timestamp;
+        }
+
+        if let Some(a) = (0..v_state.len()).find(|&a| {
+            self.v_final.get_coeff_i128(a) != v_state[a]
+                || self.t_final.get_coeff_i128(a) != t_state[a]
+        }) {
+            return Err(WitnessError::FinalMemoryMismatch(a));
+        }
+
+        let memory_layout = &program_io.memory_layout;
+        let v_io = io_values(program_io, v_state.len());
+        let io_start = memory_address_to_witness_index(memory_layout.input_start, memory_layout);
+        let io_end =
+            memory_address_to_witness_index(RAM_START_ADDRESS, memory_layout).min(v_state.len());
+        match (io_start..io_end).find(|&a| v_state[a] != v_io[a] as i128) {
+            Some(a) => Err(WitnessError::IoMismatch(
+                memory_layout.input_start + 4 * (a - io_start) as u64,
+            )),
+            None => Ok(()),
         }
     }
 }
@@ -676,7 +1005,7 @@ where
     fn check_multiset_equality(
         _preprocessing: &Self::Preprocessing,
         multiset_hashes: &MultisetHashes<F>,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         assert_eq!(
             multiset_hashes.read_hashes.len(),
             MEMORY_OPS_PER_INSTRUCTION
@@ -693,11 +1022,47 @@ where
         let init_hash = multiset_hashes.init_hashes[0];
         let final_hash = multiset_hashes.final_hashes[0];
 
-        assert_eq!(
-            init_hash * write_hash,
-            final_hash * read_hash,
-            "Multiset hashes don't match"
-        );
+        if init_hash * write_hash != final_hash * read_hash {
+            return Err(ProofVerifyError::MultisetHashMismatch);
+        }
+        Ok(())
+    }
+
+    fn compute_preprocessing_openings(
+        preprocessing: &ReadWriteMemoryPreprocessing,
+        polynomials: &ReadWriteMemoryPolynomials<F>,
+        openings: &mut ReadWriteMemoryOpenings<F>,
+        opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        r_init_final: &[F],
+        transcript: &mut ProofTranscript,
+    ) {
+        if let Some(image) = preprocessing.committed_image {
+            let image_poly = preprocessing.program_image::<F>(image.offset);
+            let r_image = &r_init_final[r_init_final.len() - image.num_vars..];
+            let (evals, eq) = MultilinearPolynomial::batch_evaluate(&[&image_poly], r_image);
+            opening_accumulator.append(
+                &[&image_poly],
+                DensePolynomial::new(eq),
+                r_image.to_vec(),
+                &evals,
+                transcript,
+            );
+            openings.program_image = Some(evals[0]);
+        }
+
+        // Each blob is a subcube of `v_init`, so is opened at a suffix of `r_init_final`
+        for blob in polynomials.blobs.iter() {
+            let r_blob = &r_init_final[r_init_final.len() - blob.get_num_vars()..];
+            let (evals, eq) = MultilinearPolynomial::batch_evaluate(&[blob], r_blob);
+            opening_accumulator.append(
+                &[blob],
+                DensePolynomial::new(eq),
+                r_blob.to_vec(),
+                &evals,
+                transcript,
+            );
+            openings.blobs.push(evals[0]);
+        }
     }
 
     fn protocol_name() -> &'static [u8] {
@@ -712,6 +1077,62 @@ where
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
     ProofTranscript: Transcript,
 {
+    fn verify_preprocessing_openings(
+        preprocessing: &ReadWriteMemoryPreprocessing,
+        commitments: &ReadWriteMemoryCommitments<PCS, ProofTranscript>,
+        openings: &ReadWriteMemoryOpenings<F>,
+        opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        r_init_final: &[F],
+        transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError> {
+        if let Some(image) = preprocessing.committed_image {
+            let (Some(image_commitment), Some(image_opening)) =
+                (&commitments.program_image, &openings.program_image)
+            else {
+                return Err(ProofVerifyError::MissingPreprocessingOpening);
+            };
+            if image.num_vars > r_init_final.len() {
+                return Err(ProofVerifyError::InternalError);
+            }
+            opening_accumulator.append(
+                &[image_commitment],
+                r_init_final[r_init_final.len() - image.num_vars..].to_vec(),
+                &[image_opening],
+                transcript,
+            );
+        }
+
+        let program_io = preprocessing.program_io.as_ref().unwrap();
+        if commitments.blobs.len() != program_io.blobs.len()
+            || openings.blobs.len() != program_io.blobs.len()
+        {
+            return Err(ProofVerifyError::MissingPreprocessingOpening);
+        }
+        for ((blob, commitment), opening) in program_io
+            .blobs
+            .iter()
+            .zip(commitments.blobs.iter())
+            .zip(openings.blobs.iter())
+        {
+            if blob_digest::<PCS, ProofTranscript>(commitment) != blob.digest {
+                return Err(ProofVerifyError::BlobDigestMismatch(blob.name.clone()));
+            }
+            // The blob must lie within the memory, i.e. the domain of `v_init`
+            let index = memory_address_to_witness_index(blob.address, &program_io.memory_layout);
+            if index as u64 + blob.num_words() > 1 << r_init_final.len() {
+                return Err(ProofVerifyError::InternalError);
+            }
+            let num_vars = (blob.num_words() as usize).log_2();
+            opening_accumulator.append(
+                &[commitment],
+                r_init_final[r_init_final.len() - num_vars..].to_vec(),
+                &[opening],
+                transcript,
+            );
+        }
+        Ok(())
+    }
+
     fn compute_verifier_openings(
         openings: &mut Self::Openings,
         preprocessing: &Self::Preprocessing,
@@ -724,7 +1145,35 @@ where
         openings.a_init_final =
             Some(IdentityPolynomial::new(r_init_final.len()).evaluate(r_init_final));
 
-        let memory_layout = &preprocessing.program_io.as_ref().unwrap().memory_layout;
+        let program_io = preprocessing.program_io.as_ref().unwrap();
+        let memory_layout = &program_io.memory_layout;
+
+        if let Some(image) = preprocessing.committed_image {
+            // The opening of the program image was checked in `verify_preprocessing_openings`
+            let (r_padding, _) = r_init_final.split_at(r_init_final.len() - image.num_vars);
+            let mut v_init: F = r_padding.iter().map(|r_i| F::one() - r_i).product::<F>()
+                * openings.program_image.unwrap();
+            // Inputs are sparse, so add their contribution term by term
+            let mut input_index =
+                memory_address_to_witness_index(memory_layout.input_start, memory_layout);
+            for chunk in program_io.inputs.chunks(4) {
+                let mut word = [0u8; 4];
+                for (i, byte) in chunk.iter().enumerate() {
+                    word[i] = *byte;
+                }
+                let word = u32::from_le_bytes(word);
+                v_init += F::from_u32(word) * eq_at_index(r_init_final, input_index);
+                input_index += 1;
+            }
+            // So is the blob directory
+            if let Some((directory_index, directory)) = blob_directory_words(program_io) {
+                for (i, word) in directory.into_iter().enumerate() {
+                    v_init += F::from_u32(word) * eq_at_index(r_init_final, directory_index + i);
+                }
+            }
+            openings.v_init = Some(v_init + blobs_contribution(program_io, openings, r_init_final));
+            return;
+        }
 
         // TODO(moodlezoup): Compute opening without instantiating v_init polynomial itself
         let memory_size = r_init_final.len().pow2();
@@ -738,7 +1187,7 @@ where
         }
         v_init_index = memory_address_to_witness_index(memory_layout.input_start, memory_layout);
         // Convert input bytes into words and populate `v_init`
-        for chunk in preprocessing.program_io.as_ref().unwrap().inputs.chunks(4) {
+        for chunk in program_io.inputs.chunks(4) {
             let mut word = [0u8; 4];
             for (i, byte) in chunk.iter().enumerate() {
                 word[i] = *byte;
@@ -747,8 +1196,16 @@ where
             v_init[v_init_index] = word as u64;
             v_init_index += 1;
         }
+        if let Some((directory_index, directory)) = blob_directory_words(program_io) {
+            for (i, word) in directory.into_iter().enumerate() {
+                v_init[directory_index + i] = word as u64;
+            }
+        }
 
-        openings.v_init = Some(DensePolynomial::from_u64(&v_init).evaluate(r_init_final));
+        openings.v_init = Some(
+            DensePolynomial::from_u64(&v_init).evaluate(r_init_final)
+                + blobs_contribution(program_io, openings, r_init_final),
+        );
     }
 
     fn read_tuples(
@@ -877,48 +1334,7 @@ where
             })
             .collect();
 
-        let mut v_io: Vec<u32> = vec![0; memory_size];
-        let mut input_index = memory_address_to_witness_index(
-            program_io.memory_layout.input_start,
-            &program_io.memory_layout,
-        );
-        // Convert input bytes into words and populate `v_io`
-        for chunk in program_io.inputs.chunks(4) {
-            let mut word = [0u8; 4];
-            for (i, byte) in chunk.iter().enumerate() {
-                word[i] = *byte;
-            }
-            let word = u32::from_le_bytes(word);
-            v_io[input_index] = word;
-            input_index += 1;
-        }
-        let mut output_index = memory_address_to_witness_index(
-            program_io.memory_layout.output_start,
-            &program_io.memory_layout,
-        );
-        // Convert output bytes into words and populate `v_io`
-        for chunk in program_io.outputs.chunks(4) {
-            let mut word = [0u8; 4];
-            for (i, byte) in chunk.iter().enumerate() {
-                word[i] = *byte;
-            }
-            let word = u32::from_le_bytes(word);
-            v_io[output_index] = word;
-            output_index += 1;
-        }
-
-        // Copy panic bit
-        v_io[memory_address_to_witness_index(
-            program_io.memory_layout.panic,
-            &program_io.memory_layout,
-        )] = program_io.panic as u32;
-        if !program_io.panic {
-            // Set termination bit
-            v_io[memory_address_to_witness_index(
-                program_io.memory_layout.termination,
-                &program_io.memory_layout,
-            )] = 1;
-        }
+        let v_io = io_values(program_io, memory_size);
 
         let mut sumcheck_polys = vec![
             eq,
@@ -1030,9 +1446,15 @@ where
             v_io[output_index] = word as u64;
             output_index += 1;
         }
+        // Copy the blob directory, which the guest may not modify
+        if let Some((directory_index, directory)) = blob_directory_words(program_io) {
+            for (i, word) in directory.into_iter().enumerate() {
+                v_io[directory_index + i] = word as u64;
+            }
+        }
         // Copy panic bit
         v_io[memory_address_to_witness_index(memory_layout.panic, memory_layout)] =
-            program_io.panic as u64;
+            program_io.panic_code as u64;
         if !program_io.panic {
             // Set termination bit
             v_io[memory_address_to_witness_index(memory_layout.termination, memory_layout)] = 1;
@@ -1042,11 +1464,9 @@ where
             .evaluate(&r_sumcheck[(proof.num_rounds - log_io_memory_size)..]);
         v_io_eval *= r_prod;
 
-        assert_eq!(
-            eq_eval * io_witness_range_eval * (proof.opening - v_io_eval),
-            sumcheck_claim,
-            "Output sumcheck check failed."
-        );
+        if eq_eval * io_witness_range_eval * (proof.opening - v_io_eval) != sumcheck_claim {
+            return Err(ProofVerifyError::IoMismatch);
+        }
 
         opening_accumulator.append(
             &[&commitment.v_final],
@@ -1139,14 +1559,19 @@ where
             commitments,
             opening_accumulator,
             transcript,
-        )?;
+        )
+        .map_err(|e| ProofVerifyError::ReadWriteMemoryError(Box::new(e)))?;
         OutputSumcheckProof::verify(
             &self.output_proof,
             preprocessing,
             &commitments.read_write_memory,
             opening_accumulator,
             transcript,
-        )?;
+        )
+        .map_err(|e| match e {
+            ProofVerifyError::IoMismatch => e,
+            e => ProofVerifyError::ReadWriteMemoryError(Box::new(e)),
+        })?;
         TimestampValidityProof::verify(
             &mut self.timestamp_validity_proof,
             generators,
@@ -1154,6 +1579,7 @@ where
             opening_accumulator,
             transcript,
         )
+        .map_err(|e| ProofVerifyError::TimestampRangeCheckError(Box::new(e)))
     }
 }
 
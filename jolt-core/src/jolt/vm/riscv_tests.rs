@@ -0,0 +1,94 @@
+//! Runs the rv32ui and rv32um [riscv-tests](https://github.com/riscv-software-src/riscv-tests)
+//! through the tracer and `RV32IJoltVM`. The test ELFs are built by `jolt-core/riscv-tests`.
+
+use ark_bn254::{Bn254, Fr};
+use std::path::PathBuf;
+
+use crate::host;
+use crate::jolt::vm::rv32i_vm::{RV32IJoltVM, C, M};
+use crate::jolt::vm::Jolt;
+use crate::poly::commitment::hyperkzg::HyperKZG;
+use crate::utils::transcript::KeccakTranscript;
+use common::constants::{DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE, DEFAULT_MEMORY_SIZE};
+use common::rv_trace::MemoryLayout;
+
+/// Traces the riscv-test `name`, checks that it passed, then proves and verifies it.
+fn riscv_test(name: &str) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "riscv-tests", "elf", name]
+        .iter()
+        .collect();
+    let memory_layout = MemoryLayout::new(DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE);
+    // The tests use sp as a general purpose register, so there is no stack whose
+    // overflow the tracer could detect.
+    let mut program = host::Program::from_elf_path(&path, &memory_layout, DEFAULT_MEMORY_SIZE, 0)
+        .unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+    let (bytecode, memory_init) = program.decode();
+    let (io_device, trace, end_code) = program
+        .trace_test()
+        .unwrap_or_else(|e| panic!("{name}: {e}"));
+    match end_code {
+        1 => {}
+        0 => panic!("{name}: did not write tohost"),
+        _ => panic!("{name}: test case {} failed", end_code >> 1),
+    }
+
+    let preprocessing = RV32IJoltVM::prover_preprocess(
+        bytecode,
+        io_device.memory_layout.clone(),
+        memory_init,
+        1 << 20,
+        1 << 20,
+        1 << 20,
+    );
+    let (proof, commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
+        Fr,
+        HyperKZG<Bn254, KeccakTranscript>,
+        C,
+        M,
+        KeccakTranscript,
+    >>::prove(
+        io_device,
+        trace,
+        preprocessing.clone(),
+    );
+    let verification_result = RV32IJoltVM::verify(
+        preprocessing.shared,
+        proof,
+        commitments,
+        verifier_io_device,
+        debug_info,
+    );
+    assert!(
+        verification_result.is_ok(),
+        "{name}: verification failed with error: {:?}",
+        verification_result.err()
+    );
+}
+
+macro_rules! riscv_tests {
+    ($suite:literal: $($test:ident),+ $(,)?) => {
+        $(
+            #[test]
+            #[ignore = "requires the riscv-tests ELFs, see jolt-core/riscv-tests/README.md"]
+            fn $test() {
+                riscv_test(concat!($suite, "-jolt-", stringify!($test)));
+            }
+        )+
+    };
+}
+
+mod rv32ui {
+    use super::riscv_test;
+
+    riscv_tests!("rv32ui":
+        simple, add, addi, and, andi, auipc, beq, bge, bgeu, blt, bltu, bne, jal, jalr, lb,
+        lbu, lh, lhu, lui, lw, or, ori, sb, sh, sll, slli, slt, slti, sltiu, sltu, sra, srai,
+        srl, srli, sub, sw, xor, xori,
+    );
+}
+
+mod rv32um {
+    use super::riscv_test;
+
+    riscv_tests!("rv32um": div, divu, mul, mulh, mulhsu, mulhu, rem, remu);
+}
@@ -1,182 +1,504 @@
 use crate::field::JoltField;
-use crate::jolt::instruction::virtual_assert_halfword_alignment::AssertHalfwordAlignmentInstruction;
-use crate::jolt::instruction::virtual_assert_valid_div0::AssertValidDiv0Instruction;
-use crate::jolt::instruction::virtual_assert_valid_unsigned_remainder::AssertValidUnsignedRemainderInstruction;
-use crate::jolt::instruction::virtual_move::MOVEInstruction;
-use crate::jolt::instruction::virtual_pow2::POW2Instruction;
-use crate::jolt::instruction::virtual_right_shift_padding::RightShiftPaddingInstruction;
-use crate::jolt::subtable::div_by_zero::DivByZeroSubtable;
-use crate::jolt::subtable::low_bit::LowBitSubtable;
-use crate::jolt::subtable::right_is_zero::RightIsZeroSubtable;
+use crate::jolt::instruction::narrow_shift;
 use crate::poly::commitment::hyperkzg::HyperKZG;
 use crate::r1cs::constraints::JoltRV32IMConstraints;
 use crate::r1cs::inputs::JoltR1CSInputs;
+use crate::utils::math::Math;
 use ark_bn254::{Bn254, Fr};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
-use enum_dispatch::enum_dispatch;
-use paste::paste;
-use rand::{prelude::StdRng, RngCore};
-use serde::{Deserialize, Serialize};
-use std::any::TypeId;
-use strum::{EnumCount, IntoEnumIterator};
-use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
-
-use super::{Jolt, JoltCommitments, JoltProof};
-use crate::jolt::instruction::{
-    add::ADDInstruction, and::ANDInstruction, beq::BEQInstruction, bge::BGEInstruction,
-    bgeu::BGEUInstruction, bne::BNEInstruction, mul::MULInstruction, mulhu::MULHUInstruction,
-    mulu::MULUInstruction, or::ORInstruction, sll::SLLInstruction, slt::SLTInstruction,
-    sltu::SLTUInstruction, sra::SRAInstruction, srl::SRLInstruction, sub::SUBInstruction,
-    virtual_advice::ADVICEInstruction, virtual_assert_lte::ASSERTLTEInstruction,
-    virtual_assert_valid_signed_remainder::AssertValidSignedRemainderInstruction,
-    virtual_movsign::MOVSIGNInstruction, xor::XORInstruction, JoltInstruction, JoltInstructionSet,
-    SubtableIndices,
-};
-use crate::jolt::subtable::{
-    and::AndSubtable, eq::EqSubtable, eq_abs::EqAbsSubtable, identity::IdentitySubtable,
-    left_is_zero::LeftIsZeroSubtable, left_msb::LeftMSBSubtable, lt_abs::LtAbsSubtable,
-    ltu::LtuSubtable, or::OrSubtable, right_msb::RightMSBSubtable, sign_extend::SignExtendSubtable,
-    sll::SllSubtable, sra_sign::SraSignSubtable, srl::SrlSubtable, xor::XorSubtable,
-    JoltSubtableSet, LassoSubtable, SubtableId,
-};
+use common::blob::Blob;
+use serde::Deserialize;
+
+use super::container::{decode_container_payload, encode_container, ContainerKind};
+use super::{DevProof, Jolt, JoltCommitments, JoltProof, JoltVerifierPreprocessing};
 use crate::poly::commitment::commitment_scheme::CommitmentScheme;
 
 /// Generates an enum out of a list of JoltInstruction types. All JoltInstruction methods
-/// are callable on the enum type via enum_dispatch.
+/// are callable on the enum type, forwarding to the wrapped instruction. Attributes
+/// (e.g. additional derives) are applied to the generated enum.
+///
+/// Exported so that other crates can define their own instruction sets; see
+/// [`rv32i_instruction_set!`](crate::rv32i_instruction_set) to extend RV32I.
+#[macro_export]
 macro_rules! instruction_set {
-    ($enum_name:ident, $($alias:ident: $struct:ty),+) => {
-        paste! {
-            #[allow(non_camel_case_types)]
-            #[repr(u8)]
-            #[derive(Copy, Clone, Debug, PartialEq, EnumIter, EnumCountMacro, Serialize, Deserialize)]
-            #[enum_dispatch(JoltInstruction)]
-            pub enum $enum_name {
-                $([<$alias>]($struct)),+
-            }
+    (
+        @define [$($attr:tt)*] $enum_name:ident,
+        { $($set_items:tt)* },
+        $($alias:ident: $struct:ty),+
+    ) => {
+        $($attr)*
+        #[allow(non_camel_case_types)]
+        #[allow(clippy::upper_case_acronyms)]
+        #[repr(u8)]
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        pub enum $enum_name {
+            $($alias($struct)),+
         }
-        impl JoltInstructionSet for $enum_name {}
-        impl $enum_name {
-            pub fn random_instruction(rng: &mut StdRng) -> Self {
-                let index = rng.next_u64() as usize % $enum_name::COUNT;
-                let instruction = $enum_name::iter()
-                    .enumerate()
-                    .filter(|(i, _)| *i == index)
-                    .map(|(_, x)| x)
-                    .next()
-                    .unwrap();
-                instruction.random(rng)
+
+        const _: () = {
+            use $crate::__private::serde::{Serialize, Serializer};
+            use $crate::__private::strum::{EnumCount, IntoEnumIterator};
+            use $crate::__private::{RngCore, StdRng};
+            use $crate::field::JoltField;
+            use $crate::jolt::instruction::{JoltInstruction, JoltInstructionSet, SubtableIndices};
+            use $crate::jolt::subtable::LassoSubtable;
+
+            $(
+                impl From<$struct> for $enum_name {
+                    fn from(instruction: $struct) -> Self {
+                        $enum_name::$alias(instruction)
+                    }
+                }
+            )+
+
+            impl JoltInstruction for $enum_name {
+                fn to_lookup_index(&self) -> u64 {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.to_lookup_index()),+
+                    }
+                }
+
+                fn materialize_entry(&self, index: u64) -> u64 {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.materialize_entry(index)),+
+                    }
+                }
+
+                fn evaluate_mle<F: JoltField>(&self, r: &[F]) -> F {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.evaluate_mle(r)),+
+                    }
+                }
+
+                fn operands(&self) -> (u64, u64) {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.operands()),+
+                    }
+                }
+
+                fn combine_lookups<F: JoltField>(&self, vals: &[F], c: usize, m: usize) -> F {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.combine_lookups(vals, c, m)),+
+                    }
+                }
+
+                fn g_poly_degree(&self, c: usize) -> usize {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.g_poly_degree(c)),+
+                    }
+                }
+
+                fn subtables<F: JoltField>(
+                    &self,
+                    c: usize,
+                    m: usize,
+                ) -> Vec<(Box<dyn LassoSubtable<F>>, SubtableIndices)> {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.subtables(c, m)),+
+                    }
+                }
+
+                fn to_indices(&self, c: usize, log_m: usize) -> Vec<usize> {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.to_indices(c, log_m)),+
+                    }
+                }
+
+                fn lookup_entry(&self) -> u64 {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.lookup_entry()),+
+                    }
+                }
+
+                fn operand_chunks(&self, c: usize, log_m: usize) -> (Vec<u8>, Vec<u8>) {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.operand_chunks(c, log_m)),+
+                    }
+                }
+
+                fn random(&self, rng: &mut StdRng) -> Self {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.random(rng).into()),+
+                    }
+                }
+
+                fn slice_values<'a, F: JoltField>(
+                    &self,
+                    vals: &'a [F],
+                    c: usize,
+                    m: usize,
+                ) -> Vec<&'a [F]> {
+                    match self {
+                        $($enum_name::$alias(instruction) => instruction.slice_values(vals, c, m)),+
+                    }
+                }
             }
-        }
-        // Need a default so that we can derive EnumIter on `JoltR1CSInputs`
-        impl Default for $enum_name {
-            fn default() -> Self {
-                $enum_name::iter().collect::<Vec<_>>()[0]
+
+            impl JoltInstructionSet for $enum_name {
+                $($set_items)*
             }
-        }
+
+            impl EnumCount for $enum_name {
+                const COUNT: usize = [$(stringify!($alias)),+].len();
+            }
+
+            impl IntoEnumIterator for $enum_name {
+                type Iterator = std::vec::IntoIter<Self>;
+
+                fn iter() -> Self::Iterator {
+                    vec![$($enum_name::$alias(Default::default())),+].into_iter()
+                }
+            }
+
+            // Serialized like `#[derive(Serialize)]` would, without requiring the
+            // invoking crate to depend on serde.
+            impl Serialize for $enum_name {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    match self {
+                        $($enum_name::$alias(instruction) => serializer.serialize_newtype_variant(
+                            stringify!($enum_name),
+                            $enum_name::enum_index(self) as u32,
+                            stringify!($alias),
+                            instruction,
+                        )),+
+                    }
+                }
+            }
+
+            impl $enum_name {
+                pub fn random_instruction(rng: &mut StdRng) -> Self {
+                    let index = rng.next_u64() as usize % $enum_name::COUNT;
+                    let instruction = $enum_name::iter()
+                        .enumerate()
+                        .filter(|(i, _)| *i == index)
+                        .map(|(_, x)| x)
+                        .next()
+                        .unwrap();
+                    instruction.random(rng)
+                }
+            }
+
+            // Need a default so that we can derive EnumIter on `JoltR1CSInputs`
+            impl Default for $enum_name {
+                fn default() -> Self {
+                    $enum_name::iter().next().unwrap()
+                }
+            }
+        };
+    };
+    ($(#[$attr:meta])* $enum_name:ident, $($alias:ident: $struct:ty),+ $(,)?) => {
+        $crate::instruction_set!(@define [$(#[$attr])*] $enum_name, {}, $($alias: $struct),+);
     };
 }
 
 /// Generates an enum out of a list of LassoSubtable types. All LassoSubtable methods
-/// are callable on the enum type via enum_dispatch.
+/// are callable on the enum type, forwarding to the wrapped subtable. Each subtable
+/// type must have a `new()` constructor.
+///
+/// Exported so that other crates can define their own subtable sets; see
+/// [`rv32i_subtable_enum!`](crate::rv32i_subtable_enum) to extend the RV32I subtables.
+#[macro_export]
 macro_rules! subtable_enum {
-    ($enum_name:ident, $($alias:ident: $struct:ty),+) => {
-        paste! {
-            #[allow(non_camel_case_types)]
-            #[repr(u8)]
-            #[enum_dispatch(LassoSubtable<F>)]
-            #[derive(EnumCountMacro, EnumIter)]
-            pub enum $enum_name<F: JoltField> { $([<$alias>]($struct)),+ }
-        }
-        impl<F: JoltField> From<SubtableId> for $enum_name<F> {
-          fn from(subtable_id: SubtableId) -> Self {
+    ($enum_name:ident, $($alias:ident: $struct:ty),+ $(,)?) => {
+        #[allow(non_camel_case_types)]
+        #[allow(clippy::upper_case_acronyms)]
+        #[repr(u8)]
+        pub enum $enum_name<F: $crate::field::JoltField> { $($alias($struct)),+ }
+
+        const _: () = {
+            use std::any::TypeId;
+            use $crate::__private::strum::{EnumCount, IntoEnumIterator};
+            use $crate::field::JoltField;
+            use $crate::jolt::subtable::{JoltSubtableSet, LassoSubtable, SubtableId};
+
             $(
-              if subtable_id == TypeId::of::<$struct>() {
-                $enum_name::from(<$struct>::new())
-              } else
+                impl<F: JoltField> From<$struct> for $enum_name<F> {
+                    fn from(subtable: $struct) -> Self {
+                        $enum_name::$alias(subtable)
+                    }
+                }
             )+
-            { panic!("Unexpected subtable id {:?}", subtable_id) } // TODO(moodlezoup): better error handling
-          }
-        }
 
-        impl<F: JoltField> From<$enum_name<F>> for usize {
-            fn from(subtable: $enum_name<F>) -> usize {
-                // Discriminant: https://doc.rust-lang.org/reference/items/enumerations.html#pointer-casting
-                let byte = unsafe { *(&subtable as *const $enum_name<F> as *const u8) };
-                byte as usize
+            impl<F: JoltField> LassoSubtable<F> for $enum_name<F> {
+                fn subtable_id(&self) -> SubtableId {
+                    match self {
+                        $($enum_name::$alias(subtable) => subtable.subtable_id()),+
+                    }
+                }
+
+                fn materialize(&self, m: usize) -> Vec<u32> {
+                    match self {
+                        $($enum_name::$alias(subtable) => subtable.materialize(m)),+
+                    }
+                }
+
+                fn evaluate_mle(&self, point: &[F]) -> F {
+                    match self {
+                        $($enum_name::$alias(subtable) => subtable.evaluate_mle(point)),+
+                    }
+                }
             }
-        }
-        impl<F: JoltField> JoltSubtableSet<F> for $enum_name<F> {}
+
+            impl<F: JoltField> From<SubtableId> for $enum_name<F> {
+              fn from(subtable_id: SubtableId) -> Self {
+                $(
+                  if subtable_id == TypeId::of::<$struct>() {
+                    $enum_name::from(<$struct>::new())
+                  } else
+                )+
+                { panic!("Unexpected subtable id {:?}", subtable_id) } // TODO(moodlezoup): better error handling
+              }
+            }
+
+            impl<F: JoltField> From<$enum_name<F>> for usize {
+                fn from(subtable: $enum_name<F>) -> usize {
+                    // Discriminant: https://doc.rust-lang.org/reference/items/enumerations.html#pointer-casting
+                    let byte = unsafe { *(&subtable as *const $enum_name<F> as *const u8) };
+                    byte as usize
+                }
+            }
+
+            impl<F: JoltField> EnumCount for $enum_name<F> {
+                const COUNT: usize = [$(stringify!($alias)),+].len();
+            }
+
+            impl<F: JoltField> IntoEnumIterator for $enum_name<F> {
+                // Subtables need not be `Clone`, so construct each variant on demand
+                type Iterator = std::iter::Map<std::ops::Range<usize>, fn(usize) -> Self>;
+
+                fn iter() -> Self::Iterator {
+                    let variant: fn(usize) -> Self = |index| {
+                        match [$(stringify!($alias)),+][index] {
+                            $(stringify!($alias) => $enum_name::$alias(<$struct>::new()),)+
+                            _ => unreachable!(),
+                        }
+                    };
+                    (0..Self::COUNT).map(variant)
+                }
+            }
+
+            impl<F: JoltField> JoltSubtableSet<F> for $enum_name<F> {}
+        };
     };
 }
 
-const WORD_SIZE: usize = 32;
-
-instruction_set!(
-  RV32I,
-  ADD: ADDInstruction<WORD_SIZE>,
-  SUB: SUBInstruction<WORD_SIZE>,
-  AND: ANDInstruction<WORD_SIZE>,
-  OR: ORInstruction<WORD_SIZE>,
-  XOR: XORInstruction<WORD_SIZE>,
-  BEQ: BEQInstruction<WORD_SIZE>,
-  BGE: BGEInstruction<WORD_SIZE>,
-  BGEU: BGEUInstruction<WORD_SIZE>,
-  BNE: BNEInstruction<WORD_SIZE>,
-  SLT: SLTInstruction<WORD_SIZE>,
-  SLTU: SLTUInstruction<WORD_SIZE>,
-  SLL: SLLInstruction<WORD_SIZE>,
-  SRA: SRAInstruction<WORD_SIZE>,
-  SRL: SRLInstruction<WORD_SIZE>,
-  MOVSIGN: MOVSIGNInstruction<WORD_SIZE>,
-  MUL: MULInstruction<WORD_SIZE>,
-  MULU: MULUInstruction<WORD_SIZE>,
-  MULHU: MULHUInstruction<WORD_SIZE>,
-  VIRTUAL_ADVICE: ADVICEInstruction<WORD_SIZE>,
-  VIRTUAL_MOVE: MOVEInstruction<WORD_SIZE>,
-  VIRTUAL_ASSERT_LTE: ASSERTLTEInstruction<WORD_SIZE>,
-  VIRTUAL_ASSERT_VALID_SIGNED_REMAINDER: AssertValidSignedRemainderInstruction<WORD_SIZE>,
-  VIRTUAL_ASSERT_VALID_UNSIGNED_REMAINDER: AssertValidUnsignedRemainderInstruction<WORD_SIZE>,
-  VIRTUAL_ASSERT_VALID_DIV0: AssertValidDiv0Instruction<WORD_SIZE>,
-  VIRTUAL_ASSERT_HALFWORD_ALIGNMENT: AssertHalfwordAlignmentInstruction<WORD_SIZE>,
-  VIRTUAL_POW2: POW2Instruction<WORD_SIZE>,
-  VIRTUAL_SRA_PADDING: RightShiftPaddingInstruction<WORD_SIZE>
-);
-subtable_enum!(
-  RV32ISubtables,
-  AND: AndSubtable<F>,
-  EQ_ABS: EqAbsSubtable<F>,
-  EQ: EqSubtable<F>,
-  LEFT_MSB: LeftMSBSubtable<F>,
-  RIGHT_MSB: RightMSBSubtable<F>,
-  IDENTITY: IdentitySubtable<F>,
-  LT_ABS: LtAbsSubtable<F>,
-  LTU: LtuSubtable<F>,
-  OR: OrSubtable<F>,
-  SIGN_EXTEND_16: SignExtendSubtable<F, 16>,
-  SLL0: SllSubtable<F, 0, WORD_SIZE>,
-  SLL1: SllSubtable<F, 1, WORD_SIZE>,
-  SLL2: SllSubtable<F, 2, WORD_SIZE>,
-  SLL3: SllSubtable<F, 3, WORD_SIZE>,
-  SRA_SIGN: SraSignSubtable<F, WORD_SIZE>,
-  SRL0: SrlSubtable<F, 0, WORD_SIZE>,
-  SRL1: SrlSubtable<F, 1, WORD_SIZE>,
-  SRL2: SrlSubtable<F, 2, WORD_SIZE>,
-  SRL3: SrlSubtable<F, 3, WORD_SIZE>,
-  XOR: XorSubtable<F>,
-  LEFT_IS_ZERO: LeftIsZeroSubtable<F>,
-  RIGHT_IS_ZERO: RightIsZeroSubtable<F>,
-  DIV_BY_ZERO: DivByZeroSubtable<F>,
-  LSB: LowBitSubtable<F>
+/// Generates an instruction set containing all of the [`RV32I`] instructions, in the
+/// same order (so their instruction flags keep the same indices), followed by the
+/// given custom instructions, which must implement [`CustomInstruction`].
+///
+/// For an extended instruction set, this also generates `From<RV32I>` and decodes the
+/// custom instructions from `ELFInstruction`s/`RVTraceRow`s by their opcode and
+/// `funct3`/`funct7`. Use it with [`ExtendedJoltVM`].
+///
+/// [`CustomInstruction`]: crate::jolt::vm::extension::CustomInstruction
+/// [`ExtendedJoltVM`]: crate::jolt::vm::extension::ExtendedJoltVM
+#[macro_export]
+macro_rules! rv32i_instruction_set {
+    (@define [$($attr:tt)*] $enum_name:ident, [], $($alias:ident: $struct:ty),+) => {
+        $crate::instruction_set!(@define [$($attr)*] $enum_name, {}, $($alias: $struct),+);
+    };
+    (
+        @define [$($attr:tt)*] $enum_name:ident,
+        [$($custom_alias:ident: $custom_struct:ty),+],
+        $($alias:ident: $struct:ty),+
+    ) => {
+        $crate::instruction_set!(
+            @define [$($attr)*] $enum_name,
+            {
+                fn register_custom_instructions() {
+                    $(
+                        $crate::jolt::vm::extension::register_custom_instruction::<$custom_struct>();
+                    )+
+                }
+            },
+            $($alias: $struct,)+
+            $($custom_alias: $custom_struct),+
+        );
+
+        const _: () = {
+            use $crate::jolt::vm::extension::{CustomInstruction, ELFInstruction, RVTraceRow};
+            use $crate::jolt::vm::rv32i_vm::RV32I;
+
+            impl From<RV32I> for $enum_name {
+                fn from(instruction: RV32I) -> Self {
+                    match instruction {
+                        $(RV32I::$alias(instruction) => $enum_name::$alias(instruction)),+
+                    }
+                }
+            }
+
+            impl TryFrom<&ELFInstruction> for $enum_name {
+                type Error = &'static str;
+
+                fn try_from(instruction: &ELFInstruction) -> Result<Self, Self::Error> {
+                    $(
+                        if <$custom_struct as CustomInstruction>::is_encoded_by(instruction) {
+                            return Ok($enum_name::$custom_alias(Default::default()));
+                        }
+                    )+
+                    RV32I::try_from(instruction).map(Self::from)
+                }
+            }
+
+            impl TryFrom<&RVTraceRow> for $enum_name {
+                type Error = &'static str;
+
+                fn try_from(row: &RVTraceRow) -> Result<Self, Self::Error> {
+                    $(
+                        if <$custom_struct as CustomInstruction>::is_encoded_by(&row.instruction) {
+                            return Ok($enum_name::$custom_alias(
+                                <$custom_struct as CustomInstruction>::from_operands(
+                                    row.register_state.rs1_val.unwrap(),
+                                    row.register_state.rs2_val.unwrap(),
+                                ),
+                            ));
+                        }
+                    )+
+                    RV32I::try_from(row).map(Self::from)
+                }
+            }
+        };
+    };
+    ($(#[$attr:meta])* $enum_name:ident $(, $custom_alias:ident: $custom_struct:ty)* $(,)?) => {
+        $crate::rv32i_instruction_set!(
+            @define [$(#[$attr])*] $enum_name,
+            [$($custom_alias: $custom_struct),*],
+            ADD: $crate::jolt::instruction::add::ADDInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SUB: $crate::jolt::instruction::sub::SUBInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            AND: $crate::jolt::instruction::and::ANDInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            OR: $crate::jolt::instruction::or::ORInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            XOR: $crate::jolt::instruction::xor::XORInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            BEQ: $crate::jolt::instruction::beq::BEQInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            BGE: $crate::jolt::instruction::bge::BGEInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            BGEU: $crate::jolt::instruction::bgeu::BGEUInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            BNE: $crate::jolt::instruction::bne::BNEInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLT: $crate::jolt::instruction::slt::SLTInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLTU: $crate::jolt::instruction::sltu::SLTUInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL: $crate::jolt::instruction::sll::SLLInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRA: $crate::jolt::instruction::sra::SRAInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL: $crate::jolt::instruction::srl::SRLInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            MOVSIGN: $crate::jolt::instruction::virtual_movsign::MOVSIGNInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            MUL: $crate::jolt::instruction::mul::MULInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            MULU: $crate::jolt::instruction::mulu::MULUInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            MULHU: $crate::jolt::instruction::mulhu::MULHUInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ADVICE: $crate::jolt::instruction::virtual_advice::ADVICEInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_MOVE: $crate::jolt::instruction::virtual_move::MOVEInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ASSERT_LTE: $crate::jolt::instruction::virtual_assert_lte::ASSERTLTEInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ASSERT_VALID_SIGNED_REMAINDER: $crate::jolt::instruction::virtual_assert_valid_signed_remainder::AssertValidSignedRemainderInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ASSERT_VALID_UNSIGNED_REMAINDER: $crate::jolt::instruction::virtual_assert_valid_unsigned_remainder::AssertValidUnsignedRemainderInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ASSERT_VALID_DIV0: $crate::jolt::instruction::virtual_assert_valid_div0::AssertValidDiv0Instruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_ASSERT_HALFWORD_ALIGNMENT: $crate::jolt::instruction::virtual_assert_halfword_alignment::AssertHalfwordAlignmentInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_POW2: $crate::jolt::instruction::virtual_pow2::POW2Instruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            VIRTUAL_SRA_PADDING: $crate::jolt::instruction::virtual_right_shift_padding::RightShiftPaddingInstruction<{ $crate::jolt::vm::rv32i_vm::WORD_SIZE }>
+        );
+    };
+}
+
+/// Generates a subtable set containing all of the [`RV32ISubtables`], in the same
+/// order, followed by the given custom subtables.
+#[macro_export]
+macro_rules! rv32i_subtable_enum {
+    (@define $enum_name:ident, [$($custom_alias:ident: $custom_struct:ty),*], $($alias:ident: $struct:ty),+) => {
+        $crate::subtable_enum!($enum_name, $($alias: $struct),+ $(, $custom_alias: $custom_struct)*);
+    };
+    ($enum_name:ident $(, $custom_alias:ident: $custom_struct:ty)* $(,)?) => {
+        $crate::rv32i_subtable_enum!(
+            @define $enum_name,
+            [$($custom_alias: $custom_struct),*],
+            AND: $crate::jolt::subtable::and::AndSubtable<F>,
+            EQ_ABS: $crate::jolt::subtable::eq_abs::EqAbsSubtable<F>,
+            EQ: $crate::jolt::subtable::eq::EqSubtable<F>,
+            LEFT_MSB: $crate::jolt::subtable::left_msb::LeftMSBSubtable<F>,
+            RIGHT_MSB: $crate::jolt::subtable::right_msb::RightMSBSubtable<F>,
+            IDENTITY: $crate::jolt::subtable::identity::IdentitySubtable<F>,
+            LT_ABS: $crate::jolt::subtable::lt_abs::LtAbsSubtable<F>,
+            LTU: $crate::jolt::subtable::ltu::LtuSubtable<F>,
+            OR: $crate::jolt::subtable::or::OrSubtable<F>,
+            SIGN_EXTEND_8: $crate::jolt::subtable::sign_extend::SignExtendSubtable<F, 8>,
+            SIGN_EXTEND_16: $crate::jolt::subtable::sign_extend::SignExtendSubtable<F, 16>,
+            SLL0: $crate::jolt::subtable::sll::SllSubtable<F, 0, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL1: $crate::jolt::subtable::sll::SllSubtable<F, 1, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL2: $crate::jolt::subtable::sll::SllSubtable<F, 2, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL3: $crate::jolt::subtable::sll::SllSubtable<F, 3, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL4: $crate::jolt::subtable::sll::SllSubtable<F, 4, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL5: $crate::jolt::subtable::sll::SllSubtable<F, 5, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL6: $crate::jolt::subtable::sll::SllSubtable<F, 6, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SLL7: $crate::jolt::subtable::sll::SllSubtable<F, 7, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRA_SIGN: $crate::jolt::subtable::sra_sign::SraSignSubtable<F, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL0: $crate::jolt::subtable::srl::SrlSubtable<F, 0, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL1: $crate::jolt::subtable::srl::SrlSubtable<F, 1, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL2: $crate::jolt::subtable::srl::SrlSubtable<F, 2, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL3: $crate::jolt::subtable::srl::SrlSubtable<F, 3, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL4: $crate::jolt::subtable::srl::SrlSubtable<F, 4, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL5: $crate::jolt::subtable::srl::SrlSubtable<F, 5, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL6: $crate::jolt::subtable::srl::SrlSubtable<F, 6, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            SRL7: $crate::jolt::subtable::srl::SrlSubtable<F, 7, { $crate::jolt::vm::rv32i_vm::WORD_SIZE }>,
+            XOR: $crate::jolt::subtable::xor::XorSubtable<F>,
+            LEFT_IS_ZERO: $crate::jolt::subtable::left_is_zero::LeftIsZeroSubtable<F>,
+            RIGHT_IS_ZERO: $crate::jolt::subtable::right_is_zero::RightIsZeroSubtable<F>,
+            DIV_BY_ZERO: $crate::jolt::subtable::div_by_zero::DivByZeroSubtable<F>,
+            LSB: $crate::jolt::subtable::low_bit::LowBitSubtable<F>
+        );
+    };
+}
+
+pub const WORD_SIZE: usize = 32;
+
+rv32i_instruction_set!(
+    #[derive(Deserialize)]
+    RV32I
 );
+rv32i_subtable_enum!(RV32ISubtables);
 
 // ==================== JOLT ====================
 
-pub enum RV32IJoltVM {}
+/// The RV32I Jolt VM, with instruction lookups decomposed into `C` chunks that
+/// are each looked up in subtables of size `M`. See [`assert_valid_decomposition`]
+/// for the supported settings.
+pub enum RV32IJoltVMWith<const C: usize, const M: usize> {}
+
+/// The RV32I Jolt VM with the default decomposition parameters [`C`] and [`M`].
+pub type RV32IJoltVM = RV32IJoltVMWith<C, M>;
 
 pub const C: usize = 4;
 pub const M: usize = 1 << 16;
 
-impl<F, PCS, ProofTranscript> Jolt<F, PCS, C, M, ProofTranscript> for RV32IJoltVM
+/// Checks that RV32I instruction lookups can be decomposed into `c` chunks that
+/// are each looked up in subtables of size `m`.
+///
+/// A lookup query interleaves two `WORD_SIZE`-bit operands, and the subtables that
+/// extract sign bits (e.g. `LEFT_MSB`, `SRA_SIGN`, `SIGN_EXTEND_16`) expect the
+/// operands' most significant bits in the first chunk, so the `c` chunks must cover
+/// the query exactly. Each shift subtable only sees a single chunk of the shift
+/// amount, so if an operand chunk is too narrow to hold any shift amount, shifts are
+/// expanded into shifts by smaller amounts (see [`narrow_shift`]), which needs chunks of
+/// at least [`narrow_shift::MIN_OPERAND_CHUNK_WIDTH`] bits. E.g. `C = 4, M = 2^16` and
+/// `C = 8, M = 2^8` are supported.
+pub fn assert_valid_decomposition(c: usize, m: usize) {
+    assert!(m.is_power_of_two(), "M = {m} must be a power of two");
+    let log_m = m.log_2();
+    assert!(
+        log_m % 2 == 0,
+        "log2(M) = {log_m} must be even to split queries into two operands"
+    );
+    assert!(
+        log_m <= 16,
+        "log2(M) = {log_m} exceeds 16; subtable indices are stored as u16"
+    );
+    assert!(
+        c * log_m == 2 * WORD_SIZE,
+        "C = {c} chunks of log2(M) = {log_m} bits must exactly cover the {} bits of a lookup query",
+        2 * WORD_SIZE
+    );
+    assert!(
+        log_m / 2 >= narrow_shift::MIN_OPERAND_CHUNK_WIDTH,
+        "operand chunks of log2(M) / 2 = {} bits are too narrow to expand shifts",
+        log_m / 2
+    );
+}
+
+impl<F, PCS, ProofTranscript, const C: usize, const M: usize> Jolt<F, PCS, C, M, ProofTranscript>
+    for RV32IJoltVMWith<C, M>
 where
     F: JoltField,
     PCS: CommitmentScheme<ProofTranscript, Field = F>,
@@ -184,18 +506,30 @@ where
 {
     type InstructionSet = RV32I;
     type Subtables = RV32ISubtables<F>;
-    type Constraints = JoltRV32IMConstraints;
+    type Constraints = JoltRV32IMConstraints<M>;
+
+    fn validate_decomposition() {
+        assert_valid_decomposition(C, M);
+    }
 }
 
-pub type RV32IJoltProof<F, PCS, ProofTranscript> =
-    JoltProof<C, M, JoltR1CSInputs, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
+pub type RV32IJoltProof<
+    F,
+    PCS,
+    ProofTranscript,
+    const C: usize = { self::C },
+    const M: usize = { self::M },
+> = JoltProof<C, M, JoltR1CSInputs, F, PCS, RV32I, RV32ISubtables<F>, ProofTranscript>;
 
+use crate::utils::errors::ContainerError;
 use crate::utils::transcript::{KeccakTranscript, Transcript};
 use eyre::Result;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
+/// Raw (headerless) compressed serialization. Prefer the versioned container
+/// format (see [`super::container`]) for artifacts that are stored or shared.
 pub trait Serializable: CanonicalSerialize + CanonicalDeserialize + Sized {
     /// Gets the byte size of the serialized data
     fn size(&self) -> Result<usize> {
@@ -233,48 +567,156 @@ pub trait Serializable: CanonicalSerialize + CanonicalDeserialize + Sized {
 
 pub type ProofTranscript = KeccakTranscript;
 pub type PCS = HyperKZG<Bn254, ProofTranscript>;
+
+/// A HyperKZG proof of an execution on [`RV32IJoltVMWith<C, M>`].
 #[derive(CanonicalSerialize, CanonicalDeserialize)]
-pub struct JoltHyperKZGProof {
-    pub proof: RV32IJoltProof<Fr, PCS, ProofTranscript>,
+pub struct JoltHyperKZGProofWith<const C: usize, const M: usize> {
+    pub proof: RV32IJoltProof<Fr, PCS, ProofTranscript, C, M>,
     pub commitments: JoltCommitments<PCS, ProofTranscript>,
+    /// The blobs attached to the program (see `host::Program::add_blob`), without their
+    /// contents. The proof only shows that the guest read blobs with these digests: a
+    /// verifier must check them against the digests it expects (see
+    /// `JoltVerifierPreprocessing::blob_digest`).
+    pub blobs: Vec<Blob>,
+}
+
+/// A HyperKZG proof with the default decomposition parameters [`C`] and [`M`].
+pub type JoltHyperKZGProof = JoltHyperKZGProofWith<C, M>;
+
+impl<const C: usize, const M: usize> Serializable for JoltHyperKZGProofWith<C, M> {}
+
+impl<const C: usize, const M: usize> JoltHyperKZGProofWith<C, M> {
+    /// Serializes the proof into a versioned container tagged with the
+    /// commitment scheme, transcript, `C`/`M` and program of `preprocessing`.
+    pub fn to_container_bytes(
+        &self,
+        preprocessing: &JoltVerifierPreprocessing<C, Fr, PCS, ProofTranscript>,
+    ) -> std::result::Result<Vec<u8>, ContainerError> {
+        encode_container(&preprocessing.container_header(ContainerKind::Proof), self)
+    }
+
+    /// Deserializes a proof container, checking that it was produced for the
+    /// same configuration and program as `preprocessing`.
+    pub fn from_container_bytes(
+        bytes: &[u8],
+        preprocessing: &JoltVerifierPreprocessing<C, Fr, PCS, ProofTranscript>,
+    ) -> std::result::Result<Self, ContainerError> {
+        let expected = preprocessing.container_header(ContainerKind::Proof);
+        let (_, proof) = decode_container_payload(bytes, &expected)?;
+        Ok(proof)
+    }
+
+    pub fn save_to_container<P: AsRef<Path>>(
+        &self,
+        path: P,
+        preprocessing: &JoltVerifierPreprocessing<C, Fr, PCS, ProofTranscript>,
+    ) -> std::result::Result<(), ContainerError> {
+        fs::write(path, self.to_container_bytes(preprocessing)?)?;
+        Ok(())
+    }
+
+    pub fn read_from_container<P: AsRef<Path>>(
+        path: P,
+        preprocessing: &JoltVerifierPreprocessing<C, Fr, PCS, ProofTranscript>,
+    ) -> std::result::Result<Self, ContainerError> {
+        Self::from_container_bytes(&fs::read(path)?, preprocessing)
+    }
+}
+
+/// A proof returned by the SDK's `prove_*` functions. In dev mode (see
+/// `host::Program::set_dev_mode`) the execution is only checked, not proven, and the
+/// SDK's verifiers reject the resulting [`DevProof`].
+#[allow(clippy::large_enum_variant)]
+pub enum SdkProofWith<const C: usize, const M: usize> {
+    Jolt(JoltHyperKZGProofWith<C, M>),
+    Dev {
+        proof: DevProof,
+        /// The blobs attached to the program. Their digests are not computed in dev
+        /// mode, and are left zeroed.
+        blobs: Vec<Blob>,
+    },
 }
 
-impl Serializable for JoltHyperKZGProof {}
+/// An SDK proof with the default decomposition parameters [`C`] and [`M`].
+pub type SdkProof = SdkProofWith<C, M>;
+
+impl<const C: usize, const M: usize> SdkProofWith<C, M> {
+    /// The blobs attached to the program, see [`JoltHyperKZGProofWith::blobs`].
+    pub fn blobs(&self) -> &[Blob] {
+        match self {
+            Self::Jolt(proof) => &proof.blobs,
+            Self::Dev { blobs, .. } => blobs,
+        }
+    }
+
+    pub fn is_dev(&self) -> bool {
+        matches!(self, Self::Dev { .. })
+    }
+
+    /// The Jolt proof, or `None` for a dev proof.
+    pub fn into_jolt(self) -> Option<JoltHyperKZGProofWith<C, M>> {
+        match self {
+            Self::Jolt(proof) => Some(proof),
+            Self::Dev { .. } => None,
+        }
+    }
+}
 
 // ==================== TEST ====================
 
 #[cfg(test)]
 mod tests {
     use ark_bn254::{Bn254, Fr};
+    use ark_serialize::CanonicalSerialize;
 
     use std::collections::HashSet;
 
     use crate::field::JoltField;
     use crate::host;
-    use crate::jolt::instruction::JoltInstruction;
-    use crate::jolt::vm::rv32i_vm::{Jolt, RV32IJoltVM, C, M};
+    use crate::jolt::instruction::{
+        sll::SLLInstruction, sra::SRAInstruction, srl::SRLInstruction, JoltInstruction,
+    };
+    use crate::jolt::vm::rv32i_vm::{
+        assert_valid_decomposition, Jolt, JoltHyperKZGProof, ProofTranscript, RV32IJoltVM,
+        RV32IJoltVMWith, Serializable, C, M, PCS, RV32I,
+    };
+    use crate::jolt::vm::tamper::assert_tampering_rejected;
+    use crate::jolt::vm::{JoltProverPreprocessing, JoltVerifierPreprocessing};
     use crate::poly::commitment::commitment_scheme::CommitmentScheme;
     use crate::poly::commitment::hyperkzg::HyperKZG;
     use crate::poly::commitment::mock::MockCommitScheme;
     use crate::poly::commitment::zeromorph::Zeromorph;
+    use crate::utils::errors::{ContainerError, ProofVerifyError, WitnessError, WitnessReport};
+    use crate::utils::math::Math;
     use crate::utils::transcript::{KeccakTranscript, Transcript};
+    use common::constants::{
+        DEFAULT_MAX_BYTECODE_SIZE, DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE,
+        DEFAULT_MAX_TRACE_LENGTH, DEFAULT_MEMORY_SIZE, DEFAULT_STACK_SIZE,
+    };
+    use common::rv_trace::{JoltDevice, MemoryLayout, MemoryOp, PanicReason, RV32IM};
     use std::sync::{LazyLock, Mutex};
     use strum::{EnumCount, IntoEnumIterator};
 
     // If multiple tests try to read the same trace artifacts simultaneously, they will fail
     static FIB_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
     static SHA3_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+    static MULDIV_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+    static MEMORY_OPS_FILE_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
     fn test_instruction_set_subtables<PCS, ProofTranscript>()
     where
         PCS: CommitmentScheme<ProofTranscript>,
         ProofTranscript: Transcript,
     {
+        // Some subtables are only used by narrower decompositions
         let mut subtable_set: HashSet<_> = HashSet::new();
         for instruction in
             <RV32IJoltVM as Jolt<_, PCS, C, M, ProofTranscript>>::InstructionSet::iter()
         {
-            for (subtable, _) in instruction.subtables::<Fr>(C, M) {
+            for (subtable, _) in [(C, M), (8, 1 << 8)]
+                .into_iter()
+                .flat_map(|(c, m)| instruction.subtables::<Fr>(c, m))
+            {
                 // panics if subtable cannot be cast to enum variant
                 let _ = <RV32IJoltVM as Jolt<_, PCS, C, M, ProofTranscript>>::Subtables::from(
                     subtable.subtable_id(),
@@ -295,6 +737,162 @@ mod tests {
         test_instruction_set_subtables::<HyperKZG<Bn254, KeccakTranscript>, KeccakTranscript>();
     }
 
+    /// Checks that the subtable lookups of each RV32I instruction combine to its
+    /// lookup output under the decomposition `c`, `m`.
+    fn test_instruction_lookups(c: usize, m: usize) {
+        let mut rng = <rand::rngs::StdRng as rand::SeedableRng>::seed_from_u64(c as u64);
+        // Shifts are expanded so that their amounts fit in an operand chunk
+        let shift_bound = 1 << (m.log_2() / 2);
+        for instruction in RV32I::iter() {
+            // Placeholders without subtables
+            if instruction.subtables::<Fr>(c, m).is_empty() {
+                continue;
+            }
+            for _ in 0..1000 {
+                let instruction = match instruction.random(&mut rng) {
+                    RV32I::SLL(SLLInstruction(x, y)) => {
+                        RV32I::SLL(SLLInstruction(x, y % shift_bound))
+                    }
+                    RV32I::SRL(SRLInstruction(x, y)) => {
+                        RV32I::SRL(SRLInstruction(x, y % shift_bound))
+                    }
+                    RV32I::SRA(SRAInstruction(x, y)) => {
+                        RV32I::SRA(SRAInstruction(x, y % shift_bound))
+                    }
+                    instruction => instruction,
+                };
+                let indices = instruction.to_indices(c, m.log_2());
+                let mut subtable_values = vec![];
+                for (subtable, dimension_indices) in instruction.subtables::<Fr>(c, m) {
+                    let materialized_subtable = subtable.materialize(m);
+                    for i in dimension_indices.iter() {
+                        subtable_values
+                            .push(Fr::from_u64(materialized_subtable[indices[i]] as u64));
+                    }
+                }
+                assert_eq!(
+                    instruction.combine_lookups(&subtable_values, c, m),
+                    Fr::from_u64(instruction.lookup_entry()),
+                    "{instruction:?} with C = {c}, M = {m}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn instruction_lookups() {
+        test_instruction_lookups(C, M);
+        test_instruction_lookups(8, 1 << 8);
+    }
+
+    #[test]
+    fn decomposition_settings() {
+        assert_valid_decomposition(C, M);
+        assert_valid_decomposition(8, 1 << 8);
+        for (c, m) in [
+            (16, 1 << 4),
+            (2, 1 << 32),
+            (4, 1 << 15),
+            (5, 1 << 16),
+            (6, 1 << 12),
+        ] {
+            let result = std::panic::catch_unwind(|| assert_valid_decomposition(c, m));
+            assert!(result.is_err(), "C = {c}, M = {m} should be rejected");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "too narrow to expand shifts")]
+    fn preprocess_rejects_unsupported_decomposition() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        drop(artifact_guard);
+        let memory_layout = MemoryLayout::new(DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE);
+
+        <RV32IJoltVMWith<16, { 1 << 4 }> as Jolt<
+            Fr,
+            MockCommitScheme<Fr, KeccakTranscript>,
+            16,
+            { 1 << 4 },
+            KeccakTranscript,
+        >>::verifier_preprocess(bytecode, memory_layout, memory_init, 1 << 20);
+    }
+
+    /// Proves and verifies an execution of `guest` with instruction lookups decomposed
+    /// into `C` chunks looked up in subtables of size `M`.
+    fn decomposition_e2e<const C: usize, const M: usize>(
+        guest: &str,
+        inputs: &[u8],
+        lock: &Mutex<()>,
+    ) {
+        let artifact_guard = lock.lock().unwrap();
+        let mut program = host::Program::new(guest);
+        program.set_subtable_size(M);
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(inputs);
+        drop(artifact_guard);
+
+        type MockPCS = MockCommitScheme<Fr, KeccakTranscript>;
+        let preprocessing =
+            <RV32IJoltVMWith<C, M> as Jolt<Fr, MockPCS, C, M, KeccakTranscript>>::prover_preprocess(
+                bytecode,
+                io_device.memory_layout.clone(),
+                memory_init,
+                1 << 20,
+                1 << 20,
+                1 << 20,
+            );
+        let (proof, commitments, verifier_io_device, debug_info) =
+            <RV32IJoltVMWith<C, M> as Jolt<Fr, MockPCS, C, M, KeccakTranscript>>::prove(
+                io_device,
+                trace,
+                preprocessing.clone(),
+            );
+        let verification_result =
+            <RV32IJoltVMWith<C, M> as Jolt<Fr, MockPCS, C, M, KeccakTranscript>>::verify(
+                preprocessing.shared,
+                proof,
+                commitments,
+                verifier_io_device,
+                debug_info,
+            );
+        assert!(
+            verification_result.is_ok(),
+            "Verification of {guest} with C = {C}, M = {M} failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn fib_e2e_decompositions() {
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        decomposition_e2e::<C, M>("fibonacci-guest", &inputs, &FIB_FILE_LOCK);
+        decomposition_e2e::<8, { 1 << 8 }>("fibonacci-guest", &inputs, &FIB_FILE_LOCK);
+    }
+
+    #[test]
+    fn sha3_e2e_decompositions() {
+        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+        decomposition_e2e::<C, M>("sha3-guest", &inputs, &SHA3_FILE_LOCK);
+        decomposition_e2e::<8, { 1 << 8 }>("sha3-guest", &inputs, &SHA3_FILE_LOCK);
+    }
+
+    #[test]
+    fn muldiv_e2e_decompositions() {
+        let inputs = postcard::to_stdvec(&(12031293u32, 17u32, 92u32)).unwrap();
+        decomposition_e2e::<C, M>("muldiv-guest", &inputs, &MULDIV_FILE_LOCK);
+        decomposition_e2e::<8, { 1 << 8 }>("muldiv-guest", &inputs, &MULDIV_FILE_LOCK);
+    }
+
+    #[test]
+    fn memory_ops_e2e_decompositions() {
+        decomposition_e2e::<C, M>("memory-ops-guest", &[], &MEMORY_OPS_FILE_LOCK);
+        decomposition_e2e::<8, { 1 << 8 }>("memory-ops-guest", &[], &MEMORY_OPS_FILE_LOCK);
+    }
+
     fn fib_e2e<F, PCS, ProofTranscript>()
     where
         F: JoltField,
@@ -338,30 +936,90 @@ mod tests {
     }
 
     #[test]
-    fn fib_e2e_mock() {
-        fib_e2e::<Fr, MockCommitScheme<Fr, KeccakTranscript>, KeccakTranscript>();
-    }
+    fn panic_without_panic_code_rejected() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
 
-    #[test]
-    fn fib_e2e_zeromorph() {
-        fib_e2e::<Fr, Zeromorph<Bn254, KeccakTranscript>, KeccakTranscript>();
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode,
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proof, commitments, mut verifier_io_device, debug_info) =
+            <RV32IJoltVM as Jolt<
+                Fr,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove(io_device, trace, preprocessing.clone());
+        // A panic with code 0 would require neither a nonzero panic word nor the
+        // termination bit, i.e. it would pass off any execution as a panic.
+        verifier_io_device.panic = true;
+        verifier_io_device.panic_code = 0;
+        assert!(matches!(
+            RV32IJoltVM::verify(
+                preprocessing.shared,
+                proof,
+                commitments,
+                verifier_io_device,
+                debug_info,
+            ),
+            Err(ProofVerifyError::InconsistentPanic(true, 0))
+        ));
     }
 
     #[test]
-    fn fib_e2e_hyperkzg() {
-        fib_e2e::<Fr, HyperKZG<Bn254, KeccakTranscript>, KeccakTranscript>();
+    fn reproducible_guest_build() {
+        let _artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let elf_digest = program.elf_digest();
+
+        let target_dir = "/tmp/jolt-guest-targets-reproducible";
+        let mut rebuilt_program = host::Program::new("fibonacci-guest");
+        rebuilt_program.build(target_dir);
+        assert_eq!(rebuilt_program.elf_digest(), elf_digest);
+
+        let cached_elf = host::Program::cached_elf_path(target_dir, &elf_digest);
+        assert_eq!(
+            std::fs::read(cached_elf).unwrap(),
+            std::fs::read(rebuilt_program.elf.unwrap()).unwrap()
+        );
     }
 
     #[test]
-    fn sha3_e2e_zeromorph() {
-        let guard = SHA3_FILE_LOCK.lock().unwrap();
-
-        let mut program = host::Program::new("sha3-guest");
-        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+    fn prebuilt_elf_e2e() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
         program.build(crate::host::DEFAULT_TARGET_DIR);
+        let elf_contents = std::fs::read(program.elf.unwrap()).unwrap();
+        drop(artifact_guard);
+
+        let memory_layout = MemoryLayout::new(DEFAULT_MAX_INPUT_SIZE, DEFAULT_MAX_OUTPUT_SIZE);
+        assert!(matches!(
+            host::Program::from_elf_bytes(&elf_contents, &memory_layout, 16, DEFAULT_STACK_SIZE),
+            Err(host::ElfError::SectionOutOfBounds { .. })
+        ));
+
+        let mut program = host::Program::from_elf_bytes(
+            &elf_contents,
+            &memory_layout,
+            DEFAULT_MEMORY_SIZE,
+            DEFAULT_STACK_SIZE,
+        )
+        .unwrap();
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
         let (bytecode, memory_init) = program.decode();
         let (io_device, trace) = program.trace(&inputs);
-        drop(guard);
 
         let preprocessing = RV32IJoltVM::prover_preprocess(
             bytecode.clone(),
@@ -371,20 +1029,18 @@ mod tests {
             1 << 20,
             1 << 20,
         );
-        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
-            _,
-            Zeromorph<Bn254, KeccakTranscript>,
-            C,
-            M,
-            KeccakTranscript,
-        >>::prove(
-            io_device, trace, preprocessing.clone()
-        );
-
+        let (proof, commitments, verifier_io_device, debug_info) =
+            <RV32IJoltVM as Jolt<
+                Fr,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove(io_device, trace, preprocessing.clone());
         let verification_result = RV32IJoltVM::verify(
             preprocessing.shared,
-            jolt_proof,
-            jolt_commitments,
+            proof,
+            commitments,
             verifier_io_device,
             debug_info,
         );
@@ -396,26 +1052,51 @@ mod tests {
     }
 
     #[test]
-    fn sha3_e2e_hyperkzg() {
-        let guard = SHA3_FILE_LOCK.lock().unwrap();
+    fn fib_e2e_mock() {
+        fib_e2e::<Fr, MockCommitScheme<Fr, KeccakTranscript>, KeccakTranscript>();
+    }
 
-        let mut program = host::Program::new("sha3-guest");
-        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+    #[test]
+    fn fib_e2e_zeromorph() {
+        fib_e2e::<Fr, Zeromorph<Bn254, KeccakTranscript>, KeccakTranscript>();
+    }
+
+    #[test]
+    fn fib_e2e_hyperkzg() {
+        fib_e2e::<Fr, HyperKZG<Bn254, KeccakTranscript>, KeccakTranscript>();
+    }
+
+    #[test]
+    fn fib_e2e_just_above_power_of_two() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
         program.build(crate::host::DEFAULT_TARGET_DIR);
         let (bytecode, memory_init) = program.decode();
-        let (io_device, trace) = program.trace(&inputs);
-        drop(guard);
+        // Each iteration of the guest's loop adds a few steps, so the first input whose
+        // trace outgrows a power of two overshoots it by at most one iteration: nearly
+        // half of the padded trace is no-ops.
+        let power_of_two = program
+            .trace(&postcard::to_stdvec(&1u32).unwrap())
+            .1
+            .len()
+            .next_power_of_two();
+        let (io_device, trace) = (2u32..)
+            .map(|n| program.trace(&postcard::to_stdvec(&n).unwrap()))
+            .find(|(_, trace)| trace.len() > power_of_two)
+            .unwrap();
+        drop(artifact_guard);
+        assert_eq!(trace.len().next_power_of_two(), 2 * power_of_two);
 
         let preprocessing = RV32IJoltVM::prover_preprocess(
-            bytecode.clone(),
+            bytecode,
             io_device.memory_layout.clone(),
             memory_init,
             1 << 20,
             1 << 20,
             1 << 20,
         );
-        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
-            _,
+        let (proof, commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
+            Fr,
             HyperKZG<Bn254, KeccakTranscript>,
             C,
             M,
@@ -423,11 +1104,10 @@ mod tests {
         >>::prove(
             io_device, trace, preprocessing.clone()
         );
-
         let verification_result = RV32IJoltVM::verify(
             preprocessing.shared,
-            jolt_proof,
-            jolt_commitments,
+            proof,
+            commitments,
             verifier_io_device,
             debug_info,
         );
@@ -439,15 +1119,133 @@ mod tests {
     }
 
     #[test]
-    fn memory_ops_e2e_hyperkzg() {
-        let mut program = host::Program::new("memory-ops-guest");
-        let inputs = vec![];
+    fn fib_dev_mode() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
         program.build(crate::host::DEFAULT_TARGET_DIR);
         let (bytecode, memory_init) = program.decode();
         let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
 
         let preprocessing = RV32IJoltVM::prover_preprocess(
-            bytecode.clone(),
+            bytecode,
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (dev_proof, _) =
+            <RV32IJoltVM as Jolt<
+                Fr,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove_dev(io_device.clone(), trace.clone(), preprocessing.clone())
+            .unwrap();
+        assert_eq!(dev_proof.trace_length, trace.len());
+
+        // Claiming an output the guest never wrote must fail the witness check.
+        let mut tampered_io_device = io_device.clone();
+        tampered_io_device.outputs[0] ^= 1;
+        assert!(matches!(
+            <RV32IJoltVM as Jolt<
+                Fr,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove_dev(tampered_io_device, trace.clone(), preprocessing.clone()),
+            Err(WitnessReport {
+                error: WitnessError::IoMismatch(_),
+                pc: None,
+                ..
+            })
+        ));
+
+        // Loading from the wrong address must be reported at the load, with the
+        // violated constraint.
+        let mut tampered_trace = trace;
+        let load = tampered_trace
+            .iter()
+            .position(|step| step.opcode == Some(RV32IM::LW))
+            .unwrap();
+        let MemoryOp::Read(address) = tampered_trace[load].memory_ops[3] else {
+            panic!("LW should read RAM");
+        };
+        tampered_trace[load].memory_ops[3] = MemoryOp::Read(address + 4);
+        let report = <RV32IJoltVM as Jolt<
+            Fr,
+            MockCommitScheme<Fr, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove_dev(io_device, tampered_trace, preprocessing)
+        .unwrap_err();
+        assert_eq!(report.error.step(), Some(load));
+        assert_eq!(report.opcode, Some(RV32IM::LW));
+        assert!(report.pc.is_some());
+        assert!(report.constraint.unwrap().contains("RAM_Address"));
+    }
+
+    #[test]
+    fn sha3_e2e_zeromorph() {
+        let guard = SHA3_FILE_LOCK.lock().unwrap();
+
+        let mut program = host::Program::new("sha3-guest");
+        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
+            _,
+            Zeromorph<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            jolt_proof,
+            jolt_commitments,
+            verifier_io_device,
+            debug_info,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn sha3_e2e_hyperkzg() {
+        let guard = SHA3_FILE_LOCK.lock().unwrap();
+
+        let mut program = host::Program::new("sha3-guest");
+        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
             io_device.memory_layout.clone(),
             memory_init,
             1 << 20,
@@ -478,6 +1276,752 @@ mod tests {
         );
     }
 
+    #[test]
+    fn memory_ops_e2e_hyperkzg() {
+        let guard = MEMORY_OPS_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("memory-ops-guest");
+        let inputs = vec![];
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) = <RV32IJoltVM as Jolt<
+            _,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            jolt_proof,
+            jolt_commitments,
+            verifier_io_device,
+            debug_info,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn secp256k1_e2e_mock() {
+        // A signature under the private key
+        // 0xc9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721
+        let z: [u32; 8] = [
+            0x62add1bf, 0x62113d8a, 0x68e98915, 0x1a831d02, 0x94f41fc7, 0xe2ade1d6, 0xaa9b6ec1,
+            0xaf2bdbe1,
+        ];
+        let r: [u32; 8] = [
+            0xfcd7a6c8, 0x6d970889, 0x45fb8aad, 0x783b8708, 0x83cc165c, 0x503a26ce, 0x2cb80eb6,
+            0x432310e3,
+        ];
+        let s: [u32; 8] = [
+            0x02bfab69, 0x056ce89b, 0xd85863d4, 0xa6e05074, 0x93ed071c, 0x74a6305d, 0xb81c5488,
+            0x530128b6,
+        ];
+        let public_key: ([u32; 8], [u32; 8]) = (
+            [
+                0x3a6ae645, 0x0ec1cea1, 0x2eaeb3e6, 0x0e09481f, 0x184a4ce5, 0x55e3865a, 0x9f990c6b,
+                0x2c8c31fc,
+            ],
+            [
+                0xc3328085, 0x4459822d, 0x1704275e, 0x86769b01, 0x006a29f6, 0x386e189b, 0xdb6948c0,
+                0x64b95e4f,
+            ],
+        );
+
+        let mut program = host::Program::new("secp256k1-guest");
+        program.set_stack_size(65536);
+        let inputs = postcard::to_stdvec(&(z, r, s, 0u8)).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        let output: Option<([u32; 8], [u32; 8])> =
+            postcard::from_bytes(&io_device.outputs).unwrap();
+        assert_eq!(output, Some(public_key));
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) =
+            <RV32IJoltVM as Jolt<
+                _,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove(io_device, trace, preprocessing.clone());
+        assert!(jolt_proof.precompiles.is_some());
+
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            jolt_proof,
+            jolt_commitments,
+            verifier_io_device,
+            debug_info,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn ed25519_e2e_mock() {
+        // Test 2 of RFC 8032, section 7.1
+        let public_key: [u8; 32] = [
+            0x3d, 0x40, 0x17, 0xc3, 0xe8, 0x43, 0x89, 0x5a, 0x92, 0xb7, 0x0a, 0xa7, 0x4d, 0x1b,
+            0x7e, 0xbc, 0x9c, 0x98, 0x2c, 0xcf, 0x2e, 0xc4, 0x96, 0x8c, 0xc0, 0xcd, 0x55, 0xf1,
+            0x2a, 0xf4, 0x66, 0x0c,
+        ];
+        let r: [u8; 32] = [
+            0x92, 0xa0, 0x09, 0xa9, 0xf0, 0xd4, 0xca, 0xb8, 0x72, 0x0e, 0x82, 0x0b, 0x5f, 0x64,
+            0x25, 0x40, 0xa2, 0xb2, 0x7b, 0x54, 0x16, 0x50, 0x3f, 0x8f, 0xb3, 0x76, 0x22, 0x23,
+            0xeb, 0xdb, 0x69, 0xda,
+        ];
+        let s: [u8; 32] = [
+            0x08, 0x5a, 0xc1, 0xe4, 0x3e, 0x15, 0x99, 0x6e, 0x45, 0x8f, 0x36, 0x13, 0xd0, 0xf1,
+            0x1d, 0x8c, 0x38, 0x7b, 0x2e, 0xae, 0xb4, 0x30, 0x2a, 0xee, 0xb0, 0x0d, 0x29, 0x16,
+            0x12, 0xbb, 0x0c, 0x00,
+        ];
+
+        let mut program = host::Program::new("ed25519-guest");
+        program.set_stack_size(65536);
+        let inputs = postcard::to_stdvec(&(public_key, [0x72u8].as_slice(), r, s)).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        let output: bool = postcard::from_bytes(&io_device.outputs).unwrap();
+        assert!(output);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (jolt_proof, jolt_commitments, verifier_io_device, debug_info) =
+            <RV32IJoltVM as Jolt<
+                _,
+                MockCommitScheme<Fr, KeccakTranscript>,
+                C,
+                M,
+                KeccakTranscript,
+            >>::prove(io_device, trace, preprocessing.clone());
+        assert!(jolt_proof.precompiles.is_some());
+
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            jolt_proof,
+            jolt_commitments,
+            verifier_io_device,
+            debug_info,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn tampered_memory_checking_proof() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (mut proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device,
+            trace,
+            preprocessing.clone(),
+        );
+        proof
+            .read_write_memory
+            .memory_checking_proof
+            .multiset_hashes
+            .read_hashes[0] += Fr::from(1u64);
+
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(
+            matches!(
+                &verification_result,
+                Err(ProofVerifyError::ReadWriteMemoryError(e))
+                    if matches!(**e, ProofVerifyError::MultisetHashMismatch)
+            ),
+            "Unexpected verification result: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn mismatched_program_id() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        assert_eq!(
+            preprocessing.shared.program_id,
+            preprocessing.shared.compute_program_id()
+        );
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+
+        // A verifier expecting a different program must reject the proof
+        let mut verifier_preprocessing = preprocessing.shared;
+        verifier_preprocessing.program_id[0] ^= 1;
+        let verification_result = RV32IJoltVM::verify(
+            verifier_preprocessing,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(verification_result.is_err());
+    }
+
+    fn tampering_rejected(guest: &str, inputs: &[u8], lock: &Mutex<()>) {
+        let guard = lock.lock().unwrap();
+        let mut program = host::Program::new(guest);
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(inputs);
+        drop(guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode,
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+        assert_tampering_rejected(preprocessing.shared, proof, commitments, verifier_io_device);
+    }
+
+    #[test]
+    fn fib_tampering_rejected() {
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        tampering_rejected("fibonacci-guest", &inputs, &FIB_FILE_LOCK);
+    }
+
+    #[test]
+    fn sha3_tampering_rejected() {
+        let inputs = postcard::to_stdvec(&[5u8; 32]).unwrap();
+        tampering_rejected("sha3-guest", &inputs, &SHA3_FILE_LOCK);
+    }
+
+    #[test]
+    fn fib_prove_batch() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs: Vec<_> = [5u32, 9, 12]
+            .iter()
+            .map(|n| postcard::to_stdvec(n).unwrap())
+            .collect();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let executions = program.trace_batch(&inputs);
+        drop(artifact_guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            executions[0].0.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proofs, metrics) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove_batch(executions, preprocessing.clone());
+        assert_eq!(metrics.num_proofs(), inputs.len());
+
+        for ((proof, commitments, verifier_io_device), inputs) in proofs.into_iter().zip(&inputs) {
+            assert_eq!(&verifier_io_device.inputs, inputs);
+            let verification_result = RV32IJoltVM::verify(
+                preprocessing.shared.clone(),
+                proof,
+                commitments,
+                verifier_io_device,
+                None,
+            );
+            assert!(
+                verification_result.is_ok(),
+                "Verification failed with error: {:?}",
+                verification_result.err()
+            );
+        }
+    }
+
+    #[test]
+    fn fib_batch_verify() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs: Vec<_> = [5u32, 9, 12]
+            .iter()
+            .map(|n| postcard::to_stdvec(n).unwrap())
+            .collect();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let executions = program.trace_batch(&inputs);
+        drop(artifact_guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            executions[0].0.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proofs, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove_batch(executions, preprocessing.clone());
+        let proofs: Vec<_> = proofs
+            .into_iter()
+            .map(|(proof, commitments, verifier_io_device)| {
+                let bytes = JoltHyperKZGProof {
+                    proof,
+                    commitments,
+                    blobs: vec![],
+                }
+                .serialize_to_bytes()
+                .unwrap();
+                (bytes, verifier_io_device)
+            })
+            .collect();
+        let deserialize = |proofs: &[(Vec<u8>, JoltDevice)]| {
+            proofs
+                .iter()
+                .map(|(bytes, verifier_io_device)| {
+                    let JoltHyperKZGProof {
+                        proof, commitments, ..
+                    } = JoltHyperKZGProof::deserialize_from_bytes(bytes).unwrap();
+                    (proof, commitments, verifier_io_device.clone())
+                })
+                .collect()
+        };
+
+        let verification_result =
+            RV32IJoltVM::batch_verify(preprocessing.shared.clone(), deserialize(&proofs));
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+
+        // Only the proof whose claimed output was tampered with is reported
+        let mut tampered = proofs;
+        tampered[1].1.outputs[0] ^= 1;
+        let failures = RV32IJoltVM::batch_verify(preprocessing.shared, deserialize(&tampered))
+            .unwrap_err()
+            .failures;
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+    }
+
+    #[test]
+    fn dispatcher_e2e() {
+        let mut program = host::Program::new("multi-function-guest");
+        program.set_dispatch(true);
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+
+        let mut unknown_inputs = postcard::to_stdvec("div").unwrap();
+        unknown_inputs.append(&mut postcard::to_stdvec(&(5u32, 10u32)).unwrap());
+        let (unknown_io_device, _) = program.trace(&unknown_inputs);
+        assert_eq!(
+            unknown_io_device.panic_reason(),
+            Some(PanicReason::UnknownFunction)
+        );
+
+        let mut inputs = postcard::to_stdvec("mul").unwrap();
+        inputs.append(&mut postcard::to_stdvec(&(5u32, 10u32)).unwrap());
+        let (io_device, trace) = program.trace(&inputs);
+        assert_eq!(postcard::from_bytes::<u32>(&io_device.outputs).unwrap(), 50);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+        let proof_bytes = JoltHyperKZGProof {
+            proof,
+            commitments,
+            blobs: vec![],
+        }
+        .serialize_to_bytes()
+        .unwrap();
+
+        // The function selector is part of the public inputs, so a proof of `mul`
+        // must not verify as a proof of `add`
+        let mut add_io_device = verifier_io_device.clone();
+        add_io_device.inputs[1..4].copy_from_slice(b"add");
+        let JoltHyperKZGProof {
+            proof, commitments, ..
+        } = JoltHyperKZGProof::deserialize_from_bytes(&proof_bytes).unwrap();
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared.clone(),
+            proof,
+            commitments,
+            add_io_device,
+            None,
+        );
+        assert!(verification_result.is_err());
+
+        let JoltHyperKZGProof {
+            proof, commitments, ..
+        } = JoltHyperKZGProof::deserialize_from_bytes(&proof_bytes).unwrap();
+        let verification_result = RV32IJoltVM::verify(
+            preprocessing.shared,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn succinct_preprocessing_e2e() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
+
+        let mut preprocessing: JoltProverPreprocessing<C, Fr, PCS, ProofTranscript> =
+            RV32IJoltVM::prover_preprocess(
+                bytecode.clone(),
+                io_device.memory_layout.clone(),
+                memory_init,
+                1 << 20,
+                1 << 20,
+                1 << 20,
+            );
+        let uncommitted_program_id = preprocessing.shared.program_id;
+        preprocessing.shared.commit_program();
+        assert_ne!(preprocessing.shared.program_id, uncommitted_program_id);
+
+        let verifier_preprocessing = preprocessing.shared.succinct();
+        assert_eq!(
+            verifier_preprocessing.program_id,
+            preprocessing.shared.program_id
+        );
+        assert_eq!(
+            verifier_preprocessing.program_id,
+            verifier_preprocessing.compute_program_id()
+        );
+        assert!(
+            verifier_preprocessing.bytecode.compressed_size()
+                < preprocessing.shared.bytecode.compressed_size()
+        );
+        assert!(
+            verifier_preprocessing.generators.compressed_size()
+                < preprocessing.shared.generators.compressed_size()
+        );
+
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing
+        );
+        // No debug info: comparing against the prover's openings recommits to its
+        // polynomials, which the trimmed verifier key cannot do
+        let verification_result = RV32IJoltVM::verify(
+            verifier_preprocessing,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn preprocessing_setup_sizes() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
+
+        // With the SDK's defaults, the verifier's key only covers the program, however
+        // long the traces it verifies
+        let verifier_preprocessing: JoltVerifierPreprocessing<C, Fr, PCS, ProofTranscript> =
+            RV32IJoltVM::verifier_preprocess(
+                bytecode.clone(),
+                io_device.memory_layout.clone(),
+                memory_init.clone(),
+                DEFAULT_MAX_BYTECODE_SIZE as usize,
+            );
+        let verifier_setup_len = verifier_preprocessing.bytecode.code_size().max(
+            verifier_preprocessing
+                .read_write_memory
+                .program_image_len(&verifier_preprocessing.memory_layout),
+        );
+        assert_eq!(
+            verifier_preprocessing.verifier_setup_len(),
+            verifier_setup_len
+        );
+        assert!(verifier_setup_len < DEFAULT_MAX_TRACE_LENGTH as usize);
+        // A HyperKZG setup for polynomials of length `n` holds `n + 1` powers
+        assert_eq!(
+            verifier_preprocessing.generators.0.kzg_pk.len(),
+            verifier_setup_len + 1
+        );
+
+        let preprocessing: JoltProverPreprocessing<C, Fr, PCS, ProofTranscript> =
+            RV32IJoltVM::prover_preprocess(
+                bytecode,
+                io_device.memory_layout.clone(),
+                memory_init,
+                DEFAULT_MAX_BYTECODE_SIZE as usize,
+                1 << 20,
+                1 << 20,
+            );
+        assert_eq!(
+            preprocessing.shared.generators.0.kzg_pk.len(),
+            preprocessing.shared.prover_setup_len(1 << 20, 1 << 20) + 1
+        );
+        assert_eq!(
+            preprocessing.shared.program_id,
+            verifier_preprocessing.program_id
+        );
+
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing
+        );
+        let verification_result = RV32IJoltVM::verify(
+            verifier_preprocessing,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
+    #[test]
+    fn proof_container_roundtrip() {
+        let artifact_guard = FIB_FILE_LOCK.lock().unwrap();
+        let mut program = host::Program::new("fibonacci-guest");
+        let inputs = postcard::to_stdvec(&9u32).unwrap();
+        program.build(crate::host::DEFAULT_TARGET_DIR);
+        let (bytecode, memory_init) = program.decode();
+        let (io_device, trace) = program.trace(&inputs);
+        drop(artifact_guard);
+
+        let preprocessing = RV32IJoltVM::prover_preprocess(
+            bytecode.clone(),
+            io_device.memory_layout.clone(),
+            memory_init,
+            1 << 20,
+            1 << 20,
+            1 << 20,
+        );
+        let (proof, commitments, verifier_io_device, _) = <RV32IJoltVM as Jolt<
+            Fr,
+            HyperKZG<Bn254, KeccakTranscript>,
+            C,
+            M,
+            KeccakTranscript,
+        >>::prove(
+            io_device, trace, preprocessing.clone()
+        );
+        let proof = JoltHyperKZGProof {
+            proof,
+            commitments,
+            blobs: vec![],
+        };
+        let verifier_preprocessing = preprocessing.shared;
+
+        let target_dir = "/tmp/jolt-container-test";
+        std::fs::create_dir_all(target_dir).unwrap();
+        verifier_preprocessing
+            .save_to_target_dir(target_dir)
+            .unwrap();
+        let verifier_preprocessing =
+            JoltVerifierPreprocessing::read_from_target_dir(target_dir).unwrap();
+
+        let bytes = proof.to_container_bytes(&verifier_preprocessing).unwrap();
+        let mut corrupted = bytes.clone();
+        corrupted[bytes.len() / 2] ^= 1;
+        assert!(matches!(
+            JoltHyperKZGProof::from_container_bytes(&corrupted, &verifier_preprocessing),
+            Err(ContainerError::ChecksumMismatch)
+        ));
+        let mut future_version = bytes.clone();
+        future_version[4] += 1;
+        assert!(matches!(
+            JoltHyperKZGProof::from_container_bytes(&future_version, &verifier_preprocessing),
+            Err(ContainerError::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            JoltHyperKZGProof::from_container_bytes(&bytes[4..], &verifier_preprocessing),
+            Err(ContainerError::BadMagic)
+        ));
+        let mut other_program = verifier_preprocessing.clone();
+        other_program.program_id[0] ^= 1;
+        assert!(matches!(
+            JoltHyperKZGProof::from_container_bytes(&bytes, &other_program),
+            Err(ContainerError::ProgramIdMismatch)
+        ));
+        // A preprocessing whose contents no longer match its stored program ID is
+        // rejected when loaded
+        let mut tampered = verifier_preprocessing.clone();
+        tampered.precompiles = !tampered.precompiles;
+        let tampered_dir = "/tmp/jolt-container-test-tampered";
+        std::fs::create_dir_all(tampered_dir).unwrap();
+        tampered.save_to_target_dir(tampered_dir).unwrap();
+        assert!(matches!(
+                JoltVerifierPreprocessing::<
+                    C,
+                    Fr,
+                    HyperKZG<Bn254, KeccakTranscript>,
+                    KeccakTranscript,
+                >::read_from_target_dir(tampered_dir),
+                Err(ContainerError::ProgramIdMismatch)
+            ));
+
+        let JoltHyperKZGProof {
+            proof, commitments, ..
+        } = JoltHyperKZGProof::from_container_bytes(&bytes, &verifier_preprocessing).unwrap();
+        let verification_result = RV32IJoltVM::verify(
+            verifier_preprocessing,
+            proof,
+            commitments,
+            verifier_io_device,
+            None,
+        );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
+    }
+
     #[test]
     #[should_panic]
     fn truncated_trace() {
@@ -510,13 +2054,18 @@ mod tests {
             trace,
             preprocessing.clone(),
         );
-        let _verification_result = RV32IJoltVM::verify(
+        let verification_result = RV32IJoltVM::verify(
             preprocessing.shared,
             proof,
             commitments,
             verifier_io_device,
             debug_info,
         );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
     }
 
     #[test]
@@ -557,12 +2106,17 @@ mod tests {
             trace,
             preprocessing.clone(),
         );
-        let _verification_result = RV32IJoltVM::verify(
+        let verification_result = RV32IJoltVM::verify(
             preprocessing.shared,
             proof,
             commitments,
             verifier_io_device,
             debug_info,
         );
+        assert!(
+            verification_result.is_ok(),
+            "Verification failed with error: {:?}",
+            verification_result.err()
+        );
     }
 }
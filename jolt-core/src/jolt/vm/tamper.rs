@@ -0,0 +1,281 @@
+//! Checks that [`Jolt::verify`] rejects corrupted proofs. A valid proof and its
+//! commitments are serialized, and the leaves of their [`CanonicalSerialize`]
+//! structure (field element limbs, commitments, `trace_length`...) are corrupted in
+//! turn. Each corrupted proof must either fail to deserialize or fail to verify,
+//! without panicking, as must a valid proof paired with corrupted inputs, outputs or
+//! panic bit.
+
+use std::io::{Cursor, Write};
+use std::ops::Range;
+use std::panic;
+
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use common::rv_trace::JoltDevice;
+
+use crate::field::JoltField;
+use crate::jolt::vm::rv32i_vm::{RV32IJoltProof, RV32IJoltVM, C, M};
+use crate::jolt::vm::{Jolt, JoltCommitments, JoltVerifierPreprocessing};
+use crate::poly::commitment::commitment_scheme::CommitmentScheme;
+use crate::utils::transcript::Transcript;
+
+/// The number of leaves corrupted in each top-level field of the proof and of the
+/// commitments. Larger fields are sampled evenly, including their first and last leaf.
+const LEAVES_PER_FIELD: usize = 16;
+
+/// A writer recording the byte range of every `write` call. Integers, field element
+/// limbs and group elements are each serialized with a single write, so these ranges
+/// are the leaves of the serialized structure.
+#[derive(Default)]
+struct LeafRecorder {
+    bytes: Vec<u8>,
+    leaves: Vec<Range<usize>>,
+}
+
+impl Write for LeafRecorder {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let start = self.bytes.len();
+        self.bytes.extend_from_slice(buf);
+        self.leaves.push(start..self.bytes.len());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A serialized value, its leaves, and the name of the top-level field each leaf
+/// belongs to.
+struct Serialized {
+    bytes: Vec<u8>,
+    leaves: Vec<(&'static str, Range<usize>)>,
+}
+
+impl Serialized {
+    /// Serializes a value made of the given top-level `fields`, in order.
+    fn new(fields: &[(&'static str, &dyn Serialize)]) -> Self {
+        let mut recorder = LeafRecorder::default();
+        let mut leaves = vec![];
+        for (name, field) in fields {
+            let first = recorder.leaves.len();
+            field.serialize_into(&mut recorder);
+            leaves.extend(
+                recorder.leaves[first..]
+                    .iter()
+                    .map(|leaf| (*name, leaf.clone())),
+            );
+        }
+        Self {
+            bytes: recorder.bytes,
+            leaves,
+        }
+    }
+
+    /// The leaves to corrupt: at most [`LEAVES_PER_FIELD`] per top-level field. Leaves
+    /// that may be lengths or tags are skipped, unless they make up a whole top-level
+    /// integer field such as `trace_length`.
+    fn sample(&self) -> Vec<(&'static str, Range<usize>)> {
+        let mut sample = vec![];
+        let mut start = 0;
+        while start < self.leaves.len() {
+            let name = self.leaves[start].0;
+            let count = self.leaves[start..]
+                .iter()
+                .take_while(|(field, _)| *field == name)
+                .count();
+            let leaves: Vec<_> = self.leaves[start..start + count]
+                .iter()
+                .filter(|(_, leaf)| !self.may_be_shape(leaf) || (count == 1 && leaf.len() == 8))
+                .cloned()
+                .collect();
+            if leaves.len() <= LEAVES_PER_FIELD {
+                sample.extend(leaves);
+            } else {
+                sample.extend(
+                    (0..LEAVES_PER_FIELD)
+                        .map(|i| leaves[i * (leaves.len() - 1) / (LEAVES_PER_FIELD - 1)].clone()),
+                );
+            }
+            start += count;
+        }
+        sample
+    }
+
+    /// Whether `leaf` may be a length or an `Option` tag (it is a single byte, or a
+    /// small integer, or a small field element limb). Corrupting those shifts the rest
+    /// of the parse, and `ark-serialize` allocates garbage lengths up front, which can
+    /// abort the process.
+    fn may_be_shape(&self, leaf: &Range<usize>) -> bool {
+        match leaf.len() {
+            1 => true,
+            8 => {
+                let value = u64::from_le_bytes(self.bytes[leaf.clone()].try_into().unwrap());
+                value <= u32::MAX as u64
+            }
+            _ => false,
+        }
+    }
+
+    /// A copy of the serialized bytes with the lowest bit of `leaf` flipped. Leaves are
+    /// little-endian, so this changes an integer by one, or one limb of a field element.
+    fn corrupt(&self, leaf: &Range<usize>) -> Vec<u8> {
+        let mut bytes = self.bytes.clone();
+        bytes[leaf.start] ^= 1;
+        bytes
+    }
+}
+
+/// Object-safe serialization, so that the fields of a proof can be listed together.
+trait Serialize {
+    fn serialize_into(&self, writer: &mut LeafRecorder);
+}
+
+impl<T: CanonicalSerialize> Serialize for T {
+    fn serialize_into(&self, writer: &mut LeafRecorder) {
+        self.serialize_compressed(writer).unwrap();
+    }
+}
+
+fn deserialize<T: CanonicalDeserialize>(bytes: &[u8]) -> Option<T> {
+    T::deserialize_compressed(Cursor::new(bytes)).ok()
+}
+
+/// Asserts that `proof`, `commitments` and `program_io` verify, and that corrupting
+/// any one of them makes verification fail.
+pub fn assert_tampering_rejected<F, PCS, ProofTranscript>(
+    preprocessing: JoltVerifierPreprocessing<C, F, PCS, ProofTranscript>,
+    proof: RV32IJoltProof<F, PCS, ProofTranscript>,
+    commitments: JoltCommitments<PCS, ProofTranscript>,
+    program_io: JoltDevice,
+) where
+    F: JoltField,
+    PCS: CommitmentScheme<ProofTranscript, Field = F>,
+    ProofTranscript: Transcript,
+{
+    let serialized_proof = Serialized::new(&[
+        ("trace_length", &proof.trace_length),
+        ("bytecode", &proof.bytecode),
+        ("read_write_memory", &proof.read_write_memory),
+        ("instruction_lookups", &proof.instruction_lookups),
+        ("r1cs", &proof.r1cs),
+        ("precompiles", &proof.precompiles),
+        ("opening_proof", &proof.opening_proof),
+    ]);
+    let serialized_commitments = Serialized::new(&[
+        ("bytecode commitments", &commitments.bytecode),
+        (
+            "read_write_memory commitments",
+            &commitments.read_write_memory,
+        ),
+        (
+            "instruction_lookups commitments",
+            &commitments.instruction_lookups,
+        ),
+        (
+            "timestamp_range_check commitments",
+            &commitments.timestamp_range_check,
+        ),
+        ("r1cs commitments", &commitments.r1cs),
+    ]);
+    assert_eq!(
+        serialized_proof.bytes,
+        serialize(&proof),
+        "the proof fields are out of date"
+    );
+    assert_eq!(
+        serialized_commitments.bytes,
+        serialize(&commitments),
+        "the commitment fields are out of date"
+    );
+
+    let verify = |proof: &[u8], commitments: &[u8], program_io: JoltDevice| {
+        let (Some(proof), Some(commitments)) = (deserialize(proof), deserialize(commitments))
+        else {
+            return Ok(false);
+        };
+        panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            <RV32IJoltVM as Jolt<F, PCS, C, M, ProofTranscript>>::verify(
+                preprocessing.clone(),
+                proof,
+                commitments,
+                program_io,
+                None,
+            )
+            .is_ok()
+        }))
+    };
+    assert!(
+        matches!(
+            verify(
+                &serialized_proof.bytes,
+                &serialized_commitments.bytes,
+                program_io.clone()
+            ),
+            Ok(true)
+        ),
+        "the untampered proof does not verify"
+    );
+
+    // The verifier must return an error rather than panic on malformed proofs
+    let mut failures = vec![];
+    let mut check = |field: String, proof: &[u8], commitments: &[u8], program_io| match verify(
+        proof,
+        commitments,
+        program_io,
+    ) {
+        Ok(false) => {}
+        Ok(true) => failures.push(format!("verified with corrupted {field}")),
+        Err(_) => failures.push(format!("verifier panicked with corrupted {field}")),
+    };
+    for (field, leaf) in serialized_proof.sample() {
+        check(
+            format!("{field} (byte {})", leaf.start),
+            &serialized_proof.corrupt(&leaf),
+            &serialized_commitments.bytes,
+            program_io.clone(),
+        );
+    }
+    for (field, leaf) in serialized_commitments.sample() {
+        check(
+            format!("{field} (byte {})", leaf.start),
+            &serialized_proof.bytes,
+            &serialized_commitments.corrupt(&leaf),
+            program_io.clone(),
+        );
+    }
+    for i in 0..program_io.inputs.len() {
+        let mut io = program_io.clone();
+        io.inputs[i] ^= 1;
+        check(
+            format!("input byte {i}"),
+            &serialized_proof.bytes,
+            &serialized_commitments.bytes,
+            io,
+        );
+    }
+    for i in 0..program_io.outputs.len() {
+        let mut io = program_io.clone();
+        io.outputs[i] ^= 1;
+        check(
+            format!("output byte {i}"),
+            &serialized_proof.bytes,
+            &serialized_commitments.bytes,
+            io,
+        );
+    }
+    let mut io = program_io.clone();
+    io.panic = !io.panic;
+    check(
+        "panic bit".to_string(),
+        &serialized_proof.bytes,
+        &serialized_commitments.bytes,
+        io,
+    );
+    assert!(failures.is_empty(), "{failures:#?}");
+}
+
+fn serialize<T: CanonicalSerialize>(value: &T) -> Vec<u8> {
+    let mut bytes = vec![];
+    value.serialize_compressed(&mut bytes).unwrap();
+    bytes
+}
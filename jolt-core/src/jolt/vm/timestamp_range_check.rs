@@ -477,24 +477,26 @@ where
         }
     }
 
-    fn check_multiset_equality(_: &NoPreprocessing, multiset_hashes: &MultisetHashes<F>) {
+    fn check_multiset_equality(
+        _: &NoPreprocessing,
+        multiset_hashes: &MultisetHashes<F>,
+    ) -> Result<(), ProofVerifyError> {
         let num_memories = 2 * MEMORY_OPS_PER_INSTRUCTION;
         assert_eq!(multiset_hashes.read_hashes.len(), num_memories);
         assert_eq!(multiset_hashes.write_hashes.len(), num_memories);
         assert_eq!(multiset_hashes.final_hashes.len(), num_memories);
         assert_eq!(multiset_hashes.init_hashes.len(), 1);
 
-        (0..num_memories).into_par_iter().for_each(|i| {
+        (0..num_memories).into_par_iter().try_for_each(|i| {
             let read_hash = multiset_hashes.read_hashes[i];
             let write_hash = multiset_hashes.write_hashes[i];
             let init_hash = multiset_hashes.init_hashes[0];
             let final_hash = multiset_hashes.final_hashes[i];
-            assert_eq!(
-                init_hash * write_hash,
-                final_hash * read_hash,
-                "Multiset hashes don't match"
-            );
-        });
+            if init_hash * write_hash != final_hash * read_hash {
+                return Err(ProofVerifyError::MultisetHashMismatch);
+            }
+            Ok(())
+        })
     }
 
     fn protocol_name() -> &'static [u8] {
@@ -646,7 +648,7 @@ where
         _opening_accumulator: Option<&mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>>,
         _transcript: &mut ProofTranscript,
         _setup: Option<&PCS::Setup>,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         unimplemented!("init/final grand products are batched with read/write grand products")
     }
 }
@@ -778,7 +780,8 @@ where
         TimestampValidityProof::<F, PCS, ProofTranscript>::check_multiset_equality(
             &NoPreprocessing,
             &multiset_hashes,
-        );
+        )
+        .unwrap();
         multiset_hashes.append_to_transcript(transcript);
 
         let (batched_grand_product, r_grand_product) =
@@ -807,7 +810,7 @@ where
         TimestampValidityProof::<F, PCS, ProofTranscript>::check_multiset_equality(
             &NoPreprocessing,
             &self.multiset_hashes,
-        );
+        )?;
         self.multiset_hashes.append_to_transcript(transcript);
 
         let (read_write_hashes, init_final_hashes) =
@@ -820,13 +823,14 @@ where
             );
         let concatenated_hashes = [read_write_hashes, init_final_hashes].concat();
         let batch_size = concatenated_hashes.len();
-        let (grand_product_claim, r_grand_product) = BatchedDenseGrandProduct::verify_grand_product(
-            &self.batched_grand_product,
-            &concatenated_hashes,
-            Some(opening_accumulator),
-            transcript,
-            Some(generators),
-        );
+        let (grand_product_claim, r_grand_product) =
+            BatchedDenseGrandProduct::verify_grand_product(
+                &self.batched_grand_product,
+                &concatenated_hashes,
+                Some(opening_accumulator),
+                transcript,
+                Some(generators),
+            )?;
         let (r_batch_index, r_opening) =
             r_grand_product.split_at(batch_size.next_power_of_two().log_2());
 
@@ -905,7 +909,9 @@ where
             .zip(EqPolynomial::evals(r_batch_index).iter())
             .map(|(hash, eq_eval)| *hash * eq_eval)
             .sum();
-        assert_eq!(combined_hash, grand_product_claim);
+        if combined_hash != grand_product_claim {
+            return Err(ProofVerifyError::FingerprintMismatch);
+        }
 
         Ok(())
     }
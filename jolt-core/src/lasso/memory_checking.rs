@@ -265,7 +265,7 @@ where
         let (_, r_init_final_opening) =
             r_init_final.split_at(init_final_batch_size.next_power_of_two().log_2());
 
-        let (openings, exogenous_openings) = Self::compute_openings(
+        let (mut openings, exogenous_openings) = Self::compute_openings(
             preprocessing,
             opening_accumulator,
             polynomials,
@@ -274,6 +274,14 @@ where
             r_init_final_opening,
             transcript,
         );
+        Self::compute_preprocessing_openings(
+            preprocessing,
+            polynomials,
+            &mut openings,
+            opening_accumulator,
+            r_init_final_opening,
+            transcript,
+        );
 
         MemoryCheckingProof {
             multiset_hashes,
@@ -317,7 +325,7 @@ where
         let multiset_hashes =
             Self::uninterleave_hashes(preprocessing, read_write_hashes, init_final_hashes);
         #[cfg(test)]
-        Self::check_multiset_equality(preprocessing, &multiset_hashes);
+        Self::check_multiset_equality(preprocessing, &multiset_hashes).unwrap();
         multiset_hashes.append_to_transcript(transcript);
 
         let (read_write_grand_product, r_read_write) = read_write_circuit.prove_grand_product(
@@ -403,6 +411,23 @@ where
         (openings, exogenous_openings)
     }
 
+    /// Some preprocessing polynomials may be committed to as part of the verifier
+    /// preprocessing (see `JoltVerifierPreprocessing::commit_program`), in which case the
+    /// verifier does not hold them and their openings must be proven instead. This function
+    /// populates any such fields in `openings` and appends the corresponding claims to
+    /// `opening_accumulator`. Likewise for any polynomials in `polynomials` that are opened
+    /// at (a suffix of) `r_init_final` but are not among its `init_final_values`.
+    /// Mirrors `MemoryCheckingVerifier::verify_preprocessing_openings`.
+    fn compute_preprocessing_openings(
+        _preprocessing: &Self::Preprocessing,
+        _polynomials: &Self::Polynomials,
+        _openings: &mut Self::Openings,
+        _opening_accumulator: &mut ProverOpeningAccumulator<F, ProofTranscript>,
+        _r_init_final: &[F],
+        _transcript: &mut ProofTranscript,
+    ) {
+    }
+
     /// Constructs a batched grand product circuit for the read and write multisets associated
     /// with the given leaves. Also returns the corresponding multiset hashes for each memory.
     #[tracing::instrument(skip_all, name = "MemoryCheckingProver::read_write_grand_product")]
@@ -483,23 +508,22 @@ where
     fn check_multiset_equality(
         _preprocessing: &Self::Preprocessing,
         multiset_hashes: &MultisetHashes<F>,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         let num_memories = multiset_hashes.read_hashes.len();
         assert_eq!(multiset_hashes.final_hashes.len(), num_memories);
         assert_eq!(multiset_hashes.write_hashes.len(), num_memories);
         assert_eq!(multiset_hashes.init_hashes.len(), num_memories);
 
-        (0..num_memories).into_par_iter().for_each(|i| {
+        (0..num_memories).into_par_iter().try_for_each(|i| {
             let read_hash = multiset_hashes.read_hashes[i];
             let write_hash = multiset_hashes.write_hashes[i];
             let init_hash = multiset_hashes.init_hashes[i];
             let final_hash = multiset_hashes.final_hashes[i];
-            assert_eq!(
-                init_hash * write_hash,
-                final_hash * read_hash,
-                "Multiset hashes don't match"
-            );
-        });
+            if init_hash * write_hash != final_hash * read_hash {
+                return Err(ProofVerifyError::MultisetHashMismatch);
+            }
+            Ok(())
+        })
     }
 
     /// Computes the MLE of the leaves of the read, write, init, and final grand product circuits,
@@ -554,7 +578,7 @@ where
         let protocol_name = Self::protocol_name();
         transcript.append_message(protocol_name);
 
-        Self::check_multiset_equality(preprocessing, &proof.multiset_hashes);
+        Self::check_multiset_equality(preprocessing, &proof.multiset_hashes)?;
         proof.multiset_hashes.append_to_transcript(transcript);
 
         let (read_write_hashes, init_final_hashes) = Self::interleave(
@@ -572,7 +596,7 @@ where
             Some(opening_accumulator),
             transcript,
             Some(pcs_setup),
-        );
+        )?;
         // For a batch size of k, the first log2(k) elements of `r_read_write`/`r_init_final`
         // form the point at which the output layer's MLE is evaluated. The remaining elements
         // then form the point at which the leaf layer's polynomials are evaluated.
@@ -586,7 +610,7 @@ where
             Some(opening_accumulator),
             transcript,
             Some(pcs_setup),
-        );
+        )?;
         let (r_init_final_batch_index, r_init_final_opening) =
             r_init_final.split_at(init_final_batch_size.next_power_of_two().log_2());
 
@@ -614,6 +638,15 @@ where
             transcript,
         );
 
+        Self::verify_preprocessing_openings(
+            preprocessing,
+            commitments,
+            &proof.openings,
+            opening_accumulator,
+            r_init_final_opening,
+            transcript,
+        )?;
+
         Self::compute_verifier_openings(
             &mut proof.openings,
             preprocessing,
@@ -631,8 +664,20 @@ where
             &proof.exogenous_openings,
             &gamma,
             &tau,
-        );
+        )
+    }
 
+    /// Appends the claimed openings of any preprocessing polynomials committed to in the
+    /// verifier preprocessing to `opening_accumulator`, so that they are checked by the
+    /// batched opening proof. Mirrors `MemoryCheckingProver::compute_preprocessing_openings`.
+    fn verify_preprocessing_openings(
+        _preprocessing: &Self::Preprocessing,
+        _commitments: &Self::Commitments,
+        _openings: &Self::Openings,
+        _opening_accumulator: &mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>,
+        _r_init_final: &[F],
+        _transcript: &mut ProofTranscript,
+    ) -> Result<(), ProofVerifyError> {
         Ok(())
     }
 
@@ -684,7 +729,7 @@ where
         exogenous_openings: &Self::ExogenousOpenings,
         gamma: &F,
         tau: &F,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         let read_hashes: Vec<_> = Self::read_tuples(preprocessing, openings, exogenous_openings)
             .iter()
             .map(|tuple| Self::fingerprint(tuple, gamma, tau))
@@ -726,13 +771,19 @@ where
             .zip(EqPolynomial::evals(r_read_write_batch_index).iter())
             .map(|(hash, eq_eval)| *hash * eq_eval)
             .sum();
-        assert_eq!(combined_read_write_hash, read_write_claim);
+        if combined_read_write_hash != read_write_claim {
+            return Err(ProofVerifyError::FingerprintMismatch);
+        }
 
         let combined_init_final_hash: F = init_final_hashes
             .iter()
             .zip(EqPolynomial::evals(r_init_final_batch_index).iter())
             .map(|(hash, eq_eval)| *hash * eq_eval)
             .sum();
-        assert_eq!(combined_init_final_hash, init_final_claim);
+        if combined_init_final_hash != init_final_claim {
+            return Err(ProofVerifyError::FingerprintMismatch);
+        }
+
+        Ok(())
     }
 }
@@ -22,3 +22,13 @@ pub mod poly;
 pub mod r1cs;
 pub mod subprotocols;
 pub mod utils;
+
+/// Re-exports used by the exported `instruction_set!` and `subtable_enum!` macros,
+/// so that crates invoking them do not need these dependencies themselves.
+#[doc(hidden)]
+pub mod __private {
+    pub use rand::rngs::StdRng;
+    pub use rand::RngCore;
+    pub use serde;
+    pub use strum;
+}
@@ -38,8 +38,9 @@ where
     ) -> Result<Self, ProofVerifyError> {
         (bases.len() == scalars.len())
             .then(|| {
-                let max_num_bits =
-                    max_num_bits.unwrap_or((*scalars.iter().max().unwrap() as usize).num_bits());
+                let (bases, scalars) = trim_zero_tail(bases, scalars);
+                let max_num_bits = max_num_bits
+                    .unwrap_or_else(|| scalars.iter().max().map_or(0, |s| (*s as usize).num_bits()));
                 match max_num_bits {
                     0 => Self::zero(),
                     1 => msm_binary(bases, scalars),
@@ -58,8 +59,9 @@ where
     ) -> Result<Self, ProofVerifyError> {
         (bases.len() == scalars.len())
             .then(|| {
-                let max_num_bits =
-                    max_num_bits.unwrap_or((*scalars.iter().max().unwrap() as usize).num_bits());
+                let (bases, scalars) = trim_zero_tail(bases, scalars);
+                let max_num_bits = max_num_bits
+                    .unwrap_or_else(|| scalars.iter().max().map_or(0, |s| (*s as usize).num_bits()));
                 match max_num_bits {
                     0 => Self::zero(),
                     1 => msm_binary(bases, scalars),
@@ -79,8 +81,9 @@ where
     ) -> Result<Self, ProofVerifyError> {
         (bases.len() == scalars.len())
             .then(|| {
-                let max_num_bits =
-                    max_num_bits.unwrap_or((*scalars.iter().max().unwrap() as usize).num_bits());
+                let (bases, scalars) = trim_zero_tail(bases, scalars);
+                let max_num_bits = max_num_bits
+                    .unwrap_or_else(|| scalars.iter().max().map_or(0, |s| (*s as usize).num_bits()));
                 match max_num_bits {
                     0 => Self::zero(),
                     1 => msm_binary(bases, scalars),
@@ -100,8 +103,9 @@ where
     ) -> Result<Self, ProofVerifyError> {
         (bases.len() == scalars.len())
             .then(|| {
-                let max_num_bits =
-                    max_num_bits.unwrap_or((*scalars.iter().max().unwrap() as usize).num_bits());
+                let (bases, scalars) = trim_zero_tail(bases, scalars);
+                let max_num_bits = max_num_bits
+                    .unwrap_or_else(|| scalars.iter().max().map_or(0, |s| (*s as usize).num_bits()));
                 match max_num_bits {
                     0 => Self::zero(),
                     1 => msm_binary(bases, scalars),
@@ -121,8 +125,9 @@ where
     ) -> Result<Self, ProofVerifyError> {
         (bases.len() == scalars.len())
             .then(|| {
-                let max_num_bits =
-                    max_num_bits.unwrap_or((*scalars.iter().max().unwrap()).num_bits() as usize);
+                let (bases, scalars) = trim_zero_tail(bases, scalars);
+                let max_num_bits = max_num_bits
+                    .unwrap_or_else(|| scalars.iter().max().map_or(0, |s| s.num_bits() as usize));
                 match max_num_bits {
                     0 => Self::zero(),
                     1 => scalars
@@ -733,6 +738,16 @@ fn make_digits_bigint(
     })
 }
 
+/// Drops the trailing zero scalars (and their bases), which contribute nothing to the
+/// MSM. Polynomials over padded traces are mostly zero past the trace's last step.
+fn trim_zero_tail<'a, 'b, B, T: Zero>(bases: &'a [B], scalars: &'b [T]) -> (&'a [B], &'b [T]) {
+    let len = scalars
+        .iter()
+        .rposition(|s| !s.is_zero())
+        .map_or(0, |last| last + 1);
+    (&bases[..len], &scalars[..len])
+}
+
 /// Optimized implementation of multi-scalar multiplication.
 #[tracing::instrument(skip_all)]
 fn msm_medium<F, V, T>(
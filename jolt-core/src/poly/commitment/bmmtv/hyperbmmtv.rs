@@ -114,6 +114,10 @@ where
         SRS::trim(Arc::new(srs), powers_len - 1)
     }
 
+    fn verifier_setup((_, v_srs): &Self::Setup) -> Self::Setup {
+        (KZGProverKey::empty(), *v_srs)
+    }
+
     #[tracing::instrument(skip_all, name = "HyperBmmtv::commit")]
     fn commit(
         poly: &MultilinearPolynomial<Self::Field>,
@@ -13,6 +13,7 @@ pub trait CommitmentScheme<ProofTranscript: Transcript>: Clone + Sync + Send + '
     type Field: JoltField + Sized;
     type Setup: Clone + Sync + Send + CanonicalSerialize + CanonicalDeserialize;
     type Commitment: Default
+        + Clone
         + Debug
         + Sync
         + Send
@@ -24,6 +25,18 @@ pub trait CommitmentScheme<ProofTranscript: Transcript>: Clone + Sync + Send + '
     type BatchedProof: Sync + Send + CanonicalSerialize + CanonicalDeserialize;
 
     fn setup(max_len: usize) -> Self::Setup;
+    /// The part of `setup` needed to verify opening proofs, i.e. without the parts
+    /// only used to commit and prove (such as the powers of tau of a KZG commitment
+    /// key). Can be passed to `verify` in place of `setup`.
+    fn verifier_setup(setup: &Self::Setup) -> Self::Setup {
+        setup.clone()
+    }
+    /// Restricts `setup` to polynomials of length at most `max_len`, dropping the part
+    /// of the prover key beyond it. The verifier key is unchanged, so proofs made with
+    /// the trimmed setup verify against the original one.
+    fn trim(setup: &Self::Setup, _max_len: usize) -> Self::Setup {
+        setup.clone()
+    }
     fn commit(poly: &MultilinearPolynomial<Self::Field>, setup: &Self::Setup) -> Self::Commitment;
     fn batch_commit<U>(polys: &[U], gens: &Self::Setup) -> Vec<Self::Commitment>
     where
@@ -57,6 +70,29 @@ pub trait CommitmentScheme<ProofTranscript: Transcript>: Clone + Sync + Send + '
     fn protocol_name() -> &'static [u8];
 }
 
+/// A commitment scheme whose verifier ends with a check (e.g. a pairing equation)
+/// that can be deferred and combined with the final checks of other proofs.
+pub trait DeferredCommitmentScheme<ProofTranscript: Transcript>:
+    CommitmentScheme<ProofTranscript>
+{
+    type DeferredCheck: Clone + Debug + Sync + Send;
+
+    /// Like [`CommitmentScheme::verify`], but returns the final check rather than
+    /// performing it. The opening is valid iff the check passes.
+    fn verify_deferred(
+        proof: &Self::Proof,
+        setup: &Self::Setup,
+        transcript: &mut ProofTranscript,
+        opening_point: &[Self::Field],
+        opening: &Self::Field,
+        commitment: &Self::Commitment,
+    ) -> Result<Self::DeferredCheck, ProofVerifyError>;
+
+    /// Performs the given checks at once, returning whether (with overwhelming
+    /// probability) all of them pass.
+    fn batch_check(setup: &Self::Setup, checks: &[Self::DeferredCheck]) -> bool;
+}
+
 pub trait StreamingCommitmentScheme<ProofTranscript: Transcript>:
     CommitmentScheme<ProofTranscript>
 {
@@ -8,7 +8,7 @@
 //! (2) HyperKZG is specialized to use KZG as the univariate commitment scheme, so it includes several optimizations (both during the transformation of multilinear-to-univariate claims
 //! and within the KZG commitment scheme implementation itself).
 use super::{
-    commitment_scheme::{CommitmentScheme, StreamingCommitmentScheme},
+    commitment_scheme::{CommitmentScheme, DeferredCommitmentScheme, StreamingCommitmentScheme},
     kzg::{KZGProverKey, KZGVerifierKey, UnivariateKZG},
 };
 use crate::field::JoltField;
@@ -180,6 +180,20 @@ where
     (w, v)
 }
 
+/// The pairing equation e(lhs, vk.H) == e(rhs, vk.tau_H) that a HyperKZG proof
+/// reduces to, see [`HyperKZG::verify_deferred`].
+#[derive(Clone, Debug)]
+pub struct HyperKZGPairingCheck<P: Pairing> {
+    pub lhs: P::G1,
+    pub rhs: P::G1,
+}
+
+impl<P: Pairing> HyperKZGPairingCheck<P> {
+    pub fn holds(&self, vk: &HyperKZGVerifierKey<P>) -> bool {
+        P::multi_pairing([self.lhs, -self.rhs], [vk.kzg_vk.g2, vk.kzg_vk.beta_g2]).is_zero()
+    }
+}
+
 // vk is hashed in transcript already, so we do not add it here
 fn kzg_verify_batch<P: Pairing, ProofTranscript: Transcript>(
     vk: &HyperKZGVerifierKey<P>,
@@ -188,7 +202,7 @@ fn kzg_verify_batch<P: Pairing, ProofTranscript: Transcript>(
     u: &[P::ScalarField],
     v: &[Vec<P::ScalarField>],
     transcript: &mut ProofTranscript,
-) -> bool
+) -> HyperKZGPairingCheck<P>
 where
     <P as Pairing>::ScalarField: JoltField,
     <P as Pairing>::G1: Icicle,
@@ -264,7 +278,7 @@ where
     let R = W[0] + W[1] * d_0 + W[2] * d_1;
 
     // Check that e(L, vk.H) == e(R, vk.tau_H)
-    P::multi_pairing([L, -R], [vk.kzg_vk.g2, vk.kzg_vk.beta_g2]).is_zero()
+    HyperKZGPairingCheck { lhs: L, rhs: R }
 }
 
 #[derive(Clone)]
@@ -353,6 +367,24 @@ where
         pi: &HyperKZGProof<P>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
+        let pairing_check = Self::verify_deferred(vk, C, point, P_of_x, pi, transcript)?;
+        if !pairing_check.holds(vk) {
+            return Err(ProofVerifyError::InternalError);
+        }
+
+        Ok(())
+    }
+
+    /// Performs all checks of [`HyperKZG::verify`] except the final pairing check,
+    /// which is returned so that it can be batched with those of other proofs.
+    pub fn verify_deferred(
+        vk: &HyperKZGVerifierKey<P>,
+        C: &HyperKZGCommitment<P>,
+        point: &[P::ScalarField],
+        P_of_x: &P::ScalarField,
+        pi: &HyperKZGProof<P>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<HyperKZGPairingCheck<P>, ProofVerifyError> {
         let y = P_of_x;
 
         let ell = point.len();
@@ -397,12 +429,8 @@ where
             // check below requires it
         }
 
-        // Check commitments to (Y, ypos, yneg) are valid
-        if !kzg_verify_batch(vk, &com, &pi.w, &u, &pi.v, transcript) {
-            return Err(ProofVerifyError::InternalError);
-        }
-
-        Ok(())
+        // Commitments to (Y, ypos, yneg) are valid if the returned pairing check holds
+        Ok(kzg_verify_batch(vk, &com, &pi.w, &u, &pi.v, transcript))
     }
 }
 
@@ -427,6 +455,22 @@ where
         .trim(max_poly_len)
     }
 
+    fn verifier_setup(setup: &Self::Setup) -> Self::Setup {
+        let kzg_pk = KZGProverKey::empty();
+        (HyperKZGProverKey { kzg_pk }, setup.1)
+    }
+
+    fn trim(setup: &Self::Setup, max_len: usize) -> Self::Setup {
+        // A setup for polynomials of length `n` holds `n + 1` powers (see `SRS::trim`)
+        let supported_size = (max_len + 1).min(setup.0.kzg_pk.len());
+        if supported_size == setup.0.kzg_pk.len() {
+            // Nothing to drop, so share the SRS rather than copying it
+            return setup.clone();
+        }
+        let kzg_pk = setup.0.kzg_pk.trim(supported_size);
+        (HyperKZGProverKey { kzg_pk }, setup.1)
+    }
+
     #[tracing::instrument(skip_all, name = "HyperKZG::commit")]
     fn commit(poly: &MultilinearPolynomial<Self::Field>, setup: &Self::Setup) -> Self::Commitment {
         assert!(
@@ -496,6 +540,59 @@ where
     }
 }
 
+impl<P: Pairing, ProofTranscript: Transcript> DeferredCommitmentScheme<ProofTranscript>
+    for HyperKZG<P, ProofTranscript>
+where
+    <P as Pairing>::ScalarField: JoltField,
+    <P as Pairing>::G1: Icicle,
+{
+    type DeferredCheck = HyperKZGPairingCheck<P>;
+
+    fn verify_deferred(
+        proof: &Self::Proof,
+        setup: &Self::Setup,
+        transcript: &mut ProofTranscript,
+        opening_point: &[Self::Field],
+        opening: &Self::Field,
+        commitment: &Self::Commitment,
+    ) -> Result<Self::DeferredCheck, ProofVerifyError> {
+        HyperKZG::<P, ProofTranscript>::verify_deferred(
+            &setup.1,
+            commitment,
+            opening_point,
+            opening,
+            proof,
+            transcript,
+        )
+    }
+
+    /// Checks e(∑ᵢ ρⁱ⋅lhsᵢ, vk.H) == e(∑ᵢ ρⁱ⋅rhsᵢ, vk.tau_H) with a single
+    /// multi-pairing, where ρ is derived from all of the checks.
+    fn batch_check(setup: &Self::Setup, checks: &[Self::DeferredCheck]) -> bool {
+        if checks.is_empty() {
+            return true;
+        }
+
+        let mut transcript = ProofTranscript::new(b"HyperKZG batch check");
+        transcript.append_points(
+            &checks
+                .iter()
+                .flat_map(|check| [check.lhs, check.rhs])
+                .collect::<Vec<P::G1>>(),
+        );
+        let rho_powers: Vec<P::ScalarField> = transcript.challenge_scalar_powers(checks.len());
+
+        let (lhs, rhs) = checks
+            .iter()
+            .zip(rho_powers.iter())
+            .map(|(check, rho_power)| (check.lhs * rho_power, check.rhs * rho_power))
+            .fold((P::G1::zero(), P::G1::zero()), |(lhs, rhs), (l, r)| {
+                (lhs + l, rhs + r)
+            });
+        HyperKZGPairingCheck::<P> { lhs, rhs }.holds(&setup.1)
+    }
+}
+
 // #[derive(Clone, Debug)]
 pub struct HyperKZGState<'a, P: Pairing>
 where
@@ -677,6 +774,80 @@ mod tests {
         .is_err());
     }
 
+    #[test]
+    fn test_hyperkzg_batch_check() {
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+        let ell = 4;
+        let n = 1 << ell;
+
+        let srs = HyperKZGSRS::setup(&mut rng, n);
+        let setup: (HyperKZGProverKey<Bn254>, HyperKZGVerifierKey<Bn254>) = srs.trim(n);
+        let (pk, vk) = &setup;
+
+        let mut checks = vec![];
+        for _ in 0..3 {
+            let poly =
+                MultilinearPolynomial::from((0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>());
+            let point = (0..ell).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+            let eval = poly.evaluate(&point);
+            let C = HyperKZG::<_, KeccakTranscript>::commit(pk, &poly).unwrap();
+
+            let mut tr = KeccakTranscript::new(b"TestEval");
+            let proof = HyperKZG::open(pk, &poly, &point, &eval, &mut tr).unwrap();
+
+            let mut verifier_tr = KeccakTranscript::new(b"TestEval");
+            let check =
+                HyperKZG::verify_deferred(vk, &C, &point, &eval, &proof, &mut verifier_tr).unwrap();
+            assert!(check.holds(vk));
+            checks.push(check);
+        }
+        assert!(HyperKZG::<Bn254, KeccakTranscript>::batch_check(
+            &setup, &checks
+        ));
+
+        // A single invalid pairing check must fail the batch
+        let rhs = checks[1].rhs;
+        checks[1].lhs += rhs;
+        assert!(!checks[1].holds(vk));
+        assert!(!HyperKZG::<Bn254, KeccakTranscript>::batch_check(
+            &setup, &checks
+        ));
+    }
+
+    #[test]
+    fn test_hyperkzg_trim() {
+        type PCS = HyperKZG<Bn254, KeccakTranscript>;
+        let mut rng = rand_chacha::ChaCha20Rng::seed_from_u64(0);
+        let ell = 4;
+        let n = 1 << ell;
+
+        let setup = PCS::setup(4 * n);
+        let trimmed = PCS::trim(&setup, n);
+        assert_eq!(trimmed.0.kzg_pk.len(), n + 1);
+        assert!(trimmed.compressed_size() < setup.compressed_size());
+
+        // Commitments are unchanged and proofs made with the trimmed setup verify
+        // against the original one
+        let poly =
+            MultilinearPolynomial::from((0..n).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>());
+        let point = (0..ell).map(|_| Fr::rand(&mut rng)).collect::<Vec<_>>();
+        let eval = poly.evaluate(&point);
+        let C = <PCS as CommitmentScheme<KeccakTranscript>>::commit(&poly, &trimmed);
+        assert_eq!(
+            C,
+            <PCS as CommitmentScheme<KeccakTranscript>>::commit(&poly, &setup)
+        );
+
+        let mut tr = KeccakTranscript::new(b"TestEval");
+        let proof =
+            <PCS as CommitmentScheme<KeccakTranscript>>::prove(&trimmed, &poly, &point, &mut tr);
+        let mut tr = KeccakTranscript::new(b"TestEval");
+        assert!(<PCS as CommitmentScheme<KeccakTranscript>>::verify(
+            &proof, &setup, &mut tr, &point, &eval, &C
+        )
+        .is_ok());
+    }
+
     #[test]
     fn test_hyperkzg_large() {
         // test the hyperkzg prover and verifier with random instances (derived from a seed)
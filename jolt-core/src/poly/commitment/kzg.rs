@@ -163,6 +163,50 @@ where
         }
     }
 
+    /// A key supporting polynomials of length 0, standing in for the prover key in a
+    /// verifier setup.
+    pub fn empty() -> Self {
+        let srs = SRS {
+            g1_powers: vec![],
+            g2_powers: vec![],
+            g_products: vec![],
+            gpu_g1: None,
+        };
+        Self::new(Arc::new(srs), 0, 0)
+    }
+
+    /// A key supporting the first `supported_size` powers of this one. They are copied
+    /// into an SRS of their own, so that the rest of the SRS is neither kept alive nor
+    /// serialized with the trimmed key.
+    pub fn trim(&self, supported_size: usize) -> Self {
+        assert!(
+            supported_size <= self.supported_size,
+            "cannot trim a key of size {} to size {supported_size}",
+            self.supported_size
+        );
+        // `g_products` are sums of prefixes of the whole SRS
+        let g_products = if self.offset == 0 {
+            self.srs
+                .g_products
+                .iter()
+                .enumerate()
+                .take_while(|(i, _)| 1 << i <= supported_size)
+                .map(|(_, g_product)| *g_product)
+                .collect()
+        } else {
+            vec![]
+        };
+        let srs = SRS {
+            g1_powers: self.g1_powers()[..supported_size].to_vec(),
+            g2_powers: self.srs.g2_powers.clone(),
+            g_products,
+            gpu_g1: self
+                .gpu_g1()
+                .map(|gpu_g1| gpu_g1[..supported_size].to_vec()),
+        };
+        Self::new(Arc::new(srs), 0, supported_size)
+    }
+
     pub fn g1_powers(&self) -> &[P::G1Affine] {
         &self.srs.g1_powers[self.offset..self.offset + self.supported_size]
     }
@@ -19,7 +19,7 @@ pub struct MockCommitScheme<F: JoltField, ProofTranscript: Transcript> {
     _marker: PhantomData<(F, ProofTranscript)>,
 }
 
-#[derive(CanonicalSerialize, CanonicalDeserialize, Default, Debug, PartialEq)]
+#[derive(CanonicalSerialize, CanonicalDeserialize, Clone, Default, Debug, PartialEq)]
 pub struct MockCommitment<F: JoltField> {
     poly: MultilinearPolynomial<F>,
 }
@@ -93,8 +93,9 @@ where
         commitment: &Self::Commitment,
     ) -> Result<(), ProofVerifyError> {
         let evaluation = commitment.poly.evaluate(opening_point);
-        assert_eq!(evaluation, *opening);
-        assert_eq!(proof.opening_point, opening_point);
+        if evaluation != *opening || proof.opening_point != opening_point {
+            return Err(ProofVerifyError::InternalError);
+        }
         Ok(())
     }
 
@@ -72,7 +72,7 @@ pub struct ZeromorphVerifierKey<P: Pairing> {
     pub tau_N_max_sub_2_N: P::G2Affine,
 }
 
-#[derive(Debug, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Debug, Clone, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct ZeromorphCommitment<P: Pairing>(P::G1Affine);
 
 impl<P: Pairing> Default for ZeromorphCommitment<P> {
@@ -440,6 +440,14 @@ where
         .trim(max_poly_len)
     }
 
+    fn verifier_setup(setup: &Self::Setup) -> Self::Setup {
+        let prover_key = ZeromorphProverKey {
+            commit_pp: KZGProverKey::empty(),
+            open_pp: KZGProverKey::empty(),
+        };
+        (prover_key, setup.1)
+    }
+
     fn commit(poly: &MultilinearPolynomial<Self::Field>, setup: &Self::Setup) -> Self::Commitment {
         assert!(
             setup.0.commit_pp.g1_powers().len() > poly.len(),
@@ -65,6 +65,22 @@ impl<F: JoltField> MultilinearPolynomial<F> {
         }
     }
 
+    /// The length of the (unbound) polynomial's prefix ending at its last nonzero
+    /// coefficient. For polynomials over a padded trace, this is usually the length of
+    /// the unpadded trace.
+    pub fn nonzero_len(&self) -> usize {
+        debug_assert!(!self.is_bound());
+        let last_nonzero = match self {
+            MultilinearPolynomial::LargeScalars(poly) => poly.Z.iter().rposition(|c| !c.is_zero()),
+            MultilinearPolynomial::U8Scalars(poly) => poly.coeffs.iter().rposition(|c| *c != 0),
+            MultilinearPolynomial::U16Scalars(poly) => poly.coeffs.iter().rposition(|c| *c != 0),
+            MultilinearPolynomial::U32Scalars(poly) => poly.coeffs.iter().rposition(|c| *c != 0),
+            MultilinearPolynomial::U64Scalars(poly) => poly.coeffs.iter().rposition(|c| *c != 0),
+            MultilinearPolynomial::I64Scalars(poly) => poly.coeffs.iter().rposition(|c| *c != 0),
+        };
+        last_nonzero.map_or(0, |i| i + 1)
+    }
+
     pub fn get_num_vars(&self) -> usize {
         match self {
             MultilinearPolynomial::LargeScalars(poly) => poly.get_num_vars(),
@@ -143,31 +159,41 @@ impl<F: JoltField> MultilinearPolynomial<F> {
                         MultilinearPolynomial::U8Scalars(poly) => {
                             let poly_evals = &poly.coeffs[index..];
                             for (rlc, poly_eval) in chunk.iter_mut().zip(poly_evals.iter()) {
-                                *rlc += poly_eval.field_mul(*coeff);
+                                if *poly_eval != 0 {
+                                    *rlc += poly_eval.field_mul(*coeff);
+                                }
                             }
                         }
                         MultilinearPolynomial::U16Scalars(poly) => {
                             let poly_evals = &poly.coeffs[index..];
                             for (rlc, poly_eval) in chunk.iter_mut().zip(poly_evals.iter()) {
-                                *rlc += poly_eval.field_mul(*coeff);
+                                if *poly_eval != 0 {
+                                    *rlc += poly_eval.field_mul(*coeff);
+                                }
                             }
                         }
                         MultilinearPolynomial::U32Scalars(poly) => {
                             let poly_evals = &poly.coeffs[index..];
                             for (rlc, poly_eval) in chunk.iter_mut().zip(poly_evals.iter()) {
-                                *rlc += poly_eval.field_mul(*coeff);
+                                if *poly_eval != 0 {
+                                    *rlc += poly_eval.field_mul(*coeff);
+                                }
                             }
                         }
                         MultilinearPolynomial::U64Scalars(poly) => {
                             let poly_evals = &poly.coeffs[index..];
                             for (rlc, poly_eval) in chunk.iter_mut().zip(poly_evals.iter()) {
-                                *rlc += poly_eval.field_mul(*coeff);
+                                if *poly_eval != 0 {
+                                    *rlc += poly_eval.field_mul(*coeff);
+                                }
                             }
                         }
                         MultilinearPolynomial::I64Scalars(poly) => {
                             let poly_evals = &poly.coeffs[index..];
                             for (rlc, poly_eval) in chunk.iter_mut().zip(poly_evals.iter()) {
-                                *rlc += poly_eval.field_mul(*coeff);
+                                if *poly_eval != 0 {
+                                    *rlc += poly_eval.field_mul(*coeff);
+                                }
                             }
                         }
                     }
@@ -274,30 +300,35 @@ impl<F: JoltField> MultilinearPolynomial<F> {
                 .coeffs
                 .par_iter()
                 .zip_eq(other.par_iter())
+                .filter(|(a, _)| **a != 0)
                 .map(|(a, b)| a.field_mul(*b))
                 .sum(),
             MultilinearPolynomial::U16Scalars(poly) => poly
                 .coeffs
                 .par_iter()
                 .zip_eq(other.par_iter())
+                .filter(|(a, _)| **a != 0)
                 .map(|(a, b)| a.field_mul(*b))
                 .sum(),
             MultilinearPolynomial::U32Scalars(poly) => poly
                 .coeffs
                 .par_iter()
                 .zip_eq(other.par_iter())
+                .filter(|(a, _)| **a != 0)
                 .map(|(a, b)| a.field_mul(*b))
                 .sum(),
             MultilinearPolynomial::U64Scalars(poly) => poly
                 .coeffs
                 .par_iter()
                 .zip_eq(other.par_iter())
+                .filter(|(a, _)| **a != 0)
                 .map(|(a, b)| a.field_mul(*b))
                 .sum(),
             MultilinearPolynomial::I64Scalars(poly) => poly
                 .coeffs
                 .par_iter()
                 .zip_eq(other.par_iter())
+                .filter(|(a, _)| **a != 0)
                 .map(|(a, b)| a.field_mul(*b))
                 .sum(),
         }
@@ -789,4 +820,57 @@ mod tests {
             assert_eq!(field_elements.len(), len);
         }
     }
+
+    #[test]
+    fn zero_tail() {
+        use crate::msm::VariableBaseMSM;
+        use ark_bn254::G1Projective;
+        use ark_ec::CurveGroup;
+        use ark_std::{UniformRand, Zero};
+
+        let mut rng = ChaCha20Rng::seed_from_u64(0);
+        let len = 1 << 5;
+        let nonzero_len = 19;
+        let bases: Vec<_> = (0..len).map(|_| G1Projective::rand(&mut rng)).collect();
+        let affine_bases = G1Projective::normalize_batch(&bases);
+        let r: Vec<Fr> = (0..len).map(|_| Fr::random(&mut rng)).collect();
+
+        for max_num_bits in [8, 16, 32, 64, 256] {
+            // Zero the tail of a random polynomial, like a polynomial over a padded trace
+            let mut poly = random_poly(max_num_bits, len);
+            match &mut poly {
+                MultilinearPolynomial::U8Scalars(poly) => {
+                    poly.coeffs[nonzero_len - 1] |= 1;
+                    poly.coeffs[nonzero_len..].fill(0);
+                }
+                MultilinearPolynomial::U16Scalars(poly) => {
+                    poly.coeffs[nonzero_len - 1] |= 1;
+                    poly.coeffs[nonzero_len..].fill(0);
+                }
+                MultilinearPolynomial::U32Scalars(poly) => {
+                    poly.coeffs[nonzero_len - 1] |= 1;
+                    poly.coeffs[nonzero_len..].fill(0);
+                }
+                MultilinearPolynomial::U64Scalars(poly) => {
+                    poly.coeffs[nonzero_len - 1] |= 1;
+                    poly.coeffs[nonzero_len..].fill(0);
+                }
+                MultilinearPolynomial::LargeScalars(poly) => {
+                    poly.Z[nonzero_len..].fill(Fr::zero());
+                }
+                _ => unreachable!(),
+            }
+            let coeffs: Vec<Fr> = (0..len).map(|i| poly.get_coeff(i)).collect();
+            assert_eq!(poly.nonzero_len(), nonzero_len);
+
+            let dot_product: Fr = coeffs.iter().zip(r.iter()).map(|(c, r)| *c * r).sum();
+            assert_eq!(poly.dot_product(&r), dot_product);
+
+            let msm: G1Projective = bases.iter().zip(coeffs.iter()).map(|(b, c)| *b * *c).sum();
+            assert_eq!(
+                G1Projective::msm(&affine_bases, None, &poly, None).unwrap(),
+                msm
+            );
+        }
+    }
 }
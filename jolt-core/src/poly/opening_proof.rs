@@ -9,7 +9,7 @@ use rayon::prelude::*;
 use std::marker::PhantomData;
 
 use super::{
-    commitment::commitment_scheme::CommitmentScheme,
+    commitment::commitment_scheme::{CommitmentScheme, DeferredCommitmentScheme},
     dense_mlpoly::DensePolynomial,
     eq_poly::EqPolynomial,
     multilinear_polynomial::{BindingOrder, MultilinearPolynomial, PolynomialBinding},
@@ -542,6 +542,52 @@ where
         reduced_opening_proof: &ReducedOpeningProof<F, PCS, ProofTranscript>,
         transcript: &mut ProofTranscript,
     ) -> Result<(), ProofVerifyError> {
+        let (joint_commitment, r_sumcheck, joint_claim) =
+            self.reduce(reduced_opening_proof, transcript)?;
+
+        // Verify the reduced opening proof
+        PCS::verify(
+            &reduced_opening_proof.joint_opening_proof,
+            pcs_setup,
+            transcript,
+            &r_sumcheck,
+            &joint_claim,
+            &joint_commitment,
+        )
+    }
+
+    /// Like [`VerifierOpeningAccumulator::reduce_and_verify`], but returns the final
+    /// check of the reduced opening proof instead of performing it, so that it can be
+    /// batched with those of other proofs.
+    pub fn reduce_and_verify_deferred(
+        &self,
+        pcs_setup: &PCS::Setup,
+        reduced_opening_proof: &ReducedOpeningProof<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<PCS::DeferredCheck, ProofVerifyError>
+    where
+        PCS: DeferredCommitmentScheme<ProofTranscript>,
+    {
+        let (joint_commitment, r_sumcheck, joint_claim) =
+            self.reduce(reduced_opening_proof, transcript)?;
+
+        PCS::verify_deferred(
+            &reduced_opening_proof.joint_opening_proof,
+            pcs_setup,
+            transcript,
+            &r_sumcheck,
+            &joint_claim,
+            &joint_commitment,
+        )
+    }
+
+    /// Verifies the sumcheck reducing the accumulated openings to a single opening,
+    /// returning the joint commitment, opening point and claim of that opening.
+    fn reduce(
+        &self,
+        reduced_opening_proof: &ReducedOpeningProof<F, PCS, ProofTranscript>,
+        transcript: &mut ProofTranscript,
+    ) -> Result<(PCS::Commitment, Vec<F>, F), ProofVerifyError> {
         let num_sumcheck_rounds = self
             .openings
             .iter()
@@ -614,15 +660,7 @@ where
             })
             .sum();
 
-        // Verify the reduced opening proof
-        PCS::verify(
-            &reduced_opening_proof.joint_opening_proof,
-            pcs_setup,
-            transcript,
-            &r_sumcheck,
-            &joint_claim,
-            &joint_commitment,
-        )
+        Ok((joint_commitment, r_sumcheck, joint_claim))
     }
 
     /// Verifies the sumcheck proven in `ProverOpeningAccumulator::prove_batch_opening_reduction`.
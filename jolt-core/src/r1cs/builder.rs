@@ -9,6 +9,7 @@ use crate::{
     jolt::vm::JoltPolynomials,
     poly::spartan_interleaved_poly::SpartanInterleavedPolynomial,
     r1cs::key::{SparseConstraints, UniformR1CS},
+    utils::errors::WitnessError,
 };
 use ark_ff::One;
 use rayon::prelude::*;
@@ -27,7 +28,6 @@ pub struct Constraint {
 }
 
 impl Constraint {
-    #[cfg(test)]
     pub(crate) fn pretty_fmt<const C: usize, I: ConstraintInput, F: JoltField>(
         &self,
         f: &mut String,
@@ -63,7 +63,7 @@ impl Constraint {
                         f,
                         "    {:?} = {}",
                         I::from_index::<C>(var_index),
-                        flattened_polynomials[var_index].get_coeff(step_index)
+                        flattened_polynomials[var_index].get_coeff_i128(step_index)
                     )?;
                 }
                 Variable::Constant => {}
@@ -487,6 +487,62 @@ impl OffsetEqConstraint {
             (LC::new(vec![]), false),
         )
     }
+
+    /// Formats the constraint as `cond ⇒ a == b`, followed by the values its variables
+    /// take at `step_index`. Primed linear combinations and variables refer to the next
+    /// step; past the last step, they are zero (see `eval_offset_lc`).
+    pub(crate) fn pretty_fmt<const C: usize, I: ConstraintInput, F: JoltField>(
+        &self,
+        f: &mut String,
+        flattened_polynomials: &[&MultilinearPolynomial<F>],
+        step_index: usize,
+        next_step_index: Option<usize>,
+    ) -> std::fmt::Result {
+        use std::fmt::Write as _;
+
+        let offset_lc_fmt = |f: &mut String, (offset, lc): &OffsetLC| -> std::fmt::Result {
+            lc.pretty_fmt::<C, I>(f)?;
+            if *offset {
+                write!(f, "'")?;
+            }
+            Ok(())
+        };
+        offset_lc_fmt(f, &self.cond)?;
+        write!(f, " ⇒ ")?;
+        offset_lc_fmt(f, &self.a)?;
+        write!(f, " == ")?;
+        offset_lc_fmt(f, &self.b)?;
+        writeln!(f)?;
+
+        let mut variables = Vec::new();
+        for (offset, lc) in [&self.cond, &self.a, &self.b] {
+            for term in lc.terms() {
+                if let Variable::Input(var_index) | Variable::Auxiliary(var_index) = term.0 {
+                    if !variables.contains(&(var_index, *offset)) {
+                        variables.push((var_index, *offset));
+                    }
+                }
+            }
+        }
+
+        for (var_index, offset) in variables {
+            let value = match (offset, next_step_index) {
+                (false, _) => flattened_polynomials[var_index].get_coeff_i128(step_index),
+                (true, Some(next_step_index)) => {
+                    flattened_polynomials[var_index].get_coeff_i128(next_step_index)
+                }
+                (true, None) => 0,
+            };
+            let prime = if offset { "'" } else { "" };
+            writeln!(
+                f,
+                "    {:?}{prime} = {value}",
+                I::from_index::<C>(var_index)
+            )?;
+        }
+
+        Ok(())
+    }
 }
 
 pub(crate) fn eval_offset_lc<F: JoltField>(
@@ -621,6 +677,77 @@ impl<const C: usize, F: JoltField, I: ConstraintInput> CombinedUniformBuilder<C,
         CrossStepR1CS { constraints }
     }
 
+    /// Checks every uniform and cross-step constraint against the witness, returning the
+    /// first violated constraint at the earliest step. Like the Spartan prover, treats
+    /// terms referring to the step after the last one as zero.
+    #[tracing::instrument(skip_all)]
+    pub fn check_witness(
+        &self,
+        flattened_polynomials: &[&MultilinearPolynomial<F>], // N variables of (S steps)
+    ) -> Result<(), WitnessError> {
+        let num_steps = flattened_polynomials[0].len();
+        let violation = (0..num_steps).into_par_iter().find_map_first(|step| {
+            let uniform_violation = self
+                .uniform_builder
+                .constraints
+                .iter()
+                .position(|constraint| {
+                    let [a, b, c] = [&constraint.a, &constraint.b, &constraint.c]
+                        .map(|lc| F::from_i128(lc.evaluate_row(flattened_polynomials, step)));
+                    a * b != c
+                })
+                .map(|constraint| WitnessError::UniformConstraint { step, constraint });
+
+            let next_step = (step + 1 < num_steps).then_some(step + 1);
+            let cross_step_violation = || {
+                self.offset_equality_constraints
+                    .iter()
+                    .position(|constraint| {
+                        let [cond, a, b] = [&constraint.cond, &constraint.a, &constraint.b]
+                            .map(|lc| eval_offset_lc(lc, flattened_polynomials, step, next_step));
+                        cond != 0 && a != b
+                    })
+                    .map(|constraint| WitnessError::CrossStepConstraint { step, constraint })
+            };
+
+            uniform_violation.or_else(cross_step_violation)
+        });
+
+        match violation {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Formats the R1CS constraint violated in `error` (see [`Self::check_witness`]) along
+    /// with the values its variables take at the failing step, or returns `None` if
+    /// `error` is not an R1CS error.
+    pub fn pretty_fmt_violation(
+        &self,
+        error: &WitnessError,
+        flattened_polynomials: &[&MultilinearPolynomial<F>],
+    ) -> Option<String> {
+        let mut f = String::new();
+        match *error {
+            WitnessError::UniformConstraint { step, constraint } => self
+                .uniform_builder
+                .constraints[constraint]
+                .pretty_fmt::<C, I, F>(&mut f, flattened_polynomials, step),
+            WitnessError::CrossStepConstraint { step, constraint } => {
+                let next_step = (step + 1 < flattened_polynomials[0].len()).then_some(step + 1);
+                self.offset_equality_constraints[constraint].pretty_fmt::<C, I, F>(
+                    &mut f,
+                    flattened_polynomials,
+                    step,
+                    next_step,
+                )
+            }
+            _ => return None,
+        }
+        .expect("formatting into a String cannot fail");
+        Some(f)
+    }
+
     #[tracing::instrument(skip_all)]
     pub fn compute_spartan_Az_Bz_Cz(
         &self,
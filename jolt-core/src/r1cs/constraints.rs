@@ -1,9 +1,11 @@
 use common::{constants::REGISTER_COUNT, rv_trace::CircuitFlags};
+use std::marker::PhantomData;
 use strum::IntoEnumIterator;
 
 use crate::{
     field::JoltField,
     jolt::{
+        instruction::JoltInstructionSet,
         instruction::{
             add::ADDInstruction, mul::MULInstruction, mulhu::MULHUInstruction,
             mulu::MULUInstruction, sll::SLLInstruction, sra::SRAInstruction, srl::SRLInstruction,
@@ -13,6 +15,7 @@ use crate::{
         },
         vm::rv32i_vm::RV32I,
     },
+    utils::math::Math,
 };
 
 use super::{
@@ -23,8 +26,6 @@ use super::{
 
 pub const PC_START_ADDRESS: i64 = 0x80000000;
 const PC_NOOP_SHIFT: i64 = 4;
-const LOG_M: usize = 16;
-const OPERAND_SIZE: usize = LOG_M / 2;
 
 pub trait R1CSConstraints<const C: usize, F: JoltField> {
     type Inputs: ConstraintInput;
@@ -55,184 +56,197 @@ pub trait R1CSConstraints<const C: usize, F: JoltField> {
     fn cross_step_constraints() -> Vec<OffsetEqConstraint>;
 }
 
-pub struct JoltRV32IMConstraints;
-impl<const C: usize, F: JoltField> R1CSConstraints<C, F> for JoltRV32IMConstraints {
-    type Inputs = JoltR1CSInputs;
+/// RV32IM constraints for instruction lookups decomposed into `C` chunks of
+/// `log2(M)` bits each, with an instruction flag for each instruction in `I`
+/// (a superset of `RV32I`, see `rv32i_instruction_set!`).
+pub struct JoltRV32IMConstraints<const M: usize, I = RV32I>(PhantomData<I>);
+impl<const C: usize, const M: usize, F, I> R1CSConstraints<C, F> for JoltRV32IMConstraints<M, I>
+where
+    F: JoltField,
+    I: JoltInstructionSet + From<RV32I> + 'static,
+{
+    type Inputs = JoltR1CSInputs<I>;
 
     fn uniform_constraints(cs: &mut R1CSBuilder<C, F, Self::Inputs>, memory_start: u64) {
-        for flag in RV32I::iter() {
-            cs.constrain_binary(JoltR1CSInputs::InstructionFlags(flag));
+        let log_M = M.log_2();
+        let operand_size = log_M / 2;
+        // Instruction flag of an RV32I instruction
+        let rv32i_flag =
+            |instruction: RV32I| JoltR1CSInputs::<I>::InstructionFlags(instruction.into());
+
+        for flag in I::iter() {
+            cs.constrain_binary(JoltR1CSInputs::<I>::InstructionFlags(flag));
         }
         for flag in CircuitFlags::iter() {
-            cs.constrain_binary(JoltR1CSInputs::OpFlags(flag));
+            cs.constrain_binary(JoltR1CSInputs::<I>::OpFlags(flag));
         }
 
         let flags = CircuitFlags::iter()
-            .map(|flag| JoltR1CSInputs::OpFlags(flag).into())
-            .chain(RV32I::iter().map(|flag| JoltR1CSInputs::InstructionFlags(flag).into()))
+            .map(|flag| JoltR1CSInputs::<I>::OpFlags(flag).into())
+            .chain(I::iter().map(|flag| JoltR1CSInputs::<I>::InstructionFlags(flag).into()))
             .collect();
-        cs.constrain_pack_be(flags, JoltR1CSInputs::Bytecode_Bitflags, 1);
+        cs.constrain_pack_be(flags, JoltR1CSInputs::<I>::Bytecode_Bitflags, 1);
 
         let real_pc =
-            4i64 * JoltR1CSInputs::Bytecode_ELFAddress + (PC_START_ADDRESS - PC_NOOP_SHIFT);
+            4i64 * JoltR1CSInputs::<I>::Bytecode_ELFAddress + (PC_START_ADDRESS - PC_NOOP_SHIFT);
         let x = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::LeftLookupOperand),
-            JoltR1CSInputs::OpFlags(CircuitFlags::LeftOperandIsPC),
+            JoltR1CSInputs::<I>::Aux(AuxVariable::LeftLookupOperand),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::LeftOperandIsPC),
             real_pc,
-            JoltR1CSInputs::RS1_Read,
+            JoltR1CSInputs::<I>::RS1_Read,
         );
         let y = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::RightLookupOperand),
-            JoltR1CSInputs::OpFlags(CircuitFlags::RightOperandIsImm),
-            JoltR1CSInputs::Bytecode_Imm,
-            JoltR1CSInputs::RS2_Read,
+            JoltR1CSInputs::<I>::Aux(AuxVariable::RightLookupOperand),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::RightOperandIsImm),
+            JoltR1CSInputs::<I>::Bytecode_Imm,
+            JoltR1CSInputs::<I>::RS2_Read,
         );
 
-        let is_load_or_store = JoltR1CSInputs::OpFlags(CircuitFlags::Load)
-            + JoltR1CSInputs::OpFlags(CircuitFlags::Store);
+        let is_load_or_store = JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Load)
+            + JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Store);
         let memory_start: i64 = memory_start.try_into().unwrap();
         cs.constrain_eq_conditional(
             is_load_or_store,
-            JoltR1CSInputs::RS1_Read + JoltR1CSInputs::Bytecode_Imm,
-            4 * JoltR1CSInputs::RAM_Address + memory_start - 4 * REGISTER_COUNT as i64,
+            JoltR1CSInputs::<I>::RS1_Read + JoltR1CSInputs::<I>::Bytecode_Imm,
+            4 * JoltR1CSInputs::<I>::RAM_Address + memory_start - 4 * REGISTER_COUNT as i64,
         );
 
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Load),
-            JoltR1CSInputs::RAM_Read,
-            JoltR1CSInputs::RAM_Write,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Load),
+            JoltR1CSInputs::<I>::RAM_Read,
+            JoltR1CSInputs::<I>::RAM_Write,
         );
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Load),
-            JoltR1CSInputs::RAM_Read,
-            JoltR1CSInputs::RD_Write,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Load),
+            JoltR1CSInputs::<I>::RAM_Read,
+            JoltR1CSInputs::<I>::RD_Write,
         );
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Store),
-            JoltR1CSInputs::RS2_Read,
-            JoltR1CSInputs::RAM_Write,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Store),
+            JoltR1CSInputs::<I>::RS2_Read,
+            JoltR1CSInputs::<I>::RAM_Write,
         );
 
         let query_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksQuery(i).to_index::<C>()))
+            .map(|i| Variable::Input(JoltR1CSInputs::<I>::ChunksQuery(i).to_index::<C>()))
             .collect();
         let packed_query =
-            R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(query_chunks.clone(), LOG_M);
+            R1CSBuilder::<C, F, JoltR1CSInputs<I>>::pack_be(query_chunks.clone(), log_M);
 
         // For the `AssertHalfwordAlignmentInstruction` lookups, we add the `rs1` and `imm` values
         // to obtain the memory address being accessed.
-        let add_operands = JoltR1CSInputs::InstructionFlags(ADDInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(
-                AssertHalfwordAlignmentInstruction::<32>::default().into(),
-            );
+        let add_operands = rv32i_flag(ADDInstruction::default().into())
+            + rv32i_flag(AssertHalfwordAlignmentInstruction::<32>::default().into());
         cs.constrain_eq_conditional(add_operands, packed_query.clone(), x + y);
         // Converts from unsigned to twos-complement representation
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::InstructionFlags(SUBInstruction::default().into()),
+            rv32i_flag(SUBInstruction::default().into()),
             packed_query.clone(),
             x - y + (0xffffffffi64 + 1),
         );
-        let is_mul = JoltR1CSInputs::InstructionFlags(MULInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(MULUInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(MULHUInstruction::default().into());
+        let is_mul = rv32i_flag(MULInstruction::default().into())
+            + rv32i_flag(MULUInstruction::default().into())
+            + rv32i_flag(MULHUInstruction::default().into());
         let product = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::Product),
-            JoltR1CSInputs::RS1_Read,
-            JoltR1CSInputs::RS2_Read,
+            JoltR1CSInputs::<I>::Aux(AuxVariable::Product),
+            JoltR1CSInputs::<I>::RS1_Read,
+            JoltR1CSInputs::<I>::RS2_Read,
         );
         cs.constrain_eq_conditional(is_mul, packed_query.clone(), product);
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::InstructionFlags(MOVSIGNInstruction::default().into())
-                + JoltR1CSInputs::InstructionFlags(MOVEInstruction::default().into()),
+            rv32i_flag(MOVSIGNInstruction::default().into())
+                + rv32i_flag(MOVEInstruction::default().into()),
             packed_query.clone(),
             x,
         );
 
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::Assert),
-            JoltR1CSInputs::LookupOutput,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Assert),
+            JoltR1CSInputs::<I>::LookupOutput,
             1,
         );
 
         let x_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksX(i).to_index::<C>()))
+            .map(|i| Variable::Input(JoltR1CSInputs::<I>::ChunksX(i).to_index::<C>()))
             .collect();
         let y_chunks: Vec<Variable> = (0..C)
-            .map(|i| Variable::Input(JoltR1CSInputs::ChunksY(i).to_index::<C>()))
+            .map(|i| Variable::Input(JoltR1CSInputs::<I>::ChunksY(i).to_index::<C>()))
             .collect();
-        let x_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(x_chunks.clone(), OPERAND_SIZE);
-        let y_concat = R1CSBuilder::<C, F, JoltR1CSInputs>::pack_be(y_chunks.clone(), OPERAND_SIZE);
+        let x_concat =
+            R1CSBuilder::<C, F, JoltR1CSInputs<I>>::pack_be(x_chunks.clone(), operand_size);
+        let y_concat =
+            R1CSBuilder::<C, F, JoltR1CSInputs<I>>::pack_be(y_chunks.clone(), operand_size);
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
             x_concat,
             x,
         );
         cs.constrain_eq_conditional(
-            JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
             y_concat,
             y,
         );
 
         // if is_shift ? chunks_query[i] == zip(chunks_x[i], chunks_y[C-1]) : chunks_query[i] == zip(chunks_x[i], chunks_y[i])
-        let is_shift = JoltR1CSInputs::InstructionFlags(SLLInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(SRLInstruction::default().into())
-            + JoltR1CSInputs::InstructionFlags(SRAInstruction::default().into());
+        let is_shift = rv32i_flag(SLLInstruction::default().into())
+            + rv32i_flag(SRLInstruction::default().into())
+            + rv32i_flag(SRAInstruction::default().into());
         for i in 0..C {
             let relevant_chunk_y = cs.allocate_if_else(
-                JoltR1CSInputs::Aux(AuxVariable::RelevantYChunk(i)),
+                JoltR1CSInputs::<I>::Aux(AuxVariable::RelevantYChunk(i)),
                 is_shift.clone(),
                 y_chunks[C - 1],
                 y_chunks[i],
             );
             cs.constrain_eq_conditional(
-                JoltR1CSInputs::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
+                JoltR1CSInputs::<I>::OpFlags(CircuitFlags::ConcatLookupQueryChunks),
                 query_chunks[i],
-                x_chunks[i] * (1i64 << 8) + relevant_chunk_y,
+                x_chunks[i] * (1i64 << operand_size) + relevant_chunk_y,
             );
         }
 
         // if (rd != 0 && update_rd_with_lookup_output == 1) constrain(rd_val == LookupOutput)
         let rd_nonzero_and_lookup_to_rd = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::WriteLookupOutputToRD),
-            JoltR1CSInputs::Bytecode_RD,
-            JoltR1CSInputs::OpFlags(CircuitFlags::WriteLookupOutputToRD),
+            JoltR1CSInputs::<I>::Aux(AuxVariable::WriteLookupOutputToRD),
+            JoltR1CSInputs::<I>::Bytecode_RD,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::WriteLookupOutputToRD),
         );
         cs.constrain_eq_conditional(
             rd_nonzero_and_lookup_to_rd,
-            JoltR1CSInputs::RD_Write,
-            JoltR1CSInputs::LookupOutput,
+            JoltR1CSInputs::<I>::RD_Write,
+            JoltR1CSInputs::<I>::LookupOutput,
         );
         // if (rd != 0 && is_jump_instr == 1) constrain(rd_val == 4 * PC)
         let rd_nonzero_and_jmp = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::WritePCtoRD),
-            JoltR1CSInputs::Bytecode_RD,
-            JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
+            JoltR1CSInputs::<I>::Aux(AuxVariable::WritePCtoRD),
+            JoltR1CSInputs::<I>::Bytecode_RD,
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Jump),
         );
         cs.constrain_eq_conditional(
             rd_nonzero_and_jmp,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
-            JoltR1CSInputs::RD_Write,
+            4 * JoltR1CSInputs::<I>::Bytecode_ELFAddress + PC_START_ADDRESS,
+            JoltR1CSInputs::<I>::RD_Write,
         );
 
         let next_pc_jump = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::NextPCJump),
-            JoltR1CSInputs::OpFlags(CircuitFlags::Jump),
-            JoltR1CSInputs::LookupOutput + 4,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS + 4
-                - 4 * JoltR1CSInputs::OpFlags(CircuitFlags::DoNotUpdatePC),
+            JoltR1CSInputs::<I>::Aux(AuxVariable::NextPCJump),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Jump),
+            JoltR1CSInputs::<I>::LookupOutput + 4,
+            4 * JoltR1CSInputs::<I>::Bytecode_ELFAddress + PC_START_ADDRESS + 4
+                - 4 * JoltR1CSInputs::<I>::OpFlags(CircuitFlags::DoNotUpdatePC),
         );
 
         let should_branch = cs.allocate_prod(
-            JoltR1CSInputs::Aux(AuxVariable::ShouldBranch),
-            JoltR1CSInputs::OpFlags(CircuitFlags::Branch),
-            JoltR1CSInputs::LookupOutput,
+            JoltR1CSInputs::<I>::Aux(AuxVariable::ShouldBranch),
+            JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Branch),
+            JoltR1CSInputs::<I>::LookupOutput,
         );
         let _next_pc = cs.allocate_if_else(
-            JoltR1CSInputs::Aux(AuxVariable::NextPC),
+            JoltR1CSInputs::<I>::Aux(AuxVariable::NextPC),
             should_branch,
-            4 * JoltR1CSInputs::Bytecode_ELFAddress
+            4 * JoltR1CSInputs::<I>::Bytecode_ELFAddress
                 + PC_START_ADDRESS
-                + JoltR1CSInputs::Bytecode_Imm,
+                + JoltR1CSInputs::<I>::Bytecode_Imm,
             next_pc_jump,
         );
     }
@@ -241,10 +255,10 @@ impl<const C: usize, F: JoltField> R1CSConstraints<C, F> for JoltRV32IMConstrain
         // If the next instruction's ELF address is not zero (i.e. it's
         // not padding), then check the PC update.
         let pc_constraint = OffsetEqConstraint::new(
-            (JoltR1CSInputs::Bytecode_ELFAddress, true),
-            (JoltR1CSInputs::Aux(AuxVariable::NextPC), false),
+            (JoltR1CSInputs::<I>::Bytecode_ELFAddress, true),
+            (JoltR1CSInputs::<I>::Aux(AuxVariable::NextPC), false),
             (
-                4 * JoltR1CSInputs::Bytecode_ELFAddress + PC_START_ADDRESS,
+                4 * JoltR1CSInputs::<I>::Bytecode_ELFAddress + PC_START_ADDRESS,
                 true,
             ),
         );
@@ -257,9 +271,9 @@ impl<const C: usize, F: JoltField> R1CSConstraints<C, F> for JoltRV32IMConstrain
         // trace should always end with some return handling, which shouldn't involve
         // any virtual sequences.
         let virtual_sequence_constraint = OffsetEqConstraint::new(
-            (JoltR1CSInputs::OpFlags(CircuitFlags::Virtual), false),
-            (JoltR1CSInputs::Bytecode_A, true),
-            (JoltR1CSInputs::Bytecode_A + 1, false),
+            (JoltR1CSInputs::<I>::OpFlags(CircuitFlags::Virtual), false),
+            (JoltR1CSInputs::<I>::Bytecode_A, true),
+            (JoltR1CSInputs::<I>::Bytecode_A + 1, false),
         );
 
         vec![pc_constraint, virtual_sequence_constraint]
@@ -282,9 +282,11 @@ pub trait ConstraintInput: Clone + Copy + Debug + PartialEq + Sync + Send + 'sta
     ) -> &'a mut T;
 }
 
+/// Jolt's R1CS constraint inputs, with an instruction flag for each instruction
+/// in the instruction set `I`.
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, Debug, PartialEq, EnumIter)]
-pub enum JoltR1CSInputs {
+pub enum JoltR1CSInputs<I: JoltInstructionSet = RV32I> {
     Bytecode_A, // Virtual address
     // Bytecode_V
     Bytecode_ELFAddress,
@@ -308,7 +310,7 @@ pub enum JoltR1CSInputs {
     ChunksY(usize),
 
     OpFlags(CircuitFlags),
-    InstructionFlags(RV32I),
+    InstructionFlags(I),
     Aux(AuxVariable),
 }
 
@@ -326,26 +328,34 @@ pub enum AuxVariable {
     NextPC,
 }
 
-impl_r1cs_input_lc_conversions!(JoltR1CSInputs, 4);
-impl ConstraintInput for JoltR1CSInputs {
+// The conversions index inputs with `C = 4`, which is correct for any `C` (see
+// `flatten`) except for the inputs of chunks `i >= 4`, which must be indexed with
+// `to_index::<C>()` instead.
+impl_r1cs_input_lc_conversions!([I: JoltInstructionSet + 'static,] JoltR1CSInputs<I>, 4);
+impl<I: JoltInstructionSet + 'static> ConstraintInput for JoltR1CSInputs<I> {
+    /// The inputs that don't depend on `C` come first, followed by the inputs of each
+    /// chunk in turn, so that an input's index is the same for every `C` with that chunk.
     fn flatten<const C: usize>() -> Vec<Self> {
-        JoltR1CSInputs::iter()
+        let chunk_inputs = (0..C).flat_map(|i| {
+            [
+                Self::ChunksQuery(i),
+                Self::ChunksX(i),
+                Self::ChunksY(i),
+                Self::Aux(AuxVariable::RelevantYChunk(i)),
+            ]
+        });
+        Self::iter()
             .flat_map(|variant| match variant {
-                Self::ChunksQuery(_) => (0..C).map(Self::ChunksQuery).collect(),
-                Self::ChunksX(_) => (0..C).map(Self::ChunksX).collect(),
-                Self::ChunksY(_) => (0..C).map(Self::ChunksY).collect(),
+                Self::ChunksQuery(_) | Self::ChunksX(_) | Self::ChunksY(_) => vec![],
                 Self::OpFlags(_) => CircuitFlags::iter().map(Self::OpFlags).collect(),
-                Self::InstructionFlags(_) => RV32I::iter().map(Self::InstructionFlags).collect(),
+                Self::InstructionFlags(_) => I::iter().map(Self::InstructionFlags).collect(),
                 Self::Aux(_) => AuxVariable::iter()
-                    .flat_map(|aux| match aux {
-                        AuxVariable::RelevantYChunk(_) => (0..C)
-                            .map(|i| Self::Aux(AuxVariable::RelevantYChunk(i)))
-                            .collect(),
-                        _ => vec![Self::Aux(aux)],
-                    })
+                    .filter(|aux| !matches!(aux, AuxVariable::RelevantYChunk(_)))
+                    .map(Self::Aux)
                     .collect(),
                 _ => vec![variant],
             })
+            .chain(chunk_inputs)
             .collect()
     }
 
@@ -375,7 +385,7 @@ impl ConstraintInput for JoltR1CSInputs {
             JoltR1CSInputs::ChunksY(i) => &jolt.r1cs.chunks_y[*i],
             JoltR1CSInputs::OpFlags(i) => &jolt.r1cs.circuit_flags[*i as usize],
             JoltR1CSInputs::InstructionFlags(i) => {
-                &jolt.instruction_lookups.instruction_flags[RV32I::enum_index(i)]
+                &jolt.instruction_lookups.instruction_flags[I::enum_index(i)]
             }
             Self::Aux(aux) => match aux {
                 AuxVariable::LeftLookupOperand => &aux_polynomials.left_lookup_operand,
@@ -426,17 +436,24 @@ mod tests {
     #[test]
     fn from_index_to_index() {
         const C: usize = 4;
-        for i in 0..JoltR1CSInputs::num_inputs::<C>() {
-            assert_eq!(i, JoltR1CSInputs::from_index::<C>(i).to_index::<C>());
+        for i in 0..<JoltR1CSInputs>::num_inputs::<C>() {
+            assert_eq!(i, <JoltR1CSInputs>::from_index::<C>(i).to_index::<C>());
         }
-        for var in JoltR1CSInputs::flatten::<C>() {
+        for var in <JoltR1CSInputs>::flatten::<C>() {
             assert_eq!(
                 var,
-                JoltR1CSInputs::from_index::<C>(JoltR1CSInputs::to_index::<C>(&var))
+                <JoltR1CSInputs>::from_index::<C>(<JoltR1CSInputs>::to_index::<C>(&var))
             );
         }
     }
 
+    #[test]
+    fn index_independent_of_c() {
+        for var in <JoltR1CSInputs>::flatten::<4>() {
+            assert_eq!(var.to_index::<4>(), var.to_index::<8>(), "{var:?}");
+        }
+    }
+
     #[test]
     fn get_ref() {
         const C: usize = 4;
@@ -445,9 +462,9 @@ mod tests {
 
         for aux in AuxVariable::iter().flat_map(|aux| match aux {
             AuxVariable::RelevantYChunk(_) => (0..C)
-                .map(|i| JoltR1CSInputs::Aux(AuxVariable::RelevantYChunk(i)))
+                .map(|i| JoltR1CSInputs::<RV32I>::Aux(AuxVariable::RelevantYChunk(i)))
                 .collect(),
-            _ => vec![JoltR1CSInputs::Aux(aux)],
+            _ => vec![JoltR1CSInputs::<RV32I>::Aux(aux)],
         }) {
             let ref_ptr = aux.get_ref(&jolt_polys) as *const MultilinearPolynomial<Fr>;
             let ref_mut_ptr = aux.get_ref_mut(&mut jolt_polys) as *const MultilinearPolynomial<Fr>;
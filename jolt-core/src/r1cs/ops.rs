@@ -1,11 +1,9 @@
 //! Defines the Linear Combination (LC) object and associated operations.
 //! A LinearCombination is a vector of Terms, where each Term is a pair of a Variable and a coefficient.
 
-#[cfg(test)]
 use super::inputs::ConstraintInput;
 use crate::{field::JoltField, poly::multilinear_polynomial::MultilinearPolynomial};
 use std::fmt::Debug;
-#[cfg(test)]
 use std::fmt::Write as _;
 use std::hash::Hash;
 
@@ -19,7 +17,6 @@ pub enum Variable {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Term(pub Variable, pub i64);
 impl Term {
-    #[cfg(test)]
     fn pretty_fmt<const C: usize, I: ConstraintInput>(&self, f: &mut String) -> std::fmt::Result {
         match self.0 {
             Variable::Input(var_index) | Variable::Auxiliary(var_index) => match self.1.abs() {
@@ -101,7 +98,6 @@ impl LC {
             .sum()
     }
 
-    #[cfg(test)]
     pub fn pretty_fmt<const C: usize, I: ConstraintInput>(
         &self,
         f: &mut String,
@@ -116,14 +112,13 @@ impl LC {
                 if term.1 == 0 {
                     continue;
                 }
-                if index > 0 {
-                    if term.1 < 0 {
-                        write!(f, " - ")?;
-                    } else {
-                        write!(f, " + ")?;
-                    }
+                match (index, term.1 < 0) {
+                    (0, true) => write!(f, "-")?,
+                    (0, false) => {}
+                    (_, true) => write!(f, " - ")?,
+                    (_, false) => write!(f, " + ")?,
                 }
-                term.pretty_fmt::<C, I>(f)?;
+                Term(term.0, term.1.abs()).pretty_fmt::<C, I>(f)?;
             }
             if self.0.len() > 1 {
                 write!(f, ")")?;
@@ -292,17 +287,19 @@ impl std::ops::Mul<Variable> for i64 {
     }
 }
 
-/// Conversions and arithmetic for concrete ConstraintInput
+/// Conversions and arithmetic for concrete ConstraintInput. Generic inputs list
+/// their generic parameters in brackets, with a trailing comma, e.g.
+/// `[I: Bound,] Input<I>`.
 #[macro_export]
 macro_rules! impl_r1cs_input_lc_conversions {
-    ($ConcreteInput:ty, $C:expr) => {
-        impl Into<$crate::r1cs::ops::Variable> for $ConcreteInput {
+    ([$($generics:tt)*] $ConcreteInput:ty, $C:expr) => {
+        impl<$($generics)*> Into<$crate::r1cs::ops::Variable> for $ConcreteInput {
             fn into(self) -> $crate::r1cs::ops::Variable {
                 $crate::r1cs::ops::Variable::Input(self.to_index::<$C>())
             }
         }
 
-        impl Into<$crate::r1cs::ops::Term> for $ConcreteInput {
+        impl<$($generics)*> Into<$crate::r1cs::ops::Term> for $ConcreteInput {
             fn into(self) -> $crate::r1cs::ops::Term {
                 $crate::r1cs::ops::Term(
                     $crate::r1cs::ops::Variable::Input(self.to_index::<$C>()),
@@ -311,7 +308,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl Into<$crate::r1cs::ops::LC> for $ConcreteInput {
+        impl<$($generics)*> Into<$crate::r1cs::ops::LC> for $ConcreteInput {
             fn into(self) -> $crate::r1cs::ops::LC {
                 $crate::r1cs::ops::Term(
                     $crate::r1cs::ops::Variable::Input(self.to_index::<$C>()),
@@ -321,7 +318,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl Into<$crate::r1cs::ops::LC> for Vec<$ConcreteInput> {
+        impl<$($generics)*> Into<$crate::r1cs::ops::LC> for Vec<$ConcreteInput> {
             fn into(self) -> $crate::r1cs::ops::LC {
                 let terms: Vec<$crate::r1cs::ops::Term> =
                     self.into_iter().map(Into::into).collect();
@@ -329,7 +326,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl<T: Into<$crate::r1cs::ops::LC>> std::ops::Add<T> for $ConcreteInput {
+        impl<$($generics)* T: Into<$crate::r1cs::ops::LC>> std::ops::Add<T> for $ConcreteInput {
             type Output = $crate::r1cs::ops::LC;
 
             fn add(self, rhs: T) -> Self::Output {
@@ -339,7 +336,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl<T: Into<$crate::r1cs::ops::LC>> std::ops::Sub<T> for $ConcreteInput {
+        impl<$($generics)* T: Into<$crate::r1cs::ops::LC>> std::ops::Sub<T> for $ConcreteInput {
             type Output = $crate::r1cs::ops::LC;
 
             fn sub(self, rhs: T) -> Self::Output {
@@ -349,7 +346,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl std::ops::Mul<i64> for $ConcreteInput {
+        impl<$($generics)*> std::ops::Mul<i64> for $ConcreteInput {
             type Output = $crate::r1cs::ops::Term;
 
             fn mul(self, rhs: i64) -> Self::Output {
@@ -360,7 +357,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
 
-        impl std::ops::Mul<$ConcreteInput> for i64 {
+        impl<$($generics)*> std::ops::Mul<$ConcreteInput> for i64 {
             type Output = $crate::r1cs::ops::Term;
 
             fn mul(self, rhs: $ConcreteInput) -> Self::Output {
@@ -370,7 +367,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
                 )
             }
         }
-        impl std::ops::Add<$ConcreteInput> for i64 {
+        impl<$($generics)*> std::ops::Add<$ConcreteInput> for i64 {
             type Output = $crate::r1cs::ops::LC;
 
             fn add(self, rhs: $ConcreteInput) -> Self::Output {
@@ -383,4 +380,7 @@ macro_rules! impl_r1cs_input_lc_conversions {
             }
         }
     };
+    ($ConcreteInput:ty, $C:expr) => {
+        $crate::impl_r1cs_input_lc_conversions!([] $ConcreteInput, $C);
+    };
 }
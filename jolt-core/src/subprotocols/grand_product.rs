@@ -6,6 +6,7 @@ use crate::poly::dense_interleaved_poly::DenseInterleavedPolynomial;
 use crate::poly::dense_mlpoly::DensePolynomial;
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::poly::split_eq_poly::SplitEqPolynomial;
+use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::Transcript;
@@ -27,10 +28,9 @@ impl<F: JoltField, ProofTranscript: Transcript> BatchedGrandProductLayerProof<F,
         num_rounds: usize,
         degree_bound: usize,
         transcript: &mut ProofTranscript,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         self.proof
             .verify(claim, num_rounds, degree_bound, transcript)
-            .unwrap()
     }
 }
 
@@ -114,10 +114,14 @@ where
         grand_product_claim: &mut F,
         r_grand_product: &mut Vec<F>,
         transcript: &mut ProofTranscript,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         let layer_proof = &layer_proofs[layer_index];
         let expected_sumcheck_claim: F = layer_proof.left_claim * layer_proof.right_claim * eq_eval;
-        assert_eq!(expected_sumcheck_claim, sumcheck_claim);
+        if expected_sumcheck_claim != sumcheck_claim {
+            return Err(ProofVerifyError::SumcheckClaimMismatch(
+                "Grand product layer sumcheck check failed.",
+            ));
+        }
 
         // produce a random challenge to condense two claims into a single claim
         let r_layer = transcript.challenge_scalar();
@@ -125,6 +129,7 @@ where
             layer_proof.left_claim + r_layer * (layer_proof.right_claim - layer_proof.left_claim);
 
         r_grand_product.push(r_layer);
+        Ok(())
     }
 
     /// Function used for layer sumchecks in the generic batch verifier as well as the quark layered sumcheck hybrid
@@ -133,7 +138,7 @@ where
         mut claim: F,
         transcript: &mut ProofTranscript,
         r_start: Vec<F>,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         // `r_start` is the random point at which the MLE of the first layer of the grand product is evaluated.
         // In the case of the Quarks hybrid grand product, this is obtained from the Quarks grand product sumcheck.
         // In the case of Thaler'13 GKR-based grand products, this is from Fiat-Shamir.
@@ -142,7 +147,7 @@ where
 
         for (layer_index, layer_proof) in proof_layers.iter().enumerate() {
             let (sumcheck_claim, r_sumcheck) =
-                layer_proof.verify(claim, layer_index + fixed_at_start, 3, transcript);
+                layer_proof.verify(claim, layer_index + fixed_at_start, 3, transcript)?;
 
             transcript.append_scalar(&layer_proof.left_claim);
             transcript.append_scalar(&layer_proof.right_claim);
@@ -163,10 +168,10 @@ where
                 &mut claim,
                 &mut r_grand_product,
                 transcript,
-            );
+            )?;
         }
 
-        (claim, r_grand_product)
+        Ok((claim, r_grand_product))
     }
 
     /// Verifies the given grand product proof.
@@ -176,7 +181,7 @@ where
         _opening_accumulator: Option<&mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>>,
         transcript: &mut ProofTranscript,
         _setup: Option<&PCS::Setup>,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         // Evaluate the MLE of the output layer at a random point to reduce the outputs to
         // a single claim.
         transcript.append_scalars(claimed_outputs);
@@ -444,7 +449,8 @@ mod tests {
                 None,
                 &mut verifier_transcript,
                 None,
-            );
+            )
+            .unwrap();
             assert_eq!(r_prover, r_verifier);
         }
     }
@@ -10,6 +10,7 @@ use crate::poly::eq_poly::EqPolynomial;
 use crate::poly::multilinear_polynomial::{MultilinearPolynomial, PolynomialEvaluation};
 use crate::poly::opening_proof::{ProverOpeningAccumulator, VerifierOpeningAccumulator};
 use crate::subprotocols::QuarkHybridLayerDepth;
+use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
 use crate::utils::transcript::{AppendToTranscript, Transcript};
 use ark_serialize::*;
@@ -170,7 +171,7 @@ where
         opening_accumulator: Option<&mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>>,
         transcript: &mut ProofTranscript,
         _setup: Option<&PCS::Setup>,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         QuarkGrandProductBase::verify_quark_grand_product::<Self, PCS>(
             proof,
             claimed_outputs,
@@ -244,7 +245,7 @@ where
         claimed_outputs: &[F],
         opening_accumulator: Option<&mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>>,
         transcript: &mut ProofTranscript,
-    ) -> (F, Vec<F>)
+    ) -> Result<(F, Vec<F>), ProofVerifyError>
     where
         PCS: CommitmentScheme<ProofTranscript, Field = F>,
         G: BatchedGrandProduct<F, PCS, ProofTranscript>,
@@ -267,7 +268,7 @@ where
                         transcript,
                         v_len,
                     )
-                    .unwrap_or_else(|e| panic!("quark verify error: {e:?}"))
+                    .map_err(|e| ProofVerifyError::QuarkError(e.to_string()))?
             }
             None => {
                 // Otherwise we must check the actual claims and the preset random will be empty.
@@ -275,10 +276,7 @@ where
             }
         };
 
-        let (grand_product_claim, grand_product_r) =
-            G::verify_layers(&proof.gkr_layers, claim, transcript, rand);
-
-        (grand_product_claim, grand_product_r)
+        G::verify_layers(&proof.gkr_layers, claim, transcript, rand)
     }
 }
 
@@ -711,13 +709,14 @@ mod quark_grand_product_tests {
         > = VerifierOpeningAccumulator::new();
         verifier_accumulator.compare_to(prover_accumulator, &setup);
 
-        let _ = QuarkGrandProduct::verify_grand_product(
+        QuarkGrandProduct::verify_grand_product(
             &proof,
             &known_products,
             Some(&mut verifier_accumulator),
             &mut verifier_transcript,
             None,
-        );
+        )
+        .unwrap();
         assert!(verifier_accumulator
             .reduce_and_verify(&setup, &batched_proof, &mut verifier_transcript)
             .is_ok());
@@ -13,6 +13,7 @@ use crate::poly::split_eq_poly::SplitEqPolynomial;
 use crate::poly::unipoly::UniPoly;
 use crate::subprotocols::grand_product_quarks::QuarkGrandProductBase;
 use crate::subprotocols::QuarkHybridLayerDepth;
+use crate::utils::errors::ProofVerifyError;
 use crate::utils::math::Math;
 use crate::utils::thread::drop_in_background_thread;
 use crate::utils::transcript::Transcript;
@@ -1069,7 +1070,7 @@ where
         opening_accumulator: Option<&mut VerifierOpeningAccumulator<F, PCS, ProofTranscript>>,
         transcript: &mut ProofTranscript,
         _setup: Option<&PCS::Setup>,
-    ) -> (F, Vec<F>) {
+    ) -> Result<(F, Vec<F>), ProofVerifyError> {
         QuarkGrandProductBase::verify_quark_grand_product::<Self, PCS>(
             proof,
             claimed_outputs,
@@ -1086,14 +1087,18 @@ where
         grand_product_claim: &mut F,
         r_grand_product: &mut Vec<F>,
         transcript: &mut ProofTranscript,
-    ) {
+    ) -> Result<(), ProofVerifyError> {
         let layer_proof = &layer_proofs[layer_index];
         if layer_index != layer_proofs.len() - 1 {
             // Normal grand product layer (multiplication gates)
             let expected_sumcheck_claim: F =
                 layer_proof.left_claim * layer_proof.right_claim * eq_eval;
 
-            assert_eq!(expected_sumcheck_claim, sumcheck_claim);
+            if expected_sumcheck_claim != sumcheck_claim {
+                return Err(ProofVerifyError::SumcheckClaimMismatch(
+                    "Grand product layer sumcheck check failed.",
+                ));
+            }
 
             // produce a random challenge to condense two claims into a single claim
             let r_layer = transcript.challenge_scalar();
@@ -1109,12 +1114,17 @@ where
                 * (layer_proof.left_claim * layer_proof.right_claim + F::one()
                     - layer_proof.left_claim);
 
-            assert_eq!(expected_sumcheck_claim, sumcheck_claim);
+            if expected_sumcheck_claim != sumcheck_claim {
+                return Err(ProofVerifyError::SumcheckClaimMismatch(
+                    "Grand product layer sumcheck check failed.",
+                ));
+            }
 
             // flag * fingerprint + 1 - flag
             *grand_product_claim = layer_proof.left_claim * layer_proof.right_claim + F::one()
                 - layer_proof.left_claim;
         }
+        Ok(())
     }
 }
 
@@ -1327,7 +1337,8 @@ mod tests {
             Some(&mut verifier_accumulator),
             &mut verifier_transcript,
             Some(&setup),
-        );
+        )
+        .unwrap();
 
         assert_eq!(
             r_prover, r_verifier,
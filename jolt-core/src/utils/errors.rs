@@ -1,3 +1,4 @@
+use common::rv_trace::RV32IM;
 use core::fmt::Debug;
 use thiserror::Error;
 
@@ -5,8 +6,10 @@ use thiserror::Error;
 pub enum ProofVerifyError {
     #[error("Invalid input length, expected length {0} but got {1}")]
     InvalidInputLength(usize, usize),
-    #[error("Input too large")]
-    InputTooLarge,
+    #[error("Input too large: {0} bytes exceeds max_input_size of {1} bytes")]
+    InputTooLarge(usize, usize),
+    #[error("Output too large: {0} bytes exceeds max_output_size of {1} bytes")]
+    OutputTooLarge(usize, usize),
     #[error("Proof verification failed")]
     #[default]
     InternalError,
@@ -18,4 +21,155 @@ pub enum ProofVerifyError {
     KeyLengthError(usize, usize),
     #[error("Invalid key length: {0}, expected power of 2")]
     InvalidKeyLength(usize),
+    #[error("Multiset hashes don't match")]
+    MultisetHashMismatch,
+    #[error("Grand product claim does not match the opened fingerprints")]
+    FingerprintMismatch,
+    #[error("{0}")]
+    SumcheckClaimMismatch(&'static str),
+    #[error("Quark grand product verification failed: {0}")]
+    QuarkError(String),
+    #[error("Program inputs/outputs do not match the proof")]
+    IoMismatch,
+    #[error("Panic bit {0} is inconsistent with panic code {1}")]
+    InconsistentPanic(bool, u8),
+    #[error("Proof is missing openings of committed preprocessing polynomials")]
+    MissingPreprocessingOpening,
+    #[error("Invalid blobs: {0}")]
+    InvalidBlobs(String),
+    #[error("Commitment to blob {0:?} does not match its digest")]
+    BlobDigestMismatch(String),
+    #[error("Bytecode proof verification failed: {0}")]
+    BytecodeError(Box<ProofVerifyError>),
+    #[error("Instruction lookups proof verification failed: {0}")]
+    InstructionLookupsError(Box<ProofVerifyError>),
+    #[error("Read-write memory proof verification failed: {0}")]
+    ReadWriteMemoryError(Box<ProofVerifyError>),
+    #[error("Timestamp range check proof verification failed: {0}")]
+    TimestampRangeCheckError(Box<ProofVerifyError>),
+    #[error("Precompile proof verification failed: {0}")]
+    PrecompileError(Box<ProofVerifyError>),
+    #[error("Precompile calls are malformed, overlap or exceed the trace")]
+    InvalidPrecompileCalls,
+    #[error("Batched opening proof verification failed: {0}")]
+    OpeningProofError(Box<ProofVerifyError>),
+    #[error("Dev mode proofs do not prove anything and are never valid")]
+    DevProof,
 }
+
+/// Returned by `Jolt::batch_verify` if any proof in the batch is invalid.
+#[derive(Error, Debug)]
+#[error("{} of the batched proofs failed to verify", .failures.len())]
+pub struct BatchVerifyError {
+    /// Index in the batch and error of each invalid proof, in order.
+    pub failures: Vec<(usize, ProofVerifyError)>,
+}
+
+#[derive(Error, Debug)]
+pub enum ContainerError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a Jolt container (bad magic bytes)")]
+    BadMagic,
+    #[error("Container is truncated ({0} bytes)")]
+    Truncated(usize),
+    #[error("Unsupported container format version {found}, expected {expected}")]
+    UnsupportedVersion { found: u16, expected: u16 },
+    #[error("Container checksum mismatch; the file is corrupted")]
+    ChecksumMismatch,
+    #[error("Container holds a {found}, expected a {expected}")]
+    KindMismatch { found: String, expected: String },
+    #[error("Commitment scheme mismatch: container uses `{found}`, expected `{expected}`")]
+    CommitmentSchemeMismatch { found: String, expected: String },
+    #[error("Transcript mismatch: container uses `{found}`, expected `{expected}`")]
+    TranscriptMismatch { found: String, expected: String },
+    #[error("Parameter mismatch: container has (C, M) = ({0}, {1}), expected ({2}, {3})")]
+    ParameterMismatch(u64, u64, u64, u64),
+    #[error("Program ID mismatch: container was produced for a different program")]
+    ProgramIdMismatch,
+    #[error("Failed to deserialize container: {0}")]
+    Deserialization(#[from] ark_serialize::SerializationError),
+}
+
+/// Returned by `Jolt::check_witness` if the prover's witness does not satisfy Jolt's
+/// constraints, i.e. if proving would produce an invalid proof. Steps are indices into
+/// the padded trace.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum WitnessError {
+    #[error("Uniform R1CS constraint {constraint} is violated at step {step}")]
+    UniformConstraint { step: usize, constraint: usize },
+    #[error("Cross-step R1CS constraint {constraint} is violated at step {step}")]
+    CrossStepConstraint { step: usize, constraint: usize },
+    #[error("Lookup output at step {step} is {output}, but the lookup table holds {expected}")]
+    LookupOutput {
+        step: usize,
+        output: u64,
+        expected: u64,
+    },
+    #[error("Subtable lookups at step {step} do not combine to the lookup output")]
+    LookupDecomposition { step: usize },
+    #[error("Bytecode row read at step {step} does not match the preprocessed bytecode")]
+    BytecodeMismatch { step: usize },
+    #[error("{operand} access at step {step} is inconsistent with the prior memory state")]
+    MemoryMismatch { step: usize, operand: &'static str },
+    #[error("Final memory state at witness index {0} does not match the memory accesses")]
+    FinalMemoryMismatch(usize),
+    #[error("Final memory state at address {0:#x} does not match the program inputs/outputs")]
+    IoMismatch(u64),
+}
+
+impl WitnessError {
+    /// The step the error occurs at, or `None` for errors in the final memory state.
+    pub fn step(&self) -> Option<usize> {
+        match *self {
+            Self::UniformConstraint { step, .. }
+            | Self::CrossStepConstraint { step, .. }
+            | Self::LookupOutput { step, .. }
+            | Self::LookupDecomposition { step }
+            | Self::BytecodeMismatch { step }
+            | Self::MemoryMismatch { step, .. } => Some(step),
+            Self::FinalMemoryMismatch(_) | Self::IoMismatch(_) => None,
+        }
+    }
+}
+
+/// A [`WitnessError`] along with the failing step's instruction and, for R1CS errors, the
+/// violated constraint. Returned by `Jolt::debug_witness` and `Jolt::prove_dev`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WitnessReport {
+    pub error: WitnessError,
+    /// ELF address of the failing step's instruction, or `None` if the error is not
+    /// specific to a step or the step is padding. Steps of a virtual sequence share the
+    /// address of the instruction they expand.
+    pub pc: Option<usize>,
+    /// The number of virtual instructions after the failing step in its virtual sequence.
+    pub virtual_sequence_remaining: Option<usize>,
+    /// Opcode of the failing step, or `None` if it is padding.
+    pub opcode: Option<RV32IM>,
+    /// The violated R1CS constraint and the values of its variables at the failing step.
+    pub constraint: Option<String>,
+}
+
+impl std::fmt::Display for WitnessReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)?;
+        if let Some(opcode) = self.opcode {
+            write!(f, " ({opcode:?}")?;
+            if let Some(pc) = self.pc {
+                write!(f, " at pc {pc:#x}")?;
+            }
+            if let Some(remaining) = self.virtual_sequence_remaining {
+                write!(f, ", {remaining} more in its virtual sequence")?;
+            }
+            write!(f, ")")?;
+        } else if self.error.step().is_some() {
+            write!(f, " (padding)")?;
+        }
+        if let Some(constraint) = &self.constraint {
+            write!(f, ":\n{}", constraint.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for WitnessReport {}
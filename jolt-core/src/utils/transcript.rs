@@ -97,6 +97,10 @@ impl Transcript for KeccakTranscript {
         }
     }
 
+    fn protocol_name() -> &'static [u8] {
+        b"keccak256"
+    }
+
     #[cfg(test)]
     /// Compare this transcript to `other` and panic if/when they deviate.
     /// Typically used to compare the verifier's transcript to the prover's.
@@ -220,6 +224,8 @@ impl Transcript for KeccakTranscript {
 
 pub trait Transcript: Clone + Sync + Send + 'static {
     fn new(label: &'static [u8]) -> Self;
+    /// Identifies the transcript's hash function, e.g. in serialized proof headers.
+    fn protocol_name() -> &'static [u8];
     #[cfg(test)]
     fn compare_to(&mut self, other: Self);
     fn append_message(&mut self, msg: &'static [u8]);
@@ -2,14 +2,24 @@ extern crate proc_macro;
 
 use core::panic;
 
-use common::{attributes::parse_attributes, rv_trace::MemoryLayout};
+use common::{
+    attributes::parse_attributes,
+    rv_trace::{MemoryLayout, PanicReason},
+};
 use proc_macro::TokenStream;
 use proc_macro2::TokenStream as TokenStream2;
 use quote::quote;
-use std::sync::Once;
+use std::sync::{Mutex, Once};
 use syn::{parse_macro_input, AttributeArgs, Ident, ItemFn, PatType, ReturnType, Type};
 
 static WASM_IMPORTS_INIT: Once = Once::new();
+static DISPATCHER_INIT: Once = Once::new();
+/// `(memory_size, stack_size, max_input_size, max_output_size, max_blob_size, c, m)` of
+/// the first provable function in the crate, which every other function must share to be
+/// built into a single ELF with a dispatcher.
+#[allow(clippy::type_complexity)]
+static DISPATCHER_ATTRIBUTES: Mutex<Option<(u64, u64, u64, u64, u64, Option<u64>, Option<u64>)>> =
+    Mutex::new(None);
 
 #[proc_macro_attribute]
 pub fn provable(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -19,6 +29,12 @@ pub fn provable(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut token_stream = builder.build();
 
+    // The dispatcher and its host utilities should only be added once
+    DISPATCHER_INIT.call_once(|| {
+        let dispatcher: TokenStream = builder.make_dispatcher().into();
+        token_stream.extend(dispatcher);
+    });
+
     // Add wasm utilities and functions if the function is marked as wasm
     if builder.has_wasm_attr() {
         // wasm utilities should only be added once
@@ -37,6 +53,7 @@ struct MacroBuilder {
     attr: AttributeArgs,
     func: ItemFn,
     std: bool,
+    hard_float: bool,
     func_args: Vec<(Ident, Box<Type>)>,
 }
 
@@ -47,26 +64,37 @@ impl MacroBuilder {
         let std = true;
         #[cfg(not(feature = "guest-std"))]
         let std = false;
+        let hard_float = cfg!(feature = "guest-hard-float");
 
         Self {
             attr,
             func,
             std,
+            hard_float,
             func_args,
         }
     }
 
     fn build(&mut self) -> TokenStream {
-        let build_prover_fn = self.make_build_prover_fn();
-        let build_verifier_fn = self.make_build_verifier_fn();
+        let build_prover_fn = self.make_build_prover_fn(false);
+        let build_verifier_fn = self.make_build_verifier_fn(false);
         let execute_fn = self.make_execute_function();
         let analyze_fn = self.make_analyze_function();
-        let compile_fn = self.make_compile_func();
-        let preprocess_prover_fn = self.make_preprocess_prover_func();
-        let preprocess_verifier_fn = self.make_preprocess_verifier_func();
-        let prove_fn = self.make_prove_func();
-
-        let main_fn = if let Some(func) = self.get_func_selector() {
+        let compile_fn = self.make_compile_func(false);
+        let preprocess_prover_fn =
+            self.make_preprocess_prover_func(&self.get_func_name().to_string());
+        let preprocess_verifier_fn =
+            self.make_preprocess_verifier_func(&self.get_func_name().to_string());
+        let prove_fn = self.make_prove_func(false);
+        let prove_batch_fn = self.make_prove_batch_func(false);
+        let build_dispatch_prover_fn = self.make_build_prover_fn(true);
+        let build_dispatch_verifier_fn = self.make_build_verifier_fn(true);
+        let prove_dispatch_fn = self.make_prove_func(true);
+        let prove_batch_dispatch_fn = self.make_prove_batch_func(true);
+
+        let main_fn = if self.is_dispatch_build() {
+            self.make_dispatch_entry()
+        } else if let Some(func) = self.get_func_selector() {
             if *self.get_func_name() == func {
                 self.make_main_func()
             } else {
@@ -85,27 +113,58 @@ impl MacroBuilder {
             #preprocess_prover_fn
             #preprocess_verifier_fn
             #prove_fn
+            #prove_batch_fn
+            #build_dispatch_prover_fn
+            #build_dispatch_verifier_fn
+            #prove_dispatch_fn
+            #prove_batch_dispatch_fn
             #main_fn
         }
         .into()
     }
 
-    fn make_build_prover_fn(&self) -> TokenStream2 {
+    /// Emits the utilities shared by all provable functions of a guest built with
+    /// a dispatcher: `compile_dispatcher`, `preprocess_prover_dispatcher` and
+    /// `preprocess_verifier_dispatcher` on the host, and the guest's runtime and
+    /// dispatching `main` when building the guest with `JOLT_DISPATCH`.
+    fn make_dispatcher(&self) -> TokenStream2 {
+        let compile_fn = self.make_compile_func(true);
+        let preprocess_prover_fn = self.make_preprocess_prover_func("dispatcher");
+        let preprocess_verifier_fn = self.make_preprocess_verifier_func("dispatcher");
+
+        let main_fn = if self.is_dispatch_build() {
+            self.make_dispatcher_main()
+        } else {
+            quote! {}
+        };
+
+        quote! {
+            #compile_fn
+            #preprocess_prover_fn
+            #preprocess_verifier_fn
+            #main_fn
+        }
+    }
+
+    fn make_build_prover_fn(&self, dispatch: bool) -> TokenStream2 {
+        let (c, _) = self.get_decomposition();
         let fn_name = self.get_func_name();
-        let build_prover_fn_name = Ident::new(&format!("build_prover_{fn_name}"), fn_name.span());
+        let prefix = if dispatch { "dispatch_" } else { "" };
+        let build_prover_fn_name =
+            Ident::new(&format!("build_{prefix}prover_{fn_name}"), fn_name.span());
         let prove_output_ty = self.get_prove_output_type();
 
         let input_names = self.func_args.iter().map(|(name, _)| name);
         let input_types = self.func_args.iter().map(|(_, ty)| ty);
         let inputs = &self.func.sig.inputs;
-        let prove_fn_name = Ident::new(&format!("prove_{fn_name}"), fn_name.span());
+        let prove_fn_name = Ident::new(&format!("prove_{prefix}{fn_name}"), fn_name.span());
         let imports = self.make_imports();
 
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #build_prover_fn_name(
                 program: jolt::host::Program,
-                preprocessing: jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+                preprocessing: jolt::JoltProverPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>,
             ) -> impl Fn(#(#input_types),*) -> #prove_output_ty + Sync + Send
             {
                 #imports
@@ -123,10 +182,12 @@ impl MacroBuilder {
         }
     }
 
-    fn make_build_verifier_fn(&self) -> TokenStream2 {
+    fn make_build_verifier_fn(&self, dispatch: bool) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
         let fn_name = self.get_func_name();
+        let prefix = if dispatch { "dispatch_" } else { "" };
         let build_verifier_fn_name =
-            Ident::new(&format!("build_verifier_{fn_name}"), fn_name.span());
+            Ident::new(&format!("build_{prefix}verifier_{fn_name}"), fn_name.span());
 
         let input_types = self.func_args.iter().map(|(_, ty)| ty);
         let output_type: Type = match &self.func.sig.output {
@@ -135,6 +196,8 @@ impl MacroBuilder {
         };
         let inputs = self.func.sig.inputs.iter();
         let imports = self.make_imports();
+        let set_function_selector =
+            self.make_set_function_selector(dispatch, quote!(io_device.inputs));
         let set_program_args = self.func_args.iter().map(|(name, _)| {
             quote! {
                 io_device.inputs.append(&mut jolt::postcard::to_stdvec(&#name).unwrap())
@@ -144,23 +207,28 @@ impl MacroBuilder {
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #build_verifier_fn_name(
-                preprocessing: jolt::JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
-            ) -> impl Fn(#(#input_types ,)* #output_type, jolt::JoltHyperKZGProof) -> bool + Sync + Send
+                preprocessing: jolt::JoltVerifierPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+            ) -> impl Fn(#(#input_types ,)* #output_type, jolt::SdkProofWith<{ #c }, { #m }>) -> Result<(), jolt::ProofVerifyError> + Sync + Send
             {
                 #imports
                 let preprocessing = std::sync::Arc::new(preprocessing);
 
-                let verify_closure = move |#(#inputs,)* output, proof: jolt::JoltHyperKZGProof| {
+                let verify_closure = move |#(#inputs,)* output, proof: jolt::SdkProofWith<{ #c }, { #m }>| {
+                    let jolt::SdkProofWith::Jolt(proof) = proof else {
+                        return Err(jolt::ProofVerifyError::DevProof);
+                    };
                     let preprocessing = (*preprocessing).clone();
 
                     let mut io_device = tracer::JoltDevice::new(
                         preprocessing.memory_layout.max_input_size,
                         preprocessing.memory_layout.max_output_size,
                     );
+                    #set_function_selector
                     #(#set_program_args;)*
                     io_device.outputs.append(&mut jolt::postcard::to_stdvec(&output).unwrap());
+                    io_device.blobs = proof.blobs;
 
-                    RV32IJoltVM::verify(preprocessing, proof.proof, proof.commitments, io_device, None).is_ok()
+                    jolt::RV32IJoltVMWith::<{ #c }, { #m }>::verify(preprocessing, proof.proof, proof.commitments, io_device, None)
                 };
 
                 verify_closure
@@ -187,6 +255,7 @@ impl MacroBuilder {
         let guest_name = self.get_guest_name();
         let imports = self.make_imports();
         let set_std = self.make_set_std();
+        let (_, m) = self.get_decomposition();
 
         let fn_name = self.get_func_name();
         let fn_name_str = fn_name.to_string();
@@ -208,6 +277,7 @@ impl MacroBuilder {
                 program.set_func(#fn_name_str);
                 #set_std
                 #set_mem_size
+                program.set_subtable_size(#m);
 
                 let mut input_bytes = vec![];
                 #(#set_program_args;)*
@@ -217,24 +287,36 @@ impl MacroBuilder {
         }
     }
 
-    fn make_compile_func(&self) -> TokenStream2 {
+    fn make_compile_func(&self, dispatch: bool) -> TokenStream2 {
         let imports = self.make_imports();
         let guest_name = self.get_guest_name();
         let set_mem_size = self.make_set_linker_parameters();
         let set_std = self.make_set_std();
+        let (_, m) = self.get_decomposition();
 
         let fn_name = self.get_func_name();
         let fn_name_str = fn_name.to_string();
-        let compile_fn_name = Ident::new(&format!("compile_{fn_name}"), fn_name.span());
+        let (compile_fn_name, set_func) = if dispatch {
+            (
+                Ident::new("compile_dispatcher", fn_name.span()),
+                quote! { program.set_dispatch(true); },
+            )
+        } else {
+            (
+                Ident::new(&format!("compile_{fn_name}"), fn_name.span()),
+                quote! { program.set_func(#fn_name_str); },
+            )
+        };
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #compile_fn_name(target_dir: &str) -> jolt::host::Program {
                 #imports
 
                 let mut program = Program::new(#guest_name);
-                program.set_func(#fn_name_str);
+                #set_func
                 #set_std
                 #set_mem_size
+                program.set_subtable_size(#m);
                 program.build(target_dir);
 
                 program
@@ -242,77 +324,96 @@ impl MacroBuilder {
         }
     }
 
-    fn make_preprocess_prover_func(&self) -> TokenStream2 {
+    fn make_preprocess_prover_func(&self, name: &str) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
         let attributes = parse_attributes(&self.attr);
         let max_input_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_input_size);
         let max_output_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_output_size);
+        let memory_size = proc_macro2::Literal::u64_unsuffixed(attributes.memory_size);
+        let max_blob_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_blob_size);
+        let max_trace_length = proc_macro2::Literal::u64_unsuffixed(attributes.max_trace_length);
+        let max_bytecode_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_bytecode_size);
         let imports = self.make_imports();
 
         let fn_name = self.get_func_name();
         let preprocess_prover_fn_name =
-            Ident::new(&format!("preprocess_prover_{fn_name}"), fn_name.span());
+            Ident::new(&format!("preprocess_prover_{name}"), fn_name.span());
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #preprocess_prover_fn_name(program: &jolt::host::Program)
-                -> jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>
+                -> jolt::JoltProverPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>
             {
                 #imports
 
                 let (bytecode, memory_init) = program.decode();
-                let memory_layout = MemoryLayout::new(#max_input_size, #max_output_size);
+                let memory_layout = MemoryLayout::with_blobs(
+                    #max_input_size,
+                    #max_output_size,
+                    #max_blob_size,
+                    #memory_size,
+                );
 
-                // TODO(moodlezoup): Feed in size parameters via macro
-                let preprocessing: JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
-                    RV32IJoltVM::prover_preprocess(
+                let max_memory_address = memory_layout.memory_witness_size(#memory_size) as usize;
+                let mut preprocessing: JoltProverPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript> =
+                    jolt::RV32IJoltVMWith::<{ #c }, { #m }>::prover_preprocess(
                         bytecode,
                         memory_layout,
                         memory_init,
-                        1 << 20,
-                        1 << 20,
-                        1 << 24
+                        #max_bytecode_size,
+                        max_memory_address,
+                        #max_trace_length
                     );
+                preprocessing.shared.elf_digest = Some(program.elf_digest());
 
                 preprocessing
             }
         }
     }
 
-    fn make_preprocess_verifier_func(&self) -> TokenStream2 {
+    fn make_preprocess_verifier_func(&self, name: &str) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
         let attributes = parse_attributes(&self.attr);
         let max_input_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_input_size);
         let max_output_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_output_size);
+        let memory_size = proc_macro2::Literal::u64_unsuffixed(attributes.memory_size);
+        let max_blob_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_blob_size);
+        let max_bytecode_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_bytecode_size);
         let imports = self.make_imports();
 
         let fn_name = self.get_func_name();
         let preprocess_verifier_fn_name =
-            Ident::new(&format!("preprocess_verifier_{fn_name}"), fn_name.span());
+            Ident::new(&format!("preprocess_verifier_{name}"), fn_name.span());
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #preprocess_verifier_fn_name(program: &jolt::host::Program)
-                -> jolt::JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>
+                -> jolt::JoltVerifierPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>
             {
                 #imports
 
                 let (bytecode, memory_init) = program.decode();
-                let memory_layout = MemoryLayout::new(#max_input_size, #max_output_size);
+                let memory_layout = MemoryLayout::with_blobs(
+                    #max_input_size,
+                    #max_output_size,
+                    #max_blob_size,
+                    #memory_size,
+                );
 
-                // TODO(moodlezoup): Feed in size parameters via macro
-                let preprocessing: JoltVerifierPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript> =
-                    RV32IJoltVM::verifier_preprocess(
+                let mut preprocessing: JoltVerifierPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript> =
+                    jolt::RV32IJoltVMWith::<{ #c }, { #m }>::verifier_preprocess(
                         bytecode,
                         memory_layout,
                         memory_init,
-                        1 << 20,
-                        1 << 20,
-                        1 << 24
+                        #max_bytecode_size
                     );
+                preprocessing.elf_digest = Some(program.elf_digest());
 
                 preprocessing
             }
         }
     }
 
-    fn make_prove_func(&self) -> TokenStream2 {
+    fn make_prove_func(&self, dispatch: bool) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
         let prove_output_ty = self.get_prove_output_type();
 
         let handle_return = match &self.func.sig.output {
@@ -324,6 +425,7 @@ impl MacroBuilder {
             },
         };
 
+        let set_function_selector = self.make_set_function_selector(dispatch, quote!(input_bytes));
         let set_program_args = self.func_args.iter().map(|(name, _)| {
             quote! {
                 input_bytes.append(&mut jolt::postcard::to_stdvec(&#name).unwrap())
@@ -334,83 +436,269 @@ impl MacroBuilder {
         let inputs = &self.func.sig.inputs;
         let imports = self.make_imports();
 
-        let prove_fn_name = syn::Ident::new(&format!("prove_{fn_name}"), fn_name.span());
+        let prefix = if dispatch { "dispatch_" } else { "" };
+        let prove_fn_name = syn::Ident::new(&format!("prove_{prefix}{fn_name}"), fn_name.span());
         quote! {
             #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
             pub fn #prove_fn_name(
                 mut program: jolt::host::Program,
-                preprocessing: jolt::JoltProverPreprocessing<4, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+                preprocessing: jolt::JoltProverPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>,
                 #inputs
             ) -> #prove_output_ty {
                 #imports
 
                 let mut input_bytes = vec![];
+                #set_function_selector
                 #(#set_program_args;)*
 
                 let (io_device, trace) = program.trace(&input_bytes);
 
-                let (jolt_proof, jolt_commitments, output_io_device, _) = RV32IJoltVM::prove(
-                    io_device,
-                    trace,
-                    preprocessing,
-                );
+                let (proof, output_io_device) = if program.dev_mode() {
+                    let (dev_proof, mut output_io_device) =
+                        jolt::RV32IJoltVMWith::<{ #c }, { #m }>::prove_dev(io_device, trace, preprocessing)
+                            .unwrap_or_else(|e| panic!("Dev mode witness check failed: {e}"));
+                    let proof = jolt::SdkProofWith::Dev {
+                        proof: dev_proof,
+                        blobs: std::mem::take(&mut output_io_device.blobs),
+                    };
+                    (proof, output_io_device)
+                } else {
+                    let (jolt_proof, jolt_commitments, mut output_io_device, _) = jolt::RV32IJoltVMWith::<{ #c }, { #m }>::prove(
+                        io_device,
+                        trace,
+                        preprocessing,
+                    );
+                    let proof = jolt::SdkProofWith::Jolt(jolt::JoltHyperKZGProofWith {
+                        proof: jolt_proof,
+                        commitments: jolt_commitments,
+                        blobs: std::mem::take(&mut output_io_device.blobs),
+                    });
+                    (proof, output_io_device)
+                };
 
                 #handle_return
 
-                let proof = jolt::JoltHyperKZGProof {
-                    proof: jolt_proof,
-                    commitments: jolt_commitments,
-                };
-
                 (ret_val, proof)
             }
         }
     }
 
-    fn make_main_func(&self) -> TokenStream2 {
-        let attributes = parse_attributes(&self.attr);
-        let memory_layout =
-            MemoryLayout::new(attributes.max_input_size, attributes.max_output_size);
-        let input_start = memory_layout.input_start;
-        let output_start = memory_layout.output_start;
-        let max_input_len = attributes.max_input_size as usize;
-        let max_output_len = attributes.max_output_size as usize;
-        let termination_bit = memory_layout.termination as usize;
+    fn make_prove_batch_func(&self, dispatch: bool) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
+        let prove_output_ty = self.get_prove_output_type();
 
-        let get_input_slice = quote! {
-            let input_ptr = #input_start as *const u8;
-            let input_slice = unsafe {
-                core::slice::from_raw_parts(input_ptr, #max_input_len)
-            };
+        let handle_return = match &self.func.sig.output {
+            ReturnType::Default => quote! {
+                let ret_val = ();
+            },
+            ReturnType::Type(_, ty) => quote! {
+                let ret_val = jolt::postcard::from_bytes::<#ty>(&output_io_device.outputs).unwrap();
+            },
         };
 
-        let args = &self.func_args;
-        let args_fetch = args.iter().map(|(name, ty)| {
+        let set_function_selector = self.make_set_function_selector(dispatch, quote!(input_bytes));
+        let set_program_args = self.func_args.iter().map(|(name, _)| {
             quote! {
-                let (#name, input_slice) =
-                    jolt::postcard::take_from_bytes::<#ty>(input_slice).unwrap();
+                input_bytes.append(&mut jolt::postcard::to_stdvec(&#name).unwrap())
             }
         });
 
-        // TODO: ensure that input slice hasn't overflown
-        let check_input_len = quote! {};
+        let fn_name = self.get_func_name();
+        let input_names = self.func_args.iter().map(|(name, _)| name);
+        let input_types = self.func_args.iter().map(|(_, ty)| ty);
+        let imports = self.make_imports();
 
-        let block = &self.func.block;
-        let block = quote! {let to_return = (|| -> _ { #block })();};
+        let prefix = if dispatch { "dispatch_" } else { "" };
+        let prove_batch_fn_name =
+            syn::Ident::new(&format!("prove_batch_{prefix}{fn_name}"), fn_name.span());
+        quote! {
+            #[cfg(all(not(target_arch = "wasm32"), not(feature = "guest")))]
+            pub fn #prove_batch_fn_name(
+                mut program: jolt::host::Program,
+                preprocessing: jolt::JoltProverPreprocessing<{ #c }, jolt::F, jolt::PCS, jolt::ProofTranscript>,
+                inputs: Vec<(#(#input_types,)*)>,
+            ) -> (Vec<#prove_output_ty>, jolt::BatchProvingMetrics) {
+                #imports
 
-        let handle_return = match &self.func.sig.output {
-            ReturnType::Default => quote! {},
-            ReturnType::Type(_, ty) => quote! {
-                let output_ptr = #output_start as *mut u8;
-                let output_slice = unsafe {
-                    core::slice::from_raw_parts_mut(output_ptr, #max_output_len)
+                let inputs: Vec<Vec<u8>> = inputs
+                    .into_iter()
+                    .map(|(#(#input_names,)*)| {
+                        let mut input_bytes = vec![];
+                        #set_function_selector
+                        #(#set_program_args;)*
+                        input_bytes
+                    })
+                    .collect();
+
+                let executions = program.trace_batch(&inputs);
+
+                if program.dev_mode() {
+                    let (proofs, metrics) = jolt::RV32IJoltVMWith::<{ #c }, { #m }>::prove_dev_batch(executions, preprocessing)
+                        .unwrap_or_else(|e| panic!("Dev mode witness check failed: {e}"));
+
+                    let proofs = proofs
+                        .into_iter()
+                        .map(|(dev_proof, output_io_device)| {
+                            #handle_return
+
+                            let proof = jolt::SdkProofWith::Dev {
+                                proof: dev_proof,
+                                blobs: output_io_device.blobs,
+                            };
+
+                            (ret_val, proof)
+                        })
+                        .collect();
+
+                    return (proofs, metrics);
+                }
+
+                let (proofs, metrics) = jolt::RV32IJoltVMWith::<{ #c }, { #m }>::prove_batch(executions, preprocessing);
+
+                let proofs = proofs
+                    .into_iter()
+                    .map(|(jolt_proof, jolt_commitments, output_io_device)| {
+                        #handle_return
+
+                        let proof = jolt::SdkProofWith::Jolt(jolt::JoltHyperKZGProofWith {
+                            proof: jolt_proof,
+                            commitments: jolt_commitments,
+                            blobs: output_io_device.blobs,
+                        });
+
+                        (ret_val, proof)
+                    })
+                    .collect();
+
+                (proofs, metrics)
+            }
+        }
+    }
+
+    fn make_main_func(&self) -> TokenStream2 {
+        let memory_layout = self.get_memory_layout();
+        let termination_bit = memory_layout.termination as usize;
+
+        let get_input_slice = self.make_get_input_slice();
+        let body = self.make_entry_body();
+        let runtime = self.make_runtime();
+
+        quote! {
+            #runtime
+
+            #[cfg(feature = "guest")]
+            #[no_mangle]
+            pub extern "C" fn main() {
+                let mut offset = 0;
+                #get_input_slice
+                #body
+                unsafe {
+                    core::ptr::write_volatile(#termination_bit as *mut u8, 1);
+                }
+            }
+        }
+    }
+
+    /// Emits the function's entry in the dispatcher's function table. The entry
+    /// runs the function on the input bytes following the function selector.
+    fn make_dispatch_entry(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let dispatcher_parameters = (
+            attributes.memory_size,
+            attributes.stack_size,
+            attributes.max_input_size,
+            attributes.max_output_size,
+            attributes.max_blob_size,
+            attributes.c,
+            attributes.m,
+        );
+        let expected_parameters = *DISPATCHER_ATTRIBUTES
+            .lock()
+            .unwrap()
+            .get_or_insert(dispatcher_parameters);
+        if dispatcher_parameters != expected_parameters {
+            return quote! {
+                compile_error!("all #[jolt::provable] functions must have the same memory_size, stack_size, max_input_size, max_output_size, max_blob_size, c and m to be built with a dispatcher");
+            };
+        }
+
+        let fn_name = self.get_func_name();
+        let fn_name_str = fn_name.to_string();
+        let entry_fn_name = Ident::new(&format!("jolt_dispatch_{fn_name}"), fn_name.span());
+        let entry_static_name = Ident::new(
+            &format!("JOLT_DISPATCH_ENTRY_{}", fn_name_str.to_uppercase()),
+            fn_name.span(),
+        );
+        let body = self.make_entry_body();
+
+        quote! {
+            #[cfg(feature = "guest")]
+            fn #entry_fn_name(input_slice: &[u8]) {
+                #body
+            }
+
+            #[cfg(feature = "guest")]
+            #[used]
+            #[link_section = ".jolt_entries"]
+            static #entry_static_name: jolt::DispatchEntry = jolt::DispatchEntry {
+                name: #fn_name_str,
+                entry: #entry_fn_name,
+            };
+        }
+    }
+
+    /// Emits the runtime and a `main` that reads the function selector from the
+    /// start of the inputs and calls the matching entry in the function table.
+    fn make_dispatcher_main(&self) -> TokenStream2 {
+        let memory_layout = self.get_memory_layout();
+        let termination_bit = memory_layout.termination as usize;
+
+        let get_input_slice = self.make_get_input_slice();
+        let runtime = self.make_runtime();
+        let invalid_input = PanicReason::InvalidInput as u8;
+        let unknown_function = PanicReason::UnknownFunction as u8;
+
+        quote! {
+            #runtime
+
+            #[cfg(feature = "guest")]
+            extern "C" {
+                static _JOLT_ENTRIES_START: jolt::DispatchEntry;
+                static _JOLT_ENTRIES_END: jolt::DispatchEntry;
+            }
+
+            #[cfg(feature = "guest")]
+            #[no_mangle]
+            pub extern "C" fn main() {
+                #get_input_slice
+                let (function_name, input_slice) =
+                    match jolt::postcard::take_from_bytes::<&str>(input_slice) {
+                        Ok(decoded) => decoded,
+                        Err(_) => jolt_exit_with_panic(#invalid_input),
+                    };
+
+                let entries = unsafe {
+                    let start = core::ptr::addr_of!(_JOLT_ENTRIES_START);
+                    let end = core::ptr::addr_of!(_JOLT_ENTRIES_END);
+                    core::slice::from_raw_parts(start, end.offset_from(start) as usize)
                 };
+                match entries.iter().find(|entry| entry.name == function_name) {
+                    Some(entry) => (entry.entry)(input_slice),
+                    None => jolt_exit_with_panic(#unknown_function),
+                }
 
-                jolt::postcard::to_slice::<#ty>(&to_return, output_slice).unwrap();
-            },
-        };
+                unsafe {
+                    core::ptr::write_volatile(#termination_bit as *mut u8, 1);
+                }
+            }
+        }
+    }
 
-        let panic_fn = self.make_panic(memory_layout.panic);
+    /// Emits the entry point, allocator, panic handler and `jolt_exit_with_panic`
+    /// shared by every guest binary.
+    fn make_runtime(&self) -> TokenStream2 {
+        let panic_address = self.get_memory_layout().panic;
+        let panic_fn = self.make_panic(panic_address);
         let declare_alloc = self.make_allocator();
 
         quote! {
@@ -429,32 +717,86 @@ impl MacroBuilder {
 
             #declare_alloc
 
+            /// Sets the panic word to the given `PanicReason` code and halts.
             #[cfg(feature = "guest")]
-            #[no_mangle]
-            pub extern "C" fn main() {
-                let mut offset = 0;
-                #get_input_slice
-                #(#args_fetch;)*
-                #check_input_len
-                #block
-                #handle_return
+            fn jolt_exit_with_panic(reason: u8) -> ! {
                 unsafe {
-                    core::ptr::write_volatile(#termination_bit as *mut u8, 1);
+                    core::ptr::write_volatile(#panic_address as *mut u8, reason);
                 }
+
+                loop {}
             }
 
             #panic_fn
         }
     }
 
+    fn make_get_input_slice(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let input_start = self.get_memory_layout().input_start;
+        let max_input_len = attributes.max_input_size as usize;
+
+        quote! {
+            let input_ptr = #input_start as *const u8;
+            let input_slice = unsafe {
+                core::slice::from_raw_parts(input_ptr, #max_input_len)
+            };
+        }
+    }
+
+    /// Deserializes the function's arguments from `input_slice`, runs the
+    /// function and writes its output to the output region.
+    fn make_entry_body(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let output_start = self.get_memory_layout().output_start;
+        let max_output_len = attributes.max_output_size as usize;
+
+        let invalid_input = PanicReason::InvalidInput as u8;
+        let args = &self.func_args;
+        let args_fetch = args.iter().map(|(name, ty)| {
+            quote! {
+                let (#name, input_slice) =
+                    match jolt::postcard::take_from_bytes::<#ty>(input_slice) {
+                        Ok(decoded) => decoded,
+                        Err(_) => jolt_exit_with_panic(#invalid_input),
+                    };
+            }
+        });
+
+        let block = &self.func.block;
+        let block = quote! {let to_return = (|| -> _ { #block })();};
+
+        let output_too_large = PanicReason::OutputTooLarge as u8;
+        let handle_return = match &self.func.sig.output {
+            ReturnType::Default => quote! {},
+            ReturnType::Type(_, ty) => quote! {
+                let output_ptr = #output_start as *mut u8;
+                let output_slice = unsafe {
+                    core::slice::from_raw_parts_mut(output_ptr, #max_output_len)
+                };
+
+                if jolt::postcard::to_slice::<#ty>(&to_return, output_slice).is_err() {
+                    jolt_exit_with_panic(#output_too_large);
+                }
+            },
+        };
+
+        quote! {
+            #(#args_fetch;)*
+            #block
+            #handle_return
+        }
+    }
+
     fn make_panic(&self, panic_address: u64) -> TokenStream2 {
+        let panic_code = PanicReason::Panic as u8;
         if self.std {
             quote! {
                 #[cfg(feature = "guest")]
                 #[no_mangle]
                 pub extern "C" fn jolt_panic() {
                     unsafe {
-                        core::ptr::write_volatile(#panic_address as *mut u8, 1);
+                        core::ptr::write_volatile(#panic_address as *mut u8, #panic_code);
                     }
 
                     loop {}
@@ -469,7 +811,7 @@ impl MacroBuilder {
                 #[panic_handler]
                 fn panic(_info: &PanicInfo) -> ! {
                     unsafe {
-                        core::ptr::write_volatile(#panic_address as *mut u8, 1);
+                        core::ptr::write_volatile(#panic_address as *mut u8, #panic_code);
                     }
 
                     loop {}
@@ -569,30 +911,39 @@ impl MacroBuilder {
             program.set_max_output_size(#value);
         });
 
+        let value = attributes.max_blob_size;
+        code.push(quote! {
+            program.set_max_blob_size(#value);
+        });
+
         quote! {
             #(#code;)*
         }
     }
 
     fn make_set_std(&self) -> TokenStream2 {
+        let hard_float = self.hard_float;
         if self.std {
             quote! {
                 program.set_std(true);
+                program.set_hard_float(#hard_float);
             }
         } else {
             quote! {
                 program.set_std(false);
+                program.set_hard_float(#hard_float);
             }
         }
     }
 
     fn get_prove_output_type(&self) -> TokenStream2 {
+        let (c, m) = self.get_decomposition();
         match &self.func.sig.output {
             ReturnType::Default => quote! {
-                ((), jolt::JoltHyperKZGProof)
+                ((), jolt::SdkProofWith<{ #c }, { #m }>)
             },
             ReturnType::Type(_, ty) => quote! {
-                (#ty, jolt::JoltHyperKZGProof)
+                (#ty, jolt::SdkProofWith<{ #c }, { #m }>)
             },
         }
     }
@@ -626,11 +977,62 @@ impl MacroBuilder {
         std::env::var("JOLT_FUNC_NAME").ok()
     }
 
+    fn is_dispatch_build(&self) -> bool {
+        std::env::var("JOLT_DISPATCH").is_ok()
+    }
+
+    fn get_memory_layout(&self) -> MemoryLayout {
+        let attributes = parse_attributes(&self.attr);
+        MemoryLayout::with_blobs(
+            attributes.max_input_size,
+            attributes.max_output_size,
+            attributes.max_blob_size,
+            attributes.memory_size,
+        )
+    }
+
+    /// The instruction lookup decomposition `(C, M)` of the generated code's VM,
+    /// `RV32IJoltVMWith<C, M>`: the `c` and `m` attributes, or `jolt::C` and `jolt::M`.
+    fn get_decomposition(&self) -> (TokenStream2, TokenStream2) {
+        let attributes = parse_attributes(&self.attr);
+        let param = |value: Option<u64>, default: TokenStream2| match value {
+            Some(value) => {
+                let value = proc_macro2::Literal::u64_unsuffixed(value);
+                quote!(#value)
+            }
+            None => default,
+        };
+        (
+            param(attributes.c, quote!(jolt::C)),
+            param(attributes.m, quote!(jolt::M)),
+        )
+    }
+
+    /// Prepends the function selector read by the dispatcher to `inputs`. The
+    /// selector is part of the inputs, so the proof is bound to the function.
+    fn make_set_function_selector(&self, dispatch: bool, inputs: TokenStream2) -> TokenStream2 {
+        if !dispatch {
+            return quote! {};
+        }
+        let fn_name_str = self.get_func_name().to_string();
+        quote! {
+            #inputs.append(&mut jolt::postcard::to_stdvec(#fn_name_str).unwrap());
+        }
+    }
+
     fn has_wasm_attr(&self) -> bool {
         parse_attributes(&self.attr).wasm
     }
 
     fn make_wasm_function(&self) -> TokenStream2 {
+        let attributes = parse_attributes(&self.attr);
+        let max_bytecode_size = proc_macro2::Literal::u64_unsuffixed(attributes.max_bytecode_size);
+        let max_memory_address = proc_macro2::Literal::u64_unsuffixed(
+            self.get_memory_layout()
+                .memory_witness_size(attributes.memory_size),
+        );
+        let max_trace_length = proc_macro2::Literal::u64_unsuffixed(attributes.max_trace_length);
+        let (c, m) = self.get_decomposition();
         let fn_name = self.get_func_name();
         let verify_wasm_fn_name = Ident::new(&format!("verify_{fn_name}"), fn_name.span());
 
@@ -638,20 +1040,20 @@ impl MacroBuilder {
             #[wasm_bindgen]
             #[cfg(all(target_arch = "wasm32", not(feature = "guest")))]
             pub fn #verify_wasm_fn_name(preprocessing_data: &[u8], proof_bytes: &[u8]) -> bool {
-                use jolt::{Jolt, JoltHyperKZGProof, RV32IJoltVM, ProofTranscript, Serializable};
+                use jolt::{Jolt, JoltHyperKZGProofWith, RV32IJoltVMWith, ProofTranscript, Serializable};
 
                 let decoded_preprocessing_data: DecodedData = deserialize_from_bin(preprocessing_data).unwrap();
-                let proof = JoltHyperKZGProof::deserialize_from_bytes(proof_bytes).unwrap();
+                let proof = JoltHyperKZGProofWith::<{ #c }, { #m }>::deserialize_from_bytes(proof_bytes).unwrap();
 
-                let preprocessing = RV32IJoltVM::preprocess(
+                let preprocessing = RV32IJoltVMWith::<{ #c }, { #m }>::preprocess(
                     decoded_preprocessing_data.bytecode,
                     decoded_preprocessing_data.memory_init,
-                    1 << 20,
-                    1 << 20,
-                    1 << 24,
+                    #max_bytecode_size,
+                    #max_memory_address,
+                    #max_trace_length,
                 );
 
-                let result = RV32IJoltVM::verify(preprocessing, proof.proof, proof.commitments);
+                let result = RV32IJoltVMWith::<{ #c }, { #m }>::verify(preprocessing, proof.proof, proof.commitments);
                 result.is_ok()
             }
         }
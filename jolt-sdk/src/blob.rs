@@ -0,0 +1,44 @@
+//! Read-only blobs attached to the program by the host (see `Program::add_blob`). In a
+//! guest a blob is read in place from the blob region; elsewhere there are no blobs.
+
+#[cfg(target_arch = "riscv32")]
+use common::blob::{BLOB_DIRECTORY_ENTRY_WORDS, MAX_BLOB_NAME_LEN};
+
+#[cfg(target_arch = "riscv32")]
+extern "C" {
+    /// Defined by the linker script: its address is the address of the blob directory,
+    /// or 0 if the program has no blob region.
+    static _BLOB_DIRECTORY: u8;
+}
+
+/// The contents of the blob named `name`, or `None` if no such blob is attached.
+pub fn blob(name: &str) -> Option<&'static [u8]> {
+    #[cfg(target_arch = "riscv32")]
+    {
+        let directory = unsafe { (&_BLOB_DIRECTORY) as *const u8 as usize };
+        if directory == 0 || name.len() > MAX_BLOB_NAME_LEN {
+            return None;
+        }
+
+        let word = |address: usize| unsafe { core::ptr::read(address as *const u32) } as usize;
+        let count = word(directory);
+        (0..count).find_map(|i| {
+            let entry = directory + 4 * (1 + i * BLOB_DIRECTORY_ENTRY_WORDS);
+            let entry_name =
+                unsafe { core::slice::from_raw_parts(entry as *const u8, MAX_BLOB_NAME_LEN) };
+            let matches = entry_name.starts_with(name.as_bytes())
+                && entry_name.get(name.len()).is_none_or(|byte| *byte == 0);
+            matches.then(|| {
+                let address = word(entry + MAX_BLOB_NAME_LEN);
+                let len = word(entry + MAX_BLOB_NAME_LEN + 4);
+                unsafe { core::slice::from_raw_parts(address as *const u8, len) }
+            })
+        })
+    }
+
+    #[cfg(not(target_arch = "riscv32"))]
+    {
+        let _ = name;
+        None
+    }
+}
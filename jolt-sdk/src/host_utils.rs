@@ -8,11 +8,18 @@ pub use common::{
 };
 pub use jolt_core::host;
 pub use jolt_core::jolt::instruction;
+pub use jolt_core::jolt::vm::extension;
 pub use jolt_core::jolt::vm::{
     bytecode::BytecodeRow,
     rv32i_vm::{
-        JoltHyperKZGProof, ProofTranscript, RV32IJoltProof, RV32IJoltVM, Serializable, PCS, RV32I,
+        JoltHyperKZGProof, JoltHyperKZGProofWith, ProofTranscript, RV32IJoltProof, RV32IJoltVM,
+        RV32IJoltVMWith, SdkProof, SdkProofWith, Serializable, C, M, PCS, RV32I,
     },
-    Jolt, JoltCommitments, JoltProof, JoltProverPreprocessing, JoltVerifierPreprocessing,
+    BatchProvingMetrics, DevProof, Jolt, JoltCommitments, JoltProof, JoltProverPreprocessing,
+    JoltVerifierPreprocessing,
 };
+pub use jolt_core::utils::errors::{
+    BatchVerifyError, ProofVerifyError, WitnessError, WitnessReport,
+};
+pub use jolt_core::{rv32i_instruction_set, rv32i_subtable_enum};
 pub use tracer;
@@ -13,6 +13,23 @@ pub use host_utils::*;
 pub mod alloc;
 pub use alloc::*;
 
+pub mod blob;
+pub use blob::*;
+
+pub mod precompiles;
+
+/// Entry in the function table of a guest built with a dispatcher (see
+/// `Program::set_dispatch`). `#[jolt::provable]` places one in the
+/// `.jolt_entries` section for each function, and the dispatcher calls the
+/// `entry` whose `name` matches the function selector.
+#[repr(C)]
+pub struct DispatchEntry {
+    pub name: &'static str,
+    /// Deserializes the function's arguments from the remaining input bytes,
+    /// runs it and writes its output.
+    pub entry: fn(&[u8]),
+}
+
 // This is a dummy _HEAP_PTR to keep the compiler happy.
 // It should never be used when compiled as a guest or with
 // our custom allocator
@@ -0,0 +1,106 @@
+//! Precompiles for 256-bit modular arithmetic, elliptic curves and SHA-512. Integers are
+//! little-endian arrays of 32-bit limbs, and inputs need not be reduced. In a guest
+//! each precompile compiles to a single `PRECOMPILE` instruction (see
+//! `common::precompiles`); elsewhere it falls back to the reference implementation.
+
+pub mod ed25519;
+pub mod secp256k1;
+pub mod sha512;
+
+pub use common::precompiles::{Precompile, BN254_FQ_MODULUS, BN254_FR_MODULUS, U256, U256_LIMBS};
+
+/// `a * b mod p`. Panics if `p < 2`.
+pub fn mod_mul(a: &U256, b: &U256, p: &U256) -> U256 {
+    assert_valid_modulus(p);
+    call::<{ Precompile::ModMul.id() }, U256_LIMBS>(&concat::<24>(&[a, b, p]))
+}
+
+/// `a + b mod p`. Panics if `p < 2`.
+pub fn mod_add(a: &U256, b: &U256, p: &U256) -> U256 {
+    assert_valid_modulus(p);
+    call::<{ Precompile::ModAdd.id() }, U256_LIMBS>(&concat::<24>(&[a, b, p]))
+}
+
+/// Arithmetic in the scalar field `Fr` and base field `Fq` of BN254.
+pub mod bn254 {
+    use super::*;
+
+    /// `a * b mod r`, where `r` is [`BN254_FR_MODULUS`].
+    pub fn fr_mul(a: &U256, b: &U256) -> U256 {
+        call::<{ Precompile::Bn254FrMul.id() }, U256_LIMBS>(&concat::<16>(&[a, b]))
+    }
+
+    /// `a + b mod r`, where `r` is [`BN254_FR_MODULUS`].
+    pub fn fr_add(a: &U256, b: &U256) -> U256 {
+        call::<{ Precompile::Bn254FrAdd.id() }, U256_LIMBS>(&concat::<16>(&[a, b]))
+    }
+
+    /// `a * b mod q`, where `q` is [`BN254_FQ_MODULUS`].
+    pub fn fq_mul(a: &U256, b: &U256) -> U256 {
+        call::<{ Precompile::Bn254FqMul.id() }, U256_LIMBS>(&concat::<16>(&[a, b]))
+    }
+
+    /// `a + b mod q`, where `q` is [`BN254_FQ_MODULUS`].
+    pub fn fq_add(a: &U256, b: &U256) -> U256 {
+        call::<{ Precompile::Bn254FqAdd.id() }, U256_LIMBS>(&concat::<16>(&[a, b]))
+    }
+}
+
+fn concat<const N: usize>(operands: &[&U256]) -> [u32; N] {
+    let mut input = [0; N];
+    for (words, operand) in input.chunks_exact_mut(U256_LIMBS).zip(operands) {
+        words.copy_from_slice(*operand);
+    }
+    input
+}
+
+/// `a^e mod p`.
+fn pow_mod(a: &U256, e: &U256, p: &U256) -> U256 {
+    let mut result = [0; U256_LIMBS];
+    result[0] = 1;
+    for bit in (0..32 * U256_LIMBS).rev() {
+        result = mod_mul(&result, &result, p);
+        if (e[bit / 32] >> (bit % 32)) & 1 == 1 {
+            result = mod_mul(&result, a, p);
+        }
+    }
+    result
+}
+
+fn less_than(a: &U256, b: &U256) -> bool {
+    a.iter().rev().lt(b.iter().rev())
+}
+
+/// The tracer cannot recover from an invalid modulus, so it is rejected before the
+/// precompile is invoked.
+fn assert_valid_modulus(p: &U256) {
+    assert!(
+        p[1..].iter().any(|&limb| limb != 0) || p[0] > 1,
+        "modulus must be greater than 1"
+    );
+}
+
+/// Invokes precompile `ID`, which writes `N` output words.
+#[cfg(target_arch = "riscv32")]
+fn call<const ID: u32, const N: usize>(input: &[u32]) -> [u32; N] {
+    let mut output = [0u32; N];
+    unsafe {
+        core::arch::asm!(
+            ".insn r 0x5b, 0, {id}, x0, {input}, {output}",
+            id = const ID,
+            input = in(reg) input.as_ptr(),
+            output = in(reg) output.as_mut_ptr(),
+            options(nostack),
+        );
+    }
+    output
+}
+
+#[cfg(not(target_arch = "riscv32"))]
+fn call<const ID: u32, const N: usize>(input: &[u32]) -> [u32; N] {
+    Precompile::from_id(ID)
+        .unwrap()
+        .execute(input)
+        .try_into()
+        .unwrap()
+}
@@ -0,0 +1,207 @@
+//! Edwards25519 point arithmetic and Ed25519 signature verification (RFC 8032), built
+//! on the `Ed25519Add` and `Sha512Compress` precompiles.
+
+use super::sha512::Sha512;
+use super::{call, concat, less_than, mod_add, mod_mul, pow_mod, Precompile, U256, U256_LIMBS};
+pub use common::precompiles::{ED25519_D, ED25519_L, ED25519_P};
+
+/// An affine point on edwards25519, `-x^2 + y^2 = 1 + d x^2 y^2`. The Edwards
+/// addition law is complete, so every point, including the identity, is a `Point`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: U256,
+    pub y: U256,
+}
+
+/// The base point `B` of RFC 8032.
+pub const BASEPOINT: Point = Point {
+    x: [
+        0x8f25d51a, 0xc9562d60, 0x9525a7b2, 0x692cc760, 0xfdd6dc5c, 0xc0a4e231, 0xcd6e53fe,
+        0x216936d3,
+    ],
+    y: [
+        0x66666658, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666, 0x66666666,
+        0x66666666,
+    ],
+};
+
+/// The neutral element `(0, 1)`.
+pub const IDENTITY: Point = Point { x: ZERO, y: ONE };
+
+const ZERO: U256 = [0; U256_LIMBS];
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// A square root of -1 mod p.
+const SQRT_M1: U256 = [
+    0x4a0ea0b0, 0xc4ee1b27, 0xad2fe478, 0x2f431806, 0x3dfbd7a7, 0x2b4d0099, 0x4fc1df0b, 0x2b832480,
+];
+
+/// `(p - 5) / 8`.
+const SQRT_EXPONENT: U256 = [
+    0xfffffffd, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0xffffffff, 0x0fffffff,
+];
+
+/// `2^256 mod l`.
+const R_MOD_L: U256 = [
+    0x8d98951d, 0xd6ec3174, 0x737dcf70, 0xc6ef5bf4, 0xfffffffe, 0xffffffff, 0xffffffff, 0x0fffffff,
+];
+
+impl Point {
+    /// Whether the point satisfies the curve equation, with both coordinates reduced.
+    pub fn is_on_curve(&self) -> bool {
+        let p = &ED25519_P;
+        let x2 = mod_mul(&self.x, &self.x, p);
+        let y2 = mod_mul(&self.y, &self.y, p);
+        let dx2y2 = mod_mul(&ED25519_D, &mod_mul(&x2, &y2, p), p);
+        less_than(&self.x, p)
+            && less_than(&self.y, p)
+            && mod_add(&y2, &negate(&x2), p) == mod_add(&ONE, &dx2y2, p)
+    }
+
+    /// `-P`.
+    pub fn negate(&self) -> Point {
+        Point {
+            x: negate(&self.x),
+            y: reduce(&self.y),
+        }
+    }
+
+    /// The 32-byte encoding of RFC 8032: `y` in little-endian, with the parity of
+    /// `x` in the top bit.
+    pub fn compress(&self) -> [u8; 32] {
+        let (x, y) = (reduce(&self.x), reduce(&self.y));
+        let mut bytes = to_bytes(&y);
+        bytes[31] |= ((x[0] & 1) as u8) << 7;
+        bytes
+    }
+
+    /// Decodes a point encoded by [`Point::compress`]. Returns `None` if `y` is not
+    /// reduced or there is no point with the given `y`-coordinate and sign.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<Point> {
+        let p = &ED25519_P;
+        let mut y = from_bytes(bytes);
+        let odd = y[U256_LIMBS - 1] >> 31 == 1;
+        y[U256_LIMBS - 1] &= 0x7fffffff;
+        if !less_than(&y, p) {
+            return None;
+        }
+
+        // x^2 = u / v, where u = y^2 - 1 and v = d y^2 + 1
+        let y2 = mod_mul(&y, &y, p);
+        let u = mod_add(&y2, &negate(&ONE), p);
+        let v = mod_add(&mod_mul(&ED25519_D, &y2, p), &ONE, p);
+        let v3 = mod_mul(&mod_mul(&v, &v, p), &v, p);
+        let v7 = mod_mul(&mod_mul(&v3, &v3, p), &v, p);
+        let uv3 = mod_mul(&u, &v3, p);
+        let mut x = mod_mul(&uv3, &pow_mod(&mod_mul(&u, &v7, p), &SQRT_EXPONENT, p), p);
+
+        let vx2 = mod_mul(&v, &mod_mul(&x, &x, p), p);
+        if vx2 == negate(&u) && u != ZERO {
+            x = mod_mul(&x, &SQRT_M1, p);
+        } else if vx2 != u {
+            return None;
+        }
+        if x == ZERO && odd {
+            return None;
+        }
+        if (x[0] & 1 == 1) != odd {
+            x = negate(&x);
+        }
+        Some(Point { x, y })
+    }
+}
+
+/// `P + Q`. Both points must be on the curve.
+pub fn add(a: &Point, b: &Point) -> Point {
+    let output =
+        call::<{ Precompile::Ed25519Add.id() }, 16>(&concat::<32>(&[&a.x, &a.y, &b.x, &b.y]));
+    Point {
+        x: output[..U256_LIMBS].try_into().unwrap(),
+        y: output[U256_LIMBS..].try_into().unwrap(),
+    }
+}
+
+/// `2P`.
+pub fn double(a: &Point) -> Point {
+    add(a, a)
+}
+
+/// `kP`, by double-and-add.
+pub fn mul(k: &U256, a: &Point) -> Point {
+    let mut result = IDENTITY;
+    for bit in (0..32 * U256_LIMBS).rev() {
+        result = double(&result);
+        if (k[bit / 32] >> (bit % 32)) & 1 == 1 {
+            result = add(&result, a);
+        }
+    }
+    result
+}
+
+/// Verifies the Ed25519 signature `R || S` of `message` under `public_key`, checking
+/// `[S]B = R + [k]A` where `k = SHA-512(R || A || message) mod l`. Signatures with a
+/// non-canonical `S` are rejected.
+pub fn verify(public_key: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let Some(a) = Point::decompress(public_key) else {
+        return false;
+    };
+    let (r, s) = signature.split_at(32);
+    let s = from_bytes(s.try_into().unwrap());
+    if !less_than(&s, &ED25519_L) {
+        return false;
+    }
+
+    let mut hasher = Sha512::new();
+    hasher.update(r);
+    hasher.update(public_key);
+    hasher.update(message);
+    let k = reduce_wide(&hasher.finalize());
+
+    let check = add(&mul(&s, &BASEPOINT), &mul(&k, &a.negate()));
+    check.compress()[..] == *r
+}
+
+/// A 64-byte little-endian integer mod `l`.
+fn reduce_wide(bytes: &[u8; 64]) -> U256 {
+    let l = &ED25519_L;
+    let lo = from_bytes(bytes[..32].try_into().unwrap());
+    let hi = from_bytes(bytes[32..].try_into().unwrap());
+    mod_add(&mod_mul(&hi, &R_MOD_L, l), &lo, l)
+}
+
+/// `a mod p`.
+fn reduce(a: &U256) -> U256 {
+    mod_add(a, &ZERO, &ED25519_P)
+}
+
+/// `-a mod p`.
+fn negate(a: &U256) -> U256 {
+    let a = reduce(a);
+    if a == ZERO {
+        return ZERO;
+    }
+    let mut result = ZERO;
+    let mut borrow = 0i64;
+    for i in 0..U256_LIMBS {
+        let diff = ED25519_P[i] as i64 - a[i] as i64 - borrow;
+        result[i] = diff as u32;
+        borrow = (diff < 0) as i64;
+    }
+    result
+}
+
+fn from_bytes(bytes: &[u8; 32]) -> U256 {
+    let mut limbs = ZERO;
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks_exact(4)) {
+        *limb = u32::from_le_bytes(chunk.try_into().unwrap());
+    }
+    limbs
+}
+
+fn to_bytes(limbs: &U256) -> [u8; 32] {
+    let mut bytes = [0; 32];
+    for (chunk, limb) in bytes.chunks_exact_mut(4).zip(limbs) {
+        chunk.copy_from_slice(&limb.to_le_bytes());
+    }
+    bytes
+}
@@ -0,0 +1,222 @@
+//! secp256k1 point arithmetic and ECDSA, built on the `Secp256k1Add` and
+//! `Secp256k1Double` precompiles. Scalars are reduced modulo the group order with
+//! [`mod_mul`](super::mod_mul) and [`mod_add`](super::mod_add).
+
+use super::{call, concat, less_than, mod_add, mod_mul, pow_mod, Precompile, U256, U256_LIMBS};
+pub use common::precompiles::{SECP256K1_N, SECP256K1_P};
+
+/// An affine point on secp256k1. The point at infinity is represented as `None` by
+/// the functions of this module.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: U256,
+    pub y: U256,
+}
+
+/// The generator of the secp256k1 group.
+pub const GENERATOR: Point = Point {
+    x: [
+        0x16f81798, 0x59f2815b, 0x2dce28d9, 0x029bfcdb, 0xce870b07, 0x55a06295, 0xf9dcbbac,
+        0x79be667e,
+    ],
+    y: [
+        0xfb10d4b8, 0x9c47d08f, 0xa6855419, 0xfd17b448, 0x0e1108a8, 0x5da4fbfc, 0x26a3c465,
+        0x483ada77,
+    ],
+};
+
+const ZERO: U256 = [0; U256_LIMBS];
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+impl Point {
+    /// Whether `y^2 = x^3 + 7 mod p`, with both coordinates reduced.
+    pub fn is_on_curve(&self) -> bool {
+        let p = &SECP256K1_P;
+        less_than(&self.x, p)
+            && less_than(&self.y, p)
+            && mod_mul(&self.y, &self.y, p) == curve_rhs(&self.x)
+    }
+
+    /// `-P`.
+    pub fn negate(&self) -> Point {
+        Point {
+            x: self.x,
+            y: negate(&self.y, &SECP256K1_P),
+        }
+    }
+}
+
+/// `P + Q`.
+pub fn add(a: Option<Point>, b: Option<Point>) -> Option<Point> {
+    let (a, b) = match (a, b) {
+        (None, b) => return b,
+        (a, None) => return a,
+        (Some(a), Some(b)) => (a, b),
+    };
+    if reduce(&a.x, &SECP256K1_P) == reduce(&b.x, &SECP256K1_P) {
+        return if reduce(&a.y, &SECP256K1_P) == reduce(&b.y, &SECP256K1_P) {
+            double(Some(a))
+        } else {
+            None
+        };
+    }
+    let output =
+        call::<{ Precompile::Secp256k1Add.id() }, 16>(&concat::<32>(&[&a.x, &a.y, &b.x, &b.y]));
+    Some(to_point(&output))
+}
+
+/// `2P`.
+pub fn double(a: Option<Point>) -> Option<Point> {
+    // secp256k1 has no points of order 2, so this only excludes points off the curve
+    let a = a.filter(|a| reduce(&a.y, &SECP256K1_P) != ZERO)?;
+    let output = call::<{ Precompile::Secp256k1Double.id() }, 16>(&concat::<16>(&[&a.x, &a.y]));
+    Some(to_point(&output))
+}
+
+/// `kP`, by double-and-add.
+pub fn mul(k: &U256, a: Option<Point>) -> Option<Point> {
+    let mut result = None;
+    for bit in (0..32 * U256_LIMBS).rev() {
+        result = double(result);
+        if (k[bit / 32] >> (bit % 32)) & 1 == 1 {
+            result = add(result, a);
+        }
+    }
+    result
+}
+
+/// Verifies the ECDSA signature `(r, s)` of the message hash `z` under `public_key`.
+/// High-`s` signatures are accepted.
+pub fn ecdsa_verify(z: &U256, r: &U256, s: &U256, public_key: &Point) -> bool {
+    let n = &SECP256K1_N;
+    if !is_scalar(r) || !is_scalar(s) || !public_key.is_on_curve() {
+        return false;
+    }
+    let s_inv = inv_mod(s, n);
+    let u1 = mod_mul(z, &s_inv, n);
+    let u2 = mod_mul(r, &s_inv, n);
+    match add(mul(&u1, Some(GENERATOR)), mul(&u2, Some(*public_key))) {
+        Some(point) => reduce(&point.x, n) == *r,
+        None => false,
+    }
+}
+
+/// Recovers the public key from the ECDSA signature `(r, s)` of the message hash `z`,
+/// where bit 0 of `recovery_id` is the parity of the `y`-coordinate of `R` and bit 1
+/// is set if the `x`-coordinate of `R` is `r + n`. Returns `None` if the signature
+/// is invalid.
+pub fn ecdsa_recover(z: &U256, r: &U256, s: &U256, recovery_id: u8) -> Option<Point> {
+    let (n, p) = (&SECP256K1_N, &SECP256K1_P);
+    if !is_scalar(r) || !is_scalar(s) || recovery_id > 3 {
+        return None;
+    }
+    let x = if recovery_id & 2 != 0 {
+        let (x, overflow) = add_with_carry(r, n);
+        if overflow || !less_than(&x, p) {
+            return None;
+        }
+        x
+    } else {
+        *r
+    };
+    let big_r = lift_x(&x, recovery_id & 1 == 1)?;
+
+    // Q = r^-1 (sR - zG)
+    let r_inv = inv_mod(r, n);
+    let u1 = negate(&mod_mul(z, &r_inv, n), n);
+    let u2 = mod_mul(s, &r_inv, n);
+    add(mul(&u1, Some(GENERATOR)), mul(&u2, Some(big_r)))
+}
+
+/// The point with the given `x`-coordinate and `y`-coordinate of the given parity.
+fn lift_x(x: &U256, odd: bool) -> Option<Point> {
+    let p = &SECP256K1_P;
+    // p = 3 mod 4, so a square root of a is a^((p + 1) / 4)
+    let mut exponent = [0; U256_LIMBS];
+    let (p_plus_one, _) = add_with_carry(p, &ONE);
+    for i in 0..U256_LIMBS {
+        exponent[i] = (p_plus_one[i] >> 2) | p_plus_one.get(i + 1).map_or(0, |limb| limb << 30);
+    }
+    let rhs = curve_rhs(x);
+    let y = pow_mod(&rhs, &exponent, p);
+    if mod_mul(&y, &y, p) != rhs {
+        return None;
+    }
+    let y = if (y[0] & 1 == 1) == odd {
+        y
+    } else {
+        negate(&y, p)
+    };
+    Some(Point { x: *x, y })
+}
+
+/// `x^3 + 7 mod p`.
+fn curve_rhs(x: &U256) -> U256 {
+    let p = &SECP256K1_P;
+    let x_cubed = mod_mul(&mod_mul(x, x, p), x, p);
+    let mut seven = ZERO;
+    seven[0] = 7;
+    mod_add(&x_cubed, &seven, p)
+}
+
+fn to_point(output: &[u32; 16]) -> Point {
+    Point {
+        x: output[..U256_LIMBS].try_into().unwrap(),
+        y: output[U256_LIMBS..].try_into().unwrap(),
+    }
+}
+
+/// Whether `0 < a < n`.
+fn is_scalar(a: &U256) -> bool {
+    *a != ZERO && less_than(a, &SECP256K1_N)
+}
+
+/// `a mod modulus`, for `modulus > 2^255` (i.e. `p` or `n`), so that at most one
+/// subtraction is needed.
+fn reduce(a: &U256, modulus: &U256) -> U256 {
+    if less_than(a, modulus) {
+        return *a;
+    }
+    let mut result = ZERO;
+    let mut borrow = 0i64;
+    for i in 0..U256_LIMBS {
+        let diff = a[i] as i64 - modulus[i] as i64 - borrow;
+        result[i] = diff as u32;
+        borrow = (diff < 0) as i64;
+    }
+    result
+}
+
+/// `-a mod modulus`.
+fn negate(a: &U256, modulus: &U256) -> U256 {
+    let a = reduce(a, modulus);
+    if a == ZERO {
+        return ZERO;
+    }
+    let mut result = ZERO;
+    let mut borrow = 0i64;
+    for i in 0..U256_LIMBS {
+        let diff = modulus[i] as i64 - a[i] as i64 - borrow;
+        result[i] = diff as u32;
+        borrow = (diff < 0) as i64;
+    }
+    result
+}
+
+/// `a^-1 mod modulus`, for a prime modulus.
+fn inv_mod(a: &U256, modulus: &U256) -> U256 {
+    let mut exponent = *modulus;
+    exponent[0] -= 2;
+    pow_mod(a, &exponent, modulus)
+}
+
+fn add_with_carry(a: &U256, b: &U256) -> (U256, bool) {
+    let mut sum = ZERO;
+    let mut carry = 0u64;
+    for i in 0..U256_LIMBS {
+        let limb = a[i] as u64 + b[i] as u64 + carry;
+        sum[i] = limb as u32;
+        carry = limb >> 32;
+    }
+    (sum, carry != 0)
+}
@@ -0,0 +1,99 @@
+//! SHA-512, built on the `Sha512Compress` precompile.
+
+use super::{call, Precompile};
+
+const INITIAL_STATE: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The SHA-512 compression function. Never inlined, since the precompile expands to
+/// a long virtual sequence at each call site.
+#[inline(never)]
+pub fn compress(state: &mut [u64; 8], block: &[u8; 128]) {
+    let words = block
+        .chunks_exact(8)
+        .map(|bytes| u64::from_be_bytes(bytes.try_into().unwrap()));
+    let mut input = [0u32; 48];
+    for (pair, word) in input
+        .chunks_exact_mut(2)
+        .zip(state.iter().copied().chain(words))
+    {
+        pair[0] = word as u32;
+        pair[1] = (word >> 32) as u32;
+    }
+    let output = call::<{ Precompile::Sha512Compress.id() }, 144>(&input);
+    for (word, pair) in state.iter_mut().zip(output.chunks_exact(2)) {
+        *word = pair[0] as u64 | (pair[1] as u64) << 32;
+    }
+}
+
+/// An incremental SHA-512 hasher.
+#[derive(Clone)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: [u8; 128],
+    buffered: usize,
+    length: u128,
+}
+
+impl Default for Sha512 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sha512 {
+    pub fn new() -> Self {
+        Self {
+            state: INITIAL_STATE,
+            buffer: [0; 128],
+            buffered: 0,
+            length: 0,
+        }
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u128;
+        while !data.is_empty() {
+            let n = data.len().min(128 - self.buffered);
+            self.buffer[self.buffered..self.buffered + n].copy_from_slice(&data[..n]);
+            self.buffered += n;
+            data = &data[n..];
+            if self.buffered == 128 {
+                compress(&mut self.state, &self.buffer);
+                self.buffered = 0;
+            }
+        }
+    }
+
+    pub fn finalize(mut self) -> [u8; 64] {
+        let bit_length = self.length * 8;
+        self.update(&[0x80]);
+        if self.buffered > 112 {
+            self.update(&[0; 128][self.buffered..]);
+        }
+        self.buffer[self.buffered..112].fill(0);
+        self.buffer[112..].copy_from_slice(&bit_length.to_be_bytes());
+        compress(&mut self.state, &self.buffer);
+
+        let mut digest = [0; 64];
+        for (bytes, word) in digest.chunks_exact_mut(8).zip(self.state.iter()) {
+            bytes.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+/// The SHA-512 digest of `data`.
+pub fn sha512(data: &[u8]) -> [u8; 64] {
+    let mut hasher = Sha512::new();
+    hasher.update(data);
+    hasher.finalize()
+}
@@ -0,0 +1,57 @@
+//! Registry of user-defined instructions in the custom-0/custom-1 opcode space.
+//! See `common::rv_trace::custom_instruction_funct`.
+
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+
+use common::rv_trace::RV32IM;
+
+/// Computes the value written to `rd` from the values of `rs1` and `rs2`.
+pub type CustomInstructionFn = fn(u32, u32) -> u32;
+
+type CustomInstructionKey = (RV32IM, u32, u32);
+
+static CUSTOM_INSTRUCTIONS: LazyLock<RwLock<HashMap<CustomInstructionKey, CustomInstructionFn>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Registers the semantics of the R-type custom instruction with the given major
+/// `opcode` (`RV32IM::CUSTOM_0` or `RV32IM::CUSTOM_1`), `funct3` and `funct7`.
+/// Registering the same instruction again replaces its semantics.
+pub fn register_custom_instruction(
+    opcode: RV32IM,
+    funct3: u32,
+    funct7: u32,
+    execute: CustomInstructionFn,
+) {
+    assert!(
+        matches!(opcode, RV32IM::CUSTOM_0 | RV32IM::CUSTOM_1),
+        "{opcode:?} is not a custom opcode"
+    );
+    assert!(funct3 < 1 << 3, "funct3 = {funct3} does not fit in 3 bits");
+    assert!(funct7 < 1 << 7, "funct7 = {funct7} does not fit in 7 bits");
+    CUSTOM_INSTRUCTIONS
+        .write()
+        .unwrap()
+        .insert((opcode, funct3, funct7), execute);
+}
+
+/// Removes a registration made by [`register_custom_instruction`].
+#[cfg(test)]
+pub(crate) fn unregister_custom_instruction(opcode: RV32IM, funct3: u32, funct7: u32) {
+    CUSTOM_INSTRUCTIONS
+        .write()
+        .unwrap()
+        .remove(&(opcode, funct3, funct7));
+}
+
+pub(crate) fn custom_instruction(
+    opcode: RV32IM,
+    funct3: u32,
+    funct7: u32,
+) -> Option<CustomInstructionFn> {
+    CUSTOM_INSTRUCTIONS
+        .read()
+        .unwrap()
+        .get(&(opcode, funct3, funct7))
+        .copied()
+}
@@ -0,0 +1,88 @@
+use std::fmt;
+
+use common::constants::RAM_START_ADDRESS;
+use object::{Architecture, Object, ObjectSection};
+
+use crate::memory_bounds;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ElfError {
+    Io(String),
+    Parse(String),
+    UnsupportedArchitecture(Architecture),
+    SectionOutOfBounds {
+        name: String,
+        start: u64,
+        end: u64,
+        memory_end: u64,
+    },
+    StackOutOfBounds {
+        stack_top: u64,
+        memory_end: u64,
+    },
+}
+
+impl fmt::Display for ElfError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ElfError::Io(e) => write!(f, "Failed to read ELF: {e}"),
+            ElfError::Parse(e) => write!(f, "Failed to parse ELF: {e}"),
+            ElfError::UnsupportedArchitecture(arch) => {
+                write!(f, "Unsupported ELF architecture {arch:?}, expected RISC-V 32")
+            }
+            ElfError::SectionOutOfBounds {
+                name,
+                start,
+                end,
+                memory_end,
+            } => write!(
+                f,
+                "Section `{name}` [0x{start:X}, 0x{end:X}) does not fit in memory [0x{RAM_START_ADDRESS:X}, 0x{memory_end:X}); consider increasing `memory_size`"
+            ),
+            ElfError::StackOutOfBounds {
+                stack_top,
+                memory_end,
+            } => write!(
+                f,
+                "Stack ends at 0x{stack_top:X}, beyond the end of memory (0x{memory_end:X}); consider increasing `memory_size` or decreasing `stack_size`"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ElfError {}
+
+/// Checks that `elf` is a RISC-V 32 executable whose allocated sections, followed
+/// by a stack of `stack_size` bytes, fit in the `memory_size` bytes of memory
+/// starting at `RAM_START_ADDRESS`.
+pub fn validate_elf(elf: &[u8], memory_size: u64, stack_size: u64) -> Result<(), ElfError> {
+    let obj = object::File::parse(elf).map_err(|e| ElfError::Parse(e.to_string()))?;
+    if obj.architecture() != Architecture::Riscv32 {
+        return Err(ElfError::UnsupportedArchitecture(obj.architecture()));
+    }
+
+    let memory_end = RAM_START_ADDRESS + memory_size;
+    // Sections that are not loaded into memory (symbols, debug info, etc.) have address 0
+    for section in obj.sections().filter(|s| s.address() != 0 && s.size() != 0) {
+        let start = section.address();
+        let end = start + section.size();
+        if start < RAM_START_ADDRESS || end > memory_end {
+            return Err(ElfError::SectionOutOfBounds {
+                name: section.name().unwrap_or("<unknown>").to_string(),
+                start,
+                end,
+                memory_end,
+            });
+        }
+    }
+
+    let bounds = memory_bounds(elf, memory_size, stack_size);
+    if bounds.stack_top > bounds.memory_end {
+        return Err(ElfError::StackOutOfBounds {
+            stack_top: bounds.stack_top,
+            memory_end: bounds.memory_end,
+        });
+    }
+
+    Ok(())
+}
@@ -6,7 +6,11 @@ use std::convert::TryInto;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use crate::custom::custom_instruction;
 use crate::trace::Tracer;
+use common::constants::float_register_index;
+use common::float::{self, RoundingMode};
+use common::precompiles::Precompile;
 use common::rv_trace::*;
 
 use self::fnv::FnvHashMap;
@@ -74,7 +78,8 @@ pub struct Cpu {
     // using only lower 32bits of x, pc, and csr registers
     // for 32-bit mode
     pub x: [i64; 32],
-    f: [f64; 32],
+    /// Raw bits of the floating-point registers (see `common::float`)
+    f: [u64; 32],
     pc: u64,
     csr: [u64; CSR_CAPACITY],
     mmu: Mmu,
@@ -235,7 +240,7 @@ impl Cpu {
             privilege_mode: PrivilegeMode::Machine,
             wfi: false,
             x: [0; 32],
-            f: [0.0; 32],
+            f: [0; 32],
             pc: 0,
             csr: [0; CSR_CAPACITY],
             mmu: Mmu::new(Xlen::Bit64, terminal, tracer.clone()),
@@ -333,14 +338,15 @@ impl Cpu {
                 // setup trace
                 let trace_inst = inst.trace.unwrap()(&inst, &self.xlen, word, instruction_address);
                 self.tracer.start_instruction(trace_inst);
-                self.tracer.capture_pre_state(self.x, &self.xlen);
+                self.tracer.capture_pre_state(self.x, self.f, &self.xlen);
 
                 // execute
                 let result = (inst.operation)(self, word, instruction_address);
                 self.x[0] = 0; // hardwired zero
 
                 // complete trace
-                self.tracer.capture_post_state(self.x, &self.xlen);
+                self.tracer.capture_post_state(self.x, self.f, &self.xlen);
+                self.tracer.check_memory_bounds(self.x, &self.xlen);
                 self.tracer.end_instruction();
 
                 result
@@ -820,7 +826,7 @@ impl Cpu {
         self.csr[CSR_FCSR_ADDRESS as usize] |= 0x10;
     }
 
-    fn set_fcsr_dz(&mut self) {
+    fn _set_fcsr_dz(&mut self) {
         self.csr[CSR_FCSR_ADDRESS as usize] |= 0x8;
     }
 
@@ -1892,7 +1898,184 @@ fn trace_j(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstr
     }
 }
 
-const INSTRUCTION_NUM: usize = 116;
+fn parse_custom_funct(word: u32) -> (u32, u32) {
+    ((word >> 12) & 0x7, word >> 25) // (funct3 [14:12], funct7 [31:25])
+}
+
+fn execute_custom(cpu: &mut Cpu, opcode: RV32IM, word: u32) -> Result<(), Trap> {
+    let f = parse_format_r(word);
+    let (funct3, funct7) = parse_custom_funct(word);
+    let execute = custom_instruction(opcode, funct3, funct7).unwrap_or_else(|| {
+        panic!("Unregistered custom instruction {opcode:?} (funct3 = {funct3}, funct7 = {funct7})")
+    });
+    let result = execute(cpu.x[f.rs1] as u32, cpu.x[f.rs2] as u32);
+    cpu.x[f.rd] = cpu.sign_extend(result as i64);
+    Ok(())
+}
+
+fn trace_custom(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let (funct3, funct7) = parse_custom_funct(word);
+    ELFInstruction {
+        imm: Some(custom_instruction_funct(funct3, funct7)),
+        ..trace_r(inst, xlen, word, address)
+    }
+}
+
+fn execute_precompile(cpu: &mut Cpu, word: u32) -> Result<(), Trap> {
+    let f = parse_format_r(word);
+    let (_, id) = parse_custom_funct(word);
+    let precompile =
+        Precompile::from_id(id).unwrap_or_else(|| panic!("Unknown precompile (funct7 = {id})"));
+
+    let mut input = Vec::with_capacity(precompile.input_words());
+    for i in 0..precompile.input_words() {
+        let address = cpu.x[f.rs1].wrapping_add(4 * i as i64) as u64;
+        input.push(cpu.mmu.load_word(address)?);
+    }
+    let output = precompile.execute(&input);
+    for (i, word) in output.into_iter().enumerate() {
+        let address = cpu.x[f.rs2].wrapping_add(4 * i as i64) as u64;
+        cpu.mmu.store_word(address, word)?;
+    }
+
+    let output_address = cpu.unsigned_data(cpu.x[f.rs2]);
+    cpu.tracer.push_precompile(input, output_address);
+    Ok(())
+}
+
+fn trace_precompile(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let (_, id) = parse_custom_funct(word);
+    ELFInstruction {
+        rd: None,
+        imm: Some(id as i64),
+        ..trace_r(inst, xlen, word, address)
+    }
+}
+
+/// Which operands of a floating-point instruction are `f` registers: `(rd, rs1, rs2)`,
+/// where `rs2` is `None` if the instruction has no second operand.
+fn float_operands(opcode: RV32IM) -> (bool, bool, Option<bool>) {
+    use RV32IM::*;
+    match opcode {
+        FEQ_S | FLT_S | FLE_S | FEQ_D | FLT_D | FLE_D => (false, true, Some(true)),
+        FCLASS_S | FCLASS_D | FCVT_W_S | FCVT_WU_S | FCVT_W_D | FCVT_WU_D | FMV_X_W => {
+            (false, true, None)
+        }
+        FCVT_S_W | FCVT_S_WU | FCVT_D_W | FCVT_D_WU | FMV_W_X => (true, false, None),
+        FSQRT_S | FSQRT_D | FCVT_S_D | FCVT_D_S => (true, true, None),
+        _ => (true, true, Some(true)),
+    }
+}
+
+/// The register index of operand `index`, which is an `f` register if `float`.
+fn float_operand_index(index: usize, float: bool) -> u64 {
+    if float {
+        float_register_index(index as u64)
+    } else {
+        normalize_register(index)
+    }
+}
+
+/// The rounding mode of a floating-point instruction. A dynamic rounding mode is read
+/// from `frm`, which must be round-to-nearest-even (see `common::float`).
+fn float_rounding_mode(cpu: &Cpu, word: u32) -> Result<RoundingMode, Trap> {
+    let rm = ((word >> 12) & 0x7) as u64;
+    if rm == RoundingMode::DYNAMIC {
+        let frm = cpu.read_csr_raw(CSR_FRM_ADDRESS);
+        if frm != 0 {
+            panic!("Unsupported dynamic rounding mode (frm = {frm})");
+        }
+    }
+    RoundingMode::from_rm(rm).ok_or(Trap {
+        trap_type: TrapType::IllegalInstruction,
+        value: word as u64,
+    })
+}
+
+fn execute_float(cpu: &mut Cpu, opcode: RV32IM, word: u32) -> Result<(), Trap> {
+    let f = parse_format_r2(word);
+    let (rd_float, rs1_float, _) = float_operands(opcode);
+    let rm = if float::is_rounding(opcode) {
+        float_rounding_mode(cpu, word)?
+    } else {
+        RoundingMode::NearestEven
+    };
+    let rs1 = if rs1_float {
+        cpu.f[f.rs1]
+    } else {
+        cpu.x[f.rs1] as u32 as u64
+    };
+    let result = float::execute(opcode, rm, rs1, cpu.f[f.rs2], cpu.f[f.rs3]);
+    if rd_float {
+        cpu.f[f.rd] = result;
+    } else {
+        cpu.x[f.rd] = cpu.sign_extend(result as u32 as i32 as i64);
+    }
+    Ok(())
+}
+
+/// `FLW` (`words = 1`) or `FLD` (`words = 2`). Doubles are accessed as two words, so
+/// they only need to be word-aligned.
+fn execute_float_load(cpu: &mut Cpu, word: u32, words: usize) -> Result<(), Trap> {
+    let f = parse_format_i(word);
+    let address = cpu.x[f.rs1].wrapping_add(f.imm);
+    let lo = cpu.mmu.load_word(address as u64)? as u64;
+    let hi = match words {
+        1 => float::NAN_BOX >> 32,
+        _ => cpu.mmu.load_word(address.wrapping_add(4) as u64)? as u64,
+    };
+    cpu.f[f.rd] = lo | hi << 32;
+    Ok(())
+}
+
+/// `FSW` (`words = 1`) or `FSD` (`words = 2`).
+fn execute_float_store(cpu: &mut Cpu, word: u32, words: usize) -> Result<(), Trap> {
+    let f = parse_format_s(word);
+    let address = cpu.x[f.rs1].wrapping_add(f.imm);
+    for i in 0..words {
+        let data = (cpu.f[f.rs2] >> (32 * i)) as u32;
+        cpu.mmu
+            .store_word(address.wrapping_add(4 * i as i64) as u64, data)?;
+    }
+    Ok(())
+}
+
+/// Traces an R-type or R4-type floating-point instruction. `f` registers are traced as
+/// their register index, and `imm` holds the rounding mode and `rs3` (see
+/// `common::float::encode_imm`).
+fn trace_float(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let opcode = RV32IM::from_str(inst.name).unwrap();
+    let f = parse_format_r2(word);
+    let (rd_float, rs1_float, rs2_float) = float_operands(opcode);
+    let rs3 = float::is_fused_multiply_add(opcode).then(|| float_register_index(f.rs3 as u64));
+    ELFInstruction {
+        opcode,
+        address: normalize_u64(address, xlen),
+        imm: Some(float::encode_imm(((word >> 12) & 0x7) as u64, rs3)),
+        rs1: Some(float_operand_index(f.rs1, rs1_float)),
+        rs2: rs2_float.map(|float| float_operand_index(f.rs2, float)),
+        rd: Some(float_operand_index(f.rd, rd_float)),
+        virtual_sequence_remaining: None,
+    }
+}
+
+fn trace_float_load(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let f = parse_format_i(word);
+    ELFInstruction {
+        rd: Some(float_register_index(f.rd as u64)),
+        ..trace_i(inst, xlen, word, address)
+    }
+}
+
+fn trace_float_store(inst: &Instruction, xlen: &Xlen, word: u32, address: u64) -> ELFInstruction {
+    let f = parse_format_s(word);
+    ELFInstruction {
+        rs2: Some(float_register_index(f.rs2 as u64)),
+        ..trace_s(inst, xlen, word, address)
+    }
+}
+
+const INSTRUCTION_NUM: usize = 149;
 
 // @TODO: Reorder in often used order as
 pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
@@ -2416,6 +2599,30 @@ pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
         disassemble: dump_format_csr,
         trace: None,
     },
+    Instruction {
+        mask: 0x0000007f,
+        data: 0x0000000b,
+        name: "CUSTOM_0",
+        operation: |cpu, word, _address| execute_custom(cpu, RV32IM::CUSTOM_0, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_custom),
+    },
+    Instruction {
+        mask: 0x0000007f,
+        data: 0x0000002b,
+        name: "CUSTOM_1",
+        operation: |cpu, word, _address| execute_custom(cpu, RV32IM::CUSTOM_1, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_custom),
+    },
+    Instruction {
+        mask: 0x0000707f,
+        data: 0x0000005b,
+        name: "PRECOMPILE",
+        operation: |cpu, word, _address| execute_precompile(cpu, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_precompile),
+    },
     Instruction {
         mask: 0xfe00707f,
         data: 0x02004033,
@@ -2523,353 +2730,452 @@ pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
         trace: None,
     },
     Instruction {
-        mask: 0xfe00007f,
-        data: 0x02000053,
-        name: "FADD.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = cpu.f[f.rs1] + cpu.f[f.rs2];
-            Ok(())
-        },
-        disassemble: dump_format_r,
-        trace: None,
+        mask: 0x0000707f,
+        data: 0x00002007,
+        name: "FLW",
+        operation: |cpu, word, _address| execute_float_load(cpu, word, 1),
+        disassemble: dump_format_i_mem,
+        trace: Some(trace_float_load),
     },
     Instruction {
-        mask: 0xfff0007f,
-        data: 0xd2200053,
-        name: "FCVT.D.L",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = cpu.x[f.rs1] as f64;
-            Ok(())
-        },
+        mask: 0x0000707f,
+        data: 0x00002027,
+        name: "FSW",
+        operation: |cpu, word, _address| execute_float_store(cpu, word, 1),
+        disassemble: dump_format_s,
+        trace: Some(trace_float_store),
+    },
+    Instruction {
+        mask: 0xfe00007f,
+        data: 0x00000053,
+        name: "FADD.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FADD_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0007f,
-        data: 0x42000053,
-        name: "FCVT.D.S",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            // Is this implementation correct?
-            cpu.f[f.rd] = f32::from_bits(cpu.f[f.rs1].to_bits() as u32) as f64;
-            Ok(())
-        },
+        mask: 0xfe00007f,
+        data: 0x08000053,
+        name: "FSUB.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSUB_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0007f,
-        data: 0xd2000053,
-        name: "FCVT.D.W",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = cpu.x[f.rs1] as i32 as f64;
-            Ok(())
-        },
+        mask: 0xfe00007f,
+        data: 0x10000053,
+        name: "FMUL.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMUL_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0007f,
-        data: 0xd2100053,
-        name: "FCVT.D.WU",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = cpu.x[f.rs1] as u32 as f64;
-            Ok(())
-        },
+        mask: 0xfe00007f,
+        data: 0x18000053,
+        name: "FDIV.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FDIV_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfff0007f,
-        data: 0x40100053,
-        name: "FCVT.S.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            // Is this implementation correct?
-            cpu.f[f.rd] = cpu.f[f.rs1] as f32 as f64;
-            Ok(())
-        },
+        data: 0x58000053,
+        name: "FSQRT.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSQRT_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0007f,
-        data: 0xc2000053,
-        name: "FCVT.W.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            // Is this implementation correct?
-            cpu.x[f.rd] = cpu.f[f.rs1] as u32 as i32 as i64;
-            Ok(())
-        },
+        mask: 0xfe00707f,
+        data: 0x20000053,
+        name: "FSGNJ.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJ_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfe00007f,
-        data: 0x1a000053,
-        name: "FDIV.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            let dividend = cpu.f[f.rs1];
-            let divisor = cpu.f[f.rs2];
-            // Is this implementation correct?
-            if divisor == 0.0 {
-                cpu.f[f.rd] = std::f64::INFINITY;
-                cpu.set_fcsr_dz();
-            } else if divisor == -0.0 {
-                cpu.f[f.rd] = std::f64::NEG_INFINITY;
-                cpu.set_fcsr_dz();
-            } else {
-                cpu.f[f.rd] = dividend / divisor;
-            }
-            Ok(())
-        },
+        mask: 0xfe00707f,
+        data: 0x20001053,
+        name: "FSGNJN.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJN_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0x0000707f,
-        data: 0x0000000f,
-        name: "FENCE",
-        operation: |_cpu, _word, _address| {
-            // Do nothing?
-            Ok(())
-        },
-        disassemble: dump_empty,
-        trace: Some(trace_i),
+        mask: 0xfe00707f,
+        data: 0x20002053,
+        name: "FSGNJX.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJX_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0x0000707f,
-        data: 0x0000100f,
-        name: "FENCE.I",
-        operation: |_cpu, _word, _address| {
-            // Do nothing?
-            Ok(())
-        },
-        disassemble: dump_empty,
-        trace: None,
+        mask: 0xfe00707f,
+        data: 0x28000053,
+        name: "FMIN.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMIN_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfe00707f,
-        data: 0xa2002053,
-        name: "FEQ.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.x[f.rd] = match cpu.f[f.rs1] == cpu.f[f.rs2] {
-                true => 1,
-                false => 0,
-            };
-            Ok(())
-        },
-        disassemble: dump_empty,
-        trace: None,
+        data: 0x28001053,
+        name: "FMAX.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMAX_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0x0000707f,
-        data: 0x00003007,
-        name: "FLD",
-        operation: |cpu, word, _address| {
-            let f = parse_format_i(word);
-            cpu.f[f.rd] = match cpu
-                .mmu
-                .load_doubleword(cpu.x[f.rs1].wrapping_add(f.imm) as u64)
-            {
-                Ok(data) => f64::from_bits(data),
-                Err(e) => return Err(e),
-            };
-            Ok(())
-        },
-        disassemble: dump_format_i,
-        trace: None,
+        mask: 0xfe00707f,
+        data: 0xa0002053,
+        name: "FEQ.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FEQ_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfe00707f,
-        data: 0xa2000053,
-        name: "FLE.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.x[f.rd] = match cpu.f[f.rs1] <= cpu.f[f.rs2] {
-                true => 1,
-                false => 0,
-            };
-            Ok(())
-        },
+        data: 0xa0001053,
+        name: "FLT.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FLT_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfe00707f,
-        data: 0xa2001053,
-        name: "FLT.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.x[f.rd] = match cpu.f[f.rs1] < cpu.f[f.rs2] {
-                true => 1,
-                false => 0,
-            };
-            Ok(())
-        },
+        data: 0xa0000053,
+        name: "FLE.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FLE_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0x0000707f,
-        data: 0x00002007,
-        name: "FLW",
-        operation: |cpu, word, _address| {
-            let f = parse_format_i(word);
-            cpu.f[f.rd] = match cpu.mmu.load_word(cpu.x[f.rs1].wrapping_add(f.imm) as u64) {
-                Ok(data) => f64::from_bits(data as i32 as i64 as u64),
-                Err(e) => return Err(e),
-            };
-            Ok(())
-        },
-        disassemble: dump_format_i_mem,
-        trace: None,
+        mask: 0xfff0707f,
+        data: 0xe0001053,
+        name: "FCLASS.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCLASS_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0x0600007f,
-        data: 0x02000043,
-        name: "FMADD.D",
-        operation: |cpu, word, _address| {
-            // @TODO: Update fcsr if needed?
-            let f = parse_format_r2(word);
-            cpu.f[f.rd] = cpu.f[f.rs1] * cpu.f[f.rs2] + cpu.f[f.rs3];
-            Ok(())
-        },
-        disassemble: dump_format_r2,
-        trace: None,
+        mask: 0xfff0007f,
+        data: 0xc0000053,
+        name: "FCVT.W.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_W_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfe00007f,
-        data: 0x12000053,
-        name: "FMUL.D",
-        operation: |cpu, word, _address| {
-            // @TODO: Update fcsr if needed?
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = cpu.f[f.rs1] * cpu.f[f.rs2];
-            Ok(())
-        },
+        mask: 0xfff0007f,
+        data: 0xc0100053,
+        name: "FCVT.WU.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_WU_S, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0707f,
-        data: 0xf2000053,
-        name: "FMV.D.X",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = f64::from_bits(cpu.x[f.rs1] as u64);
-            Ok(())
-        },
+        mask: 0xfff0007f,
+        data: 0xd0000053,
+        name: "FCVT.S.W",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_S_W, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
-        mask: 0xfff0707f,
-        data: 0xe2000053,
-        name: "FMV.X.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.x[f.rd] = cpu.f[f.rs1].to_bits() as i64;
-            Ok(())
-        },
+        mask: 0xfff0007f,
+        data: 0xd0100053,
+        name: "FCVT.S.WU",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_S_WU, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfff0707f,
         data: 0xe0000053,
         name: "FMV.X.W",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.x[f.rd] = cpu.f[f.rs1].to_bits() as i32 as i64;
-            Ok(())
-        },
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMV_X_W, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfff0707f,
         data: 0xf0000053,
         name: "FMV.W.X",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r(word);
-            cpu.f[f.rd] = f64::from_bits(cpu.x[f.rs1] as u32 as u64);
-            Ok(())
-        },
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMV_W_X, word),
         disassemble: dump_format_r,
-        trace: None,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0x0600007f,
-        data: 0x0200004b,
-        name: "FNMSUB.D",
-        operation: |cpu, word, _address| {
-            let f = parse_format_r2(word);
-            cpu.f[f.rd] = -(cpu.f[f.rs1] * cpu.f[f.rs2]) + cpu.f[f.rs3];
-            Ok(())
-        },
+        data: 0x00000043,
+        name: "FMADD.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMADD_S, word),
         disassemble: dump_format_r2,
-        trace: None,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x00000047,
+        name: "FMSUB.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMSUB_S, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x0000004b,
+        name: "FNMSUB.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FNMSUB_S, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x0000004f,
+        name: "FNMADD.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FNMADD_S, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0000707f,
+        data: 0x00003007,
+        name: "FLD",
+        operation: |cpu, word, _address| execute_float_load(cpu, word, 2),
+        disassemble: dump_format_i_mem,
+        trace: Some(trace_float_load),
     },
     Instruction {
         mask: 0x0000707f,
         data: 0x00003027,
         name: "FSD",
-        operation: |cpu, word, _address| {
-            let f = parse_format_s(word);
-            cpu.mmu.store_doubleword(
-                cpu.x[f.rs1].wrapping_add(f.imm) as u64,
-                cpu.f[f.rs2].to_bits(),
-            )
-        },
+        operation: |cpu, word, _address| execute_float_store(cpu, word, 2),
         disassemble: dump_format_s,
-        trace: None,
+        trace: Some(trace_float_store),
+    },
+    Instruction {
+        mask: 0xfe00007f,
+        data: 0x02000053,
+        name: "FADD.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FADD_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00007f,
+        data: 0x0a000053,
+        name: "FSUB.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSUB_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00007f,
+        data: 0x12000053,
+        name: "FMUL.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMUL_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00007f,
+        data: 0x1a000053,
+        name: "FDIV.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FDIV_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0x5a000053,
+        name: "FSQRT.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSQRT_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
     },
     Instruction {
         mask: 0xfe00707f,
         data: 0x22000053,
         name: "FSGNJ.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJ_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0x22001053,
+        name: "FSGNJN.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJN_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0x22002053,
+        name: "FSGNJX.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FSGNJX_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0x2a000053,
+        name: "FMIN.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMIN_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0x2a001053,
+        name: "FMAX.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMAX_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0xa2002053,
+        name: "FEQ.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FEQ_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0xa2001053,
+        name: "FLT.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FLT_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfe00707f,
+        data: 0xa2000053,
+        name: "FLE.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FLE_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0707f,
+        data: 0xe2001053,
+        name: "FCLASS.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCLASS_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0xc2000053,
+        name: "FCVT.W.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_W_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0xc2100053,
+        name: "FCVT.WU.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_WU_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0xd2000053,
+        name: "FCVT.D.W",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_D_W, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0xd2100053,
+        name: "FCVT.D.WU",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_D_WU, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0x40100053,
+        name: "FCVT.S.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_S_D, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0x42000053,
+        name: "FCVT.D.S",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FCVT_D_S, word),
+        disassemble: dump_format_r,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x02000043,
+        name: "FMADD.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMADD_D, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x02000047,
+        name: "FMSUB.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FMSUB_D, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x0200004b,
+        name: "FNMSUB.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FNMSUB_D, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0x0600007f,
+        data: 0x0200004f,
+        name: "FNMADD.D",
+        operation: |cpu, word, _address| execute_float(cpu, RV32IM::FNMADD_D, word),
+        disassemble: dump_format_r2,
+        trace: Some(trace_float),
+    },
+    Instruction {
+        mask: 0xfff0007f,
+        data: 0xd2200053,
+        name: "FCVT.D.L",
         operation: |cpu, word, _address| {
             let f = parse_format_r(word);
-            let rs1_bits = cpu.f[f.rs1].to_bits();
-            let rs2_bits = cpu.f[f.rs2].to_bits();
-            let sign_bit = rs2_bits & 0x8000000000000000;
-            cpu.f[f.rd] = f64::from_bits(sign_bit | (rs1_bits & 0x7fffffffffffffff));
+            cpu.f[f.rd] = (cpu.x[f.rs1] as f64).to_bits();
             Ok(())
         },
         disassemble: dump_format_r,
         trace: None,
     },
     Instruction {
-        mask: 0xfe00707f,
-        data: 0x22002053,
-        name: "FSGNJX.D",
+        mask: 0xfff0707f,
+        data: 0xf2000053,
+        name: "FMV.D.X",
         operation: |cpu, word, _address| {
             let f = parse_format_r(word);
-            let rs1_bits = cpu.f[f.rs1].to_bits();
-            let rs2_bits = cpu.f[f.rs2].to_bits();
-            let sign_bit = (rs1_bits ^ rs2_bits) & 0x8000000000000000;
-            cpu.f[f.rd] = f64::from_bits(sign_bit | (rs1_bits & 0x7fffffffffffffff));
+            cpu.f[f.rd] = cpu.x[f.rs1] as u64;
             Ok(())
         },
         disassemble: dump_format_r,
         trace: None,
     },
     Instruction {
-        mask: 0xfe00007f,
-        data: 0x0a000053,
-        name: "FSUB.D",
+        mask: 0xfff0707f,
+        data: 0xe2000053,
+        name: "FMV.X.D",
         operation: |cpu, word, _address| {
             let f = parse_format_r(word);
-            // @TODO: Update fcsr if needed?
-            cpu.f[f.rd] = cpu.f[f.rs1] - cpu.f[f.rs2];
+            cpu.x[f.rd] = cpu.f[f.rs1] as i64;
             Ok(())
         },
         disassemble: dump_format_r,
@@ -2877,16 +3183,24 @@ pub const INSTRUCTIONS: [Instruction; INSTRUCTION_NUM] = [
     },
     Instruction {
         mask: 0x0000707f,
-        data: 0x00002027,
-        name: "FSW",
-        operation: |cpu, word, _address| {
-            let f = parse_format_s(word);
-            cpu.mmu.store_word(
-                cpu.x[f.rs1].wrapping_add(f.imm) as u64,
-                cpu.f[f.rs2].to_bits() as u32,
-            )
+        data: 0x0000000f,
+        name: "FENCE",
+        operation: |_cpu, _word, _address| {
+            // Do nothing?
+            Ok(())
         },
-        disassemble: dump_format_s,
+        disassemble: dump_empty,
+        trace: Some(trace_i),
+    },
+    Instruction {
+        mask: 0x0000707f,
+        data: 0x0000100f,
+        name: "FENCE.I",
+        operation: |_cpu, _word, _address| {
+            // Do nothing?
+            Ok(())
+        },
+        disassemble: dump_empty,
         trace: None,
     },
     Instruction {
@@ -4055,6 +4369,86 @@ mod test_cpu {
         // @TODO: Test compressed instruction operation
     }
 
+    #[test]
+    fn tick_operate_custom() {
+        // The registry is global, so unregister even if the test panics
+        struct Registration;
+        impl Drop for Registration {
+            fn drop(&mut self) {
+                crate::custom::unregister_custom_instruction(RV32IM::CUSTOM_0, 1, 2);
+            }
+        }
+        crate::register_custom_instruction(RV32IM::CUSTOM_0, 1, 2, |x, y| x.count_ones() + y);
+        let _registration = Registration;
+        let mut cpu = create_cpu();
+        cpu.get_mut_mmu().init_memory(4);
+        cpu.update_pc(DRAM_BASE);
+        // write ".insn r CUSTOM_0, 1, 2, a0, a1, a2"
+        let word = (2 << 25) | (12 << 20) | (11 << 15) | (1 << 12) | (10 << 7) | 0x0b;
+        match cpu.get_mut_mmu().store_word(DRAM_BASE, word) {
+            Ok(()) => {}
+            Err(_e) => panic!("Failed to store"),
+        };
+        cpu.x[11] = 0xff;
+        cpu.x[12] = 1;
+        match cpu.tick_operate() {
+            Ok(()) => {}
+            Err(_e) => panic!("tick_operate() unexpectedly did panic"),
+        };
+        assert_eq!(9, cpu.read_register(10));
+
+        let rows = cpu.tracer.rows.borrow();
+        let instruction = &rows.last().unwrap().instruction;
+        assert_eq!(RV32IM::CUSTOM_0, instruction.opcode);
+        assert_eq!(Some(custom_instruction_funct(1, 2)), instruction.imm);
+    }
+
+    #[test]
+    fn tick_operate_precompile() {
+        use common::precompiles::BN254_FR_MODULUS;
+
+        let mut cpu = create_cpu();
+        cpu.get_mut_mmu().init_memory(0x1000);
+        cpu.update_pc(DRAM_BASE);
+        // write ".insn r PRECOMPILE, 0, Bn254FrAdd, x0, a1, a2"
+        let id = Precompile::Bn254FrAdd.id();
+        let word = (id << 25) | (12 << 20) | (11 << 15) | 0x5b;
+        cpu.get_mut_mmu()
+            .store_word(DRAM_BASE, word)
+            .unwrap_or_else(|_| panic!("Failed to store"));
+        // (r - 1) + 2 = 1 mod r
+        let mut input = [0u32; 16];
+        input[..8].copy_from_slice(&BN254_FR_MODULUS);
+        input[0] -= 1;
+        input[8] = 2;
+        let (input_address, output_address) = (DRAM_BASE + 0x40, DRAM_BASE + 0x80);
+        for (i, word) in input.iter().enumerate() {
+            cpu.get_mut_mmu()
+                .store_word(input_address + 4 * i as u64, *word)
+                .unwrap_or_else(|_| panic!("Failed to store"));
+        }
+        cpu.x[11] = input_address as i64;
+        cpu.x[12] = output_address as i64;
+        cpu.tick_operate()
+            .unwrap_or_else(|_| panic!("tick_operate() unexpectedly did panic"));
+
+        let output: Vec<u32> = (0..8)
+            .map(|i| {
+                cpu.get_mut_mmu()
+                    .load_word(output_address + 4 * i)
+                    .unwrap_or_else(|_| panic!("Failed to load"))
+            })
+            .collect();
+        assert_eq!(output, [1, 0, 0, 0, 0, 0, 0, 0]);
+
+        let rows = cpu.tracer.rows.borrow();
+        let row = rows.last().unwrap();
+        assert_eq!(RV32IM::PRECOMPILE, row.instruction.opcode);
+        assert_eq!(Some(id as i64), row.instruction.imm);
+        assert_eq!(Some(input.to_vec()), row.precompile_input);
+        assert_eq!(Some(output_address), row.precompile_output_address);
+    }
+
     #[test]
     fn fetch() {
         // .fetch() reads four bytes from the memory
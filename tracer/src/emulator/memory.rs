@@ -21,6 +21,15 @@ impl Memory {
         }
     }
 
+    /// Grows memory to hold at least `capacity` bytes.
+    ///
+    /// # Arguments
+    /// * `capacity`
+    pub fn grow(&mut self, capacity: u64) {
+        let len = (capacity.div_ceil(8) as usize).max(self.data.len());
+        self.data.resize(len, 0);
+    }
+
     /// Reads a byte from memory.
     ///
     /// # Arguments
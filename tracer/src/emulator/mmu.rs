@@ -9,6 +9,7 @@ extern crate fnv;
 use std::rc::Rc;
 
 use crate::trace::Tracer;
+use common::blob::Blob;
 use common::rv_trace::{JoltDevice, MemoryState};
 
 use self::fnv::FnvHashMap;
@@ -139,6 +140,20 @@ impl Mmu {
         self.memory.init(capacity);
     }
 
+    /// Writes the contents of `blobs`, which must have been placed, to main memory,
+    /// growing it to hold them if needed.
+    ///
+    /// # Arguments
+    /// * `blobs`
+    pub fn load_blobs(&mut self, blobs: &[Blob]) {
+        for blob in blobs {
+            self.memory.grow(blob.address + 4 * blob.num_words());
+            for (i, byte) in blob.data.iter().enumerate() {
+                self.memory.write_byte(blob.address + i as u64, *byte);
+            }
+        }
+    }
+
     /// Initializes Virtio block disk. This method is expected to be called only once.
     ///
     /// # Arguments
@@ -534,7 +549,9 @@ impl Mmu {
                 0x10000000..=0x100000ff => self.uart.load(effective_address),
                 0x10001000..=0x10001FFF => self.disk.load(effective_address),
                 _ => {
-                    if self.jolt_device.is_input(effective_address) {
+                    if self.jolt_device.is_input(effective_address)
+                        || self.jolt_device.is_blob_directory(effective_address)
+                    {
                         self.jolt_device.load(effective_address)
                     } else {
                         panic!("Unknown memory mapping {effective_address:X}.");
@@ -553,7 +570,9 @@ impl Mmu {
             Xlen::Bit64 => 8,
         };
         if word_address < DRAM_BASE {
-            if self.jolt_device.is_input(word_address) {
+            if self.jolt_device.is_input(word_address)
+                || self.jolt_device.is_blob_directory(word_address)
+            {
                 let mut value_bytes = [0u8; 8];
                 for i in 0..bytes {
                     value_bytes[i as usize] = self.jolt_device.load(word_address + i);
@@ -1202,6 +1221,10 @@ impl MemoryWrapper {
         self.memory.read_doubleword(p_address - DRAM_BASE)
     }
 
+    pub fn grow(&mut self, p_address: u64) {
+        self.memory.grow(p_address - DRAM_BASE);
+    }
+
     pub fn write_byte(&mut self, p_address: u64, value: u8) {
         debug_assert!(
             p_address >= DRAM_BASE,
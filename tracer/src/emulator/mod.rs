@@ -103,7 +103,7 @@ impl Emulator {
             // the data in the address and terminating the test
             // if non-zero data is written.
             // End code 1 seems to mean pass.
-            let endcode = self.cpu.get_mut_mmu().load_word_raw(self.tohost_addr);
+            let endcode = self.read_tohost();
             if endcode != 0 {
                 match endcode {
                     1 => self.put_bytes_to_terminal(
@@ -118,6 +118,18 @@ impl Emulator {
         }
     }
 
+    /// Whether the program set by `setup_program()` is a
+    /// [`riscv-tests`](https://github.com/riscv/riscv-tests) program.
+    pub fn is_test(&self) -> bool {
+        self.is_test
+    }
+
+    /// The end code a [`riscv-tests`](https://github.com/riscv/riscv-tests) program
+    /// has written to `tohost`, or 0 if it has not finished yet. End code 1 means pass.
+    pub fn read_tohost(&mut self) -> u32 {
+        self.cpu.get_mut_mmu().load_word_raw(self.tohost_addr)
+    }
+
     /// Helper method. Sends ascii code bytes to terminal.
     ///
     /// # Arguments
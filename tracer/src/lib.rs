@@ -1,8 +1,6 @@
 #![allow(dead_code)]
 #![allow(clippy::legacy_numeric_constants)]
 
-use std::{fs::File, io::Read, path::PathBuf};
-
 use common::{self, constants::RAM_START_ADDRESS};
 use emulator::{
     cpu::{self, Xlen},
@@ -12,53 +10,140 @@ use emulator::{
 
 use object::{Object, ObjectSection, SectionKind};
 
+mod custom;
 mod decode;
+mod elf;
 mod emulator;
 mod trace;
 
+pub use common::blob::Blob;
 pub use common::rv_trace::{
-    ELFInstruction, JoltDevice, MemoryState, RVTraceRow, RegisterState, RV32IM,
+    ELFInstruction, JoltDevice, MemoryLayout, MemoryState, PanicReason, RVTraceRow, RegisterState,
+    RV32IM,
 };
 
+pub use crate::custom::{register_custom_instruction, CustomInstructionFn};
+pub use crate::elf::{validate_elf, ElfError};
+pub use crate::trace::TraceError;
+
 use crate::decode::decode_raw;
+use crate::trace::MemoryBounds;
+use common::blob::place_blobs;
 
 #[tracing::instrument(skip_all)]
 pub fn trace(
-    elf: &PathBuf,
+    elf_contents: &[u8],
     inputs: &[u8],
-    input_size: u64,
-    output_size: u64,
-) -> (Vec<RVTraceRow>, JoltDevice) {
+    blobs: Vec<Blob>,
+    memory_layout: &MemoryLayout,
+    memory_size: u64,
+    stack_size: u64,
+) -> Result<(Vec<RVTraceRow>, JoltDevice), TraceError> {
+    let mut emulator = setup_emulator(
+        elf_contents,
+        inputs,
+        blobs,
+        memory_layout,
+        memory_size,
+        stack_size,
+    )?;
+    run(&mut emulator)?;
+    Ok(take_trace(&mut emulator))
+}
+
+/// Traces a [riscv-tests](https://github.com/riscv-software-src/riscv-tests) program
+/// built for Jolt's test environment until it writes its end code to `tohost`. Returns
+/// the trace, the device and the end code: 1 if the test passed, `(n << 1) | 1` if
+/// test case `n` failed, or 0 if the program never wrote `tohost`.
+#[tracing::instrument(skip_all)]
+pub fn trace_test(
+    elf_contents: &[u8],
+    memory_layout: &MemoryLayout,
+    memory_size: u64,
+    stack_size: u64,
+) -> Result<(Vec<RVTraceRow>, JoltDevice, u32), TraceError> {
+    let mut emulator = setup_emulator(
+        elf_contents,
+        &[],
+        vec![],
+        memory_layout,
+        memory_size,
+        stack_size,
+    )?;
+    run(&mut emulator)?;
+    let end_code = if emulator.is_test() {
+        emulator.read_tohost()
+    } else {
+        0
+    };
+    let (rows, device) = take_trace(&mut emulator);
+    Ok((rows, device, end_code))
+}
+
+fn setup_emulator(
+    elf_contents: &[u8],
+    inputs: &[u8],
+    mut blobs: Vec<Blob>,
+    memory_layout: &MemoryLayout,
+    memory_size: u64,
+    stack_size: u64,
+) -> Result<Emulator, TraceError> {
     let term = DefaultTerminal::new();
     let mut emulator = Emulator::new(Box::new(term));
     emulator.update_xlen(get_xlen());
 
-    let mut jolt_device = JoltDevice::new(input_size, output_size);
+    let mut jolt_device = JoltDevice::with_layout(memory_layout.clone());
+    if inputs.len() as u64 > memory_layout.max_input_size {
+        return Err(TraceError::InputTooLarge {
+            len: inputs.len() as u64,
+            max_input_size: memory_layout.max_input_size,
+        });
+    }
+    if !blobs.is_empty() {
+        place_blobs(&mut blobs, memory_layout).map_err(TraceError::InvalidBlobs)?;
+    }
     jolt_device.inputs = inputs.to_vec();
+    jolt_device.blobs = blobs;
     emulator.get_mut_cpu().get_mut_mmu().jolt_device = jolt_device;
 
-    let mut elf_file = File::open(elf).unwrap();
+    let memory_bounds = MemoryBounds {
+        blob_start: memory_layout.blob_start,
+        blob_end: memory_layout.blob_end,
+        ..memory_bounds(elf_contents, memory_size, stack_size)
+    };
+    emulator.get_cpu().tracer.set_memory_bounds(memory_bounds);
 
-    let mut elf_contents = Vec::new();
-    elf_file.read_to_end(&mut elf_contents).unwrap();
+    emulator.setup_program(elf_contents.to_vec());
+    let mmu = emulator.get_mut_cpu().get_mut_mmu();
+    let blobs = std::mem::take(&mut mmu.jolt_device.blobs);
+    mmu.load_blobs(&blobs);
+    mmu.jolt_device.blobs = blobs;
 
-    emulator.setup_program(elf_contents);
+    Ok(emulator)
+}
 
+fn run(emulator: &mut Emulator) -> Result<(), TraceError> {
     let mut prev_pc = 0;
     loop {
         let pc = emulator.get_cpu().read_pc();
         emulator.tick();
 
+        if let Some(error) = emulator.get_cpu().tracer.take_error() {
+            return Err(error);
+        }
+
         // This is a trick to see if the program has terminated by throwing itself
         // into an infinite loop. It seems to be a good heuristic for now but we
         // should eventually migrate to an explicit shutdown signal.
         if prev_pc == pc {
-            break;
+            return Ok(());
         }
 
         prev_pc = pc;
     }
+}
 
+fn take_trace(emulator: &mut Emulator) -> (Vec<RVTraceRow>, JoltDevice) {
     let mut rows = emulator.get_mut_cpu().tracer.rows.try_borrow_mut().unwrap();
     let mut output = Vec::new();
     output.append(&mut rows);
@@ -69,6 +154,29 @@ pub fn trace(
     (output, device)
 }
 
+/// Computes the stack and heap bounds of a program laid out by the Jolt linker script:
+/// the stack sits directly above the last allocated section (typically `.bss`),
+/// followed by the heap, which may grow until the end of memory.
+fn memory_bounds(elf: &[u8], memory_size: u64, stack_size: u64) -> MemoryBounds {
+    let obj = object::File::parse(elf).unwrap();
+
+    let program_end = obj
+        .sections()
+        .filter(|s| s.address() >= RAM_START_ADDRESS)
+        .map(|s| s.address() + s.size())
+        .max()
+        .unwrap_or(RAM_START_ADDRESS);
+    let stack_bottom = program_end.next_multiple_of(8);
+
+    MemoryBounds {
+        stack_bottom,
+        stack_top: stack_bottom + stack_size,
+        memory_end: RAM_START_ADDRESS + memory_size,
+        blob_start: 0,
+        blob_end: 0,
+    }
+}
+
 #[tracing::instrument(skip_all)]
 pub fn decode(elf: &[u8]) -> (Vec<ELFInstruction>, Vec<(u64, u8)>) {
     let obj = object::File::parse(elf).unwrap();
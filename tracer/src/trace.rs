@@ -1,12 +1,93 @@
 use std::cell::RefCell;
+use std::fmt;
 
+use common::blob::BlobError;
+use common::constants::{float_register_index, RAM_START_ADDRESS};
+use common::float;
 use common::rv_trace::{ELFInstruction, MemoryState, RVTraceRow, RegisterState};
 
 use crate::emulator::cpu::Xlen;
 
+/// Index of the stack pointer (`sp`) in the integer register file.
+const SP: usize = 2;
+
+/// Regions of guest memory that the tracer polices while executing a program.
+/// The stack occupies `[stack_bottom, stack_top)` and grows downwards towards the
+/// program's `.bss`; the heap starts at `stack_top` and may grow up to `memory_end`.
+/// The blob region `[blob_start, blob_end)` may only be read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MemoryBounds {
+    pub stack_bottom: u64,
+    pub stack_top: u64,
+    pub memory_end: u64,
+    pub blob_start: u64,
+    pub blob_end: u64,
+}
+
+/// An error encountered while tracing a guest program.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TraceError {
+    /// The serialized inputs do not fit in the input region of the memory layout.
+    InputTooLarge { len: u64, max_input_size: u64 },
+    /// The stack pointer was moved below the bottom of the stack, i.e. into `.bss`.
+    StackOverflow { pc: u64, sp: u64, stack_bottom: u64 },
+    /// The guest accessed memory beyond the configured memory size.
+    OutOfMemory {
+        pc: u64,
+        address: u64,
+        memory_end: u64,
+    },
+    /// The blobs attached to the program are invalid or do not fit in the blob region.
+    InvalidBlobs(BlobError),
+    /// The guest wrote to the read-only blob region.
+    BlobWrite { pc: u64, address: u64 },
+}
+
+impl fmt::Display for TraceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TraceError::InputTooLarge {
+                len,
+                max_input_size,
+            } => write!(
+                f,
+                "Input too large: serialized inputs are {len} bytes but `max_input_size` is {max_input_size} bytes"
+            ),
+            TraceError::StackOverflow {
+                pc,
+                sp,
+                stack_bottom,
+            } => write!(
+                f,
+                "Stack overflow at PC 0x{pc:X}: stack pointer 0x{sp:X} is below the bottom of the stack (0x{stack_bottom:X}); consider increasing `stack_size`"
+            ),
+            TraceError::OutOfMemory {
+                pc,
+                address,
+                memory_end,
+            } => write!(
+                f,
+                "Out of memory at PC 0x{pc:X}: accessed 0x{address:X} beyond the end of memory (0x{memory_end:X}); consider increasing `memory_size`"
+            ),
+            TraceError::InvalidBlobs(error) => write!(f, "{error}"),
+            TraceError::BlobWrite { pc, address } => write!(
+                f,
+                "Write to read-only blob memory at PC 0x{pc:X}: address 0x{address:X}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for TraceError {}
+
 pub struct Tracer {
     pub rows: RefCell<Vec<RVTraceRow>>,
     open: RefCell<bool>,
+    memory_bounds: RefCell<Option<MemoryBounds>>,
+    /// Set once the stack pointer has been initialized to point into the stack,
+    /// so that the code setting up `sp` is not mistaken for a stack overflow.
+    stack_initialized: RefCell<bool>,
+    error: RefCell<Option<TraceError>>,
 }
 
 impl Tracer {
@@ -14,9 +95,21 @@ impl Tracer {
         Self {
             rows: RefCell::new(Vec::new()),
             open: RefCell::new(false),
+            memory_bounds: RefCell::new(None),
+            stack_initialized: RefCell::new(false),
+            error: RefCell::new(None),
         }
     }
 
+    pub fn set_memory_bounds(&self, memory_bounds: MemoryBounds) {
+        *self.memory_bounds.try_borrow_mut().unwrap() = Some(memory_bounds);
+    }
+
+    /// Returns the first error encountered while tracing, if any.
+    pub fn take_error(&self) -> Option<TraceError> {
+        self.error.try_borrow_mut().unwrap().take()
+    }
+
     pub fn start_instruction(&self, inst: ELFInstruction) {
         let mut inst = inst;
         inst.address = inst.address as u32 as u64;
@@ -31,24 +124,30 @@ impl Tracer {
         });
     }
 
-    pub fn capture_pre_state(&self, reg: [i64; 32], xlen: &Xlen) {
+    pub fn capture_pre_state(&self, reg: [i64; 32], float_reg: [u64; 32], xlen: &Xlen) {
         if !*self.open.try_borrow().unwrap() {
             return;
         }
 
         let mut rows = self.rows.try_borrow_mut().unwrap();
         let row = rows.last_mut().unwrap();
+        let value = |index: u64| register_value(index, &reg, &float_reg, xlen);
 
         if let Some(rs1) = row.instruction.rs1 {
-            row.register_state.rs1_val = Some(normalize_register_value(reg[rs1 as usize], xlen));
+            row.register_state.rs1_val = Some(value(rs1));
         }
 
         if let Some(rs2) = row.instruction.rs2 {
-            row.register_state.rs2_val = Some(normalize_register_value(reg[rs2 as usize], xlen));
+            row.register_state.rs2_val = Some(value(rs2));
+        }
+
+        if float::is_fused_multiply_add(row.instruction.opcode) {
+            let (_, rs3) = float::decode_imm(row.instruction.imm.unwrap());
+            row.register_state.rs3_val = Some(value(rs3.unwrap()));
         }
     }
 
-    pub fn capture_post_state(&self, reg: [i64; 32], xlen: &Xlen) {
+    pub fn capture_post_state(&self, reg: [i64; 32], float_reg: [u64; 32], xlen: &Xlen) {
         if !*self.open.try_borrow().unwrap() {
             return;
         }
@@ -57,7 +156,7 @@ impl Tracer {
         let row = rows.last_mut().unwrap();
 
         if let Some(rd) = row.instruction.rd {
-            row.register_state.rd_post_val = Some(normalize_register_value(reg[rd as usize], xlen));
+            row.register_state.rd_post_val = Some(register_value(rd, &reg, &float_reg, xlen));
         }
     }
 
@@ -71,9 +170,92 @@ impl Tracer {
         }
     }
 
+    /// Records the input words and output address of a `PRECOMPILE` instruction.
+    pub fn push_precompile(&self, input: Vec<u32>, output_address: u64) {
+        if !*self.open.try_borrow().unwrap() {
+            return;
+        }
+
+        if let Some(row) = self.rows.try_borrow_mut().unwrap().last_mut() {
+            row.precompile_input = Some(input);
+            row.precompile_output_address = Some(output_address);
+        }
+    }
+
     pub fn end_instruction(&self) {
         *self.open.try_borrow_mut().unwrap() = false;
     }
+
+    /// Checks the stack pointer and the memory access of the most recent instruction
+    /// against the configured memory bounds, recording a `TraceError` on violation.
+    pub fn check_memory_bounds(&self, reg: [i64; 32], xlen: &Xlen) {
+        let bounds = self.memory_bounds.try_borrow().unwrap();
+        let Some(bounds) = bounds.as_ref() else {
+            return;
+        };
+        if self.error.try_borrow().unwrap().is_some() {
+            return;
+        }
+
+        let rows = self.rows.try_borrow().unwrap();
+        let Some(row) = rows.last() else {
+            return;
+        };
+        let pc = row.instruction.address;
+
+        let sp = normalize_register_value(reg[SP], xlen);
+        let mut stack_initialized = self.stack_initialized.try_borrow_mut().unwrap();
+        if sp >= bounds.stack_bottom && sp <= bounds.stack_top {
+            *stack_initialized = true;
+        } else if *stack_initialized && sp < bounds.stack_bottom {
+            *self.error.try_borrow_mut().unwrap() = Some(TraceError::StackOverflow {
+                pc,
+                sp,
+                stack_bottom: bounds.stack_bottom,
+            });
+            return;
+        }
+
+        let mut addresses = vec![];
+        match row.memory_state {
+            Some(MemoryState::Read { address, .. }) => addresses.push(address),
+            Some(MemoryState::Write { address, .. }) => {
+                if (bounds.blob_start..bounds.blob_end).contains(&address) {
+                    *self.error.try_borrow_mut().unwrap() =
+                        Some(TraceError::BlobWrite { pc, address });
+                    return;
+                }
+                addresses.push(address)
+            }
+            None => {}
+        };
+        // Only the last memory access of a precompile is recorded, which is its
+        // last output word, so also check its last input word.
+        if let (Some(input), Some(rs1_val)) = (&row.precompile_input, row.register_state.rs1_val) {
+            addresses.push(rs1_val + 4 * (input.len() as u64 - 1));
+        }
+        if let Some(&address) = addresses.iter().find(|&&address| {
+            address >= RAM_START_ADDRESS
+                && address >= bounds.memory_end
+                && !(bounds.blob_start..bounds.blob_end).contains(&address)
+        }) {
+            *self.error.try_borrow_mut().unwrap() = Some(TraceError::OutOfMemory {
+                pc,
+                address,
+                memory_end: bounds.memory_end,
+            });
+        }
+    }
+}
+
+/// The value of register `index`: an integer register, or the 64 bits of a
+/// floating-point register (see `common::float`).
+fn register_value(index: u64, reg: &[i64; 32], float_reg: &[u64; 32], xlen: &Xlen) -> u64 {
+    if index >= float_register_index(0) {
+        float_reg[((index - float_register_index(0)) / 2) as usize]
+    } else {
+        normalize_register_value(reg[index as usize], xlen)
+    }
 }
 
 fn normalize_register_value(value: i64, xlen: &Xlen) -> u64 {
@@ -82,3 +264,139 @@ fn normalize_register_value(value: i64, xlen: &Xlen) -> u64 {
         Xlen::Bit64 => value as u64,
     }
 }
+
+#[cfg(test)]
+mod test_tracer {
+    use super::*;
+    use common::rv_trace::RV32IM;
+
+    const BOUNDS: MemoryBounds = MemoryBounds {
+        stack_bottom: RAM_START_ADDRESS + 0x1000,
+        stack_top: RAM_START_ADDRESS + 0x2000,
+        memory_end: RAM_START_ADDRESS + 0x4000,
+        blob_start: RAM_START_ADDRESS + 0x8000,
+        blob_end: RAM_START_ADDRESS + 0x9000,
+    };
+
+    fn setup_tracer() -> Tracer {
+        let tracer = Tracer::new();
+        tracer.set_memory_bounds(BOUNDS);
+        tracer
+    }
+
+    fn step(tracer: &Tracer, pc: u64, sp: u64, memory_state: Option<MemoryState>) {
+        tracer.start_instruction(ELFInstruction {
+            address: pc,
+            opcode: RV32IM::ADDI,
+            rs1: None,
+            rs2: None,
+            rd: None,
+            imm: None,
+            virtual_sequence_remaining: None,
+        });
+        if let Some(memory_state) = memory_state {
+            tracer.push_memory(memory_state);
+        }
+        let mut reg = [0; 32];
+        reg[SP] = sp as i64;
+        tracer.check_memory_bounds(reg, &Xlen::Bit32);
+        tracer.end_instruction();
+    }
+
+    #[test]
+    fn test_stack_overflow() {
+        let tracer = setup_tracer();
+
+        // `sp` is not checked until it has been set up to point into the stack
+        step(&tracer, RAM_START_ADDRESS, RAM_START_ADDRESS, None);
+        assert_eq!(tracer.take_error(), None);
+
+        step(&tracer, RAM_START_ADDRESS + 4, BOUNDS.stack_top, None);
+        step(&tracer, RAM_START_ADDRESS + 8, BOUNDS.stack_bottom, None);
+        assert_eq!(tracer.take_error(), None);
+
+        step(
+            &tracer,
+            RAM_START_ADDRESS + 12,
+            BOUNDS.stack_bottom - 16,
+            None,
+        );
+        assert_eq!(
+            tracer.take_error(),
+            Some(TraceError::StackOverflow {
+                pc: RAM_START_ADDRESS + 12,
+                sp: BOUNDS.stack_bottom - 16,
+                stack_bottom: BOUNDS.stack_bottom,
+            })
+        );
+    }
+
+    #[test]
+    fn test_out_of_memory() {
+        let tracer = setup_tracer();
+
+        step(
+            &tracer,
+            RAM_START_ADDRESS,
+            BOUNDS.stack_top,
+            Some(MemoryState::Write {
+                address: BOUNDS.memory_end - 4,
+                pre_value: 0,
+                post_value: 1,
+            }),
+        );
+        assert_eq!(tracer.take_error(), None);
+
+        step(
+            &tracer,
+            RAM_START_ADDRESS + 4,
+            BOUNDS.stack_top,
+            Some(MemoryState::Read {
+                address: BOUNDS.memory_end,
+                value: 0,
+            }),
+        );
+        assert_eq!(
+            tracer.take_error(),
+            Some(TraceError::OutOfMemory {
+                pc: RAM_START_ADDRESS + 4,
+                address: BOUNDS.memory_end,
+                memory_end: BOUNDS.memory_end,
+            })
+        );
+    }
+
+    #[test]
+    fn test_blob_write() {
+        let tracer = setup_tracer();
+
+        step(
+            &tracer,
+            RAM_START_ADDRESS,
+            BOUNDS.stack_top,
+            Some(MemoryState::Read {
+                address: BOUNDS.blob_start,
+                value: 0,
+            }),
+        );
+        assert_eq!(tracer.take_error(), None);
+
+        step(
+            &tracer,
+            RAM_START_ADDRESS + 4,
+            BOUNDS.stack_top,
+            Some(MemoryState::Write {
+                address: BOUNDS.blob_end - 4,
+                pre_value: 0,
+                post_value: 1,
+            }),
+        );
+        assert_eq!(
+            tracer.take_error(),
+            Some(TraceError::BlobWrite {
+                pc: RAM_START_ADDRESS + 4,
+                address: BOUNDS.blob_end - 4,
+            })
+        );
+    }
+}